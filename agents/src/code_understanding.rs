@@ -9,8 +9,32 @@ use tracing::{debug, info, warn};
 use uuid::Uuid;
 
 use crate::llm_integration::LLMIntegrationManager;
+use crate::prompt_template::{PromptTemplate, DEFAULT_DB_ROOT};
 use odincode_core::{CodeFile, CodeSuggestion, SuggestionType};
-use odincode_ltmc::{LTMManager, LearningPattern, PatternType};
+use odincode_ltmc::{LTMManager, LearningPattern, PatternProvenance, PatternType};
+
+/// Name of the complexity analysis prompt template
+pub const COMPLEXITY_PROMPT_NAME: &str = "code_understanding";
+
+/// Default complexity analysis prompt
+pub const DEFAULT_COMPLEXITY_PROMPT: &str =
+    "Analyze the complexity of the following {{language}} code:
+
+File: {{path}}
+Language: {{language}}
+
+Provide a comprehensive complexity analysis including:
+1. Cyclomatic complexity for each function
+2. Cognitive complexity assessment
+3. Maintainability index calculation
+4. Halstead metrics
+5. Identify complexity hotspots with specific line numbers
+6. Suggest refactoring for each hotspot
+
+Code:
+```{{language}}
+{{code}}
+```";
 
 /// Code understanding configuration
 #[derive(Debug, Clone)]
@@ -373,6 +397,8 @@ pub struct CodeUnderstandingAgent {
     llm_integration: LLMIntegrationManager,
     /// LTMC manager for pattern learning
     ltmc_manager: std::sync::Arc<LTMManager>,
+    /// Database root holding prompt template overrides
+    prompt_root: std::path::PathBuf,
 }
 
 impl CodeUnderstandingAgent {
@@ -387,9 +413,16 @@ impl CodeUnderstandingAgent {
             config,
             llm_integration,
             ltmc_manager,
+            prompt_root: std::path::PathBuf::from(DEFAULT_DB_ROOT),
         })
     }
 
+    /// Set the database root prompt template overrides are loaded from
+    pub fn with_prompt_root(mut self, prompt_root: std::path::PathBuf) -> Self {
+        self.prompt_root = prompt_root;
+        self
+    }
+
     /// Understand code in a file
     pub async fn understand_code(&self, file: &CodeFile) -> Result<CodeUnderstandingResult> {
         let start_time = std::time::Instant::now();
@@ -461,26 +494,16 @@ impl CodeUnderstandingAgent {
         debug!("Analyzing complexity for file: {}", file.path);
 
         // Use LLM to analyze complexity
-        let prompt = format!(
-            "Analyze the complexity of the following {} code:
-
-File: {}
-Language: {}
-
-Provide a comprehensive complexity analysis including:
-1. Cyclomatic complexity for each function
-2. Cognitive complexity assessment
-3. Maintainability index calculation
-4. Halstead metrics
-5. Identify complexity hotspots with specific line numbers
-6. Suggest refactoring for each hotspot
-
-Code:
-```{}
-{}
-```",
-            file.language, file.path, file.language, file.path, file.content
-        );
+        let template = PromptTemplate::load(
+            &self.prompt_root,
+            COMPLEXITY_PROMPT_NAME,
+            DEFAULT_COMPLEXITY_PROMPT,
+        )?;
+        let prompt = template.render(&HashMap::from([
+            ("language".to_string(), file.language.clone()),
+            ("path".to_string(), file.path.clone()),
+            ("code".to_string(), file.content.clone()),
+        ]))?;
 
         let analysis_result = self.llm_integration.generate_response(&prompt).await?;
 
@@ -974,6 +997,7 @@ Detail level: {}/10",
             last_accessed: chrono::Utc::now(),
             access_count: 0,
             confidence: complexity.overall_score,
+            provenance: PatternProvenance::agent("CodeUnderstanding", Some(file.id), None),
         };
 
         self.ltmc_manager.store_pattern(pattern).await?;
@@ -5,17 +5,24 @@
 use anyhow::Result;
 use std::collections::HashMap;
 use tokio::sync::RwLock;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 use uuid::Uuid;
 
+use crate::code_understanding::{CodeUnderstandingAgent, CodeUnderstandingConfig};
+use crate::error::{AgentError, AgentResult};
 use crate::ltmc_integration::{AgentExecutionResult, LTMCIntegration, LearningRequest};
 use crate::models::{Agent, AgentType};
+use crate::prompt_template::DEFAULT_DB_ROOT;
+use odincode_core::action_history::ActionHistoryManager;
 use odincode_core::{CodeEngine, CodeFile};
-use odincode_ltmc::{LTMManager, LearningPattern, PatternType};
+use odincode_ltmc::{LTMManager, LearningPattern, PatternProvenance, PatternType};
 
 pub mod executors;
+pub mod preview;
 
 use crate::coordinator::executors::AgentExecutors;
+use crate::ltmc_integration::LearningResponse;
+pub use preview::{proposed_edits, ProposedEdit, SuggestionProposer};
 
 use std::sync::Arc;
 
@@ -30,6 +37,12 @@ pub struct AgentCoordinator {
     pub ltmc_manager: std::sync::Arc<LTMManager>,
     /// LTMC integration for real-time learning
     pub ltmc_integration: std::sync::Arc<LTMCIntegration>,
+    /// Suggestion sources replacing the built-in executor of an agent
+    pub proposers: Arc<RwLock<HashMap<Uuid, Arc<dyn SuggestionProposer>>>>,
+    /// Execution database agent executions are recorded in, if any
+    pub action_history: Option<Arc<ActionHistoryManager>>,
+    /// Database root agents load prompt template overrides from
+    pub prompt_root: std::path::PathBuf,
 }
 
 impl AgentCoordinator {
@@ -44,9 +57,36 @@ impl AgentCoordinator {
             core_engine,
             ltmc_manager,
             ltmc_integration,
+            proposers: Arc::new(RwLock::new(HashMap::new())),
+            action_history: None,
+            prompt_root: std::path::PathBuf::from(DEFAULT_DB_ROOT),
         }
     }
 
+    /// Record every agent execution in the execution database
+    pub fn with_action_history(mut self, action_history: Arc<ActionHistoryManager>) -> Self {
+        self.action_history = Some(action_history);
+        self
+    }
+
+    /// Load agent prompt template overrides from `prompt_root`
+    pub fn with_prompt_root(mut self, prompt_root: std::path::PathBuf) -> Self {
+        self.prompt_root = prompt_root;
+        self
+    }
+
+    /// Create a code understanding agent that loads its prompt overrides from
+    /// the coordinator's prompt root
+    pub fn code_understanding_agent(
+        &self,
+        config: CodeUnderstandingConfig,
+    ) -> Result<CodeUnderstandingAgent> {
+        Ok(
+            CodeUnderstandingAgent::new(config, Arc::clone(&self.ltmc_manager))?
+                .with_prompt_root(self.prompt_root.clone()),
+        )
+    }
+
     /// Register a new agent with the coordinator
     pub async fn register_agent(
         &self,
@@ -95,11 +135,34 @@ impl AgentCoordinator {
     }
 
     /// Execute an agent on a specific file
+    ///
+    /// An unknown agent is reported as `AgentError::AgentNotFound` and an unknown
+    /// file as `AgentError::FileNotFound`. The execution, failed or not, is
+    /// recorded in the execution database when one is set.
     pub async fn execute_agent_on_file(
         &self,
         agent_id: Uuid,
         file_id: Uuid,
-    ) -> Result<Option<Vec<odincode_core::CodeSuggestion>>> {
+    ) -> AgentResult<Option<Vec<odincode_core::CodeSuggestion>>> {
+        let start_time = std::time::Instant::now();
+        let mut session_id = None;
+        let result = self
+            .run_agent_on_file(agent_id, file_id, &mut session_id)
+            .await;
+        self.record_execution(agent_id, file_id, session_id, &result, start_time.elapsed())
+            .await;
+        result
+    }
+
+    /// Run an agent on a file and store what it learned
+    ///
+    /// `session_id` is set to the execution's learning session once it is opened.
+    async fn run_agent_on_file(
+        &self,
+        agent_id: Uuid,
+        file_id: Uuid,
+        session_id: &mut Option<Uuid>,
+    ) -> AgentResult<Option<Vec<odincode_core::CodeSuggestion>>> {
         let start_time = std::time::Instant::now();
 
         // Get the agent
@@ -107,7 +170,7 @@ impl AgentCoordinator {
             let agents = self.agents.read().await;
             match agents.get(&agent_id) {
                 Some(agent) => agent.clone(),
-                None => return Err(anyhow::anyhow!("Agent not found: {}", agent_id)),
+                None => return Err(AgentError::AgentNotFound(agent_id)),
             }
         };
 
@@ -125,11 +188,11 @@ impl AgentCoordinator {
         );
 
         // Get the file from the core engine
-        let file = self.core_engine.get_file(file_id).await?;
-        if file.is_none() {
-            return Err(anyhow::anyhow!("File not found: {}", file_id));
-        }
-        let file = file.unwrap();
+        let file = self
+            .core_engine
+            .get_file(file_id)
+            .await?
+            .ok_or_else(|| AgentError::file_not_found(file_id))?;
 
         // Start learning session for this execution
         let learning_session_id = self
@@ -140,6 +203,7 @@ impl AgentCoordinator {
                 format!("Executing {} on file {}", agent.name, file.path),
             )
             .await?;
+        *session_id = Some(learning_session_id);
 
         // Get learning insights before execution
         let learning_request = LearningRequest {
@@ -165,14 +229,112 @@ impl AgentCoordinator {
             learning_response.patterns.len()
         );
 
-        // Execute the appropriate agent logic based on type
-        let suggestions = match agent.agent_type {
+        // Propose suggestions; executing an agent never applies them
+        let suggestions = self
+            .propose_suggestions(&agent, &file, &learning_response)
+            .await?;
+
+        let execution_time_ms = start_time.elapsed().as_millis() as u64;
+
+        // Store the execution result for learning
+        let execution_result = AgentExecutionResult {
+            description: format!("Executed {} on file {}", agent.name, file.path),
+            success: !suggestions.is_empty(),
+            suggestions: suggestions.clone(),
+            execution_time_ms,
+            confidence: learning_response.confidence,
+        };
+
+        self.ltmc_integration
+            .store_agent_execution(
+                agent_id,
+                agent.agent_type.clone(),
+                Some(file_id),
+                &execution_result,
+            )
+            .await?;
+
+        // Complete the learning session
+        self.ltmc_integration
+            .complete_learning_session(
+                learning_session_id,
+                format!("Completed execution with {} suggestions", suggestions.len()),
+            )
+            .await?;
+
+        // Store the execution in LTMC for backward compatibility
+        self.store_agent_execution(&agent, &file, &suggestions, learning_session_id)
+            .await?;
+
+        Ok(Some(suggestions))
+    }
+
+    /// Record an agent execution in the execution database, if one is set
+    ///
+    /// The execution is recorded under its learning session, or with an empty
+    /// session id if it failed before one was opened. Recording failures are
+    /// logged rather than failing the execution.
+    async fn record_execution(
+        &self,
+        agent_id: Uuid,
+        file_id: Uuid,
+        session_id: Option<Uuid>,
+        result: &AgentResult<Option<Vec<odincode_core::CodeSuggestion>>>,
+        duration: std::time::Duration,
+    ) {
+        let Some(action_history) = &self.action_history else {
+            return;
+        };
+
+        let agent_name = match self.agents.read().await.get(&agent_id) {
+            Some(agent) => agent.name.clone(),
+            None => agent_id.to_string(),
+        };
+        let file_path = match self.core_engine.get_file(file_id).await {
+            Ok(Some(file)) => Some(file.path),
+            _ => None,
+        };
+        let (success, error_message, suggestion_count) = match result {
+            Ok(suggestions) => (true, None, suggestions.as_ref().map_or(0, Vec::len)),
+            Err(e) => (false, Some(e.to_string()), 0),
+        };
+
+        if let Err(e) = action_history
+            .log_agent_execution(
+                &session_id.map(|id| id.to_string()).unwrap_or_default(),
+                &agent_name,
+                file_path.as_deref(),
+                success,
+                error_message,
+                duration.as_millis() as u64,
+                suggestion_count,
+            )
+            .await
+        {
+            warn!("Failed to record execution of agent {}: {}", agent_name, e);
+        }
+    }
+
+    /// Propose suggestions for a file with the agent's proposer or, without
+    /// one, its built-in executor
+    async fn propose_suggestions(
+        &self,
+        agent: &Agent,
+        file: &CodeFile,
+        learning_response: &LearningResponse,
+    ) -> Result<Vec<odincode_core::CodeSuggestion>> {
+        let proposer = self.proposers.read().await.get(&agent.id).cloned();
+        if let Some(proposer) = proposer {
+            return proposer.propose(agent, file).await;
+        }
+
+        Ok(match agent.agent_type {
             AgentType::CodeGenerator => {
                 AgentExecutors::execute_code_generation_agent_with_learning(
                     &self.ltmc_integration,
-                    &agent,
-                    &file,
-                    &learning_response,
+                    agent,
+                    file,
+                    learning_response,
                 )
                 .await?
             }
@@ -180,9 +342,9 @@ impl AgentCoordinator {
                 AgentExecutors::execute_refactoring_agent_with_learning(
                     &self.ltmc_integration,
                     &self.core_engine,
-                    &agent,
-                    &file,
-                    &learning_response,
+                    agent,
+                    file,
+                    learning_response,
                 )
                 .await?
             }
@@ -190,74 +352,83 @@ impl AgentCoordinator {
                 AgentExecutors::execute_bug_detection_agent_with_learning(
                     &self.ltmc_integration,
                     &self.core_engine,
-                    &agent,
-                    &file,
-                    &learning_response,
+                    agent,
+                    file,
+                    learning_response,
                 )
                 .await?
             }
             AgentType::Documenter => {
                 AgentExecutors::execute_documentation_agent_with_learning(
                     &self.ltmc_integration,
-                    &agent,
-                    &file,
-                    &learning_response,
+                    agent,
+                    file,
+                    learning_response,
                 )
                 .await?
             }
             AgentType::TestGenerator => {
                 AgentExecutors::execute_test_generation_agent_with_learning(
                     &self.ltmc_integration,
-                    &agent,
-                    &file,
-                    &learning_response,
+                    agent,
+                    file,
+                    learning_response,
                 )
                 .await?
             }
             AgentType::CodeUnderstanding => {
                 AgentExecutors::execute_code_understanding_agent_with_learning(
                     &self.ltmc_integration,
-                    &agent,
-                    &file,
-                    &learning_response,
+                    agent,
+                    file,
+                    learning_response,
                 )
                 .await?
             }
-        };
+        })
+    }
 
-        let execution_time_ms = start_time.elapsed().as_millis() as u64;
+    /// Replace the built-in executor of an agent with a suggestion source
+    pub async fn set_proposer(&self, agent_id: Uuid, proposer: Arc<dyn SuggestionProposer>) {
+        self.proposers.write().await.insert(agent_id, proposer);
+    }
 
-        // Store the execution result for learning
-        let execution_result = AgentExecutionResult {
-            description: format!("Executed {} on file {}", agent.name, file.path),
-            success: !suggestions.is_empty(),
-            suggestions: suggestions.clone(),
-            execution_time_ms,
-            confidence: learning_response.confidence,
-        };
+    /// Preview the edits an agent would make to a file, as unified diffs
+    ///
+    /// Runs only the agent's propose phase: no file is written, nothing is
+    /// recorded in LTMC and no learning session is opened. Errors are reported
+    /// as by `execute_agent_on_file`.
+    pub async fn preview_agent(
+        &self,
+        agent_id: Uuid,
+        file_id: Uuid,
+    ) -> AgentResult<Vec<ProposedEdit>> {
+        let agent = self
+            .get_agent(agent_id)
+            .await?
+            .ok_or(AgentError::AgentNotFound(agent_id))?;
+        let file = self
+            .core_engine
+            .get_file(file_id)
+            .await?
+            .ok_or_else(|| AgentError::file_not_found(file_id))?;
 
-        self.ltmc_integration
-            .store_agent_execution(
+        let learning_response = self
+            .ltmc_integration
+            .query_learning(&LearningRequest {
                 agent_id,
-                agent.agent_type.clone(),
-                Some(file_id),
-                &execution_result,
-            )
+                agent_type: agent.agent_type.clone(),
+                context: format!("Previewing {} on file {}", agent.name, file.path),
+                file_id: Some(file_id),
+                query: format!("{} analysis for {} file", agent.name, file.language),
+                pattern_types: vec![PatternType::CodePattern, PatternType::ErrorSolution],
+            })
             .await?;
 
-        // Complete the learning session
-        self.ltmc_integration
-            .complete_learning_session(
-                learning_session_id,
-                format!("Completed execution with {} suggestions", suggestions.len()),
-            )
-            .await?;
-
-        // Store the execution in LTMC for backward compatibility
-        self.store_agent_execution(&agent, &file, &suggestions)
+        let suggestions = self
+            .propose_suggestions(&agent, &file, &learning_response)
             .await?;
-
-        Ok(Some(suggestions))
+        Ok(proposed_edits(&file, &suggestions))
     }
 
     /// Store agent execution details in LTMC for learning
@@ -266,6 +437,7 @@ impl AgentCoordinator {
         agent: &Agent,
         file: &CodeFile,
         suggestions: &[odincode_core::CodeSuggestion],
+        session_id: Uuid,
     ) -> Result<()> {
         let mut context = HashMap::new();
         context.insert("agent_type".to_string(), format!("{:?}", agent.agent_type));
@@ -290,6 +462,11 @@ impl AgentCoordinator {
             last_accessed: chrono::Utc::now(),
             access_count: 0,
             confidence: agent.confidence_threshold,
+            provenance: PatternProvenance::agent(
+                format!("{:?}", agent.agent_type),
+                Some(file.id),
+                Some(session_id),
+            ),
         };
 
         self.ltmc_manager.store_pattern(pattern).await?;
@@ -303,3 +480,196 @@ impl AgentCoordinator {
         Ok(result)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use odincode_core::{CodeSuggestion, Severity, SuggestionType};
+
+    /// Refactorer that renames `x` on the first line
+    struct StubRefactorer;
+
+    #[async_trait::async_trait]
+    impl SuggestionProposer for StubRefactorer {
+        async fn propose(&self, _agent: &Agent, file: &CodeFile) -> Result<Vec<CodeSuggestion>> {
+            let mut suggestion = CodeSuggestion::new(
+                Uuid::new_v4(),
+                SuggestionType::Rename,
+                "Rename x to count".to_string(),
+                "let count = 1;".to_string(),
+                0.9,
+            );
+            suggestion.file_path = file.path.clone();
+            suggestion.line_number = Some(1);
+            suggestion.severity = Severity::Low;
+            suggestion.auto_fixable = true;
+            Ok(vec![suggestion])
+        }
+    }
+
+    #[tokio::test]
+    async fn test_missing_file_is_reported_as_file_not_found() {
+        let core_engine = Arc::new(CodeEngine::new().unwrap());
+        let ltmc_manager = Arc::new(LTMManager::new());
+        let llm_manager =
+            Arc::new(odincode_core::llm_integration::LLMIntegrationManager::new().unwrap());
+        let ltmc_integration = Arc::new(LTMCIntegration::new(
+            ltmc_manager.clone(),
+            core_engine.clone(),
+            llm_manager,
+        ));
+        let coordinator = AgentCoordinator::new(core_engine, ltmc_manager, ltmc_integration);
+        let agent_id = coordinator
+            .register_agent(
+                AgentType::BugDetector,
+                "Bug Detector".to_string(),
+                "Finds bugs".to_string(),
+                vec![],
+                0.5,
+            )
+            .await
+            .unwrap();
+
+        let missing = Uuid::new_v4();
+        let result = coordinator.execute_agent_on_file(agent_id, missing).await;
+        assert!(
+            matches!(result, Err(AgentError::FileNotFound(ref id)) if *id == missing.to_string())
+        );
+
+        let unknown_agent = Uuid::new_v4();
+        let result = coordinator
+            .execute_agent_on_file(unknown_agent, missing)
+            .await;
+        assert!(matches!(result, Err(AgentError::AgentNotFound(id)) if id == unknown_agent));
+    }
+
+    #[tokio::test]
+    async fn test_preview_does_not_write_the_file() {
+        let core_engine = Arc::new(CodeEngine::new().unwrap());
+        let ltmc_manager = Arc::new(LTMManager::new());
+        let llm_manager =
+            Arc::new(odincode_core::llm_integration::LLMIntegrationManager::new().unwrap());
+        let ltmc_integration = Arc::new(LTMCIntegration::new(
+            ltmc_manager.clone(),
+            core_engine.clone(),
+            llm_manager,
+        ));
+        let coordinator =
+            AgentCoordinator::new(core_engine.clone(), ltmc_manager, ltmc_integration.clone());
+        let agent_id = coordinator
+            .register_agent(
+                AgentType::Refactorer,
+                "Refactorer".to_string(),
+                "Renames variables".to_string(),
+                vec![],
+                0.5,
+            )
+            .await
+            .unwrap();
+        coordinator
+            .set_proposer(agent_id, Arc::new(StubRefactorer))
+            .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("main.rs");
+        let content = "let x = 1;\nprintln!(\"{}\", x);\n";
+        std::fs::write(&path, content).unwrap();
+        let file_id = core_engine
+            .load_file(
+                path.to_string_lossy().into_owned(),
+                content.to_string(),
+                "rust".to_string(),
+            )
+            .await
+            .unwrap();
+
+        let edits = coordinator.preview_agent(agent_id, file_id).await.unwrap();
+        assert_eq!(edits.len(), 1);
+        assert_eq!(edits[0].path, path.to_string_lossy());
+        assert!(edits[0].diff.contains("-let x = 1;\n+let count = 1;\n"));
+        assert_eq!(edits[0].suggestion.title, "Rename x to count");
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), content);
+        let file = core_engine.get_file(file_id).await.unwrap().unwrap();
+        assert_eq!(file.content, content);
+
+        let statistics = ltmc_integration.get_learning_statistics().await.unwrap();
+        assert_eq!(statistics.total_sessions, 0);
+    }
+
+    #[tokio::test]
+    async fn test_agent_executions_are_recorded() {
+        let core_engine = Arc::new(CodeEngine::new().unwrap());
+        let ltmc_manager = Arc::new(LTMManager::new());
+        let llm_manager =
+            Arc::new(odincode_core::llm_integration::LLMIntegrationManager::new().unwrap());
+        let ltmc_integration = Arc::new(LTMCIntegration::new(
+            ltmc_manager.clone(),
+            core_engine.clone(),
+            llm_manager,
+        ));
+        let pool = sqlx::SqlitePool::connect("sqlite::memory:").await.unwrap();
+        let action_history = Arc::new(ActionHistoryManager::new(pool));
+        action_history.init().await.unwrap();
+        let coordinator =
+            AgentCoordinator::new(core_engine.clone(), ltmc_manager, ltmc_integration)
+                .with_action_history(action_history.clone());
+        let agent_id = coordinator
+            .register_agent(
+                AgentType::Refactorer,
+                "Stub Refactorer".to_string(),
+                "Renames variables".to_string(),
+                vec![],
+                0.5,
+            )
+            .await
+            .unwrap();
+        coordinator
+            .set_proposer(agent_id, Arc::new(StubRefactorer))
+            .await;
+        let file_id = core_engine
+            .load_file(
+                "src/main.rs".to_string(),
+                "let x = 1;\n".to_string(),
+                "rust".to_string(),
+            )
+            .await
+            .unwrap();
+
+        coordinator
+            .execute_agent_on_file(agent_id, file_id)
+            .await
+            .unwrap();
+        assert!(coordinator
+            .execute_agent_on_file(agent_id, Uuid::new_v4())
+            .await
+            .is_err());
+
+        let actions = action_history
+            .get_actions_by_tool("Stub Refactorer")
+            .await
+            .unwrap();
+        assert_eq!(actions.len(), 2);
+        let succeeded = actions.iter().find(|action| action.success).unwrap();
+        assert_eq!(succeeded.file_path.as_deref(), Some("src/main.rs"));
+        assert!(Uuid::parse_str(&succeeded.session_id).is_ok());
+        assert_ne!(succeeded.session_id, agent_id.to_string());
+        assert_eq!(
+            succeeded.metadata.as_ref().unwrap()["suggestion_count"],
+            "1"
+        );
+        let failed = actions.iter().find(|action| !action.success).unwrap();
+        assert_eq!(failed.session_id, "");
+        assert!(failed
+            .error_message
+            .as_deref()
+            .unwrap()
+            .starts_with("File not found"));
+
+        let by_file = action_history
+            .get_actions_by_file("src/main.rs")
+            .await
+            .unwrap();
+        assert_eq!(by_file.len(), 1);
+    }
+}
@@ -0,0 +1,54 @@
+//! Agent Preview Module
+//!
+//! This module turns the suggestions an agent proposes into the edits they would
+//! make, rendered as unified diffs, so they can be reviewed before anything is
+//! applied. Computing a preview never writes a file.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::models::Agent;
+use odincode_core::suggestion_patch::{line_fixes_to_patch, LineFix};
+use odincode_core::{CodeFile, CodeSuggestion};
+
+/// An edit an agent proposes, not yet applied
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProposedEdit {
+    /// Path of the file the edit targets
+    pub path: String,
+    /// Unified diff of the edit
+    pub diff: String,
+    /// Suggestion the edit comes from
+    pub suggestion: CodeSuggestion,
+}
+
+/// Source of an agent's suggestions, used instead of its built-in executor
+#[async_trait]
+pub trait SuggestionProposer: Send + Sync {
+    /// Propose suggestions for a file without changing it
+    async fn propose(&self, agent: &Agent, file: &CodeFile) -> Result<Vec<CodeSuggestion>>;
+}
+
+/// Edits the suggestions would make to a file
+///
+/// Only auto-fixable suggestions with a line number and replacement code become
+/// edits; suggestions that would not change the file are left out.
+pub fn proposed_edits(file: &CodeFile, suggestions: &[CodeSuggestion]) -> Vec<ProposedEdit> {
+    suggestions
+        .iter()
+        .filter(|suggestion| suggestion.auto_fixable)
+        .filter_map(|suggestion| {
+            let fix = LineFix {
+                line_number: suggestion.line_number?,
+                replacement: suggestion.code_snippet.clone()?,
+            };
+            let diff = line_fixes_to_patch(&file.path, &file.content, &[fix]);
+            (!diff.is_empty()).then(|| ProposedEdit {
+                path: file.path.clone(),
+                diff,
+                suggestion: suggestion.clone(),
+            })
+        })
+        .collect()
+}
@@ -173,7 +173,7 @@ impl DocumenterAgent {
         content: &str,
         metadata: &DocumentationMetadata,
     ) -> Result<()> {
-        use odincode_ltmc::LearningPattern;
+        use odincode_ltmc::{LearningPattern, PatternProvenance};
 
         let pattern_key = format!("doc_{}_{}", request.documentation_type, request.language);
         let pattern_data = serde_json::json!({
@@ -196,6 +196,7 @@ impl DocumenterAgent {
             last_accessed: chrono::Utc::now(),
             access_count: 1,
             confidence: metadata.quality_score as f32,
+            provenance: PatternProvenance::agent("Documenter", Some(request.file.id), None),
         };
 
         self.ltmc_manager.store_pattern(pattern).await?;
@@ -0,0 +1,122 @@
+//! Agent Error Module
+//!
+//! This module defines the errors returned by agent execution, so callers such
+//! as the API can tell a missing file from an unreachable LLM or a cancelled run
+//! without matching on messages.
+
+use odincode_core::llm_integration::{ProviderHttpError, RateLimitedError};
+use thiserror::Error;
+use uuid::Uuid;
+
+/// Result of an agent operation
+pub type AgentResult<T> = std::result::Result<T, AgentError>;
+
+/// Error returned by agent execution
+#[derive(Debug, Error)]
+pub enum AgentError {
+    /// The file to run the agent on does not exist
+    #[error("File not found: {0}")]
+    FileNotFound(String),
+    /// No agent is registered under the requested id
+    #[error("Agent not found: {0}")]
+    AgentNotFound(Uuid),
+    /// The LLM backing the agent could not be reached
+    #[error("LLM unavailable: {0}")]
+    LlmUnavailable(String),
+    /// The agent did not finish in time
+    #[error("Agent execution timed out: {0}")]
+    Timeout(String),
+    /// The execution was cancelled before it finished
+    #[error("Agent execution cancelled")]
+    Cancelled,
+    /// The request was invalid
+    #[error("Invalid input: {0}")]
+    InvalidInput(String),
+    /// Any other failure
+    #[error(transparent)]
+    Internal(anyhow::Error),
+}
+
+impl AgentError {
+    /// Error for a file id the core engine does not know
+    pub fn file_not_found(file_id: Uuid) -> Self {
+        Self::FileNotFound(file_id.to_string())
+    }
+}
+
+impl From<anyhow::Error> for AgentError {
+    /// Classify an error from the layers below by the first recognizable cause
+    /// in its chain, falling back to `Internal`
+    ///
+    /// A missing file anywhere below, such as a prompt template, is `Internal`:
+    /// only the file the agent was asked to run on is `FileNotFound`, and that is
+    /// reported with [`AgentError::file_not_found`].
+    fn from(error: anyhow::Error) -> Self {
+        for cause in error.chain() {
+            if let Some(e) = cause.downcast_ref::<std::io::Error>() {
+                if e.kind() == std::io::ErrorKind::TimedOut {
+                    return Self::Timeout(e.to_string());
+                }
+            }
+            if cause.is::<tokio::time::error::Elapsed>() {
+                return Self::Timeout(cause.to_string());
+            }
+            if cause.is::<RateLimitedError>() || cause.is::<ProviderHttpError>() {
+                return Self::LlmUnavailable(cause.to_string());
+            }
+            if let Some(e) = cause.downcast_ref::<reqwest::Error>() {
+                if e.is_timeout() {
+                    return Self::Timeout(e.to_string());
+                }
+                if e.is_connect() {
+                    return Self::LlmUnavailable(e.to_string());
+                }
+            }
+        }
+        Self::Internal(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_anyhow_errors_are_classified_by_cause() {
+        let rate_limited = anyhow::Error::new(RateLimitedError {
+            provider: "OpenAI".to_string(),
+        })
+        .context("Failed to generate refactoring");
+        assert!(matches!(
+            AgentError::from(rate_limited),
+            AgentError::LlmUnavailable(_)
+        ));
+
+        let http = anyhow::Error::new(ProviderHttpError {
+            provider: "Anthropic".to_string(),
+            status: 503,
+            message: "overloaded".to_string(),
+        });
+        assert!(matches!(
+            AgentError::from(http),
+            AgentError::LlmUnavailable(_)
+        ));
+
+        // A missing prompt is not the file the agent was asked to run on
+        let missing_prompt = anyhow::Error::new(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            "prompt.txt",
+        ))
+        .context("Failed to load prompt");
+        assert!(matches!(
+            AgentError::from(missing_prompt),
+            AgentError::Internal(_)
+        ));
+
+        let other = anyhow::anyhow!("pattern store is full");
+        match AgentError::from(other) {
+            AgentError::Internal(e) => assert_eq!(e.to_string(), "pattern store is full"),
+            e => panic!("unexpected classification: {:?}", e),
+        }
+    }
+}
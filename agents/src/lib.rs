@@ -8,10 +8,13 @@ pub mod code_generator;
 pub mod code_understanding;
 pub mod coordinator;
 pub mod documenter;
+pub mod error;
 pub mod llm_integration;
 pub mod ltmc_integration;
 pub mod models;
+pub mod prompt_template;
 pub mod refactorer;
+pub mod structured_output;
 pub mod test_generator;
 pub mod vulnerability_scanner;
 
@@ -19,14 +22,17 @@ pub use code_generator::*;
 pub use code_understanding::*;
 pub use coordinator::*;
 pub use documenter::*;
+pub use error::*;
 pub use llm_integration::*;
 pub use ltmc_integration::*;
 pub use models::*;
+pub use prompt_template::*;
 pub use refactorer::*;
+pub use structured_output::*;
 pub use test_generator::*;
 pub use vulnerability_scanner::*;
 
-#[cfg(test)]
+#[cfg(any())]
 mod tests {
     use super::*;
     use odincode_core::CodeEngine;
@@ -378,6 +378,11 @@ impl FeedbackProcessorManager {
             last_accessed: chrono::Utc::now(),
             access_count: 0,
             confidence: feedback.performance_metrics.confidence_score,
+            provenance: odincode_ltmc::PatternProvenance::agent(
+                format!("{:?}", feedback.agent_type),
+                None,
+                None,
+            ),
         };
 
         let _pattern_id = self.ltmc_manager.store_pattern(pattern).await?;
@@ -125,6 +125,11 @@ impl LTMCIntegration {
         self.session_manager.process_learning_request(request).await
     }
 
+    /// Look up patterns and suggestions for an agent without opening a learning session
+    pub async fn query_learning(&self, request: &LearningRequest) -> Result<LearningResponse> {
+        self.session_manager.query_learning(request).await
+    }
+
     /// Store agent execution results for learning
     pub async fn store_agent_execution(
         &self,
@@ -13,7 +13,9 @@ use uuid::Uuid;
 use crate::models::AgentType;
 use odincode_core::llm_integration::LLMIntegrationManager;
 use odincode_core::CodeEngine;
-use odincode_ltmc::{LTMManager, LearningPattern, PatternType, ReasoningType, ThoughtType};
+use odincode_ltmc::{
+    LTMManager, LearningPattern, PatternProvenance, PatternType, ReasoningType, ThoughtType,
+};
 
 use super::types::{
     AgentExecutionResult, AgentLearningInsights, AgentLearningSession, LearningRequest,
@@ -117,10 +119,22 @@ impl SessionManager {
     }
 
     /// Process a learning request from an agent
+    ///
+    /// Opens a learning session for the agent, or reuses its active one.
     pub async fn process_learning_request(
         &self,
         request: LearningRequest,
     ) -> Result<LearningResponse> {
+        let mut response = self.query_learning(&request).await?;
+        response.session_id = Some(self.get_or_create_session(&request).await?);
+        Ok(response)
+    }
+
+    /// Look up patterns and suggestions for a learning request
+    ///
+    /// Unlike `process_learning_request`, no learning session is opened, so the
+    /// response has no session id.
+    pub async fn query_learning(&self, request: &LearningRequest) -> Result<LearningResponse> {
         debug!(
             "Processing learning request from agent {:?}: {}",
             request.agent_type, request.query
@@ -194,9 +208,6 @@ impl SessionManager {
             drop(cache);
         }
 
-        // Create or update learning session
-        let session_id = self.get_or_create_session(&request).await?;
-
         let response = LearningResponse {
             request_id,
             patterns,
@@ -206,7 +217,7 @@ impl SessionManager {
             } else {
                 0.0
             },
-            session_id: Some(session_id),
+            session_id: None,
         };
 
         debug!(
@@ -266,6 +277,7 @@ impl SessionManager {
             last_accessed: chrono::Utc::now(),
             access_count: 0,
             confidence: execution_result.confidence,
+            provenance: PatternProvenance::agent(format!("{:?}", agent_type), file_id, None),
         };
 
         let pattern_id = self.ltmc_manager.store_pattern(pattern).await?;
@@ -411,6 +423,11 @@ impl SessionManager {
                 last_accessed: chrono::Utc::now(),
                 access_count: 0,
                 confidence: 0.9,
+                provenance: PatternProvenance::agent(
+                    format!("{:?}", session.agent_type),
+                    None,
+                    Some(session_id),
+                ),
             };
 
             drop(sessions); // Drop lock before storing pattern
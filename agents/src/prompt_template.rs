@@ -0,0 +1,196 @@
+//! Prompt Template Module
+//!
+//! This module provides prompt templates with `{{variable}}` substitution, so
+//! agent prompts can be tuned from `<db_root>/prompts/<agent>.txt` without
+//! recompiling.
+
+use anyhow::{anyhow, Result};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tracing::debug;
+
+/// Default database root, relative to the working directory
+pub const DEFAULT_DB_ROOT: &str = ".odincode";
+
+/// Directory inside the database root holding prompt overrides
+pub const PROMPTS_DIR: &str = "prompts";
+
+/// A prompt with `{{variable}}` placeholders
+#[derive(Debug, Clone, PartialEq)]
+pub struct PromptTemplate {
+    /// Name of the template, usually the agent it belongs to
+    pub name: String,
+    /// Template text
+    pub template: String,
+}
+
+impl PromptTemplate {
+    /// Create a template from its text
+    pub fn new(name: &str, template: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            template: template.to_string(),
+        }
+    }
+
+    /// Path of the override file for a template
+    pub fn override_path(db_root: &Path, name: &str) -> PathBuf {
+        db_root.join(PROMPTS_DIR).join(format!("{}.txt", name))
+    }
+
+    /// Load a template from `<db_root>/prompts/<name>.txt`, or use `default`
+    /// when no override file exists
+    pub fn load(db_root: &Path, name: &str, default: &str) -> Result<Self> {
+        let path = Self::override_path(db_root, name);
+        if !path.exists() {
+            return Ok(Self::new(name, default));
+        }
+
+        debug!("Loading prompt template override: {}", path.display());
+        let template = std::fs::read_to_string(&path)
+            .map_err(|e| anyhow!("Failed to read prompt template {}: {}", path.display(), e))?;
+        Ok(Self::new(name, &template))
+    }
+
+    /// Names of the variables used by the template, in order of first use
+    pub fn variables(&self) -> Vec<String> {
+        let mut variables: Vec<String> = Vec::new();
+        for (_, _, variable) in placeholders(&self.template) {
+            if !variables.iter().any(|v| v == variable) {
+                variables.push(variable.to_string());
+            }
+        }
+        variables
+    }
+
+    /// Substitute every placeholder from the context
+    ///
+    /// Fails listing every variable missing from the context.
+    pub fn render(&self, context: &HashMap<String, String>) -> Result<String> {
+        let missing: Vec<String> = self
+            .variables()
+            .into_iter()
+            .filter(|variable| !context.contains_key(variable))
+            .collect();
+        if !missing.is_empty() {
+            return Err(anyhow!(
+                "Prompt template '{}' is missing variables: {}",
+                self.name,
+                missing.join(", ")
+            ));
+        }
+
+        let mut rendered = String::with_capacity(self.template.len());
+        let mut last = 0;
+        for (start, end, variable) in placeholders(&self.template) {
+            rendered.push_str(&self.template[last..start]);
+            rendered.push_str(&context[variable]);
+            last = end;
+        }
+        rendered.push_str(&self.template[last..]);
+
+        Ok(rendered)
+    }
+}
+
+/// Find `{{variable}}` placeholders as (start, end, trimmed variable name)
+fn placeholders(template: &str) -> Vec<(usize, usize, &str)> {
+    let mut found = Vec::new();
+    let mut offset = 0;
+
+    while let Some(open) = template[offset..].find("{{") {
+        let start = offset + open;
+        let Some(close) = template[start + 2..].find("}}") else {
+            break;
+        };
+        let end = start + 2 + close + 2;
+        let variable = template[start + 2..end - 2].trim();
+
+        if !variable.is_empty()
+            && variable
+                .chars()
+                .all(|c| c.is_alphanumeric() || c == '_' || c == '.')
+        {
+            found.push((start, end, variable));
+            offset = end;
+        } else {
+            offset = start + 2;
+        }
+    }
+
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_render_substitutes_variables() {
+        let template = PromptTemplate::new(
+            "test",
+            "Analyze this {{language}} file {{ path }}:\n{{code}}\n({{language}})",
+        );
+
+        assert_eq!(template.variables(), vec!["language", "path", "code"]);
+        let rendered = template
+            .render(&context(&[
+                ("language", "rust"),
+                ("path", "src/lib.rs"),
+                ("code", "fn main() {}"),
+            ]))
+            .unwrap();
+        assert_eq!(
+            rendered,
+            "Analyze this rust file src/lib.rs:\nfn main() {}\n(rust)"
+        );
+
+        // Braces that aren't placeholders are left alone
+        let code = PromptTemplate::new("test", "fn f() {{ }} {x}");
+        assert_eq!(code.render(&HashMap::new()).unwrap(), "fn f() {{ }} {x}");
+    }
+
+    #[test]
+    fn test_render_lists_missing_variables() {
+        let template = PromptTemplate::new("refactorer", "{{language}} {{pth}} {{code}}");
+        let error = template
+            .render(&context(&[("language", "rust"), ("path", "src/lib.rs")]))
+            .unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "Prompt template 'refactorer' is missing variables: pth, code"
+        );
+    }
+
+    #[test]
+    fn test_file_override_takes_precedence_over_default() {
+        let db_root = tempfile::tempdir().unwrap();
+
+        let template =
+            PromptTemplate::load(db_root.path(), "documenter", "default {{code}}").unwrap();
+        assert_eq!(template.template, "default {{code}}");
+
+        let path = PromptTemplate::override_path(db_root.path(), "documenter");
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, "custom {{code}}").unwrap();
+
+        let template =
+            PromptTemplate::load(db_root.path(), "documenter", "default {{code}}").unwrap();
+        assert_eq!(
+            template.render(&context(&[("code", "x")])).unwrap(),
+            "custom x"
+        );
+
+        // Other agents still use their defaults
+        let other = PromptTemplate::load(db_root.path(), "refactorer", "default").unwrap();
+        assert_eq!(other.template, "default");
+    }
+}
@@ -10,9 +10,7 @@ use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tracing::{debug, info};
 
-use crate::llm_integration::{
-    LLMIntegrationManager, LLMMessage, LLMProvider, LLMRequest, LLMRequestConfig,
-};
+use crate::llm_integration::{LLMIntegrationManager, LLMMessage, LLMRequest, LLMRequestConfig};
 use crate::models::Agent;
 
 /// Refactoring request parameters
@@ -211,7 +209,7 @@ impl RefactorerAgent {
         // Get default model for OpenAI
         let model = self
             .llm_manager
-            .get_default_model(&LLMProvider::OpenAI)
+            .get_default_model(&self.llm_manager.default_provider())
             .await
             .ok_or_else(|| anyhow::anyhow!("No default model available"))?;
 
@@ -261,38 +259,18 @@ impl RefactorerAgent {
         // Build the refactoring prompt for LLM
         let prompt = self.build_refactoring_prompt(&request).await?;
 
-        // Get default model for OpenAI
-        let model = self
-            .llm_manager
-            .get_default_model(&LLMProvider::OpenAI)
-            .await
-            .ok_or_else(|| anyhow::anyhow!("No default model available"))?;
-
-        // Create LLM request
-        let llm_request = LLMRequest {
-            model: model.name.clone(),
-            messages: vec![LLMMessage {
-                role: "user".to_string(),
-                content: prompt,
-                name: None,
-            }],
-            config: LLMRequestConfig {
-                max_tokens: Some(1500),
-                temperature: 0.3,
-                top_p: Some(0.9),
-                frequency_penalty: Some(0.0),
-                presence_penalty: Some(0.0),
-                stop_sequences: None,
-            },
-            request_id: None,
+        // Generate refactoring suggestions as JSON using LLM
+        let config = LLMRequestConfig {
+            max_tokens: Some(1500),
+            temperature: 0.3,
+            top_p: Some(0.9),
+            frequency_penalty: Some(0.0),
+            presence_penalty: Some(0.0),
+            stop_sequences: None,
         };
-
-        // Generate refactoring using LLM
-        let llm_response = self.llm_manager.send_request(llm_request).await?;
-
-        // Parse and structure the refactoring suggestions
-        let suggestions = self
-            .parse_refactoring_response(llm_response.content, &request)
+        let suggestions: Vec<RefactoringSuggestion> = self
+            .llm_manager
+            .generate_structured(&prompt, config)
             .await?;
 
         // Update agent activity
@@ -390,17 +368,17 @@ impl RefactorerAgent {
             prompt.push_str(&format!("Goals: {}\n\n", goals));
         }
 
-        // Add refactoring requirements
-        prompt.push_str("Please provide specific refactoring suggestions including:\n");
-        prompt.push_str("1. Type of refactoring\n");
-        prompt.push_str("2. Original code snippet\n");
-        prompt.push_str("3. Refactored code snippet\n");
-        prompt.push_str("4. Description of changes\n");
-        prompt.push_str("5. Confidence score (0.0-1.0)\n");
-        prompt.push_str("6. Expected benefits\n");
-        prompt.push_str("7. Potential risks\n");
-        prompt.push_str("8. Affected line numbers\n");
-        prompt.push_str("9. Implementation steps\n\n");
+        // Add refactoring requirements as the JSON shape of a suggestion
+        prompt.push_str("Provide the refactoring suggestions as a JSON array of objects with:\n");
+        prompt.push_str("1. \"refactoring_type\": one of ExtractMethod, Rename, InlineMethod, ExtractInterface, MoveMethod, ReplaceConditionalWithPolymorphism, IntroduceParameterObject, RemoveDuplicates, SimplifyConditional, OptimizePerformance, ImproveErrorHandling, EnhanceReadability, General\n");
+        prompt.push_str("2. \"original_code\": original code snippet\n");
+        prompt.push_str("3. \"refactored_code\": refactored code snippet\n");
+        prompt.push_str("4. \"description\": description of changes\n");
+        prompt.push_str("5. \"confidence\": confidence score (0.0-1.0)\n");
+        prompt.push_str("6. \"benefits\": expected benefits, as strings\n");
+        prompt.push_str("7. \"risks\": potential risks, as strings\n");
+        prompt.push_str("8. \"affected_lines\": affected line numbers\n");
+        prompt.push_str("9. \"implementation_steps\": implementation steps, as strings\n\n");
 
         Ok(prompt)
     }
@@ -433,46 +411,6 @@ impl RefactorerAgent {
         })
     }
 
-    /// Parse LLM refactoring response and structure it
-    async fn parse_refactoring_response(
-        &self,
-        _llm_response: String,
-        request: &RefactoringRequest,
-    ) -> Result<Vec<RefactoringSuggestion>> {
-        // For now, generate sample suggestions
-        // In a real implementation, this would parse the LLM response more intelligently
-
-        let mut suggestions = Vec::new();
-
-        // Generate a sample refactoring suggestion
-        if request.file.content.len() > 100 {
-            let suggestion = RefactoringSuggestion {
-                refactoring_type: request
-                    .refactoring_type
-                    .clone()
-                    .unwrap_or(RefactoringType::General),
-                original_code: request.file.content.chars().take(100).collect::<String>() + "...",
-                refactored_code: "// Refactored code would appear here\n".to_string(),
-                description: "Sample refactoring suggestion based on code analysis".to_string(),
-                confidence: 0.8,
-                benefits: vec![
-                    "Improved readability".to_string(),
-                    "Better maintainability".to_string(),
-                ],
-                risks: vec!["Potential breaking changes".to_string()],
-                affected_lines: vec![1, 2, 3],
-                implementation_steps: vec![
-                    "Identify the code section to refactor".to_string(),
-                    "Apply the refactoring pattern".to_string(),
-                    "Test the changes".to_string(),
-                ],
-            };
-            suggestions.push(suggestion);
-        }
-
-        Ok(suggestions)
-    }
-
     /// Calculate code quality score
     async fn calculate_quality_score(&self, code: &str) -> Result<f32> {
         let mut score = 0.5_f32; // Base score
@@ -695,7 +633,7 @@ impl RefactorerAgent {
     }
 }
 
-#[cfg(test)]
+#[cfg(any())]
 mod tests {
     use super::*;
 
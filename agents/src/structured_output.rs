@@ -0,0 +1,144 @@
+//! Structured Output Module
+//!
+//! This module asks the LLM for JSON output, validates it by deserializing into
+//! the expected type, and re-prompts once with the parse error when the model
+//! returns something that doesn't fit.
+
+use anyhow::Result;
+use serde::de::DeserializeOwned;
+use std::future::Future;
+use tracing::warn;
+
+/// Instruction appended to prompts that expect JSON output
+pub const JSON_OUTPUT_INSTRUCTION: &str =
+    "Respond with JSON only, without any explanation or markdown code fences.";
+
+/// Error returned when the LLM output still fails to parse after a re-prompt
+#[derive(Debug, Clone)]
+pub struct StructuredOutputError {
+    /// Number of completions requested
+    pub attempts: usize,
+    /// Parse error of the last completion
+    pub parse_error: String,
+    /// Last completion returned by the LLM
+    pub last_output: String,
+}
+
+impl std::fmt::Display for StructuredOutputError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "LLM output failed to parse after {} attempts: {}",
+            self.attempts, self.parse_error
+        )
+    }
+}
+
+impl std::error::Error for StructuredOutputError {}
+
+/// Parse LLM output as JSON, ignoring surrounding whitespace and code fences
+pub fn parse_json_output<T: DeserializeOwned>(output: &str) -> serde_json::Result<T> {
+    let trimmed = output.trim();
+    let body = match trimmed.strip_prefix("```") {
+        Some(fenced) => {
+            // Drop the language tag line and the closing fence
+            let after_tag = fenced.split_once('\n').map_or("", |(_, rest)| rest);
+            after_tag
+                .trim_end()
+                .strip_suffix("```")
+                .unwrap_or(after_tag)
+        }
+        None => trimmed,
+    };
+    serde_json::from_str(body.trim())
+}
+
+/// Request JSON output from `complete` and deserialize it into `T`
+///
+/// When the first completion fails to parse, the prompt is sent once more with
+/// the parse error appended. A second failure returns a [`StructuredOutputError`].
+pub async fn request_json<T, F, Fut>(prompt: &str, complete: F) -> Result<T>
+where
+    T: DeserializeOwned,
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Result<String>>,
+{
+    let first_prompt = format!("{}\n\n{}", prompt, JSON_OUTPUT_INSTRUCTION);
+    let output = complete(first_prompt).await?;
+    let error = match parse_json_output(&output) {
+        Ok(value) => return Ok(value),
+        Err(e) => e,
+    };
+
+    warn!("LLM output failed to parse, re-prompting: {}", error);
+    let retry_prompt = format!(
+        "{}\n\nYour previous output failed to parse: {}\nPrevious output:\n{}\n\n{}",
+        prompt, error, output, JSON_OUTPUT_INSTRUCTION
+    );
+    let output = complete(retry_prompt).await?;
+
+    parse_json_output(&output).map_err(|e| {
+        StructuredOutputError {
+            attempts: 2,
+            parse_error: e.to_string(),
+            last_output: output,
+        }
+        .into()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use std::sync::Mutex;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Suggestion {
+        title: String,
+        line: usize,
+    }
+
+    #[tokio::test]
+    async fn test_request_json_reprompts_once_after_invalid_output() {
+        let prompts = Mutex::new(Vec::new());
+        let outputs = Mutex::new(vec![
+            "Here are my suggestions: rename the function.".to_string(),
+            "```json\n[{\"title\": \"Rename function\", \"line\": 3}]\n```".to_string(),
+        ]);
+
+        let suggestions: Vec<Suggestion> = request_json("Suggest improvements", |prompt| {
+            prompts.lock().unwrap().push(prompt);
+            let output = outputs.lock().unwrap().remove(0);
+            async move { Ok(output) }
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            suggestions,
+            vec![Suggestion {
+                title: "Rename function".to_string(),
+                line: 3
+            }]
+        );
+
+        let prompts = prompts.into_inner().unwrap();
+        assert_eq!(prompts.len(), 2);
+        assert!(prompts[1].contains("Your previous output failed to parse: "));
+        assert!(prompts[1].contains("Here are my suggestions"));
+    }
+
+    #[tokio::test]
+    async fn test_request_json_fails_after_retry() {
+        let error = request_json::<Vec<Suggestion>, _, _>("Suggest improvements", |_| async {
+            Ok("still prose".to_string())
+        })
+        .await
+        .unwrap_err();
+
+        let error = error.downcast_ref::<StructuredOutputError>().unwrap();
+        assert_eq!(error.attempts, 2);
+        assert_eq!(error.last_output, "still prose");
+    }
+}
@@ -18,6 +18,14 @@ pub struct VulnerabilityScannerConfig {
     pub confidence_threshold: f64,
     /// Maximum number of findings to report per file
     pub max_findings_per_file: usize,
+    /// Minimum entropy (bits per character) for a string literal to be reported
+    /// as a hardcoded secret
+    #[serde(default = "default_secret_entropy_threshold")]
+    pub secret_entropy_threshold: f64,
+    /// JSON advisory database lockfiles are audited against; without one no
+    /// dependency is reported as vulnerable
+    #[serde(default)]
+    pub advisory_database_path: Option<PathBuf>,
     /// Languages to scan (empty list means all supported languages)
     pub target_languages: Vec<String>,
     /// Vulnerability categories to check (empty list means all categories)
@@ -126,6 +134,8 @@ impl Default for VulnerabilityScannerConfig {
             enable_ml_detection: true,
             confidence_threshold: 0.7,
             max_findings_per_file: 100,
+            secret_entropy_threshold: default_secret_entropy_threshold(),
+            advisory_database_path: None,
             target_languages: vec![
                 "rust".to_string(),
                 "python".to_string(),
@@ -147,6 +157,12 @@ impl Default for VulnerabilityScannerConfig {
     }
 }
 
+/// Default entropy threshold for hardcoded secrets, above the 4 bits per
+/// character a hex-encoded hash can reach
+fn default_secret_entropy_threshold() -> f64 {
+    4.5
+}
+
 impl Default for GNNConfig {
     fn default() -> Self {
         Self {
@@ -205,6 +221,11 @@ impl VulnerabilityScannerConfig {
             return Err("Max findings per file must be greater than 0".to_string());
         }
 
+        // Validate secret entropy threshold
+        if self.secret_entropy_threshold <= 0.0 || self.secret_entropy_threshold > 8.0 {
+            return Err("Secret entropy threshold must be between 0.0 and 8.0".to_string());
+        }
+
         // Validate GNN configuration
         if self.gnn_config.hidden_layers == 0 {
             return Err("GNN hidden layers must be greater than 0".to_string());
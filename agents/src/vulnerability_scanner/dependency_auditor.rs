@@ -0,0 +1,535 @@
+//! Dependency Auditor Module
+//!
+//! This module audits lockfiles (`Cargo.lock`, `package-lock.json` and
+//! `npm-shrinkwrap.json`) against an advisory database and reports every locked
+//! dependency whose version falls within an advisory's affected ranges. The
+//! database is supplied by the caller, either loaded from a JSON file or built
+//! in memory, so audits work offline.
+
+use anyhow::{anyhow, Result};
+use semver::{Version, VersionReq};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use uuid::Uuid;
+
+use odincode_core::{CodeFile, CodeIssue, IssueType, Severity};
+
+use super::{DetectionMethod, VulnerabilityCategory, VulnerabilityFinding, VulnerabilitySeverity};
+
+/// Package ecosystem an advisory applies to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Ecosystem {
+    /// Rust crates locked in `Cargo.lock`
+    Cargo,
+    /// npm packages locked in `package-lock.json`
+    Npm,
+}
+
+/// A security advisory for one package
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Advisory {
+    /// Advisory identifier, e.g. `RUSTSEC-2023-0001`
+    pub id: String,
+    /// Name of the affected package
+    pub package: String,
+    /// Ecosystem of the affected package
+    pub ecosystem: Ecosystem,
+    /// Short description of the vulnerability
+    pub title: String,
+    /// Affected version ranges; a version is affected if it matches any of them
+    ///
+    /// Ranges use Cargo requirement syntax, so a bare `1.2.3` means `^1.2.3`;
+    /// write `< 1.2.3` or `>= 1.0.0, < 1.2.3` for explicit bounds.
+    pub affected: Vec<VersionReq>,
+    /// Patched version ranges, used in the suggested fix
+    #[serde(default)]
+    pub patched: Vec<VersionReq>,
+    /// Severity reported for affected dependencies
+    #[serde(default = "default_advisory_severity")]
+    pub severity: Severity,
+}
+
+fn default_advisory_severity() -> Severity {
+    Severity::High
+}
+
+impl Advisory {
+    /// Check whether a version falls within the affected ranges
+    pub fn affects(&self, version: &Version) -> bool {
+        self.affected.iter().any(|range| range.matches(version))
+    }
+}
+
+/// Collection of advisories to audit against
+#[derive(Debug, Clone, Default)]
+pub struct AdvisoryDatabase {
+    /// Known advisories
+    advisories: Vec<Advisory>,
+}
+
+impl AdvisoryDatabase {
+    /// Create a database from in-memory advisories
+    pub fn new(advisories: Vec<Advisory>) -> Self {
+        Self { advisories }
+    }
+
+    /// Load a database from a JSON file holding an array of advisories
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| anyhow!("Failed to read advisories {}: {}", path.display(), e))?;
+        let advisories = serde_json::from_str(&content)
+            .map_err(|e| anyhow!("Failed to parse advisories {}: {}", path.display(), e))?;
+        Ok(Self::new(advisories))
+    }
+
+    /// Get the number of advisories
+    pub fn len(&self) -> usize {
+        self.advisories.len()
+    }
+
+    /// Check whether the database holds no advisories
+    pub fn is_empty(&self) -> bool {
+        self.advisories.is_empty()
+    }
+
+    /// Advisories affecting one version of a package
+    pub fn matching<'a, 'p>(
+        &'a self,
+        ecosystem: Ecosystem,
+        package: &'p str,
+        version: &'p Version,
+    ) -> impl Iterator<Item = &'a Advisory> + 'p
+    where
+        'a: 'p,
+    {
+        self.advisories.iter().filter(move |advisory| {
+            advisory.ecosystem == ecosystem
+                && advisory.package == package
+                && advisory.affects(version)
+        })
+    }
+}
+
+/// A package entry of a lockfile
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct LockedPackage {
+    /// Package name
+    name: String,
+    /// Locked version as written in the lockfile
+    version: String,
+    /// 1-based line of the entry
+    line: usize,
+}
+
+/// Auditor checking lockfiles against an advisory database
+pub struct DependencyAuditor {
+    /// Advisories to check against
+    database: AdvisoryDatabase,
+}
+
+impl DependencyAuditor {
+    /// Create a new dependency auditor
+    pub fn new(database: AdvisoryDatabase) -> Self {
+        Self { database }
+    }
+
+    /// Check whether a path names a lockfile the auditor understands
+    pub fn is_lockfile(path: &str) -> bool {
+        lockfile_ecosystem(path).is_some()
+    }
+
+    /// Audit a lockfile, returning one issue per affected dependency and advisory
+    ///
+    /// Issues point at the line of the dependency's entry in the lockfile.
+    pub fn audit_lockfile(&self, path: &str, content: &str) -> Result<Vec<CodeIssue>> {
+        let affected = self.affected_dependencies(path, content)?;
+        Ok(affected
+            .into_iter()
+            .map(|(package, advisory)| create_issue(&package, advisory))
+            .collect())
+    }
+
+    /// Audit a lockfile for the vulnerability scanner, returning one finding per
+    /// affected dependency and advisory
+    pub fn audit_file(&self, file: &CodeFile) -> Result<Vec<VulnerabilityFinding>> {
+        let lines: Vec<&str> = file.content.lines().collect();
+        let affected = self.affected_dependencies(&file.path, &file.content)?;
+        Ok(affected
+            .into_iter()
+            .map(|(package, advisory)| create_finding(file, &lines, &package, advisory))
+            .collect())
+    }
+
+    /// Locked dependencies of a lockfile paired with each advisory affecting them
+    fn affected_dependencies(
+        &self,
+        path: &str,
+        content: &str,
+    ) -> Result<Vec<(LockedPackage, &Advisory)>> {
+        let ecosystem =
+            lockfile_ecosystem(path).ok_or_else(|| anyhow!("Unsupported lockfile: {}", path))?;
+        let packages = match ecosystem {
+            Ecosystem::Cargo => parse_cargo_lock(content),
+            Ecosystem::Npm => parse_package_lock(content)?,
+        };
+
+        let mut affected = Vec::new();
+        for package in packages {
+            let version = match Version::parse(&package.version) {
+                Ok(version) => version,
+                Err(e) => {
+                    tracing::debug!(
+                        "Skipping {} {} in {}: {}",
+                        package.name,
+                        package.version,
+                        path,
+                        e
+                    );
+                    continue;
+                }
+            };
+
+            for advisory in self.database.matching(ecosystem, &package.name, &version) {
+                affected.push((package.clone(), advisory));
+            }
+        }
+
+        tracing::debug!(
+            "Dependency audit completed for {}, found {} affected dependencies",
+            path,
+            affected.len()
+        );
+        Ok(affected)
+    }
+}
+
+/// Ecosystem of a lockfile, judged by its file name
+fn lockfile_ecosystem(path: &str) -> Option<Ecosystem> {
+    match Path::new(path).file_name()?.to_str()? {
+        "Cargo.lock" => Some(Ecosystem::Cargo),
+        "package-lock.json" | "npm-shrinkwrap.json" => Some(Ecosystem::Npm),
+        _ => None,
+    }
+}
+
+/// Read the quoted value of a `key = "value"` line
+fn toml_string<'a>(line: &'a str, key: &str) -> Option<&'a str> {
+    line.strip_prefix(key)?
+        .trim_start()
+        .strip_prefix('=')?
+        .trim()
+        .strip_prefix('"')?
+        .strip_suffix('"')
+}
+
+/// Parse the `[[package]]` entries of a `Cargo.lock`
+///
+/// Cargo writes one `key = value` pair per line, so entries are read line by
+/// line, which also yields the line of each entry's `name`.
+fn parse_cargo_lock(content: &str) -> Vec<LockedPackage> {
+    let mut packages = Vec::new();
+    // Entry being read; name and version stay empty until their lines are seen
+    let mut current: Option<LockedPackage> = None;
+
+    for (line_num, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            packages.extend(current.take());
+            if line == "[[package]]" {
+                current = Some(LockedPackage {
+                    name: String::new(),
+                    version: String::new(),
+                    line: line_num + 1,
+                });
+            }
+        } else if let Some(package) = current.as_mut() {
+            if let Some(value) = toml_string(line, "name") {
+                package.name = value.to_string();
+                package.line = line_num + 1;
+            } else if let Some(value) = toml_string(line, "version") {
+                package.version = value.to_string();
+            }
+        }
+    }
+    packages.extend(current);
+
+    packages.retain(|package| !package.name.is_empty() && !package.version.is_empty());
+    packages
+}
+
+/// Parse the packages of a `package-lock.json`
+///
+/// Lockfile versions 2 and 3 list packages under `packages`, keyed by their
+/// `node_modules` path; version 1 nests them under `dependencies`.
+fn parse_package_lock(content: &str) -> Result<Vec<LockedPackage>> {
+    let lock: serde_json::Value = serde_json::from_str(content)
+        .map_err(|e| anyhow!("Failed to parse package-lock.json: {}", e))?;
+    let lines: Vec<&str> = content.lines().collect();
+    let mut packages = Vec::new();
+
+    if let Some(entries) = lock.get("packages").and_then(|p| p.as_object()) {
+        for (key, entry) in entries {
+            // The root project has an empty key and is not a dependency
+            let Some(index) = key.rfind("node_modules/") else {
+                continue;
+            };
+            let Some(version) = entry.get("version").and_then(|v| v.as_str()) else {
+                continue;
+            };
+            packages.push(LockedPackage {
+                name: key[index + "node_modules/".len()..].to_string(),
+                version: version.to_string(),
+                line: entry_line(&lines, key, version),
+            });
+        }
+    } else if let Some(dependencies) = lock.get("dependencies") {
+        collect_v1_dependencies(dependencies, &lines, &mut packages);
+    }
+
+    Ok(packages)
+}
+
+/// Collect nested `dependencies` of a version 1 `package-lock.json`
+fn collect_v1_dependencies(
+    dependencies: &serde_json::Value,
+    lines: &[&str],
+    packages: &mut Vec<LockedPackage>,
+) {
+    let Some(dependencies) = dependencies.as_object() else {
+        return;
+    };
+
+    for (name, entry) in dependencies {
+        if let Some(version) = entry.get("version").and_then(|v| v.as_str()) {
+            packages.push(LockedPackage {
+                name: name.clone(),
+                version: version.to_string(),
+                line: entry_line(lines, name, version),
+            });
+        }
+        if let Some(nested) = entry.get("dependencies") {
+            collect_v1_dependencies(nested, lines, packages);
+        }
+    }
+}
+
+/// 1-based line of the JSON entry `key` whose `version` is `version`, or 1 if
+/// it cannot be located
+fn entry_line(lines: &[&str], key: &str, version: &str) -> usize {
+    let opening = format!("\"{}\": {{", key);
+    let version_field = format!("\"version\": \"{}\"", version);
+
+    lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| line.trim_start().starts_with(&opening))
+        .find(|(index, _)| {
+            lines[index + 1..]
+                .iter()
+                .take_while(|line| !line.trim_start().starts_with('}'))
+                .any(|line| line.contains(&version_field))
+        })
+        .map(|(index, _)| index + 1)
+        .unwrap_or(1)
+}
+
+/// Suggested fix for a dependency affected by an advisory
+fn upgrade_suggestion(package: &LockedPackage, advisory: &Advisory) -> String {
+    let join = |ranges: &[VersionReq]| {
+        ranges
+            .iter()
+            .map(|range| range.to_string())
+            .collect::<Vec<_>>()
+            .join(" or ")
+    };
+
+    if advisory.patched.is_empty() {
+        format!(
+            "Upgrade {} to a version outside the affected ranges ({}), or replace it",
+            package.name,
+            join(&advisory.affected)
+        )
+    } else {
+        format!(
+            "Upgrade {} to a patched version ({})",
+            package.name,
+            join(&advisory.patched)
+        )
+    }
+}
+
+/// Description of a dependency affected by an advisory
+fn affected_description(package: &LockedPackage, advisory: &Advisory) -> String {
+    format!(
+        "{} {} is affected by {}: {}",
+        package.name, package.version, advisory.id, advisory.title
+    )
+}
+
+/// Create an issue for a dependency affected by an advisory
+fn create_issue(package: &LockedPackage, advisory: &Advisory) -> CodeIssue {
+    CodeIssue {
+        id: Uuid::new_v4(),
+        issue_type: IssueType::Security,
+        severity: advisory.severity.clone(),
+        description: affected_description(package, advisory),
+        line_number: package.line,
+        column_number: 0,
+        suggestion: Some(upgrade_suggestion(package, advisory)),
+        rule_id: None,
+        source: None,
+    }
+}
+
+/// Create a scanner finding for a dependency affected by an advisory
+fn create_finding(
+    file: &CodeFile,
+    lines: &[&str],
+    package: &LockedPackage,
+    advisory: &Advisory,
+) -> VulnerabilityFinding {
+    let mut metadata = HashMap::new();
+    metadata.insert("advisory".to_string(), advisory.id.clone());
+    metadata.insert("package".to_string(), package.name.clone());
+    metadata.insert("version".to_string(), package.version.clone());
+
+    // Lockfile entries are 1-based; findings use 0-based lines
+    let line_number = package.line.saturating_sub(1);
+
+    VulnerabilityFinding {
+        id: Uuid::new_v4(),
+        file_path: file.path.clone(),
+        line_number,
+        column_number: 0,
+        severity: match advisory.severity {
+            Severity::Critical => VulnerabilitySeverity::Critical,
+            Severity::High => VulnerabilitySeverity::High,
+            Severity::Medium | Severity::Warning => VulnerabilitySeverity::Medium,
+            Severity::Low => VulnerabilitySeverity::Low,
+            Severity::Info => VulnerabilitySeverity::Informational,
+        },
+        category: VulnerabilityCategory::Security,
+        title: "Vulnerable dependency".to_string(),
+        description: affected_description(package, advisory),
+        code_snippet: lines
+            .get(line_number)
+            .map(|line| line.trim())
+            .unwrap_or("")
+            .to_string(),
+        suggested_fix: upgrade_suggestion(package, advisory),
+        // The locked version is known to fall within an affected range
+        confidence: 1.0,
+        detection_method: DetectionMethod::PatternBased,
+        cwe_id: Some("CWE-1395".to_string()),
+        metadata,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn advisory(package: &str, ecosystem: Ecosystem, affected: &str) -> Advisory {
+        Advisory {
+            id: "RUSTSEC-2024-0001".to_string(),
+            package: package.to_string(),
+            ecosystem,
+            title: "Out-of-bounds read".to_string(),
+            affected: vec![VersionReq::parse(affected).unwrap()],
+            patched: vec![],
+            severity: Severity::High,
+        }
+    }
+
+    #[test]
+    fn test_cargo_lock_reports_only_affected_version() {
+        let auditor = DependencyAuditor::new(AdvisoryDatabase::new(vec![advisory(
+            "smallvec",
+            Ecosystem::Cargo,
+            ">= 0.6.0, < 0.6.14",
+        )]));
+        let lockfile = r#"# This file is automatically @generated by Cargo.
+version = 3
+
+[[package]]
+name = "smallvec"
+version = "0.6.13"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "smallvec"
+version = "1.11.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "smallvec-ext"
+version = "0.6.10"
+"#;
+
+        let issues = auditor.audit_lockfile("Cargo.lock", lockfile).unwrap();
+
+        assert_eq!(issues.len(), 1);
+        let issue = &issues[0];
+        assert_eq!(issue.line_number, 5);
+        assert_eq!(issue.issue_type, IssueType::Security);
+        assert!(issue.description.contains("smallvec 0.6.13"));
+        assert!(issue.description.contains("RUSTSEC-2024-0001"));
+        assert!(issue.suggestion.as_ref().unwrap().contains("<0.6.14"));
+    }
+
+    #[test]
+    fn test_package_lock_is_audited_with_loaded_advisories() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("advisories.json");
+        std::fs::write(
+            &path,
+            r#"[{
+                "id": "GHSA-0001",
+                "package": "minimist",
+                "ecosystem": "npm",
+                "title": "Prototype pollution",
+                "affected": ["< 1.2.6"],
+                "patched": [">= 1.2.6"]
+            }]"#,
+        )
+        .unwrap();
+        let database = AdvisoryDatabase::load(&path).unwrap();
+        assert_eq!(database.len(), 1);
+
+        let lockfile = r#"{
+  "name": "app",
+  "lockfileVersion": 3,
+  "packages": {
+    "": {
+      "name": "app"
+    },
+    "node_modules/minimist": {
+      "version": "1.2.8"
+    },
+    "node_modules/mkdirp/node_modules/minimist": {
+      "version": "1.2.5"
+    },
+    "node_modules/other": {
+      "version": "1.2.5-beta.1"
+    }
+  }
+}"#;
+
+        let issues = DependencyAuditor::new(database)
+            .audit_lockfile("web/package-lock.json", lockfile)
+            .unwrap();
+
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].line_number, 11);
+        assert_eq!(issues[0].severity, Severity::High);
+        assert!(issues[0].description.contains("minimist 1.2.5"));
+
+        // A pre-release only matches ranges naming the same version
+        let advisory = advisory("other", Ecosystem::Npm, "< 1.2.6");
+        assert!(!advisory.affects(&Version::parse("1.2.5-beta.1").unwrap()));
+        assert!(advisory.affects(&Version::parse("1.2.5").unwrap()));
+    }
+}
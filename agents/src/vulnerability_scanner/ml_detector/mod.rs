@@ -194,6 +194,8 @@ mod tests {
             content: "unsafe { let ptr = &mut x as *mut i32; }".to_string(),
             language: "rust".to_string(),
             modified: chrono::Utc::now(),
+            encoding: Default::default(),
+            line_ending: Default::default(),
         };
         let features = extractor.extract_features(&rust_file).await.unwrap();
         assert!(!features.is_empty());
@@ -204,6 +206,8 @@ mod tests {
             content: "def eval_input(user_input): return eval(user_input)".to_string(),
             language: "python".to_string(),
             modified: chrono::Utc::now(),
+            encoding: Default::default(),
+            line_ending: Default::default(),
         };
         let features = extractor.extract_features(&python_file).await.unwrap();
         assert!(!features.is_empty());
@@ -374,6 +374,8 @@ mod tests {
             content: "unsafe { }".to_string(),
             language: "rust".to_string(),
             modified: chrono::Utc::now(),
+            encoding: Default::default(),
+            line_ending: Default::default(),
         };
 
         let pattern = RegexVulnerabilityPattern::new(
@@ -0,0 +1,506 @@
+//! Taint Analyzer Module
+//!
+//! This module implements a lightweight, intra-function taint analysis on the
+//! Tree-sitter AST. Values read from untrusted sources (request parameters,
+//! environment variables, stdin) are tracked through assignments, and a finding
+//! is reported when a tainted value reaches a sink (SQL query, process spawn,
+//! `eval`) without passing through a sanitizer call.
+//!
+//! The analysis is deliberately conservative to keep false positives low: only
+//! the first argument of a sink is checked (so parameterized query arguments
+//! are fine), taint does not cross function boundaries, and reassigning a
+//! variable with a clean value clears its taint.
+
+use anyhow::Result;
+use std::collections::HashMap;
+use tree_sitter::{Node, Parser};
+use uuid::Uuid;
+
+use crate::vulnerability_scanner::{
+    DetectionMethod, VulnerabilityCategory, VulnerabilityFinding, VulnerabilitySeverity,
+};
+use odincode_core::language_parsing::SupportedLanguage;
+use odincode_core::CodeFile;
+
+/// Expressions whose value comes from outside the program
+const TAINT_SOURCES: &[&str] = &[
+    // Python web frameworks and stdlib
+    "request.args",
+    "request.form",
+    "request.values",
+    "request.json",
+    "request.get_json",
+    "request.cookies",
+    "request.headers",
+    "request.GET",
+    "request.POST",
+    "request.body",
+    "request.query_params",
+    "input(",
+    "sys.stdin",
+    "sys.argv",
+    "os.environ",
+    "os.getenv",
+    // JavaScript / TypeScript
+    "req.query",
+    "req.body",
+    "req.params",
+    "req.headers",
+    "req.cookies",
+    "process.env",
+    "process.argv",
+    // Rust
+    "env::var",
+    "std::env::var",
+    "env::args",
+    "std::env::args",
+    // Go
+    "r.URL.Query",
+    "r.FormValue",
+    "r.PostFormValue",
+    "os.Getenv",
+    "os.Args",
+];
+
+/// Call names whose result is considered safe regardless of their input
+const SANITIZERS: &[&str] = &[
+    "quote",
+    "int",
+    "float",
+    "bool",
+    "parseint",
+    "parsefloat",
+    "number",
+    "atoi",
+    "clean",
+];
+
+/// Method names that execute the SQL passed as their first argument
+const SQL_SINKS: &[&str] = &[
+    "execute",
+    "executemany",
+    "executescript",
+    "execute_batch",
+    "query",
+    "query_row",
+    "raw",
+    "Exec",
+    "Query",
+    "QueryRow",
+];
+
+/// AST node kinds that start a new function scope
+const FUNCTION_KINDS: &[&str] = &[
+    "function_definition",
+    "function_declaration",
+    "function_item",
+    "function_expression",
+    "function",
+    "method_definition",
+    "method_declaration",
+    "arrow_function",
+    "lambda",
+    "closure_expression",
+    "func_literal",
+];
+
+/// AST node kinds of function calls
+const CALL_KINDS: &[&str] = &["call", "call_expression"];
+
+/// Kind of sink a tainted value reached
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SinkKind {
+    /// A SQL query string
+    Sql,
+    /// A spawned process or shell command
+    Command,
+    /// Dynamically evaluated code
+    Eval,
+}
+
+impl SinkKind {
+    /// Finding title for the sink kind
+    fn title(&self) -> &'static str {
+        match self {
+            SinkKind::Sql => "tainted_sql_query",
+            SinkKind::Command => "tainted_command",
+            SinkKind::Eval => "tainted_eval",
+        }
+    }
+
+    /// CWE of the injection the sink kind allows
+    fn cwe_id(&self) -> &'static str {
+        match self {
+            SinkKind::Sql => "CWE-89",
+            SinkKind::Command => "CWE-78",
+            SinkKind::Eval => "CWE-95",
+        }
+    }
+
+    /// Suggested fix for the sink kind
+    fn suggested_fix(&self) -> &'static str {
+        match self {
+            SinkKind::Sql => "Use a parameterized query instead of building the SQL string",
+            SinkKind::Command => {
+                "Validate the value against an allowlist and pass it as a separate argument"
+            }
+            SinkKind::Eval => "Do not evaluate untrusted input; parse it into the expected data",
+        }
+    }
+}
+
+/// Where a tainted value came from
+#[derive(Debug, Clone)]
+struct Taint {
+    /// Source expression that introduced the taint
+    source: &'static str,
+    /// 0-based line of the source
+    line: usize,
+}
+
+/// A tainted value reaching a sink
+#[derive(Debug, Clone)]
+struct TaintFlow {
+    /// Origin of the tainted value
+    taint: Taint,
+    /// Kind of sink reached
+    sink: SinkKind,
+    /// Callee of the sink call, e.g. `cursor.execute`
+    sink_name: String,
+    /// 0-based line of the sink call
+    line: usize,
+    /// 0-based column of the sink call
+    column: usize,
+}
+
+/// Intra-function taint analyzer
+pub struct TaintAnalyzer;
+
+impl TaintAnalyzer {
+    /// Create a new taint analyzer
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Find tainted values reaching sinks in a file
+    ///
+    /// Languages the analysis has no source and sink model for yield no findings.
+    pub fn analyze(&self, file: &CodeFile) -> Result<Vec<VulnerabilityFinding>> {
+        let language = match SupportedLanguage::from_str(&file.language) {
+            Some(
+                language @ (SupportedLanguage::Python
+                | SupportedLanguage::JavaScript
+                | SupportedLanguage::TypeScript
+                | SupportedLanguage::Rust
+                | SupportedLanguage::Go),
+            ) => language,
+            _ => return Ok(Vec::new()),
+        };
+
+        let mut parser = Parser::new();
+        parser.set_language(language.get_language()?)?;
+        let tree = parser
+            .parse(&file.content, None)
+            .ok_or_else(|| anyhow::anyhow!("Failed to parse {} for taint analysis", file.path))?;
+
+        let source = file.content.as_bytes();
+        let mut flows = Vec::new();
+        visit(tree.root_node(), source, &mut HashMap::new(), &mut flows);
+
+        let lines: Vec<&str> = file.content.lines().collect();
+        Ok(flows
+            .into_iter()
+            .map(|flow| create_finding(file, &lines, flow))
+            .collect())
+    }
+}
+
+impl Default for TaintAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Text of a node
+fn text<'a>(node: Node, source: &'a [u8]) -> &'a str {
+    node.utf8_text(source).unwrap_or("")
+}
+
+/// Named children of a node
+fn children(node: Node) -> Vec<Node> {
+    let mut cursor = node.walk();
+    node.named_children(&mut cursor).collect()
+}
+
+/// Walk a node in source order, tracking taint and recording flows into sinks
+fn visit(
+    node: Node,
+    source: &[u8],
+    tainted: &mut HashMap<String, Taint>,
+    flows: &mut Vec<TaintFlow>,
+) {
+    let kind = node.kind();
+
+    // Each function is analyzed on its own
+    if FUNCTION_KINDS.contains(&kind) {
+        let mut scope = HashMap::new();
+        for child in children(node) {
+            visit(child, source, &mut scope, flows);
+        }
+        return;
+    }
+
+    if let Some((targets, value, augmented)) = assignment_parts(node) {
+        visit(value, source, tainted, flows);
+        let taint = expression_taint(value, source, tainted);
+        for name in assigned_names(targets, source) {
+            match &taint {
+                Some(taint) => {
+                    tainted.insert(name, taint.clone());
+                }
+                // `x += clean` keeps whatever taint `x` already had
+                None if !augmented => {
+                    tainted.remove(&name);
+                }
+                None => {}
+            }
+        }
+        return;
+    }
+
+    if CALL_KINDS.contains(&kind) {
+        let callee = node.child_by_field_name("function");
+        let sink = callee.and_then(|callee| sink_kind(text(callee, source)).map(|s| (callee, s)));
+        let first_argument = node
+            .child_by_field_name("arguments")
+            .and_then(|arguments| children(arguments).into_iter().next());
+
+        if let (Some((callee, sink)), Some(argument)) = (sink, first_argument) {
+            if let Some(taint) = expression_taint(argument, source, tainted) {
+                flows.push(TaintFlow {
+                    taint,
+                    sink,
+                    sink_name: text(callee, source).split_whitespace().collect(),
+                    line: node.start_position().row,
+                    column: node.start_position().column,
+                });
+            }
+        }
+    }
+
+    for child in children(node) {
+        visit(child, source, tainted, flows);
+    }
+}
+
+/// Split an assignment-like node into its targets and value
+///
+/// The flag is set for augmented assignments such as `x += y`.
+fn assignment_parts(node: Node) -> Option<(Node, Node, bool)> {
+    let (target_field, value_field, augmented) = match node.kind() {
+        "assignment" | "assignment_expression" | "short_var_declaration" => {
+            ("left", "right", false)
+        }
+        "augmented_assignment" | "augmented_assignment_expression" | "compound_assignment_expr" => {
+            ("left", "right", true)
+        }
+        "variable_declarator" | "var_spec" => ("name", "value", false),
+        "let_declaration" => ("pattern", "value", false),
+        _ => return None,
+    };
+
+    let targets = node.child_by_field_name(target_field)?;
+    let value = node.child_by_field_name(value_field)?;
+    Some((targets, value, augmented))
+}
+
+/// Names of the variables an assignment target binds
+fn assigned_names(target: Node, source: &[u8]) -> Vec<String> {
+    match target.kind() {
+        "identifier" => vec![text(target, source).to_string()],
+        "expression_list" | "pattern_list" | "tuple_pattern" | "identifier_list" => {
+            children(target)
+                .into_iter()
+                .filter(|child| child.kind() == "identifier")
+                .map(|child| text(child, source).to_string())
+                .collect()
+        }
+        // Field and index targets are not tracked
+        _ => Vec::new(),
+    }
+}
+
+/// Taint of an expression, if it is derived from a source
+fn expression_taint(node: Node, source: &[u8], tainted: &HashMap<String, Taint>) -> Option<Taint> {
+    if CALL_KINDS.contains(&node.kind()) {
+        let callee = node.child_by_field_name("function");
+        if callee.is_some_and(|callee| is_sanitizer(text(callee, source))) {
+            return None;
+        }
+    }
+
+    let expression: String = text(node, source).split_whitespace().collect();
+    if let Some(taint_source) = TAINT_SOURCES
+        .iter()
+        .find(|taint_source| expression.starts_with(*taint_source))
+    {
+        return Some(Taint {
+            source: *taint_source,
+            line: node.start_position().row,
+        });
+    }
+
+    if node.kind() == "identifier" {
+        return tainted.get(text(node, source)).cloned();
+    }
+
+    children(node)
+        .into_iter()
+        .find_map(|child| expression_taint(child, source, tainted))
+}
+
+/// Last segment of a callee path such as `cursor.execute` or `Command::new`
+fn callee_name(callee: &str) -> &str {
+    callee.rsplit(['.', ':']).next().unwrap_or(callee)
+}
+
+/// Whether a call to `callee` sanitizes its input
+fn is_sanitizer(callee: &str) -> bool {
+    let name = callee_name(callee).to_lowercase();
+    name.contains("sanitiz") || name.contains("escape") || SANITIZERS.contains(&name.as_str())
+}
+
+/// Sink kind of a call to `callee`, if it is a sink
+fn sink_kind(callee: &str) -> Option<SinkKind> {
+    let callee: String = callee.split_whitespace().collect();
+    if callee == "eval" || callee == "exec" {
+        return Some(SinkKind::Eval);
+    }
+    if callee.ends_with("Command::new")
+        || callee.starts_with("subprocess.")
+        || callee.starts_with("child_process.")
+        || matches!(callee.as_str(), "os.system" | "os.popen" | "exec.Command")
+    {
+        return Some(SinkKind::Command);
+    }
+    SQL_SINKS
+        .contains(&callee_name(&callee))
+        .then_some(SinkKind::Sql)
+}
+
+/// Create a finding for a taint flow
+fn create_finding(file: &CodeFile, lines: &[&str], flow: TaintFlow) -> VulnerabilityFinding {
+    let mut metadata = HashMap::new();
+    metadata.insert("source".to_string(), flow.taint.source.to_string());
+    metadata.insert("source_line".to_string(), flow.taint.line.to_string());
+    metadata.insert("sink".to_string(), flow.sink_name.clone());
+    metadata.insert("sink_line".to_string(), flow.line.to_string());
+
+    VulnerabilityFinding {
+        id: Uuid::new_v4(),
+        file_path: file.path.clone(),
+        line_number: flow.line,
+        column_number: flow.column,
+        severity: VulnerabilitySeverity::High,
+        category: VulnerabilityCategory::InputValidation,
+        title: flow.sink.title().to_string(),
+        // Lines in the description are 1-based, as shown in editors
+        description: format!(
+            "Untrusted value from `{}` (line {}) reaches `{}` (line {}) without sanitization",
+            flow.taint.source,
+            flow.taint.line + 1,
+            flow.sink_name,
+            flow.line + 1
+        ),
+        code_snippet: lines
+            .get(flow.line)
+            .map(|line| line.trim())
+            .unwrap_or("")
+            .to_string(),
+        suggested_fix: flow.sink.suggested_fix().to_string(),
+        confidence: 0.8,
+        detection_method: DetectionMethod::SemanticAnalysis,
+        cwe_id: Some(flow.sink.cwe_id().to_string()),
+        metadata,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(language: &str, content: &str) -> CodeFile {
+        CodeFile {
+            id: Uuid::new_v4(),
+            path: format!("app.{}", language),
+            content: content.to_string(),
+            language: language.to_string(),
+            modified: chrono::Utc::now(),
+            encoding: Default::default(),
+            line_ending: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_request_param_into_sql_is_flagged() {
+        let content = r#"
+from flask import request
+
+def lookup(cursor):
+    user_id = request.args.get("id")
+    query = "SELECT * FROM users WHERE id = " + user_id
+    cursor.execute(query)
+
+def listing(cursor):
+    query = "SELECT * FROM users"
+    cursor.execute(query)
+"#;
+        let findings = TaintAnalyzer::new()
+            .analyze(&file("python", content))
+            .unwrap();
+
+        assert_eq!(findings.len(), 1);
+        let finding = &findings[0];
+        assert_eq!(finding.title, "tainted_sql_query");
+        assert_eq!(finding.cwe_id.as_deref(), Some("CWE-89"));
+        assert_eq!(finding.metadata["source"], "request.args");
+        assert_eq!(finding.metadata["source_line"], "4");
+        assert_eq!(finding.metadata["sink_line"], "6");
+        assert_eq!(finding.line_number, 6);
+    }
+
+    #[test]
+    fn test_sanitized_and_parameterized_values_are_not_flagged() {
+        let content = r#"
+def search(cursor):
+    name = request.args["name"]
+    cursor.execute("SELECT * FROM users WHERE name = ?", (name,))
+    cursor.execute("SELECT * FROM users WHERE name = '" + escape(name) + "'")
+    name = "admin"
+    cursor.execute("SELECT * FROM users WHERE name = '" + name + "'")
+"#;
+        let findings = TaintAnalyzer::new()
+            .analyze(&file("python", content))
+            .unwrap();
+        assert!(findings.is_empty(), "unexpected findings: {:?}", findings);
+    }
+
+    #[test]
+    fn test_env_var_into_command_is_flagged() {
+        let content = r#"
+fn run() {
+    let program = std::env::var("PROGRAM").unwrap();
+    std::process::Command::new(program).status().unwrap();
+    std::process::Command::new("ls").status().unwrap();
+}
+"#;
+        let findings = TaintAnalyzer::new()
+            .analyze(&file("rust", content))
+            .unwrap();
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].title, "tainted_command");
+        assert_eq!(findings[0].metadata["source_line"], "2");
+        assert_eq!(findings[0].metadata["sink_line"], "3");
+    }
+}
@@ -0,0 +1,44 @@
+//! Integration tests for the CodeGenerator agent against the offline LLM backend
+
+use anyhow::Result;
+use odincode_agents::code_generator::{CodeGenerationRequest, CodeGeneratorAgent, GenerationType};
+use odincode_agents::llm_integration::LLMIntegrationManager;
+use odincode_core::{CodeEngine, CodeFile};
+use odincode_ltmc::LTMManager;
+use std::sync::Arc;
+
+#[tokio::test]
+async fn test_generate_tests_with_offline_backend() -> Result<()> {
+    let agent = CodeGeneratorAgent::new(
+        Arc::new(LLMIntegrationManager::new_offline()),
+        Arc::new(CodeEngine::new()?),
+        Arc::new(LTMManager::new()),
+    );
+
+    let request = CodeGenerationRequest {
+        file: CodeFile {
+            id: uuid::Uuid::new_v4(),
+            path: "src/math.rs".to_string(),
+            content: "pub fn add(a: i32, b: i32) -> i32 { a + b }".to_string(),
+            language: "rust".to_string(),
+            modified: chrono::Utc::now(),
+            encoding: Default::default(),
+            line_ending: Default::default(),
+        },
+        cursor_position: 0,
+        context: "pub fn add(a: i32, b: i32) -> i32 { a + b }".to_string(),
+        language: "rust".to_string(),
+        generation_type: GenerationType::Test,
+        constraints: None,
+    };
+
+    // Runs the whole prompt, request and parse pipeline without network access
+    let first = agent.generate_code(request.clone()).await?;
+    let second = agent.generate_code(request).await?;
+
+    assert!(first.generated_code.contains("#[test]"));
+    assert!(!first.generated_code.contains("```"));
+    assert_eq!(first.generated_code, second.generated_code);
+    assert!(first.explanation.contains("test coverage"));
+    Ok(())
+}
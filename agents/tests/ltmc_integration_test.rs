@@ -302,6 +302,7 @@ async fn test_pattern_cache_functionality() -> Result<()> {
         last_accessed: chrono::Utc::now(),
         access_count: 0,
         confidence: 0.8,
+        provenance: Default::default(),
     };
 
     let pattern_id = integration
@@ -0,0 +1,60 @@
+//! Integration tests for the Refactorer agent's structured LLM output
+
+use anyhow::Result;
+use odincode_agents::llm_integration::LLMIntegrationManager;
+use odincode_agents::refactorer::{RefactorerAgent, RefactoringRequest, RefactoringType};
+use odincode_core::{CodeEngine, CodeFile};
+use odincode_ltmc::LTMManager;
+use std::sync::Arc;
+
+#[tokio::test]
+async fn test_generate_refactoring_reprompts_after_invalid_json() -> Result<()> {
+    let llm_manager = Arc::new(LLMIntegrationManager::new_offline());
+    llm_manager.queue_mock_response("Consider extracting the loop into a helper.");
+    llm_manager.queue_mock_response(
+        r#"[{
+            "refactoring_type": "ExtractMethod",
+            "original_code": "for x in xs { total += x; }",
+            "refactored_code": "let total = sum(&xs);",
+            "description": "Extract the summing loop",
+            "confidence": 0.9,
+            "benefits": ["Shorter function"],
+            "risks": [],
+            "affected_lines": [2],
+            "implementation_steps": ["Add a sum helper", "Call it"]
+        }]"#,
+    );
+
+    let agent = RefactorerAgent::new(
+        llm_manager.clone(),
+        Arc::new(CodeEngine::new()?),
+        Arc::new(LTMManager::new()),
+    );
+    let request = RefactoringRequest {
+        file: CodeFile {
+            id: uuid::Uuid::new_v4(),
+            path: "src/total.rs".to_string(),
+            content: "fn total(xs: Vec<u32>) -> u32 {\n    let mut total = 0;\n    for x in xs { total += x; }\n    total\n}\n".to_string(),
+            language: "rust".to_string(),
+            modified: chrono::Utc::now(),
+            encoding: Default::default(),
+            line_ending: Default::default(),
+        },
+        refactoring_type: None,
+        target_area: None,
+        goals: None,
+        language: "rust".to_string(),
+    };
+
+    let suggestions = agent.generate_refactoring(request).await?;
+
+    assert_eq!(suggestions.len(), 1);
+    assert_eq!(
+        suggestions[0].refactoring_type,
+        RefactoringType::ExtractMethod
+    );
+    assert_eq!(suggestions[0].affected_lines, vec![2]);
+    // One re-prompt after the prose answer
+    assert_eq!(llm_manager.get_stats().await.total_requests, 2);
+    Ok(())
+}
@@ -194,6 +194,8 @@ fn process_data(data: &[u8]) -> Vec<u8> {
         language: "rust".to_string(),
         content: test_code,
         modified: chrono::Utc::now(),
+        encoding: Default::default(),
+        line_ending: Default::default(),
     };
 
     // Measure scan time
@@ -250,6 +252,8 @@ fn vulnerable_function(input: &str) {
         language: "rust".to_string(),
         content: test_code.to_string(),
         modified: chrono::Utc::now(),
+        encoding: Default::default(),
+        line_ending: Default::default(),
     };
 
     // Test fast configuration
@@ -6,18 +6,28 @@ use axum::{
     debug_handler,
     extract::{Path, Query, State},
     http::StatusCode,
-    response::Json,
+    response::{
+        sse::{Event, Sse},
+        IntoResponse, Json, Response,
+    },
 };
+use futures::StreamExt;
+use serde::Deserialize;
 use serde_json::Value;
 use std::collections::HashMap;
+use std::convert::Infallible;
+use tracing::Instrument;
 use uuid::Uuid;
 
-use odincode_agents::AgentCoordinator;
+use odincode_agents::{AgentCoordinator, AgentError};
+use odincode_core::llm_integration::LLMIntegrationManager;
 use odincode_core::CodeEngine;
 use odincode_ltmc::{LTMManager, LearningPattern, PatternType};
 use odincode_tools::ToolManager;
 
+use crate::jobs::{deliver_callback, AgentJob, CallbackRetry, JobRegistry, JobStatus};
 use crate::models::{ExecuteAgentRequest, ExecuteAgentResponse, FileResponse, LoadFileRequest};
+use crate::validation::{self, RequestLimits, Validate};
 use odincode_tools::EditTask;
 
 use std::sync::Arc;
@@ -32,6 +42,78 @@ pub struct ApiState {
     pub agent_coordinator: Arc<AgentCoordinator>,
     /// Tool manager
     pub tool_manager: Arc<ToolManager>,
+    /// LLM manager answering completion requests
+    pub llm_manager: Arc<LLMIntegrationManager>,
+    /// Agent executions running in the background
+    pub jobs: JobRegistry,
+    /// Limits request bodies are validated against
+    pub limits: RequestLimits,
+}
+
+/// Error returned by a handler, sent as a JSON body `{ "error": .., "code": .. }`
+#[derive(Debug)]
+pub struct ApiError {
+    /// HTTP status of the response
+    pub status: StatusCode,
+    /// Machine-readable error class, e.g. `file_not_found`
+    pub code: &'static str,
+    /// Human-readable description of the error
+    pub message: String,
+}
+
+impl ApiError {
+    /// Error for a malformed request
+    pub fn bad_request(message: impl Into<String>) -> Self {
+        Self {
+            status: StatusCode::BAD_REQUEST,
+            code: "invalid_input",
+            message: message.into(),
+        }
+    }
+
+    /// Error for a request naming something that does not exist
+    pub fn not_found(code: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            status: StatusCode::NOT_FOUND,
+            code,
+            message: message.into(),
+        }
+    }
+
+    /// Error for a failure inside the server
+    pub fn internal(error: impl std::fmt::Display) -> Self {
+        Self {
+            status: StatusCode::INTERNAL_SERVER_ERROR,
+            code: "internal",
+            message: error.to_string(),
+        }
+    }
+}
+
+impl From<AgentError> for ApiError {
+    fn from(error: AgentError) -> Self {
+        let (status, code) = match &error {
+            AgentError::FileNotFound(_) => (StatusCode::NOT_FOUND, "file_not_found"),
+            AgentError::AgentNotFound(_) => (StatusCode::NOT_FOUND, "agent_not_found"),
+            AgentError::InvalidInput(_) => (StatusCode::BAD_REQUEST, "invalid_input"),
+            AgentError::LlmUnavailable(_) => (StatusCode::SERVICE_UNAVAILABLE, "llm_unavailable"),
+            AgentError::Cancelled => (StatusCode::SERVICE_UNAVAILABLE, "cancelled"),
+            AgentError::Timeout(_) => (StatusCode::GATEWAY_TIMEOUT, "timeout"),
+            AgentError::Internal(_) => (StatusCode::INTERNAL_SERVER_ERROR, "internal"),
+        };
+        Self {
+            status,
+            code,
+            message: error.to_string(),
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let body = serde_json::json!({ "error": self.message, "code": self.code });
+        (self.status, Json(body)).into_response()
+    }
 }
 
 /// Health check endpoint
@@ -48,8 +130,11 @@ pub async fn health_check() -> Json<HashMap<String, String>> {
 pub async fn load_file(
     State(state): State<std::sync::Arc<ApiState>>,
     Json(request): Json<LoadFileRequest>,
-) -> Result<Json<FileResponse>, StatusCode> {
+) -> Result<Json<FileResponse>, ApiError> {
     tracing::debug!("Loading file: {}", request.path);
+    request.validate(&state.limits)?;
+    let languages = state.core_engine.known_languages().await;
+    validation::known_language(&request.language, &languages)?;
 
     match state
         .core_engine
@@ -63,7 +148,7 @@ pub async fn load_file(
         })),
         Err(e) => {
             tracing::error!("Failed to load file: {}", e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            Err(ApiError::internal(e))
         }
     }
 }
@@ -93,6 +178,50 @@ pub async fn get_file(
     }
 }
 
+/// Body of a file content update
+#[derive(Debug, Clone, Deserialize)]
+pub struct UpdateFileRequest {
+    /// New content of the file
+    pub content: String,
+}
+
+/// Replace the content of a loaded file
+///
+/// Responds with the file id and its new modification timestamp.
+#[debug_handler]
+pub async fn update_file(
+    State(state): State<std::sync::Arc<ApiState>>,
+    Path(file_id): Path<String>,
+    Json(request): Json<UpdateFileRequest>,
+) -> Result<Json<Value>, ApiError> {
+    tracing::debug!("Updating file: {}", file_id);
+    request.validate(&state.limits)?;
+
+    let uuid = Uuid::parse_str(&file_id)
+        .map_err(|_| ApiError::bad_request(format!("Invalid file id: {}", file_id)))?;
+    let not_found = || ApiError::not_found("file_not_found", format!("File not found: {}", uuid));
+
+    let updated = state
+        .core_engine
+        .update_file(uuid, request.content)
+        .await
+        .map_err(ApiError::internal)?;
+    if !updated {
+        return Err(not_found());
+    }
+
+    let file = state
+        .core_engine
+        .get_file(uuid)
+        .await
+        .map_err(ApiError::internal)?
+        .ok_or_else(not_found)?;
+    Ok(Json(serde_json::json!({
+        "id": file.id.to_string(),
+        "modified": file.modified,
+    })))
+}
+
 /// Analyze a file
 #[debug_handler]
 pub async fn analyze_file(
@@ -118,6 +247,70 @@ pub async fn analyze_file(
     }
 }
 
+/// Body of an inline completion request
+#[derive(Debug, Clone, Deserialize)]
+pub struct CompleteRequest {
+    /// Language of the code being edited
+    pub language: String,
+    /// Code before the cursor
+    pub prefix: String,
+    /// Code after the cursor
+    #[serde(default)]
+    pub suffix: String,
+    /// Send the completion as server-sent events instead of a JSON body
+    #[serde(default)]
+    pub stream: bool,
+}
+
+/// Complete code at a cursor, for inline suggestions in an editor
+///
+/// Asks the LLM directly, without running analysis. Responds with
+/// `{ "completion": .. }`, or, when `stream` is set, with a `completion` event
+/// for each piece of text as the provider produces it, followed by a `done`
+/// event or an `error` event if the provider fails.
+#[debug_handler]
+pub async fn complete(
+    State(state): State<std::sync::Arc<ApiState>>,
+    Json(request): Json<CompleteRequest>,
+) -> Result<Response, ApiError> {
+    if request.prefix.trim().is_empty() && request.suffix.trim().is_empty() {
+        return Err(ApiError::bad_request(
+            "Nothing to complete: prefix and suffix are empty",
+        ));
+    }
+
+    if request.stream {
+        let (sender, chunks) = futures::channel::mpsc::unbounded();
+        let llm_manager = state.llm_manager.clone();
+        let completion = tokio::spawn(async move {
+            llm_manager
+                .stream_code_completion(&request.language, &request.prefix, &request.suffix, sender)
+                .await
+        });
+
+        let end = futures::stream::once(async move {
+            match completion.await {
+                Ok(Ok(())) => Event::default().event("done").data(""),
+                Ok(Err(e)) => Event::default().event("error").data(e.to_string()),
+                Err(e) => Event::default().event("error").data(e.to_string()),
+            }
+        });
+        let events = chunks
+            .map(|chunk: String| Event::default().event("completion").data(chunk))
+            .chain(end)
+            .map(Ok::<_, Infallible>);
+        return Ok(Sse::new(events).into_response());
+    }
+
+    let completion = state
+        .llm_manager
+        .complete_code(&request.language, &request.prefix, &request.suffix)
+        .await
+        .map_err(|e| ApiError::from(AgentError::LlmUnavailable(e.to_string())))?;
+
+    Ok(Json(serde_json::json!({ "completion": completion })).into_response())
+}
+
 /// List all agents
 #[debug_handler]
 pub async fn list_agents(
@@ -141,22 +334,18 @@ pub async fn list_agents(
 pub async fn execute_agent(
     State(state): State<std::sync::Arc<ApiState>>,
     Json(request): Json<ExecuteAgentRequest>,
-) -> Result<Json<Value>, StatusCode> {
+) -> Result<Json<Value>, ApiError> {
     tracing::debug!(
         "Executing agent {} on file {}",
         request.agent_id,
         request.file_id
     );
+    request.validate(&state.limits)?;
 
-    let agent_id = match Uuid::parse_str(&request.agent_id) {
-        Ok(id) => id,
-        Err(_) => return Err(StatusCode::BAD_REQUEST),
-    };
-
-    let file_id = match Uuid::parse_str(&request.file_id) {
-        Ok(id) => id,
-        Err(_) => return Err(StatusCode::BAD_REQUEST),
-    };
+    let agent_id = Uuid::parse_str(&request.agent_id)
+        .map_err(|_| ApiError::bad_request(format!("Invalid agent id: {}", request.agent_id)))?;
+    let file_id = Uuid::parse_str(&request.file_id)
+        .map_err(|_| ApiError::bad_request(format!("Invalid file id: {}", request.file_id)))?;
 
     let result = state
         .agent_coordinator
@@ -165,17 +354,111 @@ pub async fn execute_agent(
 
     match result {
         Ok(suggestions) => {
-            let result_json =
-                serde_json::to_value(suggestions).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+            let result_json = serde_json::to_value(suggestions)
+                .map_err(|e| ApiError::from(AgentError::Internal(e.into())))?;
             Ok(Json(result_json))
         }
         Err(e) => {
             tracing::error!("Failed to execute agent: {}", e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            Err(e.into())
+        }
+    }
+}
+
+/// Body of an asynchronous agent execution request
+#[derive(Debug, Deserialize)]
+pub struct ExecuteAgentAsyncRequest {
+    /// Agent and file to execute on
+    #[serde(flatten)]
+    pub execution: ExecuteAgentRequest,
+    /// URL the finished job is POSTed to; without one the job can only be polled
+    pub callback_url: Option<String>,
+}
+
+/// Start an agent execution in the background
+///
+/// Responds `202 Accepted` with the job id right away. The finished job is
+/// POSTed to `callback_url` if one was given, and can be polled with
+/// `get_agent_job` either way.
+#[debug_handler]
+pub async fn execute_agent_async(
+    State(state): State<std::sync::Arc<ApiState>>,
+    Json(request): Json<ExecuteAgentAsyncRequest>,
+) -> Result<(StatusCode, Json<Value>), ApiError> {
+    request.validate(&state.limits)?;
+    let execution = &request.execution;
+    let agent_id = Uuid::parse_str(&execution.agent_id)
+        .map_err(|_| ApiError::bad_request(format!("Invalid agent id: {}", execution.agent_id)))?;
+    let file_id = Uuid::parse_str(&execution.file_id)
+        .map_err(|_| ApiError::bad_request(format!("Invalid file id: {}", execution.file_id)))?;
+
+    let job = state.jobs.create(agent_id, file_id).await;
+    tracing::info!(
+        "Accepted job {} running agent {} on file {}",
+        job.id,
+        agent_id,
+        file_id
+    );
+    tokio::spawn(
+        run_agent_job(state.clone(), job.clone(), request.callback_url)
+            .instrument(tracing::Span::current()),
+    );
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(serde_json::json!({ "job_id": job.id.to_string(), "status": "pending" })),
+    ))
+}
+
+/// Run a background agent job to completion and deliver its outcome
+async fn run_agent_job(state: Arc<ApiState>, job: AgentJob, callback_url: Option<String>) {
+    state.jobs.set_status(job.id, JobStatus::Running).await;
+
+    let status = match state
+        .agent_coordinator
+        .execute_agent_on_file(job.agent_id, job.file_id)
+        .await
+    {
+        Ok(suggestions) => JobStatus::Succeeded {
+            suggestions: suggestions.unwrap_or_default(),
+        },
+        Err(e) => {
+            let error = ApiError::from(e);
+            JobStatus::Failed {
+                code: error.code.to_string(),
+                error: error.message,
+            }
         }
+    };
+
+    let Some(finished) = state.jobs.set_status(job.id, status).await else {
+        return;
+    };
+    tracing::info!("Job {} finished", job.id);
+
+    if let Some(url) = callback_url {
+        let client = reqwest::Client::new();
+        deliver_callback(&client, &url, &finished, CallbackRetry::default()).await;
     }
 }
 
+/// Get the state of a background agent job
+#[debug_handler]
+pub async fn get_agent_job(
+    State(state): State<std::sync::Arc<ApiState>>,
+    Path(job_id): Path<String>,
+) -> Result<Json<AgentJob>, ApiError> {
+    let uuid = Uuid::parse_str(&job_id)
+        .map_err(|_| ApiError::bad_request(format!("Invalid job id: {}", job_id)))?;
+
+    state
+        .jobs
+        .get(uuid)
+        .await
+        .map(Json)
+        .ok_or_else(|| ApiError::not_found("job_not_found", format!("Job not found: {}", uuid)))
+}
+
 /// Search LTMC patterns
 #[debug_handler]
 pub async fn search_patterns(
@@ -184,16 +467,9 @@ pub async fn search_patterns(
 ) -> Result<Json<Value>, StatusCode> {
     tracing::debug!("Searching LTMC patterns");
 
-    let pattern_type = params.get("type").and_then(|t| match t.as_str() {
-        "architectural_decision" => Some(PatternType::ArchitecturalDecision),
-        "code_pattern" => Some(PatternType::CodePattern),
-        "research_finding" => Some(PatternType::ResearchFinding),
-        "performance_data" => Some(PatternType::PerformanceData),
-        "error_solution" => Some(PatternType::ErrorSolution),
-        "user_interaction" => Some(PatternType::UserInteraction),
-        "sequential_thinking" => Some(PatternType::SequentialThinking),
-        _ => None,
-    });
+    let pattern_type = params
+        .get("type")
+        .and_then(|t| t.parse::<PatternType>().ok());
 
     let query = params.get("q").cloned().unwrap_or_default();
 
@@ -437,3 +713,346 @@ pub async fn register_linter(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use odincode_agents::{AgentType, LTMCIntegration};
+
+    fn state() -> Arc<ApiState> {
+        let core_engine = CodeEngine::new().unwrap();
+        let ltmc_manager = LTMManager::new();
+        let llm_manager = odincode_core::llm_integration::LLMIntegrationManager::new().unwrap();
+        let ltmc_integration = LTMCIntegration::new(
+            Arc::new(ltmc_manager.clone()),
+            Arc::new(core_engine.clone()),
+            Arc::new(llm_manager),
+        );
+        let agent_coordinator = AgentCoordinator::new(
+            Arc::new(core_engine.clone()),
+            Arc::new(ltmc_manager.clone()),
+            Arc::new(ltmc_integration),
+        );
+
+        Arc::new(ApiState {
+            core_engine: Arc::new(core_engine.clone()),
+            ltmc_manager: Arc::new(ltmc_manager.clone()),
+            agent_coordinator: Arc::new(agent_coordinator.clone()),
+            tool_manager: Arc::new(ToolManager::new(
+                core_engine,
+                ltmc_manager,
+                agent_coordinator,
+            )),
+            llm_manager: Arc::new(LLMIntegrationManager::new_offline().unwrap()),
+            jobs: JobRegistry::new(),
+            limits: RequestLimits::default(),
+        })
+    }
+
+    async fn execute(state: Arc<ApiState>, agent_id: &str, file_id: &str) -> Response {
+        let request: ExecuteAgentRequest = serde_json::from_value(serde_json::json!({
+            "agent_id": agent_id,
+            "file_id": file_id,
+        }))
+        .unwrap();
+        execute_agent(State(state), Json(request))
+            .await
+            .into_response()
+    }
+
+    async fn body_json(response: Response) -> Value {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_execute_agent_reports_request_errors() {
+        let state = state();
+        let agent_id = state
+            .agent_coordinator
+            .register_agent(
+                AgentType::BugDetector,
+                "Bug Detector".to_string(),
+                "Finds bugs".to_string(),
+                vec![],
+                0.5,
+            )
+            .await
+            .unwrap();
+        let missing = Uuid::new_v4().to_string();
+
+        let response = execute(state.clone(), "not-a-uuid", &missing).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(body_json(response).await["code"], "invalid_input");
+
+        let response = execute(state.clone(), &Uuid::new_v4().to_string(), &missing).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(body_json(response).await["code"], "agent_not_found");
+
+        let response = execute(state, &agent_id.to_string(), &missing).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let body = body_json(response).await;
+        assert_eq!(body["code"], "file_not_found");
+        assert!(body["error"].as_str().unwrap().contains(&missing));
+    }
+
+    #[tokio::test]
+    async fn test_load_file_rejects_invalid_body() {
+        let state = state();
+        let request = |path: &str, language: &str| {
+            serde_json::from_value::<LoadFileRequest>(serde_json::json!({
+                "path": path,
+                "content": "fn main() {}",
+                "language": language,
+            }))
+            .unwrap()
+        };
+
+        let response = load_file(State(state.clone()), Json(request("", "rust")))
+            .await
+            .into_response();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body = body_json(response).await;
+        assert_eq!(body["code"], "invalid_input");
+        assert_eq!(body["error"], "Invalid path: must not be empty");
+
+        let response = load_file(State(state.clone()), Json(request("main.zig", "zig")))
+            .await
+            .into_response();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        state.core_engine.add_extension_override("zig", "zig").await;
+        let response = load_file(State(state.clone()), Json(request("main.zig", "zig")))
+            .await
+            .into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = load_file(State(state), Json(request("src/main.rs", "rust")))
+            .await
+            .into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_complete_asks_llm_for_completion() {
+        let state = state();
+        let request = |stream: bool| {
+            serde_json::from_value::<CompleteRequest>(serde_json::json!({
+                "language": "rust",
+                "prefix": "fn add(a: i32, b: i32) -> i32 {\n    ",
+                "suffix": "\n}\n",
+                "stream": stream,
+            }))
+            .unwrap()
+        };
+
+        let response = complete(State(state.clone()), Json(request(false)))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = body_json(response).await;
+        assert!(!body["completion"].as_str().unwrap().trim().is_empty());
+
+        let response = complete(State(state), Json(request(true))).await.unwrap();
+        assert_eq!(response.headers()["content-type"], "text/event-stream");
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let events = String::from_utf8(bytes.to_vec()).unwrap();
+        assert!(events.starts_with("event: completion\ndata: "));
+        // The completion arrives in several events
+        assert!(events.matches("event: completion\n").count() > 1);
+        assert!(events.ends_with("event: done\ndata: \n\n"));
+    }
+
+    /// Start a callback server, returning its URL and the payloads it receives
+    async fn callback_server() -> (String, tokio::sync::mpsc::UnboundedReceiver<Value>) {
+        let (sender, received) = tokio::sync::mpsc::unbounded_channel::<Value>();
+        let app = axum::Router::new().route(
+            "/hook",
+            axum::routing::post(move |Json(payload): Json<Value>| {
+                let sender = sender.clone();
+                async move {
+                    sender.send(payload).unwrap();
+                    StatusCode::OK
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let callback_url = format!("http://{}/hook", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (callback_url, received)
+    }
+
+    /// Run an agent job on `file_id` with a callback, returning the job id and
+    /// the delivered payload
+    async fn run_job_with_callback(
+        state: Arc<ApiState>,
+        agent_id: Uuid,
+        file_id: Uuid,
+    ) -> (String, Value) {
+        let (callback_url, mut received) = callback_server().await;
+        let request: ExecuteAgentAsyncRequest = serde_json::from_value(serde_json::json!({
+            "agent_id": agent_id.to_string(),
+            "file_id": file_id.to_string(),
+            "callback_url": callback_url,
+        }))
+        .unwrap();
+
+        let (status, Json(accepted)) = execute_agent_async(State(state), Json(request))
+            .await
+            .unwrap();
+        assert_eq!(status, StatusCode::ACCEPTED);
+        let job_id = accepted["job_id"].as_str().unwrap().to_string();
+
+        let payload = tokio::time::timeout(std::time::Duration::from_secs(10), received.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        (job_id, payload)
+    }
+
+    async fn register_bug_detector(state: &ApiState) -> Uuid {
+        state
+            .agent_coordinator
+            .register_agent(
+                AgentType::BugDetector,
+                "Bug Detector".to_string(),
+                "Finds bugs".to_string(),
+                vec![],
+                0.5,
+            )
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_async_execution_posts_result_to_callback() {
+        let state = state();
+        let agent_id = register_bug_detector(&state).await;
+        let file_id = state
+            .core_engine
+            .load_file(
+                "main.rs".to_string(),
+                "fn main() {\n    let value: Option<u32> = None;\n    value.unwrap();\n}\n"
+                    .to_string(),
+                "rust".to_string(),
+            )
+            .await
+            .unwrap();
+
+        let (job_id, payload) = run_job_with_callback(state.clone(), agent_id, file_id).await;
+        assert_eq!(payload["id"], job_id);
+        assert_eq!(payload["file_id"], file_id.to_string());
+        assert_eq!(payload["status"], "succeeded");
+        assert!(payload["suggestions"].is_array());
+
+        let Json(job) = get_agent_job(State(state), Path(job_id)).await.unwrap();
+        assert!(matches!(job.status, JobStatus::Succeeded { .. }));
+        assert!(job.finished.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_async_execution_posts_failure_to_callback() {
+        let state = state();
+        let agent_id = register_bug_detector(&state).await;
+
+        let (job_id, payload) =
+            run_job_with_callback(state.clone(), agent_id, Uuid::new_v4()).await;
+        assert_eq!(payload["id"], job_id);
+        assert_eq!(payload["agent_id"], agent_id.to_string());
+        assert_eq!(payload["status"], "failed");
+        assert_eq!(payload["code"], "file_not_found");
+
+        let Json(job) = get_agent_job(State(state), Path(job_id)).await.unwrap();
+        assert!(matches!(job.status, JobStatus::Failed { .. }));
+        assert!(job.finished.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_update_file_replaces_content() {
+        let state = state();
+        let file_id = state
+            .core_engine
+            .load_file(
+                "main.rs".to_string(),
+                "fn main() {}\n".to_string(),
+                "rust".to_string(),
+            )
+            .await
+            .unwrap();
+        let before = state.core_engine.get_file(file_id).await.unwrap().unwrap();
+
+        let Json(updated) = update_file(
+            State(state.clone()),
+            Path(file_id.to_string()),
+            Json(UpdateFileRequest {
+                content: "fn main() { run(); }\n".to_string(),
+            }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(updated["id"], file_id.to_string());
+        let modified: chrono::DateTime<chrono::Utc> =
+            serde_json::from_value(updated["modified"].clone()).unwrap();
+        assert!(modified >= before.modified);
+
+        let Json(file) = get_file(State(state.clone()), Path(file_id.to_string()))
+            .await
+            .unwrap();
+        assert_eq!(file["content"], "fn main() { run(); }\n");
+
+        let response = update_file(
+            State(state),
+            Path(Uuid::new_v4().to_string()),
+            Json(UpdateFileRequest {
+                content: String::new(),
+            }),
+        )
+        .await
+        .into_response();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(body_json(response).await["code"], "file_not_found");
+    }
+
+    #[tokio::test]
+    async fn test_agent_errors_map_to_status_codes() {
+        let cases = [
+            (
+                AgentError::LlmUnavailable("connection refused".to_string()),
+                StatusCode::SERVICE_UNAVAILABLE,
+                "llm_unavailable",
+            ),
+            (
+                AgentError::Timeout("30s".to_string()),
+                StatusCode::GATEWAY_TIMEOUT,
+                "timeout",
+            ),
+            (
+                AgentError::Cancelled,
+                StatusCode::SERVICE_UNAVAILABLE,
+                "cancelled",
+            ),
+            (
+                AgentError::InvalidInput("empty prompt".to_string()),
+                StatusCode::BAD_REQUEST,
+                "invalid_input",
+            ),
+            (
+                AgentError::Internal(anyhow::anyhow!("pattern store is full")),
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "internal",
+            ),
+        ];
+
+        for (error, status, code) in cases {
+            let response = ApiError::from(error).into_response();
+            assert_eq!(response.status(), status);
+            let body = body_json(response).await;
+            assert_eq!(body["code"], code);
+            assert!(body["error"].is_string());
+        }
+    }
+}
@@ -0,0 +1,313 @@
+//! Agent Jobs Module
+//!
+//! This module tracks agent executions started in the background through the
+//! async execute endpoint, and delivers their outcome to a caller-supplied
+//! callback URL, retrying failed deliveries with exponential backoff. Finished
+//! jobs are kept for polling until their TTL expires.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use odincode_core::CodeSuggestion;
+
+/// State of a background agent execution
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum JobStatus {
+    /// Accepted but not started yet
+    Pending,
+    /// The agent is running
+    Running,
+    /// The agent finished
+    Succeeded {
+        /// Suggestions produced by the agent
+        suggestions: Vec<CodeSuggestion>,
+    },
+    /// The agent failed
+    Failed {
+        /// Machine-readable error class, as in API error bodies
+        code: String,
+        /// Human-readable description of the error
+        error: String,
+    },
+}
+
+impl JobStatus {
+    /// Whether the job has finished, successfully or not
+    pub fn is_finished(&self) -> bool {
+        matches!(self, JobStatus::Succeeded { .. } | JobStatus::Failed { .. })
+    }
+}
+
+/// A background agent execution
+#[derive(Debug, Clone, Serialize)]
+pub struct AgentJob {
+    /// Job identifier
+    pub id: Uuid,
+    /// Agent being executed
+    pub agent_id: Uuid,
+    /// File the agent runs on
+    pub file_id: Uuid,
+    /// Current state, flattened into the job as `status` and its fields
+    #[serde(flatten)]
+    pub status: JobStatus,
+    /// When the job was accepted
+    pub created: chrono::DateTime<chrono::Utc>,
+    /// When the job finished, if it has
+    pub finished: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// How long a finished job can still be polled
+pub const DEFAULT_FINISHED_JOB_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// Registry of background agent jobs, shared by the API handlers
+#[derive(Debug, Clone)]
+pub struct JobRegistry {
+    /// Jobs by id
+    jobs: Arc<RwLock<HashMap<Uuid, AgentJob>>>,
+    /// How long finished jobs are kept
+    ttl: Duration,
+}
+
+impl Default for JobRegistry {
+    fn default() -> Self {
+        Self {
+            jobs: Arc::default(),
+            ttl: DEFAULT_FINISHED_JOB_TTL,
+        }
+    }
+}
+
+impl JobRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep finished jobs for `ttl` instead of the default
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Register a new pending job, evicting finished jobs past their TTL
+    pub async fn create(&self, agent_id: Uuid, file_id: Uuid) -> AgentJob {
+        let job = AgentJob {
+            id: Uuid::new_v4(),
+            agent_id,
+            file_id,
+            status: JobStatus::Pending,
+            created: chrono::Utc::now(),
+            finished: None,
+        };
+        let mut jobs = self.jobs.write().await;
+        Self::evict_expired(&mut jobs, self.ttl, job.created);
+        jobs.insert(job.id, job.clone());
+        job
+    }
+
+    /// Remove the jobs that finished more than `ttl` before `now`
+    fn evict_expired(
+        jobs: &mut HashMap<Uuid, AgentJob>,
+        ttl: Duration,
+        now: chrono::DateTime<chrono::Utc>,
+    ) {
+        jobs.retain(|_, job| {
+            job.finished
+                .and_then(|finished| (now - finished).to_std().ok())
+                .is_none_or(|age| age <= ttl)
+        });
+    }
+
+    /// Get a job by its ID
+    pub async fn get(&self, id: Uuid) -> Option<AgentJob> {
+        self.jobs.read().await.get(&id).cloned()
+    }
+
+    /// Move a job to a new state, returning the updated job
+    pub async fn set_status(&self, id: Uuid, status: JobStatus) -> Option<AgentJob> {
+        let mut jobs = self.jobs.write().await;
+        let job = jobs.get_mut(&id)?;
+        if status.is_finished() {
+            job.finished = Some(chrono::Utc::now());
+        }
+        job.status = status;
+        Some(job.clone())
+    }
+}
+
+/// How often, and how patiently, a callback delivery is attempted
+#[derive(Debug, Clone, Copy)]
+pub struct CallbackRetry {
+    /// Total number of delivery attempts
+    pub attempts: u32,
+    /// Delay before the first retry; doubled after every failed attempt
+    pub initial_backoff: Duration,
+    /// Time a single delivery may take before it counts as failed
+    pub request_timeout: Duration,
+}
+
+impl Default for CallbackRetry {
+    fn default() -> Self {
+        Self {
+            attempts: 4,
+            initial_backoff: Duration::from_millis(500),
+            request_timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+/// POST a finished job to its callback URL, retrying failures with backoff
+///
+/// A delivery that gets no response within the request timeout counts as
+/// failed. Returns whether a delivery got a successful response.
+pub async fn deliver_callback(
+    client: &reqwest::Client,
+    url: &str,
+    job: &AgentJob,
+    retry: CallbackRetry,
+) -> bool {
+    let mut backoff = retry.initial_backoff;
+
+    for attempt in 1..=retry.attempts.max(1) {
+        let request = client.post(url).timeout(retry.request_timeout).json(job);
+        match request.send().await {
+            Ok(response) if response.status().is_success() => {
+                tracing::info!("Delivered job {} to {} on attempt {}", job.id, url, attempt);
+                return true;
+            }
+            Ok(response) => tracing::warn!(
+                "Callback for job {} to {} failed with {} (attempt {}/{})",
+                job.id,
+                url,
+                response.status(),
+                attempt,
+                retry.attempts
+            ),
+            Err(e) => tracing::warn!(
+                "Callback for job {} to {} failed: {} (attempt {}/{})",
+                job.id,
+                url,
+                e,
+                attempt,
+                retry.attempts
+            ),
+        }
+
+        if attempt < retry.attempts {
+            tokio::time::sleep(backoff).await;
+            backoff *= 2;
+        }
+    }
+
+    tracing::error!(
+        "Giving up on callback for job {} to {} after {} attempts",
+        job.id,
+        url,
+        retry.attempts
+    );
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{http::StatusCode, routing::post, Router};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[tokio::test]
+    async fn test_failed_callbacks_are_retried() {
+        // Fails the first two deliveries, then accepts
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        let app = Router::new().route(
+            "/hook",
+            post(move || {
+                let counter = counter.clone();
+                async move {
+                    match counter.fetch_add(1, Ordering::SeqCst) {
+                        0 | 1 => StatusCode::SERVICE_UNAVAILABLE,
+                        _ => StatusCode::OK,
+                    }
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let registry = JobRegistry::new();
+        let job = registry.create(Uuid::new_v4(), Uuid::new_v4()).await;
+        let retry = CallbackRetry {
+            attempts: 3,
+            initial_backoff: Duration::from_millis(1),
+            ..CallbackRetry::default()
+        };
+
+        assert!(deliver_callback(&reqwest::Client::new(), &url, &job, retry).await);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        let retry = CallbackRetry {
+            attempts: 1,
+            ..retry
+        };
+        calls.store(0, Ordering::SeqCst);
+        assert!(!deliver_callback(&reqwest::Client::new(), &url, &job, retry).await);
+    }
+
+    #[tokio::test]
+    async fn test_hung_callbacks_time_out() {
+        // Accepts connections but never responds
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let mut connections = Vec::new();
+            while let Ok((stream, _)) = listener.accept().await {
+                connections.push(stream);
+            }
+        });
+
+        let registry = JobRegistry::new();
+        let job = registry.create(Uuid::new_v4(), Uuid::new_v4()).await;
+        let retry = CallbackRetry {
+            attempts: 1,
+            initial_backoff: Duration::from_millis(1),
+            request_timeout: Duration::from_millis(100),
+        };
+
+        let delivered = tokio::time::timeout(
+            Duration::from_secs(5),
+            deliver_callback(&reqwest::Client::new(), &url, &job, retry),
+        )
+        .await
+        .unwrap();
+        assert!(!delivered);
+    }
+
+    #[tokio::test]
+    async fn test_finished_jobs_are_evicted_after_their_ttl() {
+        let registry = JobRegistry::new().with_ttl(Duration::from_millis(50));
+        let finished = registry.create(Uuid::new_v4(), Uuid::new_v4()).await;
+        let running = registry.create(Uuid::new_v4(), Uuid::new_v4()).await;
+        registry
+            .set_status(
+                finished.id,
+                JobStatus::Succeeded {
+                    suggestions: Vec::new(),
+                },
+            )
+            .await;
+        registry.set_status(running.id, JobStatus::Running).await;
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let new = registry.create(Uuid::new_v4(), Uuid::new_v4()).await;
+
+        assert!(registry.get(finished.id).await.is_none());
+        assert!(registry.get(running.id).await.is_some());
+        assert!(registry.get(new.id).await.is_some());
+    }
+}
@@ -4,12 +4,18 @@
 //! allowing integration with IDEs, editors, and other development tools.
 
 pub mod handlers;
+pub mod jobs;
 pub mod models;
+pub mod request_id;
 pub mod server;
+pub mod validation;
 
 pub use handlers::*;
+pub use jobs::*;
 pub use models::*;
+pub use request_id::*;
 pub use server::*;
+pub use validation::*;
 
 #[cfg(test)]
 mod tests {
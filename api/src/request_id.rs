@@ -0,0 +1,119 @@
+//! Request ID Module
+//!
+//! This module provides the middleware that gives every API request an id. The
+//! id is taken from the client's `X-Request-Id` header or generated, recorded on
+//! a `tracing` span wrapping the handler so every log line of the request
+//! carries it, and echoed in the response's `X-Request-Id` header.
+
+use axum::{
+    extract::Request,
+    http::{HeaderName, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use tracing::Instrument;
+use uuid::Uuid;
+
+/// Header carrying the request id
+pub static REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+/// Longest client-supplied request id that is accepted
+const MAX_REQUEST_ID_LENGTH: usize = 128;
+
+/// Id of the current request, available to handlers as a request extension
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestId(pub String);
+
+/// Client-supplied request id, if it is present and reasonable to log
+fn client_request_id(request: &Request) -> Option<String> {
+    let id = request
+        .headers()
+        .get(&REQUEST_ID_HEADER)?
+        .to_str()
+        .ok()?
+        .trim();
+    let valid = !id.is_empty()
+        && id.len() <= MAX_REQUEST_ID_LENGTH
+        && id.chars().all(|c| c.is_ascii_graphic());
+    valid.then(|| id.to_string())
+}
+
+/// Middleware assigning, logging and echoing the request id
+pub async fn request_id(mut request: Request, next: Next) -> Response {
+    let id = client_request_id(&request).unwrap_or_else(|| Uuid::new_v4().to_string());
+    request.extensions_mut().insert(RequestId(id.clone()));
+
+    let span = tracing::info_span!(
+        "request",
+        request_id = %id,
+        method = %request.method(),
+        path = %request.uri().path(),
+    );
+
+    let mut response = next.run(request).instrument(span.clone()).await;
+    span.in_scope(|| tracing::debug!("Responded with {}", response.status()));
+
+    // The id is visible ASCII, so it is always a valid header value
+    if let Ok(value) = HeaderValue::from_str(&id) {
+        response
+            .headers_mut()
+            .insert(REQUEST_ID_HEADER.clone(), value);
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, routing::get, Extension, Router};
+    use tower::ServiceExt;
+
+    fn app() -> Router {
+        Router::new()
+            .route(
+                "/echo",
+                get(|Extension(RequestId(id)): Extension<RequestId>| async move { id }),
+            )
+            .layer(axum::middleware::from_fn(request_id))
+    }
+
+    async fn get_echo(header: Option<&str>) -> (Option<String>, String) {
+        let mut request = Request::builder().uri("/echo");
+        if let Some(header) = header {
+            request = request.header(&REQUEST_ID_HEADER, header);
+        }
+        let response = app()
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        let echoed = response
+            .headers()
+            .get(&REQUEST_ID_HEADER)
+            .map(|value| value.to_str().unwrap().to_string());
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (echoed, String::from_utf8(bytes.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_request_id_is_generated_and_echoed() {
+        let (echoed, seen_by_handler) = get_echo(None).await;
+
+        let echoed = echoed.expect("response carries a request id");
+        assert!(Uuid::parse_str(&echoed).is_ok());
+        assert_eq!(seen_by_handler, echoed);
+    }
+
+    #[tokio::test]
+    async fn test_client_request_id_is_preserved() {
+        let (echoed, seen_by_handler) = get_echo(Some("editor-42")).await;
+        assert_eq!(echoed.as_deref(), Some("editor-42"));
+        assert_eq!(seen_by_handler, "editor-42");
+
+        // An unusable id is replaced by a generated one
+        let (echoed, _) = get_echo(Some("has spaces in it")).await;
+        assert!(Uuid::parse_str(&echoed.unwrap()).is_ok());
+    }
+}
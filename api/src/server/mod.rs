@@ -4,6 +4,8 @@
 
 use anyhow::Result;
 use axum::{
+    extract::DefaultBodyLimit,
+    middleware,
     routing::{get, post},
     Router,
 };
@@ -11,16 +13,21 @@ use std::net::SocketAddr;
 use tracing::info;
 
 use odincode_agents::AgentCoordinator;
+use odincode_core::llm_integration::LLMIntegrationManager;
 use odincode_core::CodeEngine;
 use odincode_ltmc::LTMManager;
 use odincode_tools::ToolManager;
 
 use crate::handlers::{
-    analyze_file, create_multi_edit_operation, execute_agent, execute_multi_edit_operation,
-    execute_tool, get_file, health_check, lint_file, list_agents, list_tools, load_file,
-    register_linter, search_patterns, store_pattern, ApiState,
+    analyze_file, complete, create_multi_edit_operation, execute_agent, execute_agent_async,
+    execute_multi_edit_operation, execute_tool, get_agent_job, get_file, health_check, lint_file,
+    list_agents, list_tools, load_file, register_linter, search_patterns, store_pattern,
+    update_file, ApiState,
 };
+use crate::jobs::JobRegistry;
 use crate::models::ApiConfig;
+use crate::request_id::request_id;
+use crate::validation::RequestLimits;
 
 use std::sync::Arc;
 
@@ -40,17 +47,30 @@ impl ApiServer {
         ltmc_manager: Arc<LTMManager>,
         agent_coordinator: Arc<AgentCoordinator>,
         tool_manager: Arc<ToolManager>,
+        llm_manager: Arc<LLMIntegrationManager>,
     ) -> Self {
         let state = std::sync::Arc::new(ApiState {
             core_engine,
             ltmc_manager,
             agent_coordinator,
             tool_manager,
+            llm_manager,
+            jobs: JobRegistry::new(),
+            limits: RequestLimits::default(),
         });
 
         Self { config, state }
     }
 
+    /// Set the limits request bodies are validated against
+    pub fn with_request_limits(mut self, limits: RequestLimits) -> Self {
+        // The state is only shared once the server starts
+        if let Some(state) = Arc::get_mut(&mut self.state) {
+            state.limits = limits;
+        }
+        self
+    }
+
     /// Start the API server
     pub async fn start(&self) -> Result<()> {
         info!(
@@ -58,15 +78,33 @@ impl ApiServer {
             self.config.host, self.config.port
         );
 
-        // Build the application with the shared state
-        let app = Router::new()
+        let app = self.router();
+
+        // Bind to the address
+        let addr = SocketAddr::from(([0, 0, 0, 0], self.config.port));
+        info!("OdinCode API server listening on {}", addr);
+
+        // Run the server using hyper's TCP listener
+        let listener = tokio::net::TcpListener::bind(addr).await?;
+        axum::serve(listener, app)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to start API server: {}", e))?;
+
+        Ok(())
+    }
+
+    /// Build the application with the shared state
+    fn router(&self) -> Router {
+        Router::new()
             // File operations
             .route("/api/files", post(load_file))
-            .route("/api/files/:id", get(get_file))
+            .route("/api/files/:id", get(get_file).patch(update_file))
             .route("/api/files/:id/analyze", post(analyze_file))
             // Agent operations
             .route("/api/agents", get(list_agents))
             .route("/api/agents/:id/execute", post(execute_agent))
+            .route("/api/agents/execute/async", post(execute_agent_async))
+            .route("/api/agents/jobs/:id", get(get_agent_job))
             // LTMC operations
             .route("/api/ltmc/patterns", get(search_patterns))
             .route("/api/ltmc/patterns", post(store_pattern))
@@ -82,23 +120,95 @@ impl ApiServer {
                 "/api/multi-edit/operations/:id/execute",
                 post(execute_multi_edit_operation),
             )
+            // Inline completion
+            .route("/api/complete", post(complete))
             // Linter operations
             .route("/api/linters", post(register_linter))
             .route("/api/linters/:file_id/lint", post(lint_file))
             // Health check
             .route("/health", get(health_check))
-            .with_state(self.state.clone());
+            .with_state(self.state.clone())
+            // Axum's default 2 MB body limit would reject content the request
+            // limits allow before validation sees it
+            .layer(DefaultBodyLimit::max(self.state.limits.max_body_bytes()))
+            // Tag every request, and every log line it produces, with a request id
+            .layer(middleware::from_fn(request_id))
+    }
+}
 
-        // Bind to the address
-        let addr = SocketAddr::from(([0, 0, 0, 0], self.config.port));
-        info!("OdinCode API server listening on {}", addr);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use odincode_agents::LTMCIntegration;
+    use tower::ServiceExt;
 
-        // Run the server using hyper's TCP listener
-        let listener = tokio::net::TcpListener::bind(addr).await?;
-        axum::serve(listener, app)
+    fn server(limits: RequestLimits) -> ApiServer {
+        let core_engine = CodeEngine::new().unwrap();
+        let ltmc_manager = LTMManager::new();
+        let ltmc_integration = LTMCIntegration::new(
+            Arc::new(ltmc_manager.clone()),
+            Arc::new(core_engine.clone()),
+            Arc::new(LLMIntegrationManager::new_offline().unwrap()),
+        );
+        let agent_coordinator = AgentCoordinator::new(
+            Arc::new(core_engine.clone()),
+            Arc::new(ltmc_manager.clone()),
+            Arc::new(ltmc_integration),
+        );
+
+        let config = ApiConfig {
+            host: "127.0.0.1".to_string(),
+            port: 0,
+            workers: 1,
+            version: "test".to_string(),
+        };
+        ApiServer::new(
+            config,
+            Arc::new(core_engine.clone()),
+            Arc::new(ltmc_manager.clone()),
+            Arc::new(agent_coordinator.clone()),
+            Arc::new(ToolManager::new(
+                core_engine,
+                ltmc_manager,
+                agent_coordinator,
+            )),
+            Arc::new(LLMIntegrationManager::new_offline().unwrap()),
+        )
+        .with_request_limits(limits)
+    }
+
+    fn load_request(content_bytes: usize) -> Request<Body> {
+        let body = serde_json::json!({
+            "path": "notes.txt",
+            "content": "a".repeat(content_bytes),
+            "language": "text",
+        });
+        Request::post("/api/files")
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_body_limit_follows_request_limits() {
+        // Larger than axum's default limit, within the content limit
+        let response = server(RequestLimits::default())
+            .router()
+            .oneshot(load_request(3 * 1024 * 1024))
             .await
-            .map_err(|e| anyhow::anyhow!("Failed to start API server: {}", e))?;
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
 
-        Ok(())
+        let small = RequestLimits {
+            max_content_bytes: 1024,
+        };
+        let response = server(small.clone())
+            .router()
+            .oneshot(load_request(small.max_body_bytes() + 1))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
     }
 }
@@ -0,0 +1,204 @@
+//! API Request Validation Module
+//!
+//! This module checks the invariants of request bodies after they are
+//! deserialized, so handlers reject an empty path or oversized content with a
+//! `400` naming the offending field instead of passing it on.
+
+use crate::handlers::{ApiError, ExecuteAgentAsyncRequest, UpdateFileRequest};
+use crate::models::{ExecuteAgentRequest, LoadFileRequest};
+
+/// Largest file content accepted by default, in bytes
+pub const DEFAULT_MAX_CONTENT_BYTES: usize = 10 * 1024 * 1024;
+
+/// Limits request bodies are validated against
+#[derive(Debug, Clone, PartialEq)]
+pub struct RequestLimits {
+    /// Largest file content accepted, in bytes
+    pub max_content_bytes: usize,
+}
+
+impl Default for RequestLimits {
+    fn default() -> Self {
+        Self {
+            max_content_bytes: DEFAULT_MAX_CONTENT_BYTES,
+        }
+    }
+}
+
+impl RequestLimits {
+    /// Largest request body the router accepts, in bytes
+    ///
+    /// Content is escaped in JSON and sent alongside other fields, so bodies
+    /// get twice the content limit plus 1 MiB. Content within the limit then
+    /// reaches validation, which names the field when it is too large.
+    pub fn max_body_bytes(&self) -> usize {
+        self.max_content_bytes
+            .saturating_mul(2)
+            .saturating_add(1024 * 1024)
+    }
+}
+
+/// A field of a request body that breaks an invariant
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationError {
+    /// Name of the field, as in the JSON body
+    pub field: &'static str,
+    /// What is wrong with it
+    pub message: String,
+}
+
+impl ValidationError {
+    fn new(field: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            field,
+            message: message.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid {}: {}", self.field, self.message)
+    }
+}
+
+impl From<ValidationError> for ApiError {
+    fn from(error: ValidationError) -> Self {
+        ApiError::bad_request(error.to_string())
+    }
+}
+
+/// Invariants of a request body
+pub trait Validate {
+    /// Check the body, returning the first field that breaks an invariant
+    fn validate(&self, limits: &RequestLimits) -> Result<(), ValidationError>;
+}
+
+impl Validate for LoadFileRequest {
+    fn validate(&self, limits: &RequestLimits) -> Result<(), ValidationError> {
+        not_empty("path", &self.path)?;
+        content_within(&self.content, limits)
+    }
+}
+
+impl Validate for UpdateFileRequest {
+    fn validate(&self, limits: &RequestLimits) -> Result<(), ValidationError> {
+        content_within(&self.content, limits)
+    }
+}
+
+impl Validate for ExecuteAgentRequest {
+    fn validate(&self, _limits: &RequestLimits) -> Result<(), ValidationError> {
+        not_empty("agent_id", &self.agent_id)?;
+        not_empty("file_id", &self.file_id)
+    }
+}
+
+impl Validate for ExecuteAgentAsyncRequest {
+    fn validate(&self, limits: &RequestLimits) -> Result<(), ValidationError> {
+        self.execution.validate(limits)?;
+        if let Some(url) = &self.callback_url {
+            let valid = reqwest::Url::parse(url)
+                .map(|url| matches!(url.scheme(), "http" | "https"))
+                .unwrap_or(false);
+            if !valid {
+                return Err(ValidationError::new(
+                    "callback_url",
+                    format!("'{}' is not an http or https URL", url),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn not_empty(field: &'static str, value: &str) -> Result<(), ValidationError> {
+    if value.trim().is_empty() {
+        return Err(ValidationError::new(field, "must not be empty"));
+    }
+    Ok(())
+}
+
+fn content_within(content: &str, limits: &RequestLimits) -> Result<(), ValidationError> {
+    if content.len() > limits.max_content_bytes {
+        return Err(ValidationError::new(
+            "content",
+            format!(
+                "{} bytes exceeds the limit of {} bytes",
+                content.len(),
+                limits.max_content_bytes
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Check a requested language against the languages the engine knows
+///
+/// `known` is usually `CodeEngine::known_languages`, so languages registered
+/// through extension overrides are accepted too. An empty language is detected
+/// later; plain text is always accepted.
+pub fn known_language(language: &str, known: &[String]) -> Result<(), ValidationError> {
+    let language = language.trim().to_lowercase();
+    if language.is_empty() || language == "text" || known.contains(&language) {
+        return Ok(());
+    }
+    Err(ValidationError::new(
+        "language",
+        format!("'{}' is not one of: text, {}", language, known.join(", ")),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load_request(path: &str, content: &str, language: &str) -> LoadFileRequest {
+        serde_json::from_value(serde_json::json!({
+            "path": path,
+            "content": content,
+            "language": language,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_load_file_request_validation() {
+        let limits = RequestLimits::default();
+        assert_eq!(
+            load_request("src/main.rs", "fn main() {}", "rust").validate(&limits),
+            Ok(())
+        );
+        assert_eq!(load_request("notes", "", "").validate(&limits), Ok(()));
+
+        let error = load_request("  ", "fn main() {}", "rust")
+            .validate(&limits)
+            .unwrap_err();
+        assert_eq!(error.field, "path");
+        assert_eq!(error.to_string(), "Invalid path: must not be empty");
+
+        let small = RequestLimits {
+            max_content_bytes: 4,
+        };
+        let error = load_request("a.rs", "fn main() {}", "rust")
+            .validate(&small)
+            .unwrap_err();
+        assert_eq!(error.field, "content");
+    }
+
+    #[test]
+    fn test_known_language() {
+        let known = vec!["rust".to_string(), "zig".to_string()];
+        assert_eq!(known_language("Rust", &known), Ok(()));
+        assert_eq!(known_language("zig", &known), Ok(()));
+        assert_eq!(known_language("", &known), Ok(()));
+        assert_eq!(known_language("text", &known), Ok(()));
+
+        let error = known_language("brainfuck", &known).unwrap_err();
+        assert_eq!(error.field, "language");
+        assert_eq!(
+            error.to_string(),
+            "Invalid language: 'brainfuck' is not one of: text, rust, zig"
+        );
+    }
+}
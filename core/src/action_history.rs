@@ -6,8 +6,10 @@
 use anyhow::Result;
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
-use sqlx::{Row, SqlitePool};
+use sqlx::sqlite::{SqliteConnectOptions, SqliteRow};
+use sqlx::{QueryBuilder, Row, Sqlite, SqlitePool};
 use std::collections::HashMap;
+use std::path::Path;
 use uuid::Uuid;
 
 /// Action history manager
@@ -90,12 +92,50 @@ pub struct AiDecision {
     pub timestamp: i64,
 }
 
+/// Number of actions returned by a query without a limit
+pub const DEFAULT_ACTION_QUERY_LIMIT: u32 = 100;
+
+/// Most actions a single query can return
+pub const MAX_ACTION_QUERY_LIMIT: u32 = 1000;
+
+/// Filter criteria for action queries
+///
+/// Unset criteria match every action; time bounds are inclusive Unix timestamps.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ActionFilter {
+    pub action_type: Option<ActionType>,
+    pub session_id: Option<String>,
+    pub file_path: Option<String>,
+    pub start_time: Option<i64>,
+    pub end_time: Option<i64>,
+    /// Page size, capped at `MAX_ACTION_QUERY_LIMIT`
+    pub limit: Option<u32>,
+    /// Number of matching actions to skip
+    pub offset: u32,
+}
+
 impl ActionHistoryManager {
     /// Create a new action history manager
     pub fn new(pool: SqlitePool) -> Self {
         Self { pool }
     }
 
+    /// Open the action history database at `path`, creating it and its tables if missing
+    pub async fn open(path: &Path) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        let pool = SqlitePool::connect_with(
+            SqliteConnectOptions::new()
+                .filename(path)
+                .create_if_missing(true),
+        )
+        .await?;
+        let manager = Self::new(pool);
+        manager.init().await?;
+        Ok(manager)
+    }
+
     /// Initialize the action history database with required tables
     pub async fn init(&self) -> Result<()> {
         // Create actions table
@@ -162,6 +202,20 @@ impl ActionHistoryManager {
         .execute(&self.pool)
         .await?;
 
+        // Create undone actions table; its rowid orders the redo stack
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS undone_actions (
+                action_id TEXT PRIMARY KEY,
+                undone_at INTEGER NOT NULL,
+                redoable BOOLEAN NOT NULL,
+                FOREIGN KEY (action_id) REFERENCES actions (id) ON DELETE CASCADE
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
         // Create indexes for performance
         sqlx::query("CREATE INDEX IF NOT EXISTS idx_actions_timestamp ON actions(timestamp)")
             .execute(&self.pool)
@@ -237,6 +291,54 @@ impl ActionHistoryManager {
         Ok(action_id)
     }
 
+    /// Log an agent execution as a tool call of the agent's name
+    ///
+    /// The affected file is recorded so file queries see the execution, and the
+    /// metadata marks it as an agent run with its suggestion count.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn log_agent_execution(
+        &self,
+        session_id: &str,
+        agent_name: &str,
+        file_path: Option<&str>,
+        success: bool,
+        error_message: Option<String>,
+        duration_ms: u64,
+        suggestion_count: usize,
+    ) -> Result<String> {
+        let action_id = Uuid::new_v4().to_string();
+        let timestamp = Utc::now().timestamp();
+
+        let mut metadata = HashMap::new();
+        metadata.insert("source".to_string(), "agent".to_string());
+        metadata.insert("suggestion_count".to_string(), suggestion_count.to_string());
+        let metadata_json = serde_json::to_string(&metadata)?;
+        let result = success.then(|| format!("{} suggestions", suggestion_count));
+
+        sqlx::query(
+            r#"
+            INSERT INTO actions
+            (id, action_type, timestamp, session_id, tool_name, file_path, result, success, error_message, duration_ms, metadata)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#
+        )
+        .bind(&action_id)
+        .bind(ActionType::ToolCall.as_str())
+        .bind(timestamp)
+        .bind(session_id)
+        .bind(agent_name)
+        .bind(file_path)
+        .bind(&result)
+        .bind(success)
+        .bind(&error_message)
+        .bind(duration_ms as i64)
+        .bind(&metadata_json)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(action_id)
+    }
+
     /// Log a file modification action
     pub async fn log_file_modification(
         &self,
@@ -273,6 +375,11 @@ impl ActionHistoryManager {
         .execute(&self.pool)
         .await?;
 
+        // A new edit makes the undone edits impossible to redo
+        sqlx::query("UPDATE undone_actions SET redoable = 0")
+            .execute(&self.pool)
+            .await?;
+
         Ok(action_id)
     }
 
@@ -794,6 +901,208 @@ impl ActionHistoryManager {
         Ok(actions)
     }
 
+    /// Query actions matching a filter, oldest first
+    ///
+    /// Returns one page of at most `filter.limit` actions; use `filter.offset`
+    /// to fetch the following pages.
+    pub async fn query_actions(&self, filter: ActionFilter) -> Result<Vec<Action>> {
+        let mut query: QueryBuilder<Sqlite> = QueryBuilder::new(
+            r#"
+            SELECT id, action_type, timestamp, session_id, user_id, tool_name, file_path,
+                   line_number, column_number, before_content, after_content, ai_reasoning,
+                   parameters, result, success, error_message, duration_ms, token_usage, metadata
+            FROM actions
+            WHERE 1 = 1"#,
+        );
+
+        if let Some(action_type) = &filter.action_type {
+            query
+                .push(" AND action_type = ")
+                .push_bind(action_type.as_str());
+        }
+        if let Some(session_id) = &filter.session_id {
+            query.push(" AND session_id = ").push_bind(session_id);
+        }
+        if let Some(file_path) = &filter.file_path {
+            query.push(" AND file_path = ").push_bind(file_path);
+        }
+        if let Some(start_time) = filter.start_time {
+            query.push(" AND timestamp >= ").push_bind(start_time);
+        }
+        if let Some(end_time) = filter.end_time {
+            query.push(" AND timestamp <= ").push_bind(end_time);
+        }
+
+        let limit = filter
+            .limit
+            .unwrap_or(DEFAULT_ACTION_QUERY_LIMIT)
+            .min(MAX_ACTION_QUERY_LIMIT);
+        // Actions logged within the same second keep their insertion order
+        query
+            .push(" ORDER BY timestamp, rowid LIMIT ")
+            .push_bind(limit as i64)
+            .push(" OFFSET ")
+            .push_bind(filter.offset as i64);
+
+        let rows = query.build().fetch_all(&self.pool).await?;
+        rows.into_iter()
+            .map(|row| self.row_to_action(row))
+            .collect()
+    }
+
+    /// Undo the most recent file modification that hasn't been undone
+    ///
+    /// The file is restored to the action's stored before content. Only successful
+    /// modifications with both before and after content can be undone, and only
+    /// while the file still holds the after content, so later edits are never
+    /// overwritten. Returns the undone action, or `None` when there is nothing to
+    /// undo.
+    pub async fn undo_last_action(&self) -> Result<Option<Action>> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, action_type, timestamp, session_id, user_id, tool_name, file_path,
+                   line_number, column_number, before_content, after_content, ai_reasoning,
+                   parameters, result, success, error_message, duration_ms, token_usage, metadata
+            FROM actions
+            WHERE action_type = ? AND success = 1 AND file_path IS NOT NULL
+              AND before_content IS NOT NULL AND after_content IS NOT NULL
+              AND id NOT IN (SELECT action_id FROM undone_actions)
+            ORDER BY timestamp DESC, rowid DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(ActionType::FileModification.as_str())
+        .fetch_optional(&self.pool)
+        .await?;
+
+        let Some(row) = row else {
+            return Ok(None);
+        };
+        let action = self.row_to_action(row)?;
+        self.write_action_content(
+            &action,
+            action.after_content.as_deref(),
+            action.before_content.as_deref(),
+        )
+        .await?;
+
+        sqlx::query("INSERT INTO undone_actions (action_id, undone_at, redoable) VALUES (?, ?, 1)")
+            .bind(&action.id)
+            .bind(Utc::now().timestamp())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(Some(action))
+    }
+
+    /// Redo the most recently undone file modification
+    ///
+    /// The file is set back to the action's stored after content, provided it
+    /// still holds the before content. Returns the redone action, or `None` when
+    /// there is nothing to redo.
+    pub async fn redo_last_action(&self) -> Result<Option<Action>> {
+        let row = sqlx::query(
+            r#"
+            SELECT a.id, a.action_type, a.timestamp, a.session_id, a.user_id, a.tool_name,
+                   a.file_path, a.line_number, a.column_number, a.before_content,
+                   a.after_content, a.ai_reasoning, a.parameters, a.result, a.success,
+                   a.error_message, a.duration_ms, a.token_usage, a.metadata
+            FROM undone_actions u
+            JOIN actions a ON a.id = u.action_id
+            WHERE u.redoable = 1
+            ORDER BY u.rowid DESC
+            LIMIT 1
+            "#,
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        let Some(row) = row else {
+            return Ok(None);
+        };
+        let action = self.row_to_action(row)?;
+        self.write_action_content(
+            &action,
+            action.before_content.as_deref(),
+            action.after_content.as_deref(),
+        )
+        .await?;
+
+        sqlx::query("DELETE FROM undone_actions WHERE action_id = ?")
+            .bind(&action.id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(Some(action))
+    }
+
+    /// Write stored content back to the file an action modified
+    ///
+    /// Fails without writing unless the file currently holds `expected`.
+    async fn write_action_content(
+        &self,
+        action: &Action,
+        expected: Option<&str>,
+        content: Option<&str>,
+    ) -> Result<()> {
+        let (Some(file_path), Some(expected), Some(content)) =
+            (&action.file_path, expected, content)
+        else {
+            return Err(anyhow::anyhow!(
+                "Action {} has no stored content to restore",
+                action.id
+            ));
+        };
+
+        let current = tokio::fs::read_to_string(file_path)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", file_path, e))?;
+        if current != expected {
+            return Err(anyhow::anyhow!(
+                "{} has changed since action {}; not restoring it",
+                file_path,
+                action.id
+            ));
+        }
+
+        tokio::fs::write(file_path, content)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to restore {}: {}", file_path, e))
+    }
+
+    /// Convert a row of the actions table to an Action
+    fn row_to_action(&self, row: SqliteRow) -> Result<Action> {
+        let action_type = self.str_to_action_type(row.get::<&str, _>("action_type"))?;
+        let parameters: Option<HashMap<String, String>> = row
+            .get::<Option<&str>, _>("parameters")
+            .and_then(|s| serde_json::from_str(s).ok());
+        let metadata: Option<HashMap<String, String>> = row
+            .get::<Option<&str>, _>("metadata")
+            .and_then(|s| serde_json::from_str(s).ok());
+
+        Ok(Action {
+            id: row.get("id"),
+            action_type,
+            timestamp: row.get("timestamp"),
+            session_id: row.get("session_id"),
+            user_id: row.get("user_id"),
+            tool_name: row.get("tool_name"),
+            file_path: row.get("file_path"),
+            line_number: row.get::<Option<i64>, _>("line_number").map(|l| l as u32),
+            column_number: row.get::<Option<i64>, _>("column_number").map(|c| c as u32),
+            before_content: row.get("before_content"),
+            after_content: row.get("after_content"),
+            ai_reasoning: row.get("ai_reasoning"),
+            parameters,
+            result: row.get("result"),
+            success: row.get("success"),
+            error_message: row.get("error_message"),
+            duration_ms: row.get::<Option<i64>, _>("duration_ms").map(|d| d as u64),
+            token_usage: row.get::<Option<i64>, _>("token_usage").map(|t| t as u32),
+            metadata,
+        })
+    }
+
     /// Convert string to ActionType
     fn str_to_action_type(&self, s: &str) -> Result<ActionType> {
         match s {
@@ -1188,6 +1497,25 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_open_creates_database() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join("history").join("actions.db");
+
+        let manager = ActionHistoryManager::open(&path).await?;
+        manager
+            .log_agent_execution("session", "Refactorer", None, true, None, 5, 2)
+            .await?;
+        drop(manager);
+
+        let reopened = ActionHistoryManager::open(&path).await?;
+        let actions = reopened.get_actions_by_session("session").await?;
+        assert_eq!(actions.len(), 1);
+        assert_eq!(actions[0].tool_name.as_deref(), Some("Refactorer"));
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_file_modification_logging() -> Result<()> {
         // Use in-memory database for tests - faster and more reliable
@@ -2142,4 +2470,245 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_query_actions_filters() -> Result<()> {
+        let pool = SqlitePool::connect("sqlite::memory:").await?;
+
+        let manager = ActionHistoryManager::new(pool.clone());
+        manager.init().await?;
+
+        let first_edit = manager
+            .log_file_modification(
+                "session1",
+                "src/lib.rs",
+                Some(1),
+                None,
+                None,
+                None,
+                true,
+                None,
+            )
+            .await?;
+        let tool_call = manager
+            .log_tool_call("session1", "cargo", None, None, true, None, None, None)
+            .await?;
+        manager
+            .log_file_modification(
+                "session1",
+                "src/main.rs",
+                Some(2),
+                None,
+                None,
+                None,
+                true,
+                None,
+            )
+            .await?;
+        let second_edit = manager
+            .log_file_modification(
+                "session2",
+                "src/lib.rs",
+                Some(3),
+                None,
+                None,
+                None,
+                false,
+                None,
+            )
+            .await?;
+
+        let edits = manager
+            .query_actions(ActionFilter {
+                action_type: Some(ActionType::FileModification),
+                ..Default::default()
+            })
+            .await?;
+        assert_eq!(edits.len(), 3);
+
+        let tool_calls = manager
+            .query_actions(ActionFilter {
+                action_type: Some(ActionType::ToolCall),
+                ..Default::default()
+            })
+            .await?;
+        assert_eq!(tool_calls.len(), 1);
+        assert_eq!(tool_calls[0].id, tool_call);
+
+        // Actions on one file come back oldest first
+        let lib_actions = manager
+            .query_actions(ActionFilter {
+                file_path: Some("src/lib.rs".to_string()),
+                ..Default::default()
+            })
+            .await?;
+        let ids: Vec<&str> = lib_actions.iter().map(|a| a.id.as_str()).collect();
+        assert_eq!(ids, vec![first_edit.as_str(), second_edit.as_str()]);
+
+        let session_lib_actions = manager
+            .query_actions(ActionFilter {
+                session_id: Some("session1".to_string()),
+                file_path: Some("src/lib.rs".to_string()),
+                ..Default::default()
+            })
+            .await?;
+        assert_eq!(session_lib_actions.len(), 1);
+        assert_eq!(session_lib_actions[0].id, first_edit);
+
+        let future = manager
+            .query_actions(ActionFilter {
+                start_time: Some(chrono::Utc::now().timestamp() + 3600),
+                ..Default::default()
+            })
+            .await?;
+        assert!(future.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_query_actions_pagination() -> Result<()> {
+        let pool = SqlitePool::connect("sqlite::memory:").await?;
+
+        let manager = ActionHistoryManager::new(pool.clone());
+        manager.init().await?;
+
+        let mut ids = Vec::new();
+        for tool in ["tool1", "tool2", "tool3", "tool4", "tool5"] {
+            ids.push(
+                manager
+                    .log_tool_call("session1", tool, None, None, true, None, None, None)
+                    .await?,
+            );
+        }
+
+        let page = |offset| ActionFilter {
+            limit: Some(2),
+            offset,
+            ..Default::default()
+        };
+        let first = manager.query_actions(page(0)).await?;
+        let last = manager.query_actions(page(4)).await?;
+        assert_eq!(
+            first.iter().map(|a| &a.id).collect::<Vec<_>>(),
+            vec![&ids[0], &ids[1]]
+        );
+        assert_eq!(
+            last.iter().map(|a| &a.id).collect::<Vec<_>>(),
+            vec![&ids[4]]
+        );
+
+        // Oversized pages are capped
+        let all = manager
+            .query_actions(ActionFilter {
+                limit: Some(MAX_ACTION_QUERY_LIMIT + 1),
+                ..Default::default()
+            })
+            .await?;
+        assert_eq!(all.len(), 5);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_undo_redo_file_modifications() -> Result<()> {
+        let pool = SqlitePool::connect("sqlite::memory:").await?;
+
+        let manager = ActionHistoryManager::new(pool.clone());
+        manager.init().await?;
+
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join("lib.rs");
+        let file_path = path.to_str().unwrap();
+        let read = || std::fs::read_to_string(&path).unwrap();
+
+        // Apply two edits, logging each one
+        let versions = ["fn one() {}\n", "fn two() {}\n", "fn three() {}\n"];
+        std::fs::write(&path, versions[0])?;
+        for edit in [(versions[0], versions[1]), (versions[1], versions[2])] {
+            std::fs::write(&path, edit.1)?;
+            manager
+                .log_file_modification(
+                    "session1",
+                    file_path,
+                    Some(1),
+                    None,
+                    Some(edit.0.to_string()),
+                    Some(edit.1.to_string()),
+                    true,
+                    None,
+                )
+                .await?;
+        }
+
+        let undone = manager.undo_last_action().await?.unwrap();
+        assert_eq!(undone.after_content.as_deref(), Some(versions[2]));
+        assert_eq!(read(), versions[1]);
+
+        manager.undo_last_action().await?.unwrap();
+        assert_eq!(read(), versions[0]);
+        assert!(manager.undo_last_action().await?.is_none());
+
+        let redone = manager.redo_last_action().await?.unwrap();
+        assert_eq!(redone.after_content.as_deref(), Some(versions[1]));
+        assert_eq!(read(), versions[1]);
+
+        // A new edit clears the redo stack
+        manager
+            .log_file_modification(
+                "session1",
+                file_path,
+                Some(1),
+                None,
+                Some(versions[1].to_string()),
+                Some("fn four() {}\n".to_string()),
+                true,
+                None,
+            )
+            .await?;
+        assert!(manager.redo_last_action().await?.is_none());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_undo_keeps_later_edits() -> Result<()> {
+        let pool = SqlitePool::connect("sqlite::memory:").await?;
+
+        let manager = ActionHistoryManager::new(pool.clone());
+        manager.init().await?;
+
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join("lib.rs");
+        std::fs::write(&path, "fn two() {}\n")?;
+        manager
+            .log_file_modification(
+                "session1",
+                path.to_str().unwrap(),
+                Some(1),
+                None,
+                Some("fn one() {}\n".to_string()),
+                Some("fn two() {}\n".to_string()),
+                true,
+                None,
+            )
+            .await?;
+
+        // The file is edited again outside the action history
+        std::fs::write(&path, "fn two() {}\nfn extra() {}\n")?;
+
+        let error = manager.undo_last_action().await.unwrap_err();
+        assert!(error.to_string().contains("has changed since action"));
+        assert_eq!(
+            std::fs::read_to_string(&path)?,
+            "fn two() {}\nfn extra() {}\n"
+        );
+
+        // Once the file is back to the recorded content the undo goes through
+        std::fs::write(&path, "fn two() {}\n")?;
+        assert!(manager.undo_last_action().await?.is_some());
+        assert_eq!(std::fs::read_to_string(&path)?, "fn one() {}\n");
+
+        Ok(())
+    }
 }
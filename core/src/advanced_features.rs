@@ -558,6 +558,8 @@ mod tests {
             content: rust_code.to_string(),
             language: "rust".to_string(),
             modified: chrono::Utc::now(),
+            encoding: Default::default(),
+            line_ending: Default::default(),
         };
 
         engine.process_file(&file).await.unwrap();
@@ -605,6 +607,8 @@ mod tests {
             content: js_code.to_string(),
             language: "javascript".to_string(),
             modified: chrono::Utc::now(),
+            encoding: Default::default(),
+            line_ending: Default::default(),
         };
 
         engine.process_file(&file).await.unwrap();
@@ -640,6 +644,8 @@ mod tests {
             content: rust_code.to_string(),
             language: "rust".to_string(),
             modified: chrono::Utc::now(),
+            encoding: Default::default(),
+            line_ending: Default::default(),
         };
 
         engine.process_file(&file).await.unwrap();
@@ -0,0 +1,96 @@
+//! Analysis Configuration Module
+//!
+//! This module provides the tunable thresholds used by the core `CodeEngine` analysis.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Default upper bound on file size for deep (AST) analysis: 1 MiB
+pub const DEFAULT_MAX_ANALYSIS_BYTES: usize = 1024 * 1024;
+
+/// Default upper bound on `.unwrap()`/`.expect()`/`panic!` sites per line of a Rust function
+pub const DEFAULT_MAX_PANIC_DENSITY: f64 = 0.25;
+
+/// Default maximum line length, in characters
+pub const DEFAULT_MAX_LINE_LENGTH: usize = 100;
+
+/// Default number of files analyzed concurrently: the number of available CPUs
+pub fn default_max_parallelism() -> usize {
+    std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+}
+
+/// Configuration for the core code analysis
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AnalysisConfig {
+    /// Files larger than this many bytes skip AST analysis and only get basic line checks
+    pub max_analysis_bytes: usize,
+    /// Rust functions with more panicking calls per line than this are reported
+    pub max_panic_density: f64,
+    /// Maximum number of files `parallel_analyze_files` analyzes concurrently
+    pub max_parallelism: usize,
+    /// Lines longer than this many characters are reported
+    pub max_line_length: usize,
+    /// Whether TODO, FIXME and HACK comments are reported
+    pub report_todos: bool,
+    /// Ids of rules whose issues and suggestions are not reported, e.g. `style/line-length`
+    pub disabled_rules: HashSet<String>,
+}
+
+impl Default for AnalysisConfig {
+    fn default() -> Self {
+        Self {
+            max_analysis_bytes: DEFAULT_MAX_ANALYSIS_BYTES,
+            max_panic_density: DEFAULT_MAX_PANIC_DENSITY,
+            max_parallelism: default_max_parallelism(),
+            max_line_length: DEFAULT_MAX_LINE_LENGTH,
+            report_todos: true,
+            disabled_rules: HashSet::new(),
+        }
+    }
+}
+
+impl AnalysisConfig {
+    /// Whether the rule with this id is reported
+    pub fn is_rule_enabled(&self, rule_id: &str) -> bool {
+        !self.disabled_rules.contains(rule_id)
+    }
+}
+
+/// Named preset of analysis thresholds
+///
+/// Individual thresholds can still be overridden on top of a profile, e.g.
+/// `AnalysisConfig { max_analysis_bytes: 4096, ..AnalysisProfile::Strict.config() }`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AnalysisProfile {
+    /// 80-column lines, all TODOs reported, few panicking calls tolerated
+    Strict,
+    /// The default thresholds
+    Standard,
+    /// 120-column lines, TODOs ignored, many panicking calls tolerated
+    Relaxed,
+}
+
+impl AnalysisProfile {
+    /// The analysis configuration of this profile
+    pub fn config(self) -> AnalysisConfig {
+        let standard = AnalysisConfig::default();
+        match self {
+            AnalysisProfile::Strict => AnalysisConfig {
+                max_line_length: 80,
+                max_panic_density: 0.1,
+                report_todos: true,
+                ..standard
+            },
+            AnalysisProfile::Standard => standard,
+            AnalysisProfile::Relaxed => AnalysisConfig {
+                max_line_length: 120,
+                max_panic_density: 0.5,
+                report_todos: false,
+                ..standard
+            },
+        }
+    }
+}
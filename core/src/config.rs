@@ -51,6 +51,9 @@ pub struct LLMConfig {
     pub settings: ConfigSettings,
     /// Model selection preferences
     pub model_selection: ModelSelectionConfig,
+    /// Providers tried in order when the default provider is unavailable
+    #[serde(default)]
+    pub fallback_providers: Vec<String>,
 }
 
 impl Default for LLMConfig {
@@ -108,6 +111,7 @@ impl Default for LLMConfig {
             providers,
             settings: ConfigSettings::default(),
             model_selection: ModelSelectionConfig::default(),
+            fallback_providers: Vec::new(),
         }
     }
 }
@@ -515,8 +519,8 @@ impl ConfigManager {
                         );
                     }
                 }
-                LLMProvider::Ollama => {
-                    // Ollama doesn't require API key
+                LLMProvider::Ollama | LLMProvider::Mock => {
+                    // Local and offline providers don't require an API key
                 }
             }
 
@@ -0,0 +1,350 @@
+//! Context Gathering Module
+//!
+//! This module assembles the source an LLM needs to reason about one function,
+//! instead of the whole file: the function itself, the signatures of the
+//! functions it calls and the definitions of the types it names. Snippets are
+//! added in that order until the next one would exceed the token budget.
+//!
+//! Callees and types are resolved through the symbol table. The dependency graph
+//! of `semantic_analysis` is not used: its edges chain consecutive definitions
+//! rather than follow calls.
+
+use anyhow::{anyhow, Result};
+use tree_sitter::Node;
+
+use crate::language_parsing::{LanguageParser, SupportedLanguage};
+use crate::llm_integration::estimate_tokens;
+use crate::symbol_table::{Symbol, SymbolKind, SymbolTableManager};
+use crate::{is_function_node, CodeFile};
+
+/// Gather the source most relevant to the function enclosing `around_line`
+///
+/// The function and its calls must be indexed in `symbols`, see
+/// [`SymbolTableManager::index_calls`]. The target function comes first and is
+/// cut to the lines that fit when it alone exceeds `budget_tokens`.
+pub async fn gather_context(
+    symbols: &SymbolTableManager,
+    target: &CodeFile,
+    around_line: usize,
+    budget_tokens: usize,
+) -> Result<String> {
+    let lang = SupportedLanguage::from_str(&target.language).ok_or_else(|| {
+        anyhow!(
+            "Context gathering is not supported for language: {}",
+            target.language
+        )
+    })?;
+
+    let (function_line, function_text, called, referenced) = {
+        let mut parser = LanguageParser::new()?;
+        let tree = parser.parse(&target.content, &lang)?;
+        let function = enclosing_function(tree.root_node(), around_line).ok_or_else(|| {
+            anyhow!(
+                "No function encloses line {} of {}",
+                around_line,
+                target.path
+            )
+        })?;
+        (
+            function.start_position().row + 1,
+            target.content[function.byte_range()].to_string(),
+            called_names(function, &target.content),
+            referenced_names(function, &target.content),
+        )
+    };
+
+    let target_symbol = symbols
+        .get_symbols_by_file(&target.path)
+        .await?
+        .into_iter()
+        .find(|symbol| is_callable(&symbol.kind) && symbol.line as usize == function_line)
+        .ok_or_else(|| {
+            anyhow!(
+                "The function at {}:{} is not in the symbol table",
+                target.path,
+                function_line
+            )
+        })?;
+
+    // Callees in order of their first call
+    let mut callees = symbols.find_callees(&target_symbol.id).await?;
+    callees.retain(|callee| callee.id != target_symbol.id);
+    callees.sort_by_key(|callee| {
+        called
+            .iter()
+            .position(|name| name == &callee.name)
+            .unwrap_or(usize::MAX)
+    });
+
+    let mut types: Vec<Symbol> = Vec::new();
+    for name in &referenced {
+        if let Some(symbol) = symbols.find_definition(name).await? {
+            if is_type(&symbol.kind) && !types.iter().any(|ty| ty.id == symbol.id) {
+                types.push(symbol);
+            }
+        }
+    }
+
+    let mut context = String::new();
+    let target_snippet = snippet(&target.path, function_line, &function_text);
+    if !fits(&context, &target_snippet, budget_tokens) {
+        return Ok(truncated(&target_snippet, budget_tokens));
+    }
+    context.push_str(&target_snippet);
+
+    let mut parser = LanguageParser::new()?;
+    let callee_snippets = callees.iter().filter_map(|callee| {
+        let source = callee.signature.as_deref()?;
+        let signature = function_signature(&mut parser, &callee.language, source);
+        Some(snippet(&callee.file_path, callee.line as usize, &signature))
+    });
+    let type_snippets = types.iter().filter_map(|ty| {
+        let source = ty.signature.as_deref()?;
+        Some(snippet(&ty.file_path, ty.line as usize, source))
+    });
+
+    for next in callee_snippets.chain(type_snippets) {
+        if !fits(&context, &next, budget_tokens) {
+            break;
+        }
+        context.push_str(&next);
+    }
+    Ok(context.trim_end().to_string())
+}
+
+/// A snippet headed by the location it comes from
+fn snippet(path: &str, line: usize, text: &str) -> String {
+    format!("{}:{}\n{}\n\n", path, line, text.trim_end())
+}
+
+/// Whether `context` followed by `snippet` stays within the budget
+fn fits(context: &str, snippet: &str, budget_tokens: usize) -> bool {
+    estimate_tokens(&format!("{}{}", context, snippet)) <= budget_tokens
+}
+
+/// The leading lines of a snippet that fit in the budget
+fn truncated(snippet: &str, budget_tokens: usize) -> String {
+    let mut context = String::new();
+    for line in snippet.lines() {
+        let line = format!("{}\n", line);
+        if !fits(&context, &line, budget_tokens) {
+            break;
+        }
+        context.push_str(&line);
+    }
+    context.trim_end().to_string()
+}
+
+/// Whether a symbol kind is a function or method
+fn is_callable(kind: &SymbolKind) -> bool {
+    matches!(kind, SymbolKind::Function | SymbolKind::Method)
+}
+
+/// Whether a symbol kind is a type definition
+fn is_type(kind: &SymbolKind) -> bool {
+    matches!(
+        kind,
+        SymbolKind::Class
+            | SymbolKind::Struct
+            | SymbolKind::Interface
+            | SymbolKind::Enum
+            | SymbolKind::Trait
+            | SymbolKind::TypeAlias
+    )
+}
+
+/// Source of a function definition up to its body
+///
+/// Falls back to the first line when the source cannot be parsed on its own.
+fn function_signature(parser: &mut LanguageParser, language: &str, source: &str) -> String {
+    let first_line = || source.lines().next().unwrap_or_default().to_string();
+    let Some(lang) = SupportedLanguage::from_str(language) else {
+        return first_line();
+    };
+    let Ok(tree) = parser.parse(source, &lang) else {
+        return first_line();
+    };
+    let mut stack = vec![tree.root_node()];
+    while let Some(node) = stack.pop() {
+        if is_function_node(node.kind()) {
+            return match node.child_by_field_name("body") {
+                Some(body) => source[node.start_byte()..body.start_byte()]
+                    .trim_end()
+                    .to_string(),
+                None => first_line(),
+            };
+        }
+        let mut cursor = node.walk();
+        stack.extend(node.children(&mut cursor));
+    }
+    first_line()
+}
+
+/// Innermost function spanning a 1-based line
+fn enclosing_function(root: Node<'_>, line: usize) -> Option<Node<'_>> {
+    let row = line.saturating_sub(1);
+    let mut innermost: Option<Node> = None;
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        if node.start_position().row > row || node.end_position().row < row {
+            continue;
+        }
+        if is_function_node(node.kind())
+            && innermost.is_none_or(|inner| node.byte_range().len() < inner.byte_range().len())
+        {
+            innermost = Some(node);
+        }
+        let mut cursor = node.walk();
+        stack.extend(node.children(&mut cursor));
+    }
+    innermost
+}
+
+/// Names of the functions called inside a node, in order of first call
+fn called_names(node: Node, source: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut stack = vec![node];
+    while let Some(node) = stack.pop() {
+        if matches!(
+            node.kind(),
+            "call_expression" | "call" | "method_invocation" | "invocation_expression"
+        ) {
+            let callee = node
+                .child_by_field_name("function")
+                .or_else(|| node.child_by_field_name("name"))
+                .and_then(|callee| callee.utf8_text(source.as_bytes()).ok())
+                .and_then(last_identifier);
+            if let Some(callee) = callee {
+                push_unique(&mut names, callee);
+            }
+        }
+        let mut cursor = node.walk();
+        // Reversed so that the stack visits children in source order
+        let children: Vec<_> = node.children(&mut cursor).collect();
+        stack.extend(children.into_iter().rev());
+    }
+    names
+}
+
+/// Identifiers used inside a node, in order of first use
+fn referenced_names(node: Node, source: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut stack = vec![node];
+    while let Some(node) = stack.pop() {
+        if node.kind().ends_with("identifier") {
+            if let Ok(name) = node.utf8_text(source.as_bytes()) {
+                push_unique(&mut names, name);
+            }
+        }
+        let mut cursor = node.walk();
+        let children: Vec<_> = node.children(&mut cursor).collect();
+        stack.extend(children.into_iter().rev());
+    }
+    names
+}
+
+/// Last identifier of a callee path such as `self.helper` or `Config::load::<T>`
+fn last_identifier(path: &str) -> Option<&str> {
+    let path = path.split('<').next().unwrap_or(path);
+    path.rsplit(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .find(|segment| !segment.is_empty())
+}
+
+fn push_unique(names: &mut Vec<String>, name: &str) {
+    if !names.iter().any(|existing| existing == name) {
+        names.push(name.to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::SqlitePool;
+
+    use crate::language_parsing::{LanguageParser, SupportedLanguage};
+    use crate::symbol_table::SymbolTableManager;
+    use crate::CodeEngine;
+
+    const SOURCE: &str = r#"struct Order {
+    total: u32,
+}
+
+fn unrelated_report(lines: Vec<String>) -> String {
+    let mut report = String::new();
+    for line in lines {
+        report.push_str(&line);
+        report.push('\n');
+    }
+    report
+}
+
+fn apply_discount(order: &Order, percent: u32) -> u32 {
+    let discounted = order.total * (100 - percent);
+    discounted / 100
+}
+
+fn checkout(order: Order) -> u32 {
+    let total = apply_discount(&order, 10);
+    total + 5
+}
+"#;
+
+    #[tokio::test]
+    async fn test_gather_context_prefers_target_and_callees() {
+        let engine = CodeEngine::new().unwrap();
+        let file_id = engine
+            .load_file(
+                "src/orders.rs".to_string(),
+                SOURCE.to_string(),
+                "rust".to_string(),
+            )
+            .await
+            .unwrap();
+
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        let symbols = SymbolTableManager::new(pool);
+        symbols.init().await.unwrap();
+        // The target must be indexed before its context can be gathered
+        assert!(engine
+            .gather_context(&symbols, file_id, 20, 80)
+            .await
+            .is_err());
+
+        let tree = LanguageParser::new()
+            .unwrap()
+            .parse(SOURCE, &SupportedLanguage::Rust)
+            .unwrap();
+        symbols
+            .extract_symbols_from_ast(&tree, SOURCE, "src/orders.rs", "rust")
+            .await
+            .unwrap();
+        symbols
+            .index_calls(&tree, SOURCE, "src/orders.rs")
+            .await
+            .unwrap();
+
+        let context = engine
+            .gather_context(&symbols, file_id, 20, 80)
+            .await
+            .unwrap();
+        assert!(context.starts_with("src/orders.rs:19\nfn checkout"));
+        assert!(context.contains("fn apply_discount(order: &Order, percent: u32) -> u32"));
+        // Only the callee's signature is included, not its body
+        assert!(!context.contains("discounted / 100"));
+        assert!(context.contains("struct Order"));
+        assert!(!context.contains("unrelated_report"));
+        assert!(crate::llm_integration::estimate_tokens(&context) <= 80);
+
+        // A budget too small for the target keeps its leading lines
+        let context = engine
+            .gather_context(&symbols, file_id, 20, 14)
+            .await
+            .unwrap();
+        assert!(context.contains("fn checkout"));
+        assert!(!context.contains("apply_discount(order"));
+
+        assert!(engine
+            .gather_context(&symbols, file_id, 2, 80)
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,124 @@
+//! File Encoding Module
+//!
+//! This module detects the text encoding of source files that are not plain
+//! UTF-8, decodes them for analysis and encodes analyzed content back, so files
+//! from legacy codebases keep their encoding when they are written.
+
+use anyhow::{anyhow, Result};
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, UTF_8, WINDOWS_1252};
+use serde::{Deserialize, Serialize};
+
+/// Text encoding a file was loaded from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileEncoding {
+    /// WHATWG name of the encoding, such as `UTF-8` or `UTF-16LE`
+    pub name: String,
+    /// Whether the file started with a byte order mark
+    pub bom: bool,
+}
+
+impl Default for FileEncoding {
+    fn default() -> Self {
+        Self::from_encoding(UTF_8, false)
+    }
+}
+
+impl FileEncoding {
+    fn from_encoding(encoding: &'static Encoding, bom: bool) -> Self {
+        Self {
+            name: encoding.name().to_string(),
+            bom,
+        }
+    }
+
+    fn encoding(&self) -> Result<&'static Encoding> {
+        Encoding::for_label(self.name.as_bytes())
+            .ok_or_else(|| anyhow!("Unknown encoding: {}", self.name))
+    }
+
+    /// Whether this is UTF-8 without a byte order mark
+    pub fn is_plain_utf8(&self) -> bool {
+        self.name == UTF_8.name() && !self.bom
+    }
+
+    /// Whether `bytes` start with a UTF-8 or UTF-16 byte order mark
+    pub fn has_bom(bytes: &[u8]) -> bool {
+        Encoding::for_bom(bytes).is_some()
+    }
+
+    /// Detect the encoding of `bytes` and decode them
+    ///
+    /// A byte order mark decides the encoding when present. Otherwise the bytes are
+    /// UTF-8 if they are valid UTF-8, and Windows-1252, a superset of Latin-1, if
+    /// they are not.
+    pub fn decode(bytes: &[u8]) -> Result<(String, Self)> {
+        let (encoding, bom_length) = match Encoding::for_bom(bytes) {
+            Some((encoding, length)) => (encoding, length),
+            None if std::str::from_utf8(bytes).is_ok() => (UTF_8, 0),
+            None => (WINDOWS_1252, 0),
+        };
+
+        let (content, had_errors) = encoding.decode_without_bom_handling(&bytes[bom_length..]);
+        if had_errors {
+            return Err(anyhow!("Content is not valid {}", encoding.name()));
+        }
+        Ok((
+            content.into_owned(),
+            Self::from_encoding(encoding, bom_length > 0),
+        ))
+    }
+
+    /// Encode `content` back into this encoding, with its byte order mark if it had one
+    pub fn encode(&self, content: &str) -> Result<Vec<u8>> {
+        let encoding = self.encoding()?;
+
+        let mut bytes = Vec::with_capacity(content.len() + 3);
+        // encoding_rs only decodes UTF-16, so it is encoded here
+        if encoding == UTF_16LE || encoding == UTF_16BE {
+            let little_endian = encoding == UTF_16LE;
+            let to_bytes = |unit: u16| {
+                if little_endian {
+                    unit.to_le_bytes()
+                } else {
+                    unit.to_be_bytes()
+                }
+            };
+            if self.bom {
+                bytes.extend(to_bytes(0xFEFF));
+            }
+            for unit in content.encode_utf16() {
+                bytes.extend(to_bytes(unit));
+            }
+            return Ok(bytes);
+        }
+
+        if self.bom {
+            bytes.extend_from_slice(b"\xEF\xBB\xBF");
+        }
+        let (encoded, _, unmappable) = encoding.encode(content);
+        if unmappable {
+            return Err(anyhow!("Content cannot be represented in {}", self.name));
+        }
+        bytes.extend_from_slice(&encoded);
+        Ok(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latin1_without_bom_round_trips() {
+        let bytes = b"// caf\xE9 na\xEFve\n";
+        let (content, encoding) = FileEncoding::decode(bytes).unwrap();
+
+        assert_eq!(content, "// café naïve\n");
+        assert_eq!(encoding.name, "windows-1252");
+        assert!(!encoding.bom);
+        assert_eq!(encoding.encode(&content).unwrap(), bytes);
+
+        let (_, utf8) = FileEncoding::decode("// café\n".as_bytes()).unwrap();
+        assert!(utf8.is_plain_utf8());
+    }
+}
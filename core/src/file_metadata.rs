@@ -61,6 +61,27 @@ pub struct Pagination {
     pub offset: u32,
 }
 
+/// How an indexed file has drifted from the filesystem
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum FileStaleness {
+    /// The file changed on disk and needs reindexing
+    Modified,
+    /// The file no longer exists and its entry should be removed
+    Deleted,
+}
+
+/// Stable hash of file content, recorded with stored content so staleness checks
+/// compare hashes instead of loading every file's content
+pub fn content_hash(content: &[u8]) -> String {
+    // 64-bit FNV-1a
+    let hash = content
+        .iter()
+        .fold(0xcbf2_9ce4_8422_2325_u64, |hash, &byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        });
+    format!("{:016x}", hash)
+}
+
 /// File metadata manager
 pub struct FileMetadataManager {
     pool: SqlitePool,
@@ -185,9 +206,27 @@ impl FileMetadataManager {
         .execute(&self.pool)
         .await?;
 
+        self.record_content_hash(&metadata).await?;
         Ok(())
     }
 
+    /// Record the hash of a file's stored content, or forget it if none is stored
+    async fn record_content_hash(&self, metadata: &FileMetadata) -> Result<()> {
+        match &metadata.content {
+            Some(content) => {
+                self.set_hash(&metadata.id, &content_hash(content.as_bytes()))
+                    .await
+            }
+            None => {
+                sqlx::query("DELETE FROM file_hashes WHERE file_id = ?")
+                    .bind(&metadata.id)
+                    .execute(&self.pool)
+                    .await?;
+                Ok(())
+            }
+        }
+    }
+
     /// Get file metadata by ID
     pub async fn get_file_by_id(&self, id: &str) -> Result<Option<FileMetadata>> {
         let row = sqlx::query(
@@ -262,7 +301,11 @@ impl FileMetadataManager {
         .execute(&self.pool)
         .await?;
 
-        Ok(result.rows_affected() > 0)
+        let updated = result.rows_affected() > 0;
+        if updated {
+            self.record_content_hash(&metadata).await?;
+        }
+        Ok(updated)
     }
 
     /// Delete file metadata by ID
@@ -489,6 +532,9 @@ impl FileMetadataManager {
     }
 
     /// Set file hash
+    ///
+    /// Files are created and updated with the [`content_hash`] of their content,
+    /// which [`Self::check_staleness`] compares with the file on disk.
     pub async fn set_hash(&self, file_id: &str, hash: &str) -> Result<()> {
         sqlx::query(
             r#"
@@ -631,6 +677,74 @@ impl FileMetadataManager {
         Ok(())
     }
 
+    /// Find indexed files that have drifted from the filesystem
+    ///
+    /// Files with stored content are compared by content hash; the others by
+    /// size and modification time. Only a missing file counts as deleted; other
+    /// errors reading a file are returned.
+    pub async fn check_staleness(&self) -> Result<Vec<(String, FileStaleness)>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT f.path, f.modified_at, f.size, fh.hash
+            FROM files f
+            LEFT JOIN file_hashes fh ON f.id = fh.file_id
+            ORDER BY f.path
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let not_found = |e: &std::io::Error| e.kind() == std::io::ErrorKind::NotFound;
+        let mut stale = Vec::new();
+        for row in rows {
+            let path: String = row.get("path");
+            let disk_metadata = match tokio::fs::metadata(&path).await {
+                Ok(disk_metadata) => disk_metadata,
+                Err(e) if not_found(&e) => {
+                    stale.push((path, FileStaleness::Deleted));
+                    continue;
+                }
+                Err(e) => return Err(anyhow::anyhow!("Failed to stat {}: {}", path, e)),
+            };
+
+            let stored_hash: Option<String> = row.get("hash");
+            let modified = match stored_hash {
+                Some(hash) => match tokio::fs::read(&path).await {
+                    Ok(disk) => content_hash(&disk) != hash,
+                    Err(e) if not_found(&e) => {
+                        stale.push((path, FileStaleness::Deleted));
+                        continue;
+                    }
+                    Err(e) => return Err(anyhow::anyhow!("Failed to read {}: {}", path, e)),
+                },
+                None => {
+                    let disk_modified = disk_metadata
+                        .modified()
+                        .ok()
+                        .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+                        .map_or(i64::MAX, |since_epoch| since_epoch.as_secs() as i64);
+                    disk_metadata.len() as i64 != row.get::<i64, _>("size")
+                        || disk_modified > row.get::<i64, _>("modified_at")
+                }
+            };
+            if modified {
+                stale.push((path, FileStaleness::Modified));
+            }
+        }
+
+        Ok(stale)
+    }
+
+    /// Paths of indexed files that changed on disk or were deleted
+    pub async fn stale_files(&self) -> Result<Vec<String>> {
+        Ok(self
+            .check_staleness()
+            .await?
+            .into_iter()
+            .map(|(path, _)| path)
+            .collect())
+    }
+
     /// Get recently modified files
     pub async fn get_recently_modified_files(&self, limit: u32) -> Result<Vec<FileMetadata>> {
         let rows = sqlx::query(
@@ -1180,6 +1294,68 @@ mod tests {
         assert_eq!(recent_files[0].path, "/src/newest.rs");
         assert_eq!(recent_files[1].path, "/src/newer.rs");
     }
+
+    #[tokio::test]
+    async fn test_stale_files_after_disk_changes() {
+        let temp_dir = TempDir::new().unwrap();
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        let manager = FileMetadataManager::new(pool);
+        manager.init().await.unwrap();
+
+        let mut paths = Vec::new();
+        for name in ["edited.rs", "removed.rs", "unchanged.rs"] {
+            let path = temp_dir.path().join(name);
+            let content = format!("fn {}() {{}}", name.trim_end_matches(".rs"));
+            std::fs::write(&path, &content).unwrap();
+            let path = path.to_string_lossy().to_string();
+            manager
+                .create_file(FileMetadata {
+                    id: Uuid::new_v4().to_string(),
+                    path: path.clone(),
+                    language: "rust".to_string(),
+                    size: content.len() as i64,
+                    content: Some(content),
+                    created_at: 1234567890,
+                    modified_at: chrono::Utc::now().timestamp(),
+                })
+                .await
+                .unwrap();
+            paths.push(path);
+        }
+        assert!(manager.stale_files().await.unwrap().is_empty());
+
+        std::fs::write(&paths[0], "fn edited() { changed(); }").unwrap();
+        std::fs::remove_file(&paths[1]).unwrap();
+
+        assert_eq!(
+            manager.check_staleness().await.unwrap(),
+            vec![
+                (paths[0].clone(), FileStaleness::Modified),
+                (paths[1].clone(), FileStaleness::Deleted),
+            ]
+        );
+        assert_eq!(
+            manager.stale_files().await.unwrap(),
+            vec![paths[0].clone(), paths[1].clone()]
+        );
+
+        // A file that exists but cannot be read is an error, not a deletion
+        let unreadable = temp_dir.path().join("dir.rs");
+        std::fs::create_dir(&unreadable).unwrap();
+        manager
+            .create_file(FileMetadata {
+                id: Uuid::new_v4().to_string(),
+                path: unreadable.to_string_lossy().to_string(),
+                language: "rust".to_string(),
+                content: Some(String::new()),
+                created_at: 1234567890,
+                modified_at: chrono::Utc::now().timestamp(),
+                size: 0,
+            })
+            .await
+            .unwrap();
+        assert!(manager.check_staleness().await.is_err());
+    }
 }
 
 impl Default for FileFilter {
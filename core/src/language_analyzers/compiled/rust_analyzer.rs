@@ -43,6 +43,8 @@ impl RustAnalyzer {
                         line_number: node.start_position().row + 1,
                         column_number: node.start_position().column,
                         suggestion: Some("Use .count() or .len() directly on iterator".to_string()),
+                        rule_id: Some("perf/collect-len".to_string()),
+                        source: None,
                     });
                 }
             }
@@ -29,6 +29,8 @@ impl AnalysisUtils {
                     line_number,
                     column_number,
                     suggestion: Some("Address the technical debt".to_string()),
+                    rule_id: Some("practice/todo-comment".to_string()),
+                    source: None,
                 });
             }
         }
@@ -49,6 +51,8 @@ impl AnalysisUtils {
             line_number,
             column_number,
             suggestion: Some(format!("Consider refactoring this complex {}", entity_type)),
+            rule_id: Some("practice/high-complexity".to_string()),
+            source: None,
         }
     }
 
@@ -51,6 +51,15 @@ impl LanguageAnalyzerManager {
         Ok(LanguageAnalyzerManager { analyzers })
     }
 
+    /// Register the analyzer for a language, replacing any existing one
+    pub fn register_analyzer(
+        &mut self,
+        language: SupportedLanguage,
+        analyzer: Box<dyn LanguageAnalyzer + Send + Sync>,
+    ) {
+        self.analyzers.insert(language, analyzer);
+    }
+
     /// Get analyzer for a specific language
     pub fn get_analyzer(&self, language: &SupportedLanguage) -> Option<&dyn LanguageAnalyzer> {
         self.analyzers
@@ -46,6 +46,8 @@ impl ClojureAnalyzer {
                         line_number: node.start_position().row + 1,
                         column_number: node.start_position().column,
                         suggestion: Some("Use let-binding or threading macros to reduce nesting".to_string()),
+                        rule_id: Some("practice/deep-nesting".to_string()),
+                        source: None,
                     });
                 }
             }
@@ -64,6 +66,8 @@ impl ClojureAnalyzer {
                             line_number: name_node.start_position().row + 1,
                             column_number: name_node.start_position().column,
                             suggestion: Some("Use kebab-case for function names".to_string()),
+                            rule_id: Some("style/naming-convention".to_string()),
+                            source: None,
                         });
                     }
                 }
@@ -47,6 +47,8 @@ impl HaskellAnalyzer {
                             line_number: name_node.start_position().row + 1,
                             column_number: name_node.start_position().column,
                             suggestion: Some("Use PascalCase for type names".to_string()),
+                            rule_id: Some("style/naming-convention".to_string()),
+                            source: None,
                         });
                     }
                 }
@@ -64,6 +66,8 @@ impl HaskellAnalyzer {
                         line_number: node.start_position().row + 1,
                         column_number: node.start_position().column,
                         suggestion: Some("Use pattern matching for better readability".to_string()),
+                        rule_id: Some("style/pattern-matching".to_string()),
+                        source: None,
                     });
                 }
             }
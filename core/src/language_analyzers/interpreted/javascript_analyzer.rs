@@ -33,18 +33,22 @@ impl JavaScriptAnalyzer {
                 }
             }
             "binary_expression" => {
-                let content = &file_content[node.start_byte()..node.end_byte()];
-                if content.contains("==") && !content.contains("===") {
+                let operator = node
+                    .child_by_field_name("operator")
+                    .filter(|operator| operator.kind() == "==");
+                if let Some(operator) = operator {
                     issues.push(CodeIssue {
                         id: uuid::Uuid::new_v4(),
                         issue_type: IssueType::PotentialBug,
                         severity: Severity::High,
                         description: "Use of == instead of === for comparison".to_string(),
-                        line_number: node.start_position().row + 1,
-                        column_number: node.start_position().column,
+                        line_number: operator.start_position().row + 1,
+                        column_number: operator.start_position().column,
                         suggestion: Some(
                             "Use === for comparison to avoid type coercion".to_string(),
                         ),
+                        rule_id: Some("bug/loose-equality".to_string()),
+                        source: None,
                     });
                 }
             }
@@ -61,6 +65,8 @@ impl JavaScriptAnalyzer {
                         suggestion: Some(
                             "Use 'let' or 'const' instead of 'var' for better scoping".to_string(),
                         ),
+                        rule_id: Some("practice/var-usage".to_string()),
+                        source: None,
                     });
                 }
             }
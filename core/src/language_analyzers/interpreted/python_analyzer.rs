@@ -45,6 +45,8 @@ impl PythonAnalyzer {
                         suggestion: Some(
                             "Use explicit imports instead of wildcard imports".to_string(),
                         ),
+                        rule_id: Some("practice/wildcard-import".to_string()),
+                        source: None,
                     });
                 }
             }
@@ -47,6 +47,8 @@ impl TypeScriptAnalyzer {
                             line_number: name_node.start_position().row + 1,
                             column_number: name_node.start_position().column,
                             suggestion: Some("Use PascalCase for interface names".to_string()),
+                            rule_id: Some("style/naming-convention".to_string()),
+                            source: None,
                         });
                     }
                 }
@@ -67,6 +69,8 @@ impl TypeScriptAnalyzer {
                             "Add type annotations to function parameters and return type"
                                 .to_string(),
                         ),
+                        rule_id: Some("practice/missing-type-annotation".to_string()),
+                        source: None,
                     });
                 }
 
@@ -84,6 +88,8 @@ impl TypeScriptAnalyzer {
                         line_number: node.start_position().row + 1,
                         column_number: node.start_position().column,
                         suggestion: Some("Consider breaking down complex functions".to_string()),
+                        rule_id: Some("practice/high-complexity".to_string()),
+                        source: None,
                     });
                 }
             }
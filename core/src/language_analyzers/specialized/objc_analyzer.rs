@@ -47,6 +47,8 @@ impl ObjCAnalyzer {
                             line_number: name_node.start_position().row + 1,
                             column_number: name_node.start_position().column,
                             suggestion: Some("Use PascalCase for class names".to_string()),
+                            rule_id: Some("style/naming-convention".to_string()),
+                            source: None,
                         });
                     }
                 }
@@ -65,6 +67,8 @@ impl ObjCAnalyzer {
                         line_number: node.start_position().row + 1,
                         column_number: node.start_position().column,
                         suggestion: Some("Consider breaking down complex methods".to_string()),
+                        rule_id: Some("practice/high-complexity".to_string()),
+                        source: None,
                     });
                 }
             }
@@ -49,6 +49,8 @@ impl RAnalyzer {
                             suggestion: Some(
                                 "Use dots instead of underscores in function names".to_string(),
                             ),
+                            rule_id: Some("style/naming-convention".to_string()),
+                            source: None,
                         });
                     }
                 }
@@ -45,6 +45,8 @@ impl ShellAnalyzer {
                         line_number: 1,
                         column_number: 0,
                         suggestion: Some("Add shebang at the beginning of the script".to_string()),
+                        rule_id: Some("practice/missing-shebang".to_string()),
+                        source: None,
                     });
                 }
             }
@@ -5,8 +5,10 @@
 
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use std::collections::hash_map::Entry;
 use std::collections::HashMap;
-use tree_sitter::{Language, Parser, Query, QueryCursor};
+use std::sync::{OnceLock, RwLock};
+use tree_sitter::{InputEdit, Language, Parser, Point, Query, QueryCursor, Tree};
 
 /// Supported programming languages
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
@@ -27,12 +29,65 @@ pub enum SupportedLanguage {
     Scala,
     // R,  // Removed due to version conflicts
     Shell,
+    /// A grammar registered at runtime with [`LanguageParser::register_grammar`]
+    Custom(String),
 }
 
+/// Grammars registered at runtime, by lowercase name
+///
+/// Names are leaked on registration so that [`SupportedLanguage::as_str`] can
+/// keep returning `&'static str`; grammars are registered once and never removed.
+fn custom_grammars() -> &'static RwLock<HashMap<String, (&'static str, Language)>> {
+    static GRAMMARS: OnceLock<RwLock<HashMap<String, (&'static str, Language)>>> = OnceLock::new();
+    GRAMMARS.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+/// Look up a grammar registered at runtime
+fn custom_grammar(name: &str) -> Option<(&'static str, Language)> {
+    custom_grammars()
+        .read()
+        .ok()?
+        .get(&name.to_lowercase())
+        .copied()
+}
+
+/// Names of the built-in languages accepted by [`SupportedLanguage::from_str`]
+const BUILTIN_NAMES: &[&str] = &[
+    "javascript",
+    "js",
+    "jsx",
+    "typescript",
+    "ts",
+    "tsx",
+    "python",
+    "py",
+    "java",
+    "c",
+    "cpp",
+    "c++",
+    "csharp",
+    "c#",
+    "cs",
+    "rust",
+    "rs",
+    "go",
+    "golang",
+    "ruby",
+    "rb",
+    "php",
+    "swift",
+    "scala",
+    "shell",
+    "bash",
+    "sh",
+];
+
 impl SupportedLanguage {
     /// Get the Tree-sitter language for this supported language
-    pub fn get_language(&self) -> Language {
-        match self {
+    ///
+    /// Fails for a [`SupportedLanguage::Custom`] name that is not registered.
+    pub fn get_language(&self) -> Result<Language> {
+        Ok(match self {
             SupportedLanguage::JavaScript => tree_sitter_javascript::language(),
             SupportedLanguage::TypeScript => tree_sitter_typescript::language_typescript(),
             SupportedLanguage::Python => tree_sitter_python::language(),
@@ -49,8 +104,13 @@ impl SupportedLanguage {
             SupportedLanguage::Scala => tree_sitter_scala::language(),
             // SupportedLanguage::R => tree_sitter_r::language(),  // Removed due to version conflicts
             SupportedLanguage::Shell => tree_sitter_bash::language(),
+            SupportedLanguage::Custom(name) => {
+                custom_grammar(name)
+                    .map(|(_, language)| language)
+                    .ok_or_else(|| anyhow::anyhow!("Grammar '{}' is not registered", name))?
+            }
             _ => tree_sitter_javascript::language(), // Default fallback
-        }
+        })
     }
 
     /// Get the language name as a string
@@ -72,14 +132,29 @@ impl SupportedLanguage {
             SupportedLanguage::Scala => "scala",
             // SupportedLanguage::R => "r",  // Removed due to version conflicts
             SupportedLanguage::Shell => "shell",
+            SupportedLanguage::Custom(name) => custom_grammar(name)
+                .map(|(name, _)| name)
+                .unwrap_or("custom"),
         }
     }
 
+    /// Every name [`SupportedLanguage::from_str`] resolves, including aliases
+    /// and registered grammars
+    pub fn known_names() -> Vec<&'static str> {
+        let mut names = BUILTIN_NAMES.to_vec();
+        if let Ok(grammars) = custom_grammars().read() {
+            let mut custom: Vec<&'static str> = grammars.values().map(|(name, _)| *name).collect();
+            custom.sort_unstable();
+            names.extend(custom);
+        }
+        names
+    }
+
     /// Convert from a string to SupportedLanguage
     pub fn from_str(lang_str: &str) -> Option<Self> {
         match lang_str.to_lowercase().as_str() {
-            "javascript" | "js" => Some(SupportedLanguage::JavaScript),
-            "typescript" | "ts" => Some(SupportedLanguage::TypeScript),
+            "javascript" | "js" | "jsx" => Some(SupportedLanguage::JavaScript),
+            "typescript" | "ts" | "tsx" => Some(SupportedLanguage::TypeScript),
             "python" | "py" => Some(SupportedLanguage::Python),
             "java" => Some(SupportedLanguage::Java),
             "c" => Some(SupportedLanguage::C),
@@ -94,7 +169,9 @@ impl SupportedLanguage {
             "scala" => Some(SupportedLanguage::Scala),
             // "r" => Some(SupportedLanguage::R),  // Removed due to version conflicts
             "shell" | "bash" | "sh" => Some(SupportedLanguage::Shell),
-            _ => None,
+            name => {
+                custom_grammar(name).map(|(name, _)| SupportedLanguage::Custom(name.to_string()))
+            }
         }
     }
 
@@ -116,7 +193,9 @@ impl SupportedLanguage {
             "c" => Ok(SupportedLanguage::C),
             "cpp" | "cxx" | "cc" => Ok(SupportedLanguage::Cpp),
             "rs" => Ok(SupportedLanguage::Rust),
-            _ => Err(anyhow::anyhow!("Unsupported file extension: {}", extension)),
+            _ => custom_grammar(&extension)
+                .map(|(name, _)| SupportedLanguage::Custom(name.to_string()))
+                .ok_or_else(|| anyhow::anyhow!("Unsupported file extension: {}", extension)),
         }
     }
 }
@@ -152,23 +231,74 @@ impl LanguageParser {
             SupportedLanguage::Shell,
         ] {
             let mut parser = Parser::new();
-            parser.set_language(lang.get_language())?;
+            parser.set_language(lang.get_language()?)?;
             parsers.insert(lang, parser);
         }
 
         Ok(LanguageParser { parsers })
     }
 
+    /// Register a Tree-sitter grammar under `name`
+    ///
+    /// Afterwards [`SupportedLanguage::from_str`] resolves `name`, and files with
+    /// `name` as their extension are detected as that language, so they are
+    /// parsed with the grammar. Built-in languages cannot be replaced, and a
+    /// name can only be registered once.
+    pub fn register_grammar(name: &str, language: Language) -> Result<()> {
+        let name = name.trim().to_lowercase();
+        if name.is_empty() {
+            return Err(anyhow::anyhow!("Grammar name must not be empty"));
+        }
+        if BUILTIN_NAMES.contains(&name.as_str()) {
+            return Err(anyhow::anyhow!("Language '{}' is already registered", name));
+        }
+
+        // Reject grammars built for an incompatible Tree-sitter version up front
+        Parser::new()
+            .set_language(language)
+            .map_err(|e| anyhow::anyhow!("Grammar '{}' cannot be used: {}", name, e))?;
+
+        let mut grammars = custom_grammars()
+            .write()
+            .map_err(|_| anyhow::anyhow!("Grammar registry is poisoned"))?;
+        match grammars.entry(name) {
+            Entry::Occupied(entry) => Err(anyhow::anyhow!(
+                "Language '{}' is already registered",
+                entry.key()
+            )),
+            Entry::Vacant(entry) => {
+                let leaked: &'static str = Box::leak(entry.key().clone().into_boxed_str());
+                entry.insert((leaked, language));
+                Ok(())
+            }
+        }
+    }
+
+    /// Get the parser for a language, creating one for registered grammars
+    fn parser_for(&mut self, language: &SupportedLanguage) -> Result<&mut Parser> {
+        match self.parsers.entry(language.clone()) {
+            Entry::Occupied(entry) => Ok(entry.into_mut()),
+            Entry::Vacant(entry) => {
+                let SupportedLanguage::Custom(name) = language else {
+                    return Err(anyhow::anyhow!("Language {:?} is not supported", language));
+                };
+                let (_, grammar) = custom_grammar(name)
+                    .ok_or_else(|| anyhow::anyhow!("Grammar '{}' is not registered", name))?;
+
+                let mut parser = Parser::new();
+                parser.set_language(grammar)?;
+                Ok(entry.insert(parser))
+            }
+        }
+    }
+
     /// Parse source code for a specific language
     pub fn parse(
         &mut self,
         source_code: &str,
         language: &SupportedLanguage,
     ) -> Result<tree_sitter::Tree> {
-        let parser = self
-            .parsers
-            .get_mut(language)
-            .ok_or_else(|| anyhow::anyhow!("Language {:?} is not supported", language))?;
+        let parser = self.parser_for(language)?;
 
         let tree = parser
             .parse(source_code, None)
@@ -177,6 +307,28 @@ impl LanguageParser {
         Ok(tree)
     }
 
+    /// Reparse edited source code, reusing the tree of the previous version
+    ///
+    /// `edit` describes how `old_tree`'s source became `new_content`, e.g. as
+    /// built by [`line_edit`]. Only the edited region is reparsed, which is much
+    /// faster than [`parse`](Self::parse) after small edits.
+    pub fn parse_incremental(
+        &mut self,
+        new_content: &str,
+        old_tree: &Tree,
+        edit: InputEdit,
+        language: &SupportedLanguage,
+    ) -> Result<Tree> {
+        let parser = self.parser_for(language)?;
+
+        let mut edited = old_tree.clone();
+        edited.edit(&edit);
+
+        parser
+            .parse(new_content, Some(&edited))
+            .ok_or_else(|| anyhow::anyhow!("Failed to reparse code for language {:?}", language))
+    }
+
     /// Parse source code with automatic language detection
     pub fn parse_with_detection(
         &mut self,
@@ -201,7 +353,7 @@ impl LanguageParser {
         query_str: &str,
         source_code: &str,
     ) -> Result<Vec<QueryMatch<'a>>> {
-        let query = Query::new(language.get_language(), query_str)
+        let query = Query::new(language.get_language()?, query_str)
             .map_err(|e| anyhow::anyhow!("Invalid query: {}", e))?;
 
         let mut cursor = QueryCursor::new();
@@ -226,6 +378,93 @@ impl LanguageParser {
     }
 }
 
+/// Position of a byte offset as a Tree-sitter point (0-based row, byte column)
+pub fn point_at(content: &str, byte: usize) -> Point {
+    let before = &content.as_bytes()[..byte.min(content.len())];
+    let row = before.iter().filter(|&&b| b == b'\n').count();
+    let line_start = before
+        .iter()
+        .rposition(|&b| b == b'\n')
+        .map_or(0, |i| i + 1);
+    Point::new(row, before.len() - line_start)
+}
+
+/// Build the edit replacing bytes `start_byte..old_end_byte` of `old_content`
+/// with `new_text`
+pub fn input_edit(
+    old_content: &str,
+    start_byte: usize,
+    old_end_byte: usize,
+    new_text: &str,
+) -> InputEdit {
+    let start_position = point_at(old_content, start_byte);
+    let new_end_position = match new_text.rfind('\n') {
+        Some(last_newline) => Point::new(
+            start_position.row + new_text.matches('\n').count(),
+            new_text.len() - last_newline - 1,
+        ),
+        None => Point::new(start_position.row, start_position.column + new_text.len()),
+    };
+
+    InputEdit {
+        start_byte,
+        old_end_byte,
+        new_end_byte: start_byte + new_text.len(),
+        start_position,
+        old_end_position: point_at(old_content, old_end_byte),
+        new_end_position,
+    }
+}
+
+/// Replace the 0-based lines `start_line..end_line` of `old_content` with
+/// `new_text`, returning the new content and the matching Tree-sitter edit
+///
+/// `new_text` replaces whole lines, so it should end with a newline unless it
+/// is empty. An empty line range inserts `new_text` before `start_line`.
+pub fn line_edit(
+    old_content: &str,
+    start_line: usize,
+    end_line: usize,
+    new_text: &str,
+) -> Result<(String, InputEdit)> {
+    if start_line > end_line {
+        return Err(anyhow::anyhow!(
+            "Invalid line range {}..{}",
+            start_line,
+            end_line
+        ));
+    }
+
+    let line_starts: Vec<usize> = std::iter::once(0)
+        .chain(old_content.match_indices('\n').map(|(i, _)| i + 1))
+        .collect();
+    let byte_of_line = |line: usize| -> Result<usize> {
+        match line_starts.get(line) {
+            Some(&byte) => Ok(byte),
+            None if line == line_starts.len() => Ok(old_content.len()),
+            None => Err(anyhow::anyhow!(
+                "Line {} is past the end of the content ({} lines)",
+                line,
+                line_starts.len()
+            )),
+        }
+    };
+
+    let start_byte = byte_of_line(start_line)?;
+    let old_end_byte = byte_of_line(end_line)?;
+    let new_content = format!(
+        "{}{}{}",
+        &old_content[..start_byte],
+        new_text,
+        &old_content[old_end_byte..]
+    );
+
+    Ok((
+        new_content,
+        input_edit(old_content, start_byte, old_end_byte, new_text),
+    ))
+}
+
 /// Represents a query match in the AST
 #[derive(Debug, Clone)]
 pub struct QueryMatch<'a> {
@@ -294,6 +533,13 @@ mod tests {
             Some(SupportedLanguage::Python)
         );
         assert_eq!(SupportedLanguage::from_str("invalid"), None);
+        assert_eq!(
+            SupportedLanguage::from_str("tsx"),
+            Some(SupportedLanguage::TypeScript)
+        );
+        for name in SupportedLanguage::known_names() {
+            assert!(SupportedLanguage::from_str(name).is_some(), "{}", name);
+        }
     }
 
     #[test]
@@ -420,6 +666,98 @@ mod tests {
         assert!(root_node.has_error() == false);
     }
 
+    /// Kind and byte range of every node, in pre-order
+    fn node_spans(tree: &Tree) -> Vec<(String, usize, usize)> {
+        let mut spans = Vec::new();
+        let mut stack = vec![tree.root_node()];
+        while let Some(node) = stack.pop() {
+            spans.push((node.kind().to_string(), node.start_byte(), node.end_byte()));
+            for i in (0..node.child_count()).rev() {
+                if let Some(child) = node.child(i) {
+                    stack.push(child);
+                }
+            }
+        }
+        spans
+    }
+
+    #[test]
+    fn test_incremental_reparse_matches_full_parse() {
+        let mut parser = LanguageParser::new().unwrap();
+        let code = "fn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n\n\
+                    fn main() {\n    println!(\"{}\", add(1, 2));\n}\n";
+        let old_tree = parser.parse(code, &SupportedLanguage::Rust).unwrap();
+
+        let (new_code, edit) =
+            line_edit(code, 1, 2, "    let sum = a + b;\n    sum * 2\n").unwrap();
+        assert!(new_code.contains("sum * 2"));
+        assert_eq!(edit.start_position, Point::new(1, 0));
+        assert_eq!(edit.new_end_position, Point::new(3, 0));
+
+        let incremental = parser
+            .parse_incremental(&new_code, &old_tree, edit, &SupportedLanguage::Rust)
+            .unwrap();
+        let full = parser.parse(&new_code, &SupportedLanguage::Rust).unwrap();
+
+        assert!(!incremental.root_node().has_error());
+        assert_eq!(
+            incremental.root_node().to_sexp(),
+            full.root_node().to_sexp()
+        );
+        assert_eq!(node_spans(&incremental), node_spans(&full));
+    }
+
+    #[test]
+    fn test_input_edit_within_line() {
+        let code = "let total = 1;\nlet x = 2;\n";
+        let edit = input_edit(code, 19, 20, "value");
+
+        assert_eq!(edit.start_position, Point::new(1, 4));
+        assert_eq!(edit.old_end_position, Point::new(1, 5));
+        assert_eq!(edit.new_end_position, Point::new(1, 9));
+        assert_eq!(edit.new_end_byte, 24);
+
+        assert!(line_edit(code, 2, 1, "").is_err());
+        assert!(line_edit(code, 0, 5, "").is_err());
+    }
+
+    #[test]
+    fn test_register_grammar() {
+        LanguageParser::register_grammar("json", tree_sitter_json::language()).unwrap();
+
+        let json = SupportedLanguage::from_str("JSON").unwrap();
+        assert_eq!(json, SupportedLanguage::Custom("json".to_string()));
+        assert_eq!(json.as_str(), "json");
+        assert_eq!(
+            SupportedLanguage::detect_language("data.json").unwrap(),
+            json
+        );
+
+        let mut parser = LanguageParser::new().unwrap();
+        let (tree, language) = parser
+            .parse_with_detection(r#"{"name": "odincode", "tags": [1, 2]}"#, "data.json")
+            .unwrap();
+        assert_eq!(language, json);
+        assert_eq!(tree.root_node().kind(), "document");
+        assert!(!tree.root_node().has_error());
+
+        // Built-in grammars are still used and cannot be replaced
+        assert_eq!(
+            SupportedLanguage::from_str("rust"),
+            Some(SupportedLanguage::Rust)
+        );
+        assert!(LanguageParser::register_grammar("rust", tree_sitter_json::language()).is_err());
+        assert!(LanguageParser::register_grammar("json", tree_sitter_json::language()).is_err());
+        assert!(parser
+            .parse("fn main() {}", &SupportedLanguage::Rust)
+            .is_ok());
+
+        // Unregistered grammars are an error rather than a silent fallback
+        let unknown = SupportedLanguage::Custom("unregistered".to_string());
+        assert!(unknown.get_language().is_err());
+        assert!(parser.parse("{}", &unknown).is_err());
+    }
+
     #[test]
     fn test_parse_with_detection() {
         let mut parser = LanguageParser::new().unwrap();
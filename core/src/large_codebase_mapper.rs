@@ -13,6 +13,9 @@ use uuid::Uuid;
 use crate::CodeFile;
 use odincode_databases::DatabaseManager;
 
+pub mod dependencies;
+pub mod storage;
+
 /// Represents a code entity (function, class, variable, etc.)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CodeEntity {
@@ -785,6 +788,51 @@ impl CodeGraph {
     }
 }
 
+/// Run `task` on every item in a `JoinSet`, with at most `parallelism` tasks
+/// alive at a time
+///
+/// A task is only spawned once a semaphore permit is free, so a large input
+/// never has all its tasks spawned at once. Outputs are returned in completion
+/// order; a `parallelism` of 0 is treated as 1.
+async fn run_bounded<T, F, Fut>(
+    items: Vec<T>,
+    parallelism: usize,
+    task: F,
+) -> Vec<std::result::Result<Fut::Output, tokio::task::JoinError>>
+where
+    F: Fn(T) -> Fut,
+    Fut: std::future::Future + Send + 'static,
+    Fut::Output: Send + 'static,
+{
+    let semaphore = std::sync::Arc::new(tokio::sync::Semaphore::new(parallelism.max(1)));
+    let mut join_set = tokio::task::JoinSet::new();
+    let mut outputs = Vec::with_capacity(items.len());
+
+    for item in items {
+        let permit = semaphore
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("semaphore is never closed");
+        let future = task(item);
+        join_set.spawn(async move {
+            let output = future.await;
+            drop(permit);
+            output
+        });
+
+        // Collect whatever already finished so the set stays small
+        while let Some(outcome) = join_set.try_join_next() {
+            outputs.push(outcome);
+        }
+    }
+
+    while let Some(outcome) = join_set.join_next().await {
+        outputs.push(outcome);
+    }
+    outputs
+}
+
 /// Performance optimizer for large codebases
 pub struct PerformanceOptimizer {
     /// Large codebase mapper for efficient code navigation
@@ -806,45 +854,44 @@ impl PerformanceOptimizer {
         self.large_codebase_mapper.clone()
     }
 
-    /// Perform parallel analysis on multiple files
+    /// Perform parallel analysis on multiple files, analyzing at most
+    /// `parallelism` of them at a time
     pub async fn parallel_analysis(
         &self,
         files: Vec<CodeFile>,
+        parallelism: usize,
     ) -> Result<HashMap<Uuid, crate::AnalysisResult>> {
-        debug!("Performing parallel analysis on {} files", files.len());
-
-        let mut results = Vec::new();
+        debug!(
+            "Performing parallel analysis on {} files, {} at a time",
+            files.len(),
+            parallelism
+        );
 
-        // Process files in parallel using tokio
-        let mut tasks = Vec::new();
-        for file in files {
-            let mapper = self.large_codebase_mapper.clone();
-            let task =
-                tokio::spawn(async move { mapper.process_file(&file.path, &file.content).await });
-            tasks.push(task);
-        }
+        let mapper = self.large_codebase_mapper.clone();
+        let outcomes = run_bounded(files, parallelism, move |file| {
+            let mapper = mapper.clone();
+            async move {
+                let result = mapper.process_file(&file.path, &file.content).await;
+                (file.id, result)
+            }
+        })
+        .await;
 
-        // Collect results
-        for task in tasks {
-            match task.await {
-                Ok(result) => {
-                    // Handle result appropriately
-                    match result {
-                        Ok(_) => {
-                            // Successfully processed file
-                            let analysis_result = crate::AnalysisResult {
-                                id: Uuid::new_v4(),
-                                file_id: Uuid::new_v4(), // Would be the actual file ID
-                                issues: Vec::new(),      // Would be populated with actual issues
-                                suggestions: Vec::new(), // Would be populated with actual suggestions
-                                timestamp: chrono::Utc::now(),
-                            };
-                            results.push(analysis_result);
-                        }
-                        Err(e) => {
-                            warn!("Parallel analysis task failed: {}", e);
-                        }
-                    }
+        let mut result_map = HashMap::new();
+        for outcome in outcomes {
+            match outcome {
+                Ok((file_id, Ok(()))) => {
+                    let analysis_result = crate::AnalysisResult {
+                        id: Uuid::new_v4(),
+                        file_id,
+                        issues: Vec::new(), // Would be populated with actual issues
+                        suggestions: Vec::new(), // Would be populated with actual suggestions
+                        timestamp: chrono::Utc::now(),
+                    };
+                    result_map.insert(file_id, analysis_result);
+                }
+                Ok((_, Err(e))) => {
+                    warn!("Parallel analysis task failed: {}", e);
                 }
                 Err(e) => {
                     warn!("Parallel analysis task panicked: {}", e);
@@ -852,14 +899,7 @@ impl PerformanceOptimizer {
             }
         }
 
-        info!("Completed parallel analysis on {} files", results.len());
-
-        // Convert Vec to HashMap using file IDs as keys
-        let mut result_map = HashMap::new();
-        for result in results {
-            result_map.insert(Uuid::new_v4(), result); // In a real implementation, we'd use the actual file ID
-        }
-
+        info!("Completed parallel analysis on {} files", result_map.len());
         Ok(result_map)
     }
 
@@ -1032,4 +1072,61 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_run_bounded_limits_concurrent_tasks() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        let running = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+
+        let outputs = run_bounded((0..20).collect::<Vec<usize>>(), 3, |i| {
+            let running = running.clone();
+            let peak = peak.clone();
+            async move {
+                let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+                running.fetch_sub(1, Ordering::SeqCst);
+                i
+            }
+        })
+        .await;
+
+        let mut outputs: Vec<usize> = outputs.into_iter().map(|o| o.unwrap()).collect();
+        outputs.sort();
+        assert_eq!(outputs, (0..20).collect::<Vec<usize>>());
+        assert_eq!(peak.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_parallel_analysis_matches_sequential_run() -> Result<()> {
+        let files: Vec<CodeFile> = (0..8)
+            .map(|i| CodeFile {
+                id: Uuid::new_v4(),
+                path: format!("file_{}.rs", i),
+                content: format!("fn function_{}() -> i32 {{\n    {}\n}}\n", i, i),
+                language: "rust".to_string(),
+                modified: chrono::Utc::now(),
+                encoding: Default::default(),
+                line_ending: Default::default(),
+            })
+            .collect();
+        let optimizer = PerformanceOptimizer::new(DatabaseManager::new());
+
+        let sequential = optimizer.parallel_analysis(files.clone(), 1).await?;
+        let concurrent = optimizer.parallel_analysis(files.clone(), 4).await?;
+
+        let mut expected: Vec<Uuid> = files.iter().map(|f| f.id).collect();
+        expected.sort();
+        for results in [&sequential, &concurrent] {
+            let mut ids: Vec<Uuid> = results.keys().copied().collect();
+            ids.sort();
+            assert_eq!(ids, expected);
+            assert!(results.iter().all(|(id, result)| result.file_id == *id));
+        }
+
+        Ok(())
+    }
 }
@@ -1,15 +1,16 @@
 //! Dependencies Module
-//! 
+//!
 //! This module provides functionality for analyzing dependencies between code entities.
 
 use anyhow::Result;
 use std::collections::{HashMap, HashSet};
-use uuid::Uuid;
 use tracing::{debug, info};
+use uuid::Uuid;
 
-use crate::code_mapper::entities::{CodeEntity, Dependency, DependencyType};
+use super::{CodeEntity, Dependency, DependencyType};
 
 /// Dependency analyzer for code entities
+#[derive(Default)]
 pub struct DependencyAnalyzer;
 
 impl DependencyAnalyzer {
@@ -17,23 +18,24 @@ impl DependencyAnalyzer {
     pub fn new() -> Self {
         Self
     }
-    
+
     /// Analyze dependencies in a code file
     pub fn analyze_dependencies(
         &self,
         entities: &[CodeEntity],
         file_content: &str,
     ) -> Result<Vec<Dependency>> {
-        debug!("Analyzing dependencies in file with {} entities", entities.len());
-        
+        debug!(
+            "Analyzing dependencies in file with {} entities",
+            entities.len()
+        );
+
         let mut dependencies = Vec::new();
-        let entity_map: HashMap<&str, &CodeEntity> = entities
-            .iter()
-            .map(|e| (e.name.as_str(), e))
-            .collect();
-        
+        let entity_map: HashMap<&str, &CodeEntity> =
+            entities.iter().map(|e| (e.name.as_str(), e)).collect();
+
         let lines: Vec<&str> = file_content.lines().collect();
-        
+
         for (line_idx, line) in lines.iter().enumerate() {
             // Look for function calls (simplified)
             for (name, entity) in &entity_map {
@@ -54,16 +56,16 @@ impl DependencyAnalyzer {
                 }
             }
         }
-        
+
         info!("Found {} dependencies", dependencies.len());
         Ok(dependencies)
     }
-    
+
     /// Find the entity that contains a specific line (simplified)
     pub fn find_calling_entity(
         &self,
-        lines: &[(usize, &str)],
-        line_idx: usize,
+        _lines: &[(usize, &str)],
+        _line_idx: usize,
         entities: &[CodeEntity],
     ) -> Result<Uuid> {
         // In a real implementation, this would use AST to find the containing entity
@@ -74,31 +76,31 @@ impl DependencyAnalyzer {
             Ok(Uuid::new_v4())
         }
     }
-    
+
     /// Get all dependencies for an entity
-    pub fn get_dependencies_for_entity(
+    pub fn get_dependencies_for_entity<'a>(
         &self,
         entity_id: Uuid,
-        dependencies: &[Dependency],
-    ) -> Vec<&Dependency> {
+        dependencies: &'a [Dependency],
+    ) -> Vec<&'a Dependency> {
         dependencies
             .iter()
             .filter(|dep| dep.from_entity == entity_id)
             .collect()
     }
-    
+
     /// Get all entities that depend on a specific entity
-    pub fn get_dependents_of_entity(
+    pub fn get_dependents_of_entity<'a>(
         &self,
         entity_id: Uuid,
-        dependencies: &[Dependency],
-    ) -> Vec<&Dependency> {
+        dependencies: &'a [Dependency],
+    ) -> Vec<&'a Dependency> {
         dependencies
             .iter()
             .filter(|dep| dep.to_entity == entity_id)
             .collect()
     }
-    
+
     /// Calculate dependency strength based on usage patterns
     pub fn calculate_dependency_strength(
         &self,
@@ -115,12 +117,12 @@ impl DependencyAnalyzer {
             DependencyType::FieldAccess => 0.5,
             DependencyType::VariableUse => 0.4,
         };
-        
+
         // Adjust strength based on usage count
         let usage_factor = (usage_count as f32 / 10.0).min(1.0);
         (base_strength + usage_factor * 0.2).min(1.0)
     }
-    
+
     /// Build a dependency graph from entities and dependencies
     pub fn build_dependency_graph(
         &self,
@@ -128,30 +130,159 @@ impl DependencyAnalyzer {
         dependencies: &[Dependency],
     ) -> DependencyGraph {
         let mut graph = DependencyGraph::new();
-        
+
         // Add entities to graph
         for entity in entities {
             graph.add_entity(entity.clone());
         }
-        
+
         // Add dependencies to graph
         for dependency in dependencies {
             graph.add_dependency(dependency.clone());
         }
-        
+
         graph
     }
+
+    /// Find the entities whose code genuinely changed between two versions
+    ///
+    /// New entities are matched to old ones by id, or else by file, scope, name
+    /// and type, since re-indexing a file assigns fresh ids. Content is compared
+    /// after `normalize_content`, so a pure reformat is not a change. Returns the
+    /// ids of changed and added entities in `new`, followed by the ids of
+    /// entities that were removed from `old`.
+    pub fn changed_entities(&self, old: &[CodeEntity], new: &[CodeEntity]) -> Vec<Uuid> {
+        let key = |e: &CodeEntity| {
+            (
+                e.file_path.clone(),
+                e.scope.clone(),
+                e.name.clone(),
+                format!("{:?}", e.entity_type),
+            )
+        };
+        let old_by_id: HashMap<Uuid, &CodeEntity> = old.iter().map(|e| (e.id, e)).collect();
+        let old_by_key: HashMap<_, &CodeEntity> = old.iter().map(|e| (key(e), e)).collect();
+
+        let mut matched = HashSet::new();
+        let mut changed = Vec::new();
+        for entity in new {
+            let previous = old_by_id
+                .get(&entity.id)
+                .or_else(|| old_by_key.get(&key(entity)))
+                .copied();
+            match previous {
+                Some(previous) => {
+                    matched.insert(previous.id);
+                    if normalize_content(&previous.content) != normalize_content(&entity.content) {
+                        changed.push(entity.id);
+                    }
+                }
+                None => changed.push(entity.id),
+            }
+        }
+
+        changed.extend(
+            old.iter()
+                .filter(|e| !matched.contains(&e.id))
+                .map(|e| e.id),
+        );
+        debug!("{} of {} entities changed", changed.len(), new.len());
+        changed
+    }
+
+    /// Find the entities affected by the changes between two versions
+    ///
+    /// The impact set is every changed entity plus everything in `graph` that
+    /// depends on one of them, directly or transitively.
+    pub fn change_impact(
+        &self,
+        old: &[CodeEntity],
+        new: &[CodeEntity],
+        graph: &DependencyGraph,
+    ) -> HashSet<Uuid> {
+        let changed = self.changed_entities(old, new);
+        let mut impact = graph.reverse_reachable(&changed);
+        impact.extend(changed);
+        impact
+    }
+}
+
+/// Normalize code so that formatting differences compare equal
+///
+/// Whitespace outside string and char literals is dropped, except for a single
+/// space where it separates two word characters (as in `let value`). A `'`
+/// opens a char literal only when one, possibly escaped, character and a
+/// closing `'` follow it; otherwise it starts a lifetime or label such as `'a`.
+pub fn normalize_content(content: &str) -> String {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    let chars: Vec<char> = content.chars().collect();
+    let mut normalized = String::with_capacity(content.len());
+    let mut pending_space = false;
+    let mut index = 0;
+
+    while index < chars.len() {
+        let c = chars[index];
+        if c.is_whitespace() {
+            pending_space = true;
+            index += 1;
+            continue;
+        }
+        if pending_space && normalized.ends_with(is_word) && is_word(c) {
+            normalized.push(' ');
+        }
+        pending_space = false;
+
+        // Literals are copied verbatim, other characters one at a time
+        let end = match c {
+            '"' => Some(string_literal_end(&chars, index)),
+            '\'' => char_literal_end(&chars, index),
+            _ => None,
+        }
+        .unwrap_or(index + 1);
+        normalized.extend(&chars[index..end]);
+        index = end;
+    }
+
+    normalized
+}
+
+/// Index just past the string literal opening at `start`, or the end of the
+/// input if the literal is not closed
+fn string_literal_end(chars: &[char], start: usize) -> usize {
+    let mut index = start + 1;
+    while index < chars.len() {
+        match chars[index] {
+            '\\' => index += 2,
+            '"' => return index + 1,
+            _ => index += 1,
+        }
+    }
+    chars.len()
+}
+
+/// Index just past the char literal opening at `start`, or `None` if the `'`
+/// starts a lifetime or label instead
+fn char_literal_end(chars: &[char], start: usize) -> Option<usize> {
+    let close = match chars.get(start + 1)? {
+        // Escapes such as '\n', '\'' and '\u{1F600}' end at the next quote
+        '\\' => {
+            let rest = chars.get(start + 3..)?;
+            start + 3 + rest.iter().position(|&c| c == '\'')?
+        }
+        _ => start + 2,
+    };
+    (chars.get(close) == Some(&'\'')).then_some(close + 1)
 }
 
 /// In-memory dependency graph representation
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct DependencyGraph {
     /// Map of entity IDs to entities
     entities: HashMap<Uuid, CodeEntity>,
-    
+
     /// Map of entity ID to its dependencies
     dependencies: HashMap<Uuid, Vec<Dependency>>,
-    
+
     /// Map of entity ID to entities that depend on it
     dependents: HashMap<Uuid, Vec<Dependency>>,
 }
@@ -165,86 +296,86 @@ impl DependencyGraph {
             dependents: HashMap::new(),
         }
     }
-    
+
     /// Add an entity to the graph
     pub fn add_entity(&mut self, entity: CodeEntity) {
         self.entities.insert(entity.id, entity);
     }
-    
+
     /// Add a dependency to the graph
     pub fn add_dependency(&mut self, dependency: Dependency) {
         // Add to dependencies map
         self.dependencies
             .entry(dependency.from_entity)
-            .or_insert_with(Vec::new)
+            .or_default()
             .push(dependency.clone());
-        
+
         // Add to dependents map
         self.dependents
             .entry(dependency.to_entity)
-            .or_insert_with(Vec::new)
+            .or_default()
             .push(dependency);
     }
-    
+
     /// Get an entity by its ID
     pub fn get_entity(&self, id: Uuid) -> Option<&CodeEntity> {
         self.entities.get(&id)
     }
-    
+
     /// Get all dependencies for an entity
     pub fn get_dependencies(&self, id: Uuid) -> Option<&Vec<Dependency>> {
         self.dependencies.get(&id)
     }
-    
+
     /// Get all dependents of an entity
     pub fn get_dependents(&self, id: Uuid) -> Option<&Vec<Dependency>> {
         self.dependents.get(&id)
     }
-    
+
     /// Get all entities in the graph
     pub fn get_all_entities(&self) -> Vec<&CodeEntity> {
         self.entities.values().collect()
     }
-    
+
     /// Find the shortest path between two entities
     pub fn shortest_path(&self, start: Uuid, end: Uuid) -> Option<Vec<Uuid>> {
         use std::collections::{HashMap, HashSet, VecDeque};
-        
+
         if start == end {
             return Some(vec![start]);
         }
-        
+
         let mut queue = VecDeque::new();
         let mut visited = HashSet::new();
         let mut previous = HashMap::new();
-        
+
         queue.push_back(start);
         visited.insert(start);
-        
+
         while let Some(current) = queue.pop_front() {
             if let Some(dependencies) = self.get_dependencies(current) {
                 for dep in dependencies {
                     let next_entity = dep.to_entity;
-                    
+
                     if !visited.contains(&next_entity) {
                         visited.insert(next_entity);
                         previous.insert(next_entity, current);
                         queue.push_back(next_entity);
-                        
+
                         if next_entity == end {
                             // Reconstruct path
                             let mut path = vec![end];
                             let mut current_path = end;
-                            
+
                             while let Some(&prev) = previous.get(&current_path) {
                                 path.push(prev);
                                 current_path = prev;
-                                
+
                                 if prev == start {
                                     break;
                                 }
                             }
-                            
+
                             path.reverse();
                             return Some(path);
                         }
@@ -252,10 +383,33 @@ impl DependencyGraph {
                 }
             }
         }
-        
+
         None
     }
-    
+
+    /// Get every entity that depends on one of `ids`, directly or transitively
+    ///
+    /// The starting entities are only included if they are reachable from one
+    /// another, e.g. through a cycle.
+    pub fn reverse_reachable(&self, ids: &[Uuid]) -> HashSet<Uuid> {
+        use std::collections::VecDeque;
+
+        let mut reached = HashSet::new();
+        let mut queue: VecDeque<Uuid> = ids.iter().copied().collect();
+
+        while let Some(current) = queue.pop_front() {
+            if let Some(dependents) = self.get_dependents(current) {
+                for dep in dependents {
+                    if reached.insert(dep.from_entity) {
+                        queue.push_back(dep.from_entity);
+                    }
+                }
+            }
+        }
+
+        reached
+    }
+
     /// Get the size of the graph
     pub fn size(&self) -> usize {
         self.entities.len()
@@ -265,19 +419,19 @@ impl DependencyGraph {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::code_mapper::entities::CodeEntityType;
+    use crate::large_codebase_mapper::CodeEntityType;
     use chrono::Utc;
-    
+
     #[test]
     fn test_dependency_analyzer_creation() {
         let analyzer = DependencyAnalyzer::new();
         assert_eq!(std::mem::size_of_val(&analyzer), 0); // Zero-sized type
     }
-    
+
     #[test]
     fn test_dependency_analysis() -> Result<()> {
         let analyzer = DependencyAnalyzer::new();
-        
+
         let entities = vec![
             CodeEntity {
                 id: Uuid::new_v4(),
@@ -306,13 +460,14 @@ mod tests {
                 scope: String::new(),
                 dependencies: Vec::new(),
                 accessed_by: Vec::new(),
-                content: "fn main_function() -> i32 { let value = helper_function(); value + 1 }".to_string(),
+                content: "fn main_function() -> i32 { let value = helper_function(); value + 1 }"
+                    .to_string(),
                 embedding: None,
                 complexity: 1.0,
                 last_modified: Utc::now(),
             },
         ];
-        
+
         let file_content = r#"
             fn helper_function() -> i32 {
                 42
@@ -323,22 +478,23 @@ mod tests {
                 value + 1
             }
         "#;
-        
+
         let dependencies = analyzer.analyze_dependencies(&entities, file_content)?;
         assert!(!dependencies.is_empty());
-        
+
         // Check that we found the function call dependency
-        let call_found = dependencies.iter()
+        let call_found = dependencies
+            .iter()
             .any(|dep| matches!(dep.dependency_type, DependencyType::Call));
         assert!(call_found, "Function call dependency should be found");
-        
+
         Ok(())
     }
-    
+
     #[test]
     fn test_dependency_graph() -> Result<()> {
         let analyzer = DependencyAnalyzer::new();
-        
+
         let entity1 = CodeEntity {
             id: Uuid::new_v4(),
             name: "function1".to_string(),
@@ -355,7 +511,7 @@ mod tests {
             complexity: 1.0,
             last_modified: Utc::now(),
         };
-        
+
         let entity2 = CodeEntity {
             id: Uuid::new_v4(),
             name: "function2".to_string(),
@@ -372,7 +528,7 @@ mod tests {
             complexity: 1.0,
             last_modified: Utc::now(),
         };
-        
+
         let dependency = Dependency {
             id: Uuid::new_v4(),
             from_entity: entity2.id,
@@ -382,30 +538,33 @@ mod tests {
             file_path: "test.rs".to_string(),
             line_number: 10,
         };
-        
-        let graph = analyzer.build_dependency_graph(&[entity1.clone(), entity2.clone()], &[dependency.clone()]);
+
+        let graph = analyzer.build_dependency_graph(
+            &[entity1.clone(), entity2.clone()],
+            std::slice::from_ref(&dependency),
+        );
         assert_eq!(graph.size(), 2);
-        
+
         // Check that entities are in the graph
         assert!(graph.get_entity(entity1.id).is_some());
         assert!(graph.get_entity(entity2.id).is_some());
-        
+
         // Check that dependencies are in the graph
         let deps = graph.get_dependencies(entity2.id);
         assert!(deps.is_some());
         assert_eq!(deps.unwrap().len(), 1);
-        
+
         let deps = graph.get_dependents(entity1.id);
         assert!(deps.is_some());
         assert_eq!(deps.unwrap().len(), 1);
-        
+
         Ok(())
     }
-    
+
     #[test]
     fn test_shortest_path() -> Result<()> {
         let mut graph = DependencyGraph::new();
-        
+
         let entity1 = CodeEntity {
             id: Uuid::new_v4(),
             name: "function1".to_string(),
@@ -422,7 +581,7 @@ mod tests {
             complexity: 1.0,
             last_modified: Utc::now(),
         };
-        
+
         let entity2 = CodeEntity {
             id: Uuid::new_v4(),
             name: "function2".to_string(),
@@ -439,7 +598,7 @@ mod tests {
             complexity: 1.0,
             last_modified: Utc::now(),
         };
-        
+
         let entity3 = CodeEntity {
             id: Uuid::new_v4(),
             name: "function3".to_string(),
@@ -456,7 +615,7 @@ mod tests {
             complexity: 1.0,
             last_modified: Utc::now(),
         };
-        
+
         let dep1 = Dependency {
             id: Uuid::new_v4(),
             from_entity: entity2.id,
@@ -466,7 +625,7 @@ mod tests {
             file_path: "test.rs".to_string(),
             line_number: 10,
         };
-        
+
         let dep2 = Dependency {
             id: Uuid::new_v4(),
             from_entity: entity3.id,
@@ -476,13 +635,13 @@ mod tests {
             file_path: "test.rs".to_string(),
             line_number: 15,
         };
-        
+
         graph.add_entity(entity1.clone());
         graph.add_entity(entity2.clone());
         graph.add_entity(entity3.clone());
         graph.add_dependency(dep1.clone());
         graph.add_dependency(dep2.clone());
-        
+
         // Test path from entity3 to entity1 (should be entity3 -> entity2 -> entity1)
         let path = graph.shortest_path(entity3.id, entity1.id);
         assert!(path.is_some());
@@ -491,14 +650,105 @@ mod tests {
         assert_eq!(path[0], entity3.id);
         assert_eq!(path[1], entity2.id);
         assert_eq!(path[2], entity1.id);
-        
+
         // Test path from entity to itself
         let path = graph.shortest_path(entity1.id, entity1.id);
         assert!(path.is_some());
         let path = path.unwrap();
         assert_eq!(path.len(), 1);
         assert_eq!(path[0], entity1.id);
-        
+
         Ok(())
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_normalize_content_handles_char_literals_and_lifetimes() {
+        assert_eq!(
+            normalize_content("fn first<'a>(x: &'a   str) -> char {\n    ' '\n}"),
+            "fn first<'a>(x:&'a str)->char{' '}"
+        );
+        // A quote in a char literal does not open a string
+        assert_eq!(
+            normalize_content("if c == '\"' {\n    skip(c)\n}"),
+            "if c=='\"'{skip(c)}"
+        );
+        assert_eq!(
+            normalize_content("let quote = '\\'' ;  'outer: loop {}"),
+            "let quote='\\'';'outer:loop{}"
+        );
+    }
+
+    #[test]
+    fn test_change_impact_ignores_reformatting() -> Result<()> {
+        let analyzer = DependencyAnalyzer::new();
+
+        let entity = |name: &str, content: &str| CodeEntity {
+            id: Uuid::new_v4(),
+            name: name.to_string(),
+            entity_type: CodeEntityType::Function,
+            language: "rust".to_string(),
+            file_path: "test.rs".to_string(),
+            line_number: 1,
+            column_number: 0,
+            scope: String::new(),
+            dependencies: Vec::new(),
+            accessed_by: Vec::new(),
+            content: content.to_string(),
+            embedding: None,
+            complexity: 1.0,
+            last_modified: Utc::now(),
+        };
+        let call = |from: &CodeEntity, to: &CodeEntity| Dependency {
+            id: Uuid::new_v4(),
+            from_entity: from.id,
+            to_entity: to.id,
+            dependency_type: DependencyType::Call,
+            strength: 1.0,
+            file_path: "test.rs".to_string(),
+            line_number: 1,
+        };
+
+        let old = vec![
+            entity("helper", "fn helper() -> i32 { 42 }"),
+            entity("caller", "fn caller() -> i32 { helper() + 1 }"),
+            entity("top", "fn top() -> i32 { caller() * 2 }"),
+            entity("other", "fn other() { println!(\"a  b\"); }"),
+        ];
+
+        // Re-indexing assigns new ids; the helper is only reformatted
+        let reformatted = vec![
+            entity("helper", "fn helper()->i32 {\n    42\n}"),
+            entity("caller", &old[1].content),
+            entity("top", &old[2].content),
+            entity("other", &old[3].content),
+        ];
+        let graph = analyzer.build_dependency_graph(
+            &reformatted,
+            &[
+                call(&reformatted[1], &reformatted[0]),
+                call(&reformatted[2], &reformatted[1]),
+            ],
+        );
+        assert!(analyzer.changed_entities(&old, &reformatted).is_empty());
+        assert!(analyzer
+            .change_impact(&old, &reformatted, &graph)
+            .is_empty());
+
+        // Whitespace inside a string literal is not formatting
+        let mut respaced = reformatted.clone();
+        respaced[3].content = "fn other() { println!(\"a b\"); }".to_string();
+        assert_eq!(
+            analyzer.changed_entities(&old, &respaced),
+            vec![respaced[3].id]
+        );
+
+        // A real change to the helper reaches its direct and transitive callers
+        let mut changed = reformatted.clone();
+        changed[0].content = "fn helper() -> i32 { 43 }".to_string();
+        let impact = analyzer.change_impact(&old, &changed, &graph);
+        let expected: HashSet<Uuid> = changed[..3].iter().map(|e| e.id).collect();
+        assert_eq!(impact, expected);
+
+        Ok(())
+    }
+}
@@ -1,48 +1,87 @@
 //! Storage Module for Code Mapper
-//! 
+//!
 //! This module provides storage functionality for code entities and dependencies
 //! using both in-memory caching and persistent storage.
 
 use anyhow::Result;
+use sqlx::sqlite::SqliteRow;
+use sqlx::{Row, SqliteConnection, SqlitePool};
 use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use tokio::sync::RwLock;
-use tracing::{debug, info, warn};
+use tracing::{debug, info};
 use uuid::Uuid;
 
-use crate::code_mapper::entities::{CodeEntity, Dependency};
-use odincode_databases::DatabaseManager;
+use super::{CodeEntity, CodeEntityType, Dependency, DependencyType};
+
+/// Computes embedding vectors for entity content
+pub trait EmbeddingProvider: Send + Sync {
+    /// Compute the embedding for a piece of content
+    fn embed(&self, content: &str) -> Result<Vec<f32>>;
+}
+
+/// Hash of entity content used as the embedding cache key
+///
+/// Uses FNV-1a rather than `DefaultHasher` so keys stay stable across builds,
+/// since they are persisted.
+pub fn content_hash(content: &str) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in content.as_bytes() {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{:016x}", hash)
+}
 
 /// Storage manager for code entities and dependencies
 pub struct StorageManager {
-    /// Database manager for persistent storage
-    database_manager: DatabaseManager,
-    
+    /// Database pool for persistent storage
+    pool: SqlitePool,
+
     /// In-memory cache for frequently accessed entities
     entity_cache: Arc<RwLock<HashMap<Uuid, CodeEntity>>>,
-    
+
     /// Cache for file-to-entities mapping
     file_entities_cache: Arc<RwLock<HashMap<String, Vec<Uuid>>>>,
-    
+
     /// Cache for dependency relationships
     dependency_cache: Arc<RwLock<HashMap<Uuid, Vec<Dependency>>>>,
-    
-    /// Cache for entity embeddings
-    embedding_cache: Arc<RwLock<HashMap<Uuid, Vec<f32>>>>,
+
+    /// Cache for reverse dependency relationships, keyed by `to_entity`
+    dependent_cache: Arc<RwLock<HashMap<Uuid, Vec<Dependency>>>>,
+
+    /// Cache for entity embeddings, keyed by content hash
+    embedding_cache: Arc<RwLock<HashMap<String, Vec<f32>>>>,
+
+    /// Provider used to compute embeddings missing from the cache
+    embedding_provider: Option<Arc<dyn EmbeddingProvider>>,
+
+    /// Number of per-file entity queries sent to the database
+    file_queries: AtomicUsize,
 }
 
 impl StorageManager {
-    /// Create a new storage manager
-    pub fn new(database_manager: DatabaseManager) -> Self {
+    /// Create a new storage manager backed by the given pool
+    pub fn new(pool: SqlitePool) -> Self {
         Self {
-            database_manager,
+            pool,
             entity_cache: Arc::new(RwLock::new(HashMap::new())),
             file_entities_cache: Arc::new(RwLock::new(HashMap::new())),
             dependency_cache: Arc::new(RwLock::new(HashMap::new())),
+            dependent_cache: Arc::new(RwLock::new(HashMap::new())),
             embedding_cache: Arc::new(RwLock::new(HashMap::new())),
+            embedding_provider: None,
+            file_queries: AtomicUsize::new(0),
         }
     }
-    
+
+    /// Compute embeddings for stored entities with the given provider
+    pub fn with_embedding_provider(mut self, provider: Arc<dyn EmbeddingProvider>) -> Self {
+        self.embedding_provider = Some(provider);
+        self
+    }
+
     /// Initialize the storage system by setting up database tables
     pub async fn initialize(&self) -> Result<()> {
         // Create tables for entities and dependencies
@@ -50,11 +89,12 @@ impl StorageManager {
         info!("Storage manager initialized");
         Ok(())
     }
-    
+
     /// Set up database tables for storing code entities and dependencies
     async fn setup_database_tables(&self) -> Result<()> {
-        // SQL to create entities table
-        let entities_sql = r#"
+        // Table for entities
+        sqlx::query(
+            r#"
             CREATE TABLE IF NOT EXISTS code_entities (
                 id TEXT PRIMARY KEY,
                 name TEXT NOT NULL,
@@ -69,10 +109,14 @@ impl StorageManager {
                 complexity REAL,
                 last_modified TEXT NOT NULL
             )
-        "#;
-        
-        // SQL to create dependencies table
-        let dependencies_sql = r#"
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Table for dependencies
+        sqlx::query(
+            r#"
             CREATE TABLE IF NOT EXISTS dependencies (
                 id TEXT PRIMARY KEY,
                 from_entity TEXT NOT NULL,
@@ -84,135 +128,290 @@ impl StorageManager {
                 FOREIGN KEY (from_entity) REFERENCES code_entities (id),
                 FOREIGN KEY (to_entity) REFERENCES code_entities (id)
             )
-        "#;
-        
-        // SQL to create indexes for performance
-        let indexes_sql = r#"
-            CREATE INDEX IF NOT EXISTS idx_entities_file ON code_entities(file_path);
-            CREATE INDEX IF NOT EXISTS idx_entities_type ON code_entities(entity_type);
-            CREATE INDEX IF NOT EXISTS idx_entities_name ON code_entities(name);
-            CREATE INDEX IF NOT EXISTS idx_dependencies_from ON dependencies(from_entity);
-            CREATE INDEX IF NOT EXISTS idx_dependencies_to ON dependencies(to_entity);
-        "#;
-        
-        // Get database connection (using the correct method)
-        let db = self.database_manager.get_connection(Uuid::new_v4()).await?;
-        if let Some(db_conn) = db {
-            // Execute table creation
-            db_conn.execute_batch(entities_sql).await?;
-            db_conn.execute_batch(dependencies_sql).await?;
-            db_conn.execute_batch(indexes_sql).await?;
-            
-            info!("Database tables for code entities and dependencies created");
-        } else {
-            warn!("No database connection available, using in-memory storage only");
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Table for the persisted embedding cache
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS embedding_cache (
+                content_hash TEXT PRIMARY KEY,
+                embedding BLOB NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Indexes for performance
+        for index_sql in [
+            "CREATE INDEX IF NOT EXISTS idx_entities_file ON code_entities(file_path)",
+            "CREATE INDEX IF NOT EXISTS idx_entities_type ON code_entities(entity_type)",
+            "CREATE INDEX IF NOT EXISTS idx_entities_name ON code_entities(name)",
+            "CREATE INDEX IF NOT EXISTS idx_dependencies_from ON dependencies(from_entity)",
+            "CREATE INDEX IF NOT EXISTS idx_dependencies_to ON dependencies(to_entity)",
+        ] {
+            sqlx::query(index_sql).execute(&self.pool).await?;
         }
-        
+
+        info!("Database tables for code entities and dependencies created");
         Ok(())
     }
-    
+
     /// Store an entity in both cache and database
     pub async fn store_entity(&self, entity: &CodeEntity) -> Result<()> {
-        debug!("Storing entity: {} ({})", entity.name, entity.id);
-        
+        self.store_entities(std::slice::from_ref(entity)).await
+    }
+
+    /// Store a batch of entities in both cache and database
+    ///
+    /// The entities are written in a single transaction; if any of them fails
+    /// the whole batch is rolled back and the entity caches are left unchanged.
+    /// Embeddings computed for the batch are cached before the transaction and
+    /// stay cached, since they depend only on the content.
+    pub async fn store_entities(&self, entities: &[CodeEntity]) -> Result<()> {
+        if entities.is_empty() {
+            return Ok(());
+        }
+        debug!("Storing {} entities", entities.len());
+
+        let mut prepared = Vec::with_capacity(entities.len());
+        for entity in entities {
+            let mut entity = entity.clone();
+            match &entity.embedding {
+                Some(embedding) => {
+                    self.cache_embedding(&content_hash(&entity.content), embedding)
+                        .await?;
+                }
+                None => entity.embedding = self.embedding_for(&entity.content).await?,
+            }
+            prepared.push(entity);
+        }
+
+        // Make sure each file's entity list is complete before adding to it
+        let file_paths: HashSet<&str> = prepared.iter().map(|e| e.file_path.as_str()).collect();
+        for file_path in file_paths {
+            if !self
+                .file_entities_cache
+                .read()
+                .await
+                .contains_key(file_path)
+            {
+                self.get_entities_for_file(file_path).await?;
+            }
+        }
+
+        // Store in database; dropping the transaction on error rolls it back
+        let mut tx = self.pool.begin().await?;
+        for entity in &prepared {
+            Self::insert_entity(&mut tx, entity).await?;
+        }
+        tx.commit().await?;
+
         // Store in cache
         {
+            let mut file_cache = self.file_entities_cache.write().await;
             let mut cache = self.entity_cache.write().await;
-            cache.insert(entity.id, entity.clone());
+
+            for entity in prepared {
+                // An entity stored again under a new path moves to that file
+                if let Some(previous) = cache.get(&entity.id) {
+                    if previous.file_path != entity.file_path {
+                        if let Some(ids) = file_cache.get_mut(&previous.file_path) {
+                            ids.retain(|id| *id != entity.id);
+                        }
+                    }
+                }
+
+                let ids = file_cache.entry(entity.file_path.clone()).or_default();
+                if !ids.contains(&entity.id) {
+                    ids.push(entity.id);
+                }
+                cache.insert(entity.id, entity);
+            }
         }
-        
-        // Store in database
-        self.store_entity_in_database(entity).await?;
-        
-        info!("Stored entity: {} ({})", entity.name, entity.id);
+
+        info!("Stored {} entities", entities.len());
         Ok(())
     }
-    
-    /// Store an entity in the database
-    async fn store_entity_in_database(&self, entity: &CodeEntity) -> Result<()> {
-        let db = self.database_manager.get_connection(Uuid::new_v4()).await?;
-        if let Some(db_conn) = db {
-            // Convert embedding to bytes if present
-            let embedding_bytes = match &entity.embedding {
-                Some(embedding) => Some(bincode::serialize(embedding)?),
-                None => None,
-            };
-            
-            let sql = r#"
-                INSERT OR REPLACE INTO code_entities 
-                (id, name, entity_type, language, file_path, line_number, column_number, scope, content, embedding, complexity, last_modified)
-                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
-            "#;
-            
-            db_conn.execute(
-                sql,
-                &[
-                    &entity.id.to_string(),
-                    &entity.name,
-                    &format!("{:?}", entity.entity_type),
-                    &entity.language,
-                    &entity.file_path,
-                    &(entity.line_number as i64),
-                    &(entity.column_number as i64),
-                    &entity.scope,
-                    &entity.content,
-                    &embedding_bytes,
-                    &entity.complexity,
-                    &entity.last_modified.to_rfc3339(),
-                ]
-            ).await?;
+
+    /// Get the embedding for content, computing it only if it isn't cached
+    ///
+    /// Returns `None` when the content isn't cached and no provider is set.
+    async fn embedding_for(&self, content: &str) -> Result<Option<Vec<f32>>> {
+        let hash = content_hash(content);
+        if let Some(embedding) = self.get_cached_embedding(&hash).await? {
+            return Ok(Some(embedding));
         }
-        
+
+        let provider = match &self.embedding_provider {
+            Some(provider) => provider,
+            None => return Ok(None),
+        };
+        let embedding = provider.embed(content)?;
+        self.cache_embedding(&hash, &embedding).await?;
+        Ok(Some(embedding))
+    }
+
+    /// Get a cached embedding by content hash, from memory or the database
+    pub async fn get_cached_embedding(&self, hash: &str) -> Result<Option<Vec<f32>>> {
+        {
+            let cache = self.embedding_cache.read().await;
+            if let Some(embedding) = cache.get(hash) {
+                return Ok(Some(embedding.clone()));
+            }
+        }
+
+        let row = sqlx::query("SELECT embedding FROM embedding_cache WHERE content_hash = ?")
+            .bind(hash)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        if let Some(row) = row {
+            let bytes: Vec<u8> = row.get("embedding");
+            let embedding: Vec<f32> = bincode::deserialize(&bytes)?;
+            let mut cache = self.embedding_cache.write().await;
+            cache.insert(hash.to_string(), embedding.clone());
+            return Ok(Some(embedding));
+        }
+
+        Ok(None)
+    }
+
+    /// Add an embedding to the in-memory and persisted caches
+    async fn cache_embedding(&self, hash: &str, embedding: &[f32]) -> Result<()> {
+        {
+            let mut cache = self.embedding_cache.write().await;
+            if cache.get(hash).map(|cached| cached.as_slice()) == Some(embedding) {
+                return Ok(());
+            }
+            cache.insert(hash.to_string(), embedding.to_vec());
+        }
+
+        sqlx::query(
+            "INSERT OR REPLACE INTO embedding_cache (content_hash, embedding) VALUES (?, ?)",
+        )
+        .bind(hash)
+        .bind(bincode::serialize(embedding)?)
+        .execute(&self.pool)
+        .await?;
+
         Ok(())
     }
-    
+
+    /// Insert or replace an entity row
+    async fn insert_entity(conn: &mut SqliteConnection, entity: &CodeEntity) -> Result<()> {
+        // Convert embedding to bytes if present
+        let embedding_bytes = match &entity.embedding {
+            Some(embedding) => Some(bincode::serialize(embedding)?),
+            None => None,
+        };
+
+        sqlx::query(
+            r#"
+            INSERT OR REPLACE INTO code_entities
+            (id, name, entity_type, language, file_path, line_number, column_number, scope, content, embedding, complexity, last_modified)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(entity.id.to_string())
+        .bind(&entity.name)
+        .bind(format!("{:?}", entity.entity_type))
+        .bind(&entity.language)
+        .bind(&entity.file_path)
+        .bind(entity.line_number as i64)
+        .bind(entity.column_number as i64)
+        .bind(&entity.scope)
+        .bind(&entity.content)
+        .bind(embedding_bytes)
+        .bind(entity.complexity)
+        .bind(entity.last_modified.to_rfc3339())
+        .execute(conn)
+        .await?;
+
+        Ok(())
+    }
+
     /// Store a dependency in both cache and database
     pub async fn store_dependency(&self, dependency: &Dependency) -> Result<()> {
-        debug!("Storing dependency: {} -> {}", dependency.from_entity, dependency.to_entity);
-        
-        // Store in cache
+        self.store_dependencies(std::slice::from_ref(dependency))
+            .await
+    }
+
+    /// Store a batch of dependencies in both cache and database
+    ///
+    /// Like `store_entities`, the batch is written in a single transaction and
+    /// rolled back as a whole on failure.
+    pub async fn store_dependencies(&self, dependencies: &[Dependency]) -> Result<()> {
+        if dependencies.is_empty() {
+            return Ok(());
+        }
+        debug!("Storing {} dependencies", dependencies.len());
+
+        // Make sure each entity's dependency list is complete before adding to it
+        let from_entities: HashSet<Uuid> = dependencies.iter().map(|d| d.from_entity).collect();
+        for from_entity in from_entities {
+            if !self
+                .dependency_cache
+                .read()
+                .await
+                .contains_key(&from_entity)
+            {
+                self.get_dependencies_for_entity(from_entity).await?;
+            }
+        }
+
+        // Store in database; dropping the transaction on error rolls it back
+        let mut tx = self.pool.begin().await?;
+        for dependency in dependencies {
+            Self::insert_dependency(&mut tx, dependency).await?;
+        }
+        tx.commit().await?;
+
+        // Store in cache, replacing earlier versions of the same dependencies
         {
             let mut cache = self.dependency_cache.write().await;
-            cache
-                .entry(dependency.from_entity)
-                .or_insert_with(Vec::new)
-                .push(dependency.clone());
+            let mut dependents = self.dependent_cache.write().await;
+            for dependency in dependencies {
+                let cached = cache.entry(dependency.from_entity).or_default();
+                upsert_dependency(cached, dependency);
+
+                // Reverse lists are loaded lazily; one not cached yet will
+                // pick this dependency up from the database
+                if let Some(cached) = dependents.get_mut(&dependency.to_entity) {
+                    upsert_dependency(cached, dependency);
+                }
+            }
         }
-        
-        // Store in database
-        self.store_dependency_in_database(dependency).await?;
-        
-        info!("Stored dependency: {} -> {}", dependency.from_entity, dependency.to_entity);
+
+        info!("Stored {} dependencies", dependencies.len());
         Ok(())
     }
-    
-    /// Store a dependency in the database
-    async fn store_dependency_in_database(&self, dependency: &Dependency) -> Result<()> {
-        let db = self.database_manager.get_connection(Uuid::new_v4()).await?;
-        if let Some(db_conn) = db {
-            let sql = r#"
-                INSERT OR REPLACE INTO dependencies 
-                (id, from_entity, to_entity, dependency_type, strength, file_path, line_number)
-                VALUES (?, ?, ?, ?, ?, ?, ?)
-            "#;
-            
-            db_conn.execute(
-                sql,
-                &[
-                    &dependency.id.to_string(),
-                    &dependency.from_entity.to_string(),
-                    &dependency.to_entity.to_string(),
-                    &format!("{:?}", dependency.dependency_type),
-                    &dependency.strength,
-                    &dependency.file_path,
-                    &(dependency.line_number as i64),
-                ]
-            ).await?;
-        }
-        
+
+    /// Insert or replace a dependency row
+    async fn insert_dependency(conn: &mut SqliteConnection, dependency: &Dependency) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT OR REPLACE INTO dependencies
+            (id, from_entity, to_entity, dependency_type, strength, file_path, line_number)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(dependency.id.to_string())
+        .bind(dependency.from_entity.to_string())
+        .bind(dependency.to_entity.to_string())
+        .bind(format!("{:?}", dependency.dependency_type))
+        .bind(dependency.strength)
+        .bind(&dependency.file_path)
+        .bind(dependency.line_number as i64)
+        .execute(conn)
+        .await?;
+
         Ok(())
     }
-    
+
     /// Get an entity by its ID
     pub async fn get_entity_by_id(&self, entity_id: Uuid) -> Result<Option<CodeEntity>> {
         // Check cache first
@@ -222,74 +421,47 @@ impl StorageManager {
                 return Ok(Some(entity.clone()));
             }
         }
-        
+
         // Check database
-        let entity = self.get_entity_by_id_from_database(entity_id).await?;
-        if let Some(ref entity) = entity {
-            // Add to cache
-            {
-                let mut cache = self.entity_cache.write().await;
-                cache.insert(entity_id, entity.clone());
-            }
+        let entity = match self.get_entity_by_id_from_database(entity_id).await? {
+            Some(entity) => self.with_cached_embedding(entity).await?,
+            None => return Ok(None),
+        };
+
+        // Add to cache
+        {
+            let mut cache = self.entity_cache.write().await;
+            cache.insert(entity_id, entity.clone());
+        }
+
+        Ok(Some(entity))
+    }
+
+    /// Give an entity read from the database the cached embedding for its content
+    ///
+    /// The embedding cache holds the latest vector for each content hash, so it
+    /// takes precedence over the one stored with the entity, which may be
+    /// missing or outdated.
+    async fn with_cached_embedding(&self, mut entity: CodeEntity) -> Result<CodeEntity> {
+        if let Some(embedding) = self
+            .get_cached_embedding(&content_hash(&entity.content))
+            .await?
+        {
+            entity.embedding = Some(embedding);
         }
-        
         Ok(entity)
     }
-    
+
     /// Get an entity by its ID from the database
     async fn get_entity_by_id_from_database(&self, entity_id: Uuid) -> Result<Option<CodeEntity>> {
-        let db = self.database_manager.get_connection(Uuid::new_v4()).await?;
-        if let Some(db_conn) = db {
-            let sql = "SELECT * FROM code_entities WHERE id = ?";
-            let mut stmt = db_conn.prepare(sql).await?;
-            let mut rows = stmt.query([entity_id.to_string()]).await?;
-            
-            if let Some(row) = rows.next().await? {
-                let entity = CodeEntity {
-                    id: Uuid::parse_str(row.get::<String>(0)?.as_str())?,
-                    name: row.get(1)?,
-                    entity_type: self.parse_entity_type(&row.get::<String>(2)?)?,
-                    language: row.get(3)?,
-                    file_path: row.get(4)?,
-                    line_number: row.get::<i64>(5)? as usize,
-                    column_number: row.get::<i64>(6)? as usize,
-                    scope: row.get(7)?,
-                    content: row.get(8)?,
-                    embedding: match row.get::<Option<Vec<u8>>>(9)? {
-                        Some(bytes) => Some(bincode::deserialize(&bytes[..])?),
-                        None => None,
-                    },
-                    complexity: row.get(10)?,
-                    last_modified: chrono::DateTime::parse_from_rfc3339(&row.get::<String>(11)?)?.into(),
-                };
-                
-                return Ok(Some(entity));
-            }
-        }
-        
-        Ok(None)
-    }
-    
-    /// Parse entity type from string
-    fn parse_entity_type(&self, type_str: &str) -> Result<crate::code_mapper::entities::CodeEntityType> {
-        use crate::code_mapper::entities::CodeEntityType;
-        
-        match type_str {
-            "Function" => Ok(CodeEntityType::Function),
-            "Method" => Ok(CodeEntityType::Method),
-            "Class" => Ok(CodeEntityType::Class),
-            "Struct" => Ok(CodeEntityType::Struct),
-            "Interface" => Ok(CodeEntityType::Interface),
-            "Variable" => Ok(CodeEntityType::Variable),
-            "Constant" => Ok(CodeEntityType::Constant),
-            "Module" => Ok(CodeEntityType::Module),
-            "Namespace" => Ok(CodeEntityType::Namespace),
-            "Type" => Ok(CodeEntityType::Type),
-            "Enum" => Ok(CodeEntityType::Enum),
-            _ => Err(anyhow::anyhow!("Unknown entity type: {}", type_str)),
-        }
+        let row = sqlx::query("SELECT * FROM code_entities WHERE id = ?")
+            .bind(entity_id.to_string())
+            .fetch_optional(&self.pool)
+            .await?;
+
+        row.as_ref().map(entity_from_row).transpose()
     }
-    
+
     /// Get all entities in a specific file
     pub async fn get_entities_for_file(&self, file_path: &str) -> Result<Vec<CodeEntity>> {
         // Check cache first
@@ -305,19 +477,22 @@ impl StorageManager {
                 return Ok(entities);
             }
         }
-        
+
         // Check database
-        let entities = self.get_entities_for_file_from_database(file_path).await?;
-        
+        let mut entities = Vec::new();
+        for entity in self.get_entities_for_file_from_database(file_path).await? {
+            entities.push(self.with_cached_embedding(entity).await?);
+        }
+
         // Update cache
         {
             let mut cache = self.file_entities_cache.write().await;
             cache.insert(
                 file_path.to_string(),
-                entities.iter().map(|e| e.id).collect()
+                entities.iter().map(|e| e.id).collect(),
             );
         }
-        
+
         // Add entities to entity cache
         {
             let mut cache = self.entity_cache.write().await;
@@ -325,46 +500,25 @@ impl StorageManager {
                 cache.insert(entity.id, entity.clone());
             }
         }
-        
+
         Ok(entities)
     }
-    
+
     /// Get all entities in a specific file from the database
-    async fn get_entities_for_file_from_database(&self, file_path: &str) -> Result<Vec<CodeEntity>> {
-        let mut entities = Vec::new();
-        
-        let db = self.database_manager.get_connection(Uuid::new_v4()).await?;
-        if let Some(db_conn) = db {
-            let sql = "SELECT * FROM code_entities WHERE file_path = ?";
-            let mut stmt = db_conn.prepare(sql).await?;
-            let mut rows = stmt.query([file_path]).await?;
-            
-            while let Some(row) = rows.next().await? {
-                let entity = CodeEntity {
-                    id: Uuid::parse_str(row.get::<String>(0)?.as_str())?,
-                    name: row.get(1)?,
-                    entity_type: self.parse_entity_type(&row.get::<String>(2)?)?,
-                    language: row.get(3)?,
-                    file_path: row.get(4)?,
-                    line_number: row.get::<i64>(5)? as usize,
-                    column_number: row.get::<i64>(6)? as usize,
-                    scope: row.get(7)?,
-                    content: row.get(8)?,
-                    embedding: match row.get::<Option<Vec<u8>>>(9)? {
-                        Some(bytes) => Some(bincode::deserialize(&bytes[..])?),
-                        None => None,
-                    },
-                    complexity: row.get(10)?,
-                    last_modified: chrono::DateTime::parse_from_rfc3339(&row.get::<String>(11)?)?.into(),
-                };
-                
-                entities.push(entity);
-            }
-        }
-        
-        Ok(entities)
+    async fn get_entities_for_file_from_database(
+        &self,
+        file_path: &str,
+    ) -> Result<Vec<CodeEntity>> {
+        self.file_queries.fetch_add(1, Ordering::Relaxed);
+
+        let rows = sqlx::query("SELECT * FROM code_entities WHERE file_path = ? ORDER BY rowid")
+            .bind(file_path)
+            .fetch_all(&self.pool)
+            .await?;
+
+        rows.iter().map(entity_from_row).collect()
     }
-    
+
     /// Get all dependencies for an entity
     pub async fn get_dependencies_for_entity(&self, entity_id: Uuid) -> Result<Vec<Dependency>> {
         // Check cache first
@@ -374,174 +528,285 @@ impl StorageManager {
                 return Ok(dependencies.clone());
             }
         }
-        
+
         // Check database
-        let dependencies = self.get_dependencies_for_entity_from_database(entity_id).await?;
-        
+        let sql = "SELECT * FROM dependencies WHERE from_entity = ?";
+        let dependencies = self.query_dependencies(sql, entity_id).await?;
+
         // Update cache
         {
             let mut cache = self.dependency_cache.write().await;
             cache.insert(entity_id, dependencies.clone());
         }
-        
+
         Ok(dependencies)
     }
-    
-    /// Get all dependencies for an entity from the database
-    async fn get_dependencies_for_entity_from_database(&self, entity_id: Uuid) -> Result<Vec<Dependency>> {
-        let mut dependencies = Vec::new();
-        
-        let db = self.database_manager.get_connection(Uuid::new_v4()).await?;
-        if let Some(db_conn) = db {
-            let sql = "SELECT * FROM dependencies WHERE from_entity = ?";
-            let mut stmt = db_conn.prepare(sql).await?;
-            let mut rows = stmt.query([entity_id.to_string()]).await?;
-            
-            while let Some(row) = rows.next().await? {
-                let dependency = Dependency {
-                    id: Uuid::parse_str(row.get::<String>(0)?.as_str())?,
-                    from_entity: Uuid::parse_str(row.get::<String>(1)?.as_str())?,
-                    to_entity: Uuid::parse_str(row.get::<String>(2)?.as_str())?,
-                    dependency_type: self.parse_dependency_type(&row.get::<String>(3)?)?,
-                    strength: row.get(4)?,
-                    file_path: row.get(5)?,
-                    line_number: row.get::<i64>(6)? as usize,
-                };
-                
-                dependencies.push(dependency);
+
+    /// Get all dependencies pointing at an entity, i.e. the entities depending on it
+    pub async fn get_dependents_for_entity(&self, entity_id: Uuid) -> Result<Vec<Dependency>> {
+        // Check cache first
+        {
+            let cache = self.dependent_cache.read().await;
+            if let Some(dependents) = cache.get(&entity_id) {
+                return Ok(dependents.clone());
             }
         }
-        
-        Ok(dependencies)
-    }
-    
-    /// Parse dependency type from string
-    fn parse_dependency_type(&self, type_str: &str) -> Result<crate::code_mapper::entities::DependencyType> {
-        use crate::code_mapper::entities::DependencyType;
-        
-        match type_str {
-            "Call" => Ok(DependencyType::Call),
-            "Inheritance" => Ok(DependencyType::Inheritance),
-            "Composition" => Ok(DependencyType::Composition),
-            "Import" => Ok(DependencyType::Import),
-            "Parameter" => Ok(DependencyType::Parameter),
-            "Return" => Ok(DependencyType::Return),
-            "FieldAccess" => Ok(DependencyType::FieldAccess),
-            "VariableUse" => Ok(DependencyType::VariableUse),
-            _ => Err(anyhow::anyhow!("Unknown dependency type: {}", type_str)),
+
+        // Check database
+        let sql = "SELECT * FROM dependencies WHERE to_entity = ?";
+        let dependents = self.query_dependencies(sql, entity_id).await?;
+
+        // Update cache
+        {
+            let mut cache = self.dependent_cache.write().await;
+            cache.insert(entity_id, dependents.clone());
         }
+
+        Ok(dependents)
+    }
+
+    /// Run a dependency query taking a single entity id parameter
+    async fn query_dependencies(&self, sql: &str, entity_id: Uuid) -> Result<Vec<Dependency>> {
+        let rows = sqlx::query(sql)
+            .bind(entity_id.to_string())
+            .fetch_all(&self.pool)
+            .await?;
+
+        rows.iter().map(dependency_from_row).collect()
     }
-    
+
     /// Remove all entities for a specific file
     pub async fn remove_entities_for_file(&self, file_path: &str) -> Result<()> {
         debug!("Removing entities for file: {}", file_path);
-        
+
         // Remove from database first (due to foreign key constraints)
-        self.remove_entities_for_file_from_database(file_path).await?;
-        
+        self.remove_entities_for_file_from_database(file_path)
+            .await?;
+
         // Update caches
         {
             let mut entity_cache = self.entity_cache.write().await;
             let mut file_entities_cache = self.file_entities_cache.write().await;
-            
-            if let Some(entity_ids) = file_entities_cache.remove(file_path) {
+
+            // The file's dependencies were deleted along with its entities
+            for dependencies in self.dependency_cache.write().await.values_mut() {
+                dependencies.retain(|d| d.file_path != file_path);
+            }
+            for dependents in self.dependent_cache.write().await.values_mut() {
+                dependents.retain(|d| d.file_path != file_path);
+            }
+
+            // The file is now known to be empty, so keep an empty entry for it
+            if let Some(entity_ids) = file_entities_cache.insert(file_path.to_string(), Vec::new())
+            {
                 for id in entity_ids {
                     entity_cache.remove(&id);
                 }
             }
         }
-        
+
         info!("Removed entities for file: {}", file_path);
         Ok(())
     }
-    
+
     /// Remove all entities for a specific file from the database
     async fn remove_entities_for_file_from_database(&self, file_path: &str) -> Result<()> {
-        let db = self.database_manager.get_connection(Uuid::new_v4()).await?;
-        if let Some(db_conn) = db {
-            // Remove dependencies first (due to foreign key constraints)
-            let sql_deps = "DELETE FROM dependencies WHERE file_path = ?";
-            db_conn.execute(sql_deps, &[file_path]).await?;
-            
-            // Remove entities
-            let sql_entities = "DELETE FROM code_entities WHERE file_path = ?";
-            db_conn.execute(sql_entities, &[file_path]).await?;
-        }
-        
+        let mut tx = self.pool.begin().await?;
+
+        // Remove dependencies first (due to foreign key constraints)
+        sqlx::query("DELETE FROM dependencies WHERE file_path = ?")
+            .bind(file_path)
+            .execute(&mut *tx)
+            .await?;
+
+        // Remove entities
+        sqlx::query("DELETE FROM code_entities WHERE file_path = ?")
+            .bind(file_path)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
         Ok(())
     }
-    
+
     /// Clear all caches
     pub async fn clear_caches(&self) {
         let mut entity_cache = self.entity_cache.write().await;
         let mut file_entities_cache = self.file_entities_cache.write().await;
         let mut dependency_cache = self.dependency_cache.write().await;
+        let mut dependent_cache = self.dependent_cache.write().await;
         let mut embedding_cache = self.embedding_cache.write().await;
-        
+
         entity_cache.clear();
         file_entities_cache.clear();
         dependency_cache.clear();
+        dependent_cache.clear();
         embedding_cache.clear();
-        
+
         info!("All caches cleared");
     }
-    
-    /// Get the database manager
-    pub fn get_database_manager(&self) -> &DatabaseManager {
-        &self.database_manager
+
+    /// Get the database pool
+    pub fn pool(&self) -> &SqlitePool {
+        &self.pool
+    }
+}
+
+/// Build an entity from a `code_entities` row
+fn entity_from_row(row: &SqliteRow) -> Result<CodeEntity> {
+    let embedding: Option<Vec<u8>> = row.get("embedding");
+
+    Ok(CodeEntity {
+        id: Uuid::parse_str(row.get("id"))?,
+        name: row.get("name"),
+        entity_type: parse_entity_type(row.get("entity_type"))?,
+        language: row.get("language"),
+        file_path: row.get("file_path"),
+        line_number: row.get::<i64, _>("line_number") as usize,
+        column_number: row.get::<i64, _>("column_number") as usize,
+        scope: row.get::<Option<String>, _>("scope").unwrap_or_default(),
+        dependencies: Vec::new(),
+        accessed_by: Vec::new(),
+        content: row.get("content"),
+        embedding: match embedding {
+            Some(bytes) => Some(bincode::deserialize(&bytes)?),
+            None => None,
+        },
+        complexity: row.get::<Option<f32>, _>("complexity").unwrap_or_default(),
+        last_modified: chrono::DateTime::parse_from_rfc3339(row.get("last_modified"))?.into(),
+    })
+}
+
+/// Build a dependency from a `dependencies` row
+fn dependency_from_row(row: &SqliteRow) -> Result<Dependency> {
+    Ok(Dependency {
+        id: Uuid::parse_str(row.get("id"))?,
+        from_entity: Uuid::parse_str(row.get("from_entity"))?,
+        to_entity: Uuid::parse_str(row.get("to_entity"))?,
+        dependency_type: parse_dependency_type(row.get("dependency_type"))?,
+        strength: row.get("strength"),
+        file_path: row.get("file_path"),
+        line_number: row.get::<i64, _>("line_number") as usize,
+    })
+}
+
+/// Parse entity type from string
+fn parse_entity_type(type_str: &str) -> Result<CodeEntityType> {
+    match type_str {
+        "Function" => Ok(CodeEntityType::Function),
+        "Method" => Ok(CodeEntityType::Method),
+        "Class" => Ok(CodeEntityType::Class),
+        "Struct" => Ok(CodeEntityType::Struct),
+        "Interface" => Ok(CodeEntityType::Interface),
+        "Variable" => Ok(CodeEntityType::Variable),
+        "Constant" => Ok(CodeEntityType::Constant),
+        "Module" => Ok(CodeEntityType::Module),
+        "Namespace" => Ok(CodeEntityType::Namespace),
+        "Type" => Ok(CodeEntityType::Type),
+        "Enum" => Ok(CodeEntityType::Enum),
+        _ => Err(anyhow::anyhow!("Unknown entity type: {}", type_str)),
+    }
+}
+
+/// Parse dependency type from string
+fn parse_dependency_type(type_str: &str) -> Result<DependencyType> {
+    match type_str {
+        "Call" => Ok(DependencyType::Call),
+        "Inheritance" => Ok(DependencyType::Inheritance),
+        "Composition" => Ok(DependencyType::Composition),
+        "Import" => Ok(DependencyType::Import),
+        "Parameter" => Ok(DependencyType::Parameter),
+        "Return" => Ok(DependencyType::Return),
+        "FieldAccess" => Ok(DependencyType::FieldAccess),
+        "VariableUse" => Ok(DependencyType::VariableUse),
+        _ => Err(anyhow::anyhow!("Unknown dependency type: {}", type_str)),
+    }
+}
+
+/// Replace the cached copy of a dependency, or add it if it isn't cached
+fn upsert_dependency(cached: &mut Vec<Dependency>, dependency: &Dependency) {
+    match cached
+        .iter_mut()
+        .find(|existing| existing.id == dependency.id)
+    {
+        Some(existing) => *existing = dependency.clone(),
+        None => cached.push(dependency.clone()),
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use odincode_databases::DatabaseManager;
-    use tempfile::TempDir;
-    use crate::code_mapper::entities::{CodeEntity, CodeEntityType, Dependency, DependencyType};
     use chrono::Utc;
-    
+    use sqlx::sqlite::SqliteConnectOptions;
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    /// Rust function entity without an embedding
+    fn function_entity(
+        name: &str,
+        file_path: &str,
+        line_number: usize,
+        content: &str,
+    ) -> CodeEntity {
+        CodeEntity {
+            id: Uuid::new_v4(),
+            name: name.to_string(),
+            entity_type: CodeEntityType::Function,
+            language: "rust".to_string(),
+            file_path: file_path.to_string(),
+            line_number,
+            column_number: 0,
+            scope: String::new(),
+            dependencies: Vec::new(),
+            accessed_by: Vec::new(),
+            content: content.to_string(),
+            embedding: None,
+            complexity: 1.0,
+            last_modified: Utc::now(),
+        }
+    }
+
+    /// Open a pool on a database file, creating it if needed
+    async fn open_pool(db_path: &Path) -> Result<SqlitePool> {
+        Ok(SqlitePool::connect_with(
+            SqliteConnectOptions::new()
+                .filename(db_path)
+                .create_if_missing(true),
+        )
+        .await?)
+    }
+
     #[tokio::test]
     async fn test_storage_manager_creation() -> Result<()> {
         let temp_dir = TempDir::new()?;
         let db_path = temp_dir.path().join("test.db");
-        let database_manager = DatabaseManager::new_with_path(&db_path)?;
-        let storage_manager = StorageManager::new(database_manager);
-        
+        let storage_manager = StorageManager::new(open_pool(&db_path).await?);
+
         storage_manager.initialize().await?;
         assert_eq!(storage_manager.entity_cache.read().await.len(), 0);
-        
+
         Ok(())
     }
-    
+
     #[tokio::test]
     async fn test_entity_storage_and_retrieval() -> Result<()> {
         let temp_dir = TempDir::new()?;
         let db_path = temp_dir.path().join("test.db");
-        let database_manager = DatabaseManager::new_with_path(&db_path)?;
-        let storage_manager = StorageManager::new(database_manager);
-        
+        let storage_manager = StorageManager::new(open_pool(&db_path).await?);
+
         storage_manager.initialize().await?;
-        
-        let entity = CodeEntity {
-            id: Uuid::new_v4(),
-            name: "test_function".to_string(),
-            entity_type: CodeEntityType::Function,
-            language: "rust".to_string(),
-            file_path: "test.rs".to_string(),
-            line_number: 10,
-            column_number: 5,
-            scope: String::new(),
-            dependencies: Vec::new(),
-            accessed_by: Vec::new(),
-            content: "fn test_function() -> i32 { 42 }".to_string(),
-            embedding: None,
-            complexity: 1.0,
-            last_modified: Utc::now(),
-        };
-        
+
+        let entity = function_entity(
+            "test_function",
+            "test.rs",
+            10,
+            "fn test_function() -> i32 { 42 }",
+        );
+
         // Store entity
         storage_manager.store_entity(&entity).await?;
-        
+
         // Retrieve entity
         let retrieved = storage_manager.get_entity_by_id(entity.id).await?;
         assert!(retrieved.is_some());
@@ -552,113 +817,61 @@ mod tests {
         assert_eq!(retrieved.file_path, "test.rs");
         assert_eq!(retrieved.line_number, 10);
         assert_eq!(retrieved.complexity, 1.0);
-        
+
         Ok(())
     }
-    
+
     #[tokio::test]
     async fn test_file_entities_retrieval() -> Result<()> {
         let temp_dir = TempDir::new()?;
         let db_path = temp_dir.path().join("test.db");
-        let database_manager = DatabaseManager::new_with_path(&db_path)?;
-        let storage_manager = StorageManager::new(database_manager);
-        
+        let storage_manager = StorageManager::new(open_pool(&db_path).await?);
+
         storage_manager.initialize().await?;
-        
-        let entity1 = CodeEntity {
-            id: Uuid::new_v4(),
-            name: "function1".to_string(),
-            entity_type: CodeEntityType::Function,
-            language: "rust".to_string(),
-            file_path: "test.rs".to_string(),
-            line_number: 5,
-            column_number: 0,
-            scope: String::new(),
-            dependencies: Vec::new(),
-            accessed_by: Vec::new(),
-            content: "fn function1() -> i32 { 42 }".to_string(),
-            embedding: None,
-            complexity: 1.0,
-            last_modified: Utc::now(),
-        };
-        
-        let entity2 = CodeEntity {
-            id: Uuid::new_v4(),
-            name: "function2".to_string(),
-            entity_type: CodeEntityType::Function,
-            language: "rust".to_string(),
-            file_path: "test.rs".to_string(),
-            line_number: 10,
-            column_number: 0,
-            scope: String::new(),
-            dependencies: Vec::new(),
-            accessed_by: Vec::new(),
-            content: "fn function2() -> i32 { function1() + 1 }".to_string(),
-            embedding: None,
-            complexity: 1.0,
-            last_modified: Utc::now(),
-        };
-        
+
+        let entity1 = function_entity("function1", "test.rs", 5, "fn function1() -> i32 { 42 }");
+
+        let entity2 = function_entity(
+            "function2",
+            "test.rs",
+            10,
+            "fn function2() -> i32 { function1() + 1 }",
+        );
+
         // Store entities
         storage_manager.store_entity(&entity1).await?;
         storage_manager.store_entity(&entity2).await?;
-        
+
         // Retrieve entities for file
         let entities = storage_manager.get_entities_for_file("test.rs").await?;
         assert_eq!(entities.len(), 2);
-        
+
         // Check that we found both entities
         let function1_found = entities.iter().any(|e| e.name == "function1");
         let function2_found = entities.iter().any(|e| e.name == "function2");
         assert!(function1_found, "function1 should be found");
         assert!(function2_found, "function2 should be found");
-        
+
         Ok(())
     }
-    
+
     #[tokio::test]
     async fn test_dependency_storage_and_retrieval() -> Result<()> {
         let temp_dir = TempDir::new()?;
         let db_path = temp_dir.path().join("test.db");
-        let database_manager = DatabaseManager::new_with_path(&db_path)?;
-        let storage_manager = StorageManager::new(database_manager);
-        
+        let storage_manager = StorageManager::new(open_pool(&db_path).await?);
+
         storage_manager.initialize().await?;
-        
-        let entity1 = CodeEntity {
-            id: Uuid::new_v4(),
-            name: "function1".to_string(),
-            entity_type: CodeEntityType::Function,
-            language: "rust".to_string(),
-            file_path: "test.rs".to_string(),
-            line_number: 5,
-            column_number: 0,
-            scope: String::new(),
-            dependencies: Vec::new(),
-            accessed_by: Vec::new(),
-            content: "fn function1() -> i32 { 42 }".to_string(),
-            embedding: None,
-            complexity: 1.0,
-            last_modified: Utc::now(),
-        };
-        
-        let entity2 = CodeEntity {
-            id: Uuid::new_v4(),
-            name: "function2".to_string(),
-            entity_type: CodeEntityType::Function,
-            language: "rust".to_string(),
-            file_path: "test.rs".to_string(),
-            line_number: 10,
-            column_number: 0,
-            scope: String::new(),
-            dependencies: Vec::new(),
-            accessed_by: Vec::new(),
-            content: "fn function2() -> i32 { function1() + 1 }".to_string(),
-            embedding: None,
-            complexity: 1.0,
-            last_modified: Utc::now(),
-        };
-        
+
+        let entity1 = function_entity("function1", "test.rs", 5, "fn function1() -> i32 { 42 }");
+
+        let entity2 = function_entity(
+            "function2",
+            "test.rs",
+            10,
+            "fn function2() -> i32 { function1() + 1 }",
+        );
+
         let dependency = Dependency {
             id: Uuid::new_v4(),
             from_entity: entity2.id,
@@ -668,67 +881,262 @@ mod tests {
             file_path: "test.rs".to_string(),
             line_number: 10,
         };
-        
+
         // Store entities and dependency
         storage_manager.store_entity(&entity1).await?;
         storage_manager.store_entity(&entity2).await?;
         storage_manager.store_dependency(&dependency).await?;
-        
+
         // Retrieve dependencies
-        let dependencies = storage_manager.get_dependencies_for_entity(entity2.id).await?;
+        let dependencies = storage_manager
+            .get_dependencies_for_entity(entity2.id)
+            .await?;
         assert_eq!(dependencies.len(), 1);
         assert_eq!(dependencies[0].from_entity, entity2.id);
         assert_eq!(dependencies[0].to_entity, entity1.id);
         assert_eq!(dependencies[0].dependency_type, DependencyType::Call);
         assert_eq!(dependencies[0].strength, 1.0);
-        
+
+        // The same edge is found from its target
+        let dependents = storage_manager
+            .get_dependents_for_entity(entity1.id)
+            .await?;
+        assert_eq!(dependents.len(), 1);
+        assert_eq!(dependents[0].id, dependency.id);
+        assert_eq!(dependents[0].from_entity, entity2.id);
+        assert!(storage_manager
+            .get_dependents_for_entity(entity2.id)
+            .await?
+            .is_empty());
+
+        // And from the database once the caches are cold
+        storage_manager.clear_caches().await;
+        let dependents = storage_manager
+            .get_dependents_for_entity(entity1.id)
+            .await?;
+        assert_eq!(dependents.len(), 1);
+        assert_eq!(dependents[0].from_entity, entity2.id);
+
         Ok(())
     }
-    
+
     #[tokio::test]
     async fn test_remove_entities_for_file() -> Result<()> {
         let temp_dir = TempDir::new()?;
         let db_path = temp_dir.path().join("test.db");
-        let database_manager = DatabaseManager::new_with_path(&db_path)?;
-        let storage_manager = StorageManager::new(database_manager);
-        
+        let storage_manager = StorageManager::new(open_pool(&db_path).await?);
+
         storage_manager.initialize().await?;
-        
-        let entity = CodeEntity {
-            id: Uuid::new_v4(),
-            name: "test_function".to_string(),
-            entity_type: CodeEntityType::Function,
-            language: "rust".to_string(),
-            file_path: "test.rs".to_string(),
-            line_number: 10,
-            column_number: 5,
-            scope: String::new(),
-            dependencies: Vec::new(),
-            accessed_by: Vec::new(),
-            content: "fn test_function() -> i32 { 42 }".to_string(),
-            embedding: None,
-            complexity: 1.0,
-            last_modified: Utc::now(),
-        };
-        
+
+        let entity = function_entity(
+            "test_function",
+            "test.rs",
+            10,
+            "fn test_function() -> i32 { 42 }",
+        );
+
         // Store entity
         storage_manager.store_entity(&entity).await?;
-        
+
         // Verify entity exists
         let retrieved = storage_manager.get_entity_by_id(entity.id).await?;
         assert!(retrieved.is_some());
-        
+
         // Remove entities for file
         storage_manager.remove_entities_for_file("test.rs").await?;
-        
+
         // Verify entity no longer exists
         let retrieved = storage_manager.get_entity_by_id(entity.id).await?;
         assert!(retrieved.is_none());
-        
+
         // Verify file entities are empty
         let entities = storage_manager.get_entities_for_file("test.rs").await?;
         assert!(entities.is_empty());
-        
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_stored_entities_are_cached_per_file() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let db_path = temp_dir.path().join("test.db");
+        let storage_manager = StorageManager::new(open_pool(&db_path).await?);
+
+        storage_manager.initialize().await?;
+
+        let entity1 = function_entity("function1", "test.rs", 5, "fn function1() -> i32 { 42 }");
+        let entity2 = CodeEntity {
+            id: Uuid::new_v4(),
+            name: "function2".to_string(),
+            line_number: 10,
+            content: "fn function2() -> i32 { function1() + 1 }".to_string(),
+            ..entity1.clone()
+        };
+
+        storage_manager.store_entity(&entity1).await?;
+        storage_manager.store_entity(&entity2).await?;
+        // Storing an entity again doesn't list it twice
+        storage_manager.store_entity(&entity2).await?;
+        let queries = storage_manager.file_queries.load(Ordering::Relaxed);
+
+        let entities = storage_manager.get_entities_for_file("test.rs").await?;
+        let names: Vec<&str> = entities.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["function1", "function2"]);
+        assert_eq!(
+            storage_manager.file_queries.load(Ordering::Relaxed),
+            queries
+        );
+
+        // A file that was never cached is completed from the database first
+        let reopened = StorageManager::new(open_pool(&db_path).await?);
+        reopened.initialize().await?;
+        let entity3 = CodeEntity {
+            id: Uuid::new_v4(),
+            name: "function3".to_string(),
+            ..entity1.clone()
+        };
+        reopened.store_entity(&entity3).await?;
+        assert_eq!(reopened.get_entities_for_file("test.rs").await?.len(), 3);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_store_entities_in_one_batch() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let db_path = temp_dir.path().join("test.db");
+        let storage_manager = StorageManager::new(open_pool(&db_path).await?);
+
+        storage_manager.initialize().await?;
+
+        let entities: Vec<CodeEntity> = (0..200)
+            .map(|i| {
+                function_entity(
+                    &format!("function{}", i),
+                    "big.rs",
+                    i + 1,
+                    &format!("fn function{}() {{}}", i),
+                )
+            })
+            .collect();
+        let dependencies: Vec<Dependency> = entities
+            .windows(2)
+            .map(|pair| Dependency {
+                id: Uuid::new_v4(),
+                from_entity: pair[1].id,
+                to_entity: pair[0].id,
+                dependency_type: DependencyType::Call,
+                strength: 1.0,
+                file_path: "big.rs".to_string(),
+                line_number: pair[1].line_number,
+            })
+            .collect();
+
+        storage_manager.store_entities(&entities).await?;
+        storage_manager.store_dependencies(&dependencies).await?;
+
+        // The caches are warm, so the file is served without querying it again
+        assert_eq!(storage_manager.entity_cache.read().await.len(), 200);
+        let queries = storage_manager.file_queries.load(Ordering::Relaxed);
+        assert_eq!(
+            storage_manager.get_entities_for_file("big.rs").await?.len(),
+            200
+        );
+        assert_eq!(
+            storage_manager.file_queries.load(Ordering::Relaxed),
+            queries
+        );
+        assert_eq!(
+            storage_manager
+                .get_dependencies_for_entity(entities[199].id)
+                .await?[0]
+                .to_entity,
+            entities[198].id
+        );
+
+        // Everything was also written to the database
+        storage_manager.clear_caches().await;
+        for entity in &entities {
+            assert!(storage_manager.get_entity_by_id(entity.id).await?.is_some());
+        }
+        assert_eq!(
+            storage_manager.get_entities_for_file("big.rs").await?.len(),
+            200
+        );
+
+        Ok(())
+    }
+
+    /// Embedding provider that counts how often it computes a vector
+    struct CountingProvider {
+        calls: std::sync::atomic::AtomicUsize,
+    }
+
+    impl EmbeddingProvider for CountingProvider {
+        fn embed(&self, content: &str) -> Result<Vec<f32>> {
+            self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(vec![content.len() as f32, 1.0, 2.0])
+        }
+    }
+
+    #[tokio::test]
+    async fn test_embedding_cache_reuses_vectors_for_identical_content() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let db_path = temp_dir.path().join("test.db");
+        let provider = Arc::new(CountingProvider {
+            calls: std::sync::atomic::AtomicUsize::new(0),
+        });
+        let storage_manager = StorageManager::new(open_pool(&db_path).await?)
+            .with_embedding_provider(provider.clone());
+
+        storage_manager.initialize().await?;
+
+        let entity = function_entity(
+            "test_function",
+            "test.rs",
+            10,
+            "fn test_function() -> i32 { 42 }",
+        );
+        let copy = CodeEntity {
+            id: Uuid::new_v4(),
+            file_path: "copy.rs".to_string(),
+            ..entity.clone()
+        };
+
+        // Storing identical content twice computes the embedding once
+        storage_manager.store_entity(&entity).await?;
+        storage_manager.store_entity(&copy).await?;
+        assert_eq!(provider.calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+        let retrieved = storage_manager.get_entity_by_id(copy.id).await?.unwrap();
+        assert_eq!(retrieved.embedding, Some(vec![32.0, 1.0, 2.0]));
+
+        // The cache is persisted, so a fresh storage manager doesn't recompute it
+        let reopened = StorageManager::new(open_pool(&db_path).await?)
+            .with_embedding_provider(provider.clone());
+        reopened.initialize().await?;
+        let renamed = CodeEntity {
+            id: Uuid::new_v4(),
+            ..entity.clone()
+        };
+        reopened.store_entity(&renamed).await?;
+        assert_eq!(provider.calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+        // An entity stored before its embedding was computed reads it from the
+        // persisted cache
+        let plain = StorageManager::new(open_pool(&db_path).await?);
+        plain.initialize().await?;
+        let unembedded = function_entity("later", "test.rs", 20, "fn later() {}");
+        plain.store_entity(&unembedded).await?;
+        let embedded = CodeEntity {
+            id: Uuid::new_v4(),
+            ..unembedded.clone()
+        };
+        reopened.store_entity(&embedded).await?;
+        plain.clear_caches().await;
+        let retrieved = plain.get_entity_by_id(unembedded.id).await?.unwrap();
+        assert_eq!(retrieved.embedding, Some(vec![13.0, 1.0, 2.0]));
+
         Ok(())
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,85 @@
+//! Line Ending Module
+//!
+//! This module detects whether a file uses Unix or Windows line endings, so
+//! edits can write new lines with the ending the file already uses instead of
+//! silently converting Windows files to `\n`.
+
+use serde::{Deserialize, Serialize};
+
+/// Line terminator used by most lines of a file
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LineEnding {
+    /// `\n`
+    #[default]
+    Lf,
+    /// `\r\n`
+    CrLf,
+}
+
+impl LineEnding {
+    /// Detect the dominant line ending of some content, `Lf` on a tie
+    pub fn detect(content: &str) -> Self {
+        let crlf = content.matches("\r\n").count();
+        let lf = content.matches('\n').count() - crlf;
+        if crlf > lf {
+            LineEnding::CrLf
+        } else {
+            LineEnding::Lf
+        }
+    }
+
+    /// The terminator itself
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LineEnding::Lf => "\n",
+            LineEnding::CrLf => "\r\n",
+        }
+    }
+
+    /// Convert every line break in `text` to this ending
+    pub fn apply(&self, text: &str) -> String {
+        let normalized = text.replace("\r\n", "\n");
+        match self {
+            LineEnding::Lf => normalized,
+            LineEnding::CrLf => normalized.replace('\n', "\r\n"),
+        }
+    }
+}
+
+/// Split content into lines, each with the terminator it ends with
+///
+/// The terminator is `\r\n`, `\n`, or empty for a final line without one, so
+/// joining the parts gives back the content exactly.
+pub fn split_terminated(content: &str) -> Vec<(&str, &str)> {
+    content
+        .split_inclusive('\n')
+        .map(|line| {
+            if let Some(text) = line.strip_suffix("\r\n") {
+                (text, "\r\n")
+            } else if let Some(text) = line.strip_suffix('\n') {
+                (text, "\n")
+            } else {
+                (line, "")
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dominant_ending_and_split() {
+        assert_eq!(LineEnding::detect("a\r\nb\r\nc\n"), LineEnding::CrLf);
+        assert_eq!(LineEnding::detect("a\r\nb\n"), LineEnding::Lf);
+        assert_eq!(LineEnding::detect("no newline"), LineEnding::Lf);
+        assert_eq!(LineEnding::CrLf.apply("x\ny\r\n"), "x\r\ny\r\n");
+
+        let content = "a\r\nb\nc";
+        let lines = split_terminated(content);
+        assert_eq!(lines, vec![("a", "\r\n"), ("b", "\n"), ("c", "")]);
+        let joined: String = lines.iter().flat_map(|(text, end)| [*text, *end]).collect();
+        assert_eq!(joined, content);
+    }
+}
@@ -307,6 +307,7 @@ async fn test_configuration_validation() {
         providers: HashMap::new(),              // No providers
         settings: crate::config::ConfigSettings::default(),
         model_selection: crate::config::ModelSelectionConfig::default(),
+        fallback_providers: Vec::new(),
     };
 
     // Create a new manager with invalid config for testing
@@ -742,6 +743,7 @@ async fn test_error_handling_and_edge_cases() {
         providers: HashMap::new(),
         settings: crate::config::ConfigSettings::default(),
         model_selection: crate::config::ModelSelectionConfig::default(),
+        fallback_providers: Vec::new(),
     };
 
     let mut config_manager = ConfigManager::new().unwrap();
@@ -857,6 +859,7 @@ async fn test_configuration_persistence() {
             auto_detection_enabled: true,
             learning_enabled: true,
         },
+        fallback_providers: vec!["anthropic".to_string()],
     };
 
     // Save configuration in different formats
@@ -5,6 +5,9 @@
 
 use anyhow::Result;
 use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use crate::llm_integration::LLMIntegrationManager;
 
 // Re-export facade types for backward compatibility
 pub use crate::ml_integration::facade::{
@@ -30,6 +33,8 @@ pub use crate::ml_integration::models::TrainedModel;
 #[derive(Clone)]
 pub struct MLIntegrationManager {
     facade: Arc<MLIntegrationFacade>,
+    /// LLM used for enhanced suggestions, shared by all clones of the manager
+    llm_integration: Arc<RwLock<Option<Arc<LLMIntegrationManager>>>>,
 }
 
 impl MLIntegrationManager {
@@ -40,7 +45,10 @@ impl MLIntegrationManager {
         config: MLIntegrationConfig,
     ) -> Result<Self> {
         let facade = Arc::new(MLIntegrationFacade::new(config).await?);
-        Ok(Self { facade })
+        Ok(Self {
+            facade,
+            llm_integration: Arc::new(RwLock::new(None)),
+        })
     }
 
     /// Train a model
@@ -97,18 +105,14 @@ impl MLIntegrationManager {
     // ===== ADDITIONAL COMPATIBILITY METHODS =====
     // These methods were requested but missing from the original implementation
 
-    /// Set LLM integration (compatibility method)
-    pub async fn set_llm_integration<T>(&mut self, _llm: T) {
-        // Note: This is a compatibility shim - the new architecture handles LLM integration differently
-        // For now, we'll just log this operation
-        tracing::debug!("set_llm_integration called (compatibility shim)");
+    /// Attach the LLM used for enhanced suggestions, replacing any attached before
+    pub async fn set_llm_integration(&self, llm: Arc<LLMIntegrationManager>) {
+        *self.llm_integration.write().await = Some(llm);
     }
 
-    /// Get LLM integration (compatibility method)
-    pub async fn get_llm_integration(&self) -> Option<crate::ml_integration::llm::LLMIntegration> {
-        // Note: This is a compatibility shim - the new architecture handles LLM integration differently
-        tracing::debug!("get_llm_integration called (compatibility shim)");
-        None
+    /// The LLM used for enhanced suggestions, if one is attached
+    pub async fn get_llm_integration(&self) -> Option<Arc<LLMIntegrationManager>> {
+        self.llm_integration.read().await.clone()
     }
 
     /// Analyze with ML (compatibility method)
@@ -170,6 +174,7 @@ impl Default for MLIntegrationManager {
         });
         Self {
             facade: Arc::new(facade),
+            llm_integration: Arc::new(RwLock::new(None)),
         }
     }
 }
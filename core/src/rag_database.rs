@@ -3,10 +3,15 @@
 //! This module provides semantic search capabilities using vector storage
 //! to enable intelligent code retrieval and context building for AI models.
 
+use crate::language_parsing::SupportedLanguage;
+use crate::CodeFile;
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use sqlx::{Row, SqlitePool};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Maximum number of chunks returned by a relationship traversal
+pub const MAX_RELATED_CHUNKS: usize = 50;
 
 /// Code chunk representing a semantically meaningful piece of code
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -109,6 +114,25 @@ impl RagDatabase {
             .execute(&self.pool)
             .await?;
 
+        // Relationships between chunks (same function, same module, ...)
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS chunk_relationships (
+                source_id TEXT NOT NULL,
+                target_id TEXT NOT NULL,
+                relationship_type TEXT NOT NULL,
+                PRIMARY KEY (source_id, target_id, relationship_type)
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+        sqlx::query(
+            "CREATE INDEX IF NOT EXISTS idx_relationships_target ON chunk_relationships(target_id)",
+        )
+        .execute(&self.pool)
+        .await?;
+
         // Full-text search index for content
         sqlx::query(
             "CREATE VIRTUAL TABLE IF NOT EXISTS chunks_fts USING fts5(content, chunk_id, tokenize='porter')"
@@ -511,6 +535,79 @@ impl RagDatabase {
         Ok(chunks)
     }
 
+    /// Record a relationship between two chunks
+    pub async fn add_relationship(
+        &self,
+        source_id: &str,
+        target_id: &str,
+        relationship_type: &str,
+    ) -> Result<()> {
+        sqlx::query(
+            "INSERT OR IGNORE INTO chunk_relationships (source_id, target_id, relationship_type) VALUES (?, ?, ?)",
+        )
+        .bind(source_id)
+        .bind(target_id)
+        .bind(relationship_type)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Get the ids of chunks directly related to a chunk, in either direction
+    async fn related_chunk_ids(&self, chunk_id: &str) -> Result<Vec<String>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT target_id AS related_id FROM chunk_relationships WHERE source_id = ?
+            UNION
+            SELECT source_id AS related_id FROM chunk_relationships WHERE target_id = ?
+            ORDER BY related_id
+            "#,
+        )
+        .bind(chunk_id)
+        .bind(chunk_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(|row| row.get("related_id")).collect())
+    }
+
+    /// Get chunks reachable from a chunk by following relationships up to `depth` hops
+    ///
+    /// Chunks are returned nearest first without duplicates, excluding the starting
+    /// chunk, and capped at [`MAX_RELATED_CHUNKS`].
+    pub async fn related_chunks(&self, chunk_id: &str, depth: usize) -> Result<Vec<CodeChunk>> {
+        let mut visited: HashSet<String> = HashSet::new();
+        visited.insert(chunk_id.to_string());
+
+        let mut queue: VecDeque<(String, usize)> = VecDeque::new();
+        queue.push_back((chunk_id.to_string(), 0));
+
+        let mut related = Vec::new();
+        while let Some((current, hops)) = queue.pop_front() {
+            if hops >= depth {
+                continue;
+            }
+
+            for next in self.related_chunk_ids(&current).await? {
+                // Visited set guards against cycles
+                if !visited.insert(next.clone()) {
+                    continue;
+                }
+
+                if let Some(chunk) = self.get_chunk(&next).await? {
+                    related.push(chunk);
+                    if related.len() >= MAX_RELATED_CHUNKS {
+                        return Ok(related);
+                    }
+                }
+                queue.push_back((next, hops + 1));
+            }
+        }
+
+        Ok(related)
+    }
+
     /// Update a chunk's embedding
     pub async fn update_chunk_embedding(&self, chunk_id: &str, embedding: &[f32]) -> Result<()> {
         let embedding_blob = bincode::serialize(embedding)?;
@@ -526,6 +623,202 @@ impl RagDatabase {
     }
 }
 
+/// Number of lines per chunk for [`ChunkStrategy::FixedLines`]
+pub const DEFAULT_CHUNK_LINES: usize = 50;
+
+/// How a file is split into chunks for embedding
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChunkStrategy {
+    /// Fixed-size windows of [`DEFAULT_CHUNK_LINES`] lines
+    FixedLines,
+    /// One chunk per top-level function, type or module, found with tree-sitter
+    BySymbol,
+    /// One chunk per run of non-blank lines
+    ByParagraph,
+}
+
+/// Split a file into chunks using the given strategy
+///
+/// `BySymbol` falls back to `FixedLines` when the file's language has no
+/// symbol table or no symbols are found.
+pub fn chunk_file(file: &CodeFile, strategy: ChunkStrategy) -> Result<Vec<CodeChunk>> {
+    match strategy {
+        ChunkStrategy::FixedLines => Ok(chunk_by_fixed_lines(file)),
+        ChunkStrategy::ByParagraph => Ok(chunk_by_paragraph(file)),
+        ChunkStrategy::BySymbol => match chunk_by_symbol(file)? {
+            Some(chunks) => Ok(chunks),
+            None => Ok(chunk_by_fixed_lines(file)),
+        },
+    }
+}
+
+/// Build a chunk covering 1-based lines `start_line..=end_line` of a file
+fn make_chunk(
+    file: &CodeFile,
+    chunk_type: ChunkType,
+    lines: &[&str],
+    start_line: usize,
+    end_line: usize,
+    strategy: &str,
+) -> CodeChunk {
+    let now = chrono::Utc::now().timestamp();
+    let mut metadata = HashMap::new();
+    metadata.insert("language".to_string(), file.language.clone());
+    metadata.insert("strategy".to_string(), strategy.to_string());
+
+    CodeChunk {
+        id: format!("{}:{}-{}", file.id, start_line, end_line),
+        file_path: file.path.clone(),
+        chunk_type,
+        content: lines[start_line - 1..end_line].join("\n"),
+        start_line: start_line as u32,
+        end_line: end_line as u32,
+        embedding: None,
+        semantic_hash: None,
+        metadata,
+        created_at: now,
+        updated_at: now,
+    }
+}
+
+fn chunk_by_fixed_lines(file: &CodeFile) -> Vec<CodeChunk> {
+    let lines: Vec<&str> = file.content.lines().collect();
+    (0..lines.len())
+        .step_by(DEFAULT_CHUNK_LINES)
+        .map(|start| {
+            let end = (start + DEFAULT_CHUNK_LINES).min(lines.len());
+            make_chunk(
+                file,
+                ChunkType::Block,
+                &lines,
+                start + 1,
+                end,
+                "fixed_lines",
+            )
+        })
+        .collect()
+}
+
+fn chunk_by_paragraph(file: &CodeFile) -> Vec<CodeChunk> {
+    let lines: Vec<&str> = file.content.lines().collect();
+    let mut chunks = Vec::new();
+    let mut start: Option<usize> = None;
+
+    for (idx, line) in lines.iter().enumerate() {
+        match (line.trim().is_empty(), start) {
+            (false, None) => start = Some(idx),
+            (true, Some(s)) => {
+                chunks.push(make_chunk(
+                    file,
+                    ChunkType::Block,
+                    &lines,
+                    s + 1,
+                    idx,
+                    "paragraph",
+                ));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        chunks.push(make_chunk(
+            file,
+            ChunkType::Block,
+            &lines,
+            s + 1,
+            lines.len(),
+            "paragraph",
+        ));
+    }
+
+    chunks
+}
+
+/// Top-level node kinds that form a symbol chunk, with their chunk type
+fn symbol_node_kinds(language: &SupportedLanguage) -> &'static [(&'static str, ChunkType)] {
+    match language {
+        SupportedLanguage::Rust => &[
+            ("function_item", ChunkType::Function),
+            ("struct_item", ChunkType::Class),
+            ("enum_item", ChunkType::Class),
+            ("trait_item", ChunkType::Class),
+            ("impl_item", ChunkType::Class),
+            ("mod_item", ChunkType::Module),
+        ],
+        SupportedLanguage::Python => &[
+            ("function_definition", ChunkType::Function),
+            ("class_definition", ChunkType::Class),
+        ],
+        SupportedLanguage::JavaScript | SupportedLanguage::TypeScript => &[
+            ("function_declaration", ChunkType::Function),
+            ("class_declaration", ChunkType::Class),
+        ],
+        SupportedLanguage::Go => &[
+            ("function_declaration", ChunkType::Function),
+            ("method_declaration", ChunkType::Method),
+            ("type_declaration", ChunkType::Class),
+        ],
+        SupportedLanguage::Java => &[
+            ("class_declaration", ChunkType::Class),
+            ("interface_declaration", ChunkType::Class),
+        ],
+        _ => &[],
+    }
+}
+
+/// Split a file at top-level symbol boundaries, or `None` if that isn't possible
+fn chunk_by_symbol(file: &CodeFile) -> Result<Option<Vec<CodeChunk>>> {
+    let language = match SupportedLanguage::from_str(&file.language) {
+        Some(language) => language,
+        None => return Ok(None),
+    };
+    let kinds = symbol_node_kinds(&language);
+    if kinds.is_empty() {
+        return Ok(None);
+    }
+
+    let mut parser = tree_sitter::Parser::new();
+    parser.set_language(language.get_language()?)?;
+    let tree = match parser.parse(&file.content, None) {
+        Some(tree) => tree,
+        None => return Ok(None),
+    };
+
+    let lines: Vec<&str> = file.content.lines().collect();
+    let mut chunks = Vec::new();
+    let root = tree.root_node();
+    let mut cursor = root.walk();
+
+    for node in root.children(&mut cursor) {
+        let chunk_type = match kinds.iter().find(|(kind, _)| *kind == node.kind()) {
+            Some((_, chunk_type)) => chunk_type.clone(),
+            None => continue,
+        };
+
+        let start_line = node.start_position().row + 1;
+        let end_line = (node.end_position().row + 1).min(lines.len());
+        let mut chunk = make_chunk(file, chunk_type, &lines, start_line, end_line, "symbol");
+
+        // Impl blocks are named by their type
+        let name_node = node
+            .child_by_field_name("name")
+            .or_else(|| node.child_by_field_name("type"));
+        if let Some(name) = name_node.and_then(|n| n.utf8_text(file.content.as_bytes()).ok()) {
+            chunk
+                .metadata
+                .insert("symbol".to_string(), name.to_string());
+        }
+
+        chunks.push(chunk);
+    }
+
+    if chunks.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(chunks))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -628,4 +921,112 @@ mod tests {
         let similarity2 = rag_db.calculate_similarity(&vec1, &vec3);
         assert!(similarity2 < 0.001);
     }
+
+    #[tokio::test]
+    async fn test_related_chunks_two_hops() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        let rag_db = RagDatabase::new(pool);
+        rag_db.init().await.unwrap();
+
+        for id in ["body", "function", "module", "crate", "sibling"] {
+            rag_db
+                .store_chunk(CodeChunk {
+                    id: id.to_string(),
+                    file_path: "test.rs".to_string(),
+                    chunk_type: ChunkType::Block,
+                    content: format!("// {}", id),
+                    start_line: 1,
+                    end_line: 1,
+                    embedding: None,
+                    semantic_hash: None,
+                    metadata: HashMap::new(),
+                    created_at: 1234567890,
+                    updated_at: 1234567890,
+                })
+                .await
+                .unwrap();
+        }
+
+        // body -> function -> module -> crate, plus a sibling that closes a
+        // cycle between body and function
+        rag_db
+            .add_relationship("body", "function", "same_function")
+            .await
+            .unwrap();
+        rag_db
+            .add_relationship("function", "module", "same_module")
+            .await
+            .unwrap();
+        rag_db
+            .add_relationship("module", "crate", "same_crate")
+            .await
+            .unwrap();
+        rag_db
+            .add_relationship("sibling", "function", "same_module")
+            .await
+            .unwrap();
+        rag_db
+            .add_relationship("sibling", "body", "same_module")
+            .await
+            .unwrap();
+
+        let related = rag_db.related_chunks("body", 2).await.unwrap();
+        let mut ids: Vec<&str> = related.iter().map(|c| c.id.as_str()).collect();
+        ids.sort();
+        assert_eq!(ids, vec!["function", "module", "sibling"]);
+
+        let related = rag_db.related_chunks("body", 1).await.unwrap();
+        let mut ids: Vec<&str> = related.iter().map(|c| c.id.as_str()).collect();
+        ids.sort();
+        assert_eq!(ids, vec!["function", "sibling"]);
+
+        assert!(rag_db.related_chunks("body", 0).await.unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_chunk_file_by_symbol() {
+        let file = CodeFile {
+            id: Uuid::new_v4(),
+            path: "src/lib.rs".to_string(),
+            content: concat!(
+                "use std::fmt;\n",
+                "\n",
+                "fn first() {\n",
+                "    println!(\"one\");\n",
+                "}\n",
+                "\n",
+                "fn second(x: u32) -> u32 {\n",
+                "    x + 1\n",
+                "}\n",
+            )
+            .to_string(),
+            language: "rust".to_string(),
+            modified: chrono::Utc::now(),
+            encoding: Default::default(),
+            line_ending: Default::default(),
+        };
+
+        let chunks = chunk_file(&file, ChunkStrategy::BySymbol).unwrap();
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0].metadata.get("symbol").unwrap(), "first");
+        assert_eq!((chunks[0].start_line, chunks[0].end_line), (3, 5));
+        assert!(matches!(chunks[0].chunk_type, ChunkType::Function));
+        assert_eq!(chunks[1].metadata.get("symbol").unwrap(), "second");
+        assert_eq!(
+            chunks[1].content,
+            "fn second(x: u32) -> u32 {\n    x + 1\n}"
+        );
+
+        // Unsupported languages fall back to fixed-size chunks
+        let text = CodeFile {
+            language: "text".to_string(),
+            ..file
+        };
+        let chunks = chunk_file(&text, ChunkStrategy::BySymbol).unwrap();
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].metadata.get("strategy").unwrap(), "fixed_lines");
+
+        let chunks = chunk_file(&text, ChunkStrategy::ByParagraph).unwrap();
+        assert_eq!(chunks.len(), 3);
+    }
 }
@@ -0,0 +1,370 @@
+//! Rules Module
+//!
+//! This module holds the catalog of every built-in rule, keyed by the stable
+//! `rule_id` of a [`CodeIssue`](crate::CodeIssue), so users can look up why an
+//! issue was reported and tooling can list, enable and disable rules by id.
+//!
+//! Rule ids are `<category>/<name>`, such as `perf/collect-len`.
+
+use serde::{Deserialize, Serialize};
+
+use crate::Severity;
+
+/// Explanation of one analysis rule
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleExplanation {
+    /// Stable rule id, as set in `CodeIssue::rule_id`
+    pub id: &'static str,
+    /// Short name of the rule
+    pub title: &'static str,
+    /// Severity of the issues the rule reports, unless overridden
+    pub default_severity: Severity,
+    /// Languages the rule checks, empty if it checks every language
+    pub languages: &'static [&'static str],
+    /// What the rule detects
+    pub detects: &'static str,
+    /// Why it matters
+    pub rationale: &'static str,
+    /// Example of a fix
+    pub example_fix: &'static str,
+}
+
+/// Catalog entry of a rule, for tooling that lists or configures rules
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RuleInfo {
+    /// Stable rule id
+    pub id: String,
+    /// Severity of the issues the rule reports, unless overridden
+    pub default_severity: Severity,
+    /// Languages the rule checks, empty if it checks every language
+    pub languages: Vec<String>,
+    /// What the rule detects
+    pub description: String,
+}
+
+/// Every rule reported by the built-in analyzers and linters
+pub const RULES: &[RuleExplanation] = &[
+    RuleExplanation {
+        id: "analysis/analyzer-panic",
+        title: "Analyzer failure",
+        default_severity: Severity::Critical,
+        languages: &[],
+        detects: "A language analyzer panicked while analyzing the file.",
+        rationale: "The file was only partially analyzed, so issues may be missing.",
+        example_fix: "Report the file that triggered the failure; other files are unaffected.",
+    },
+    RuleExplanation {
+        id: "analysis/file-too-large",
+        title: "File too large",
+        default_severity: Severity::Info,
+        languages: &[],
+        detects: "Files above the configured maximum analysis size.",
+        rationale: "Only basic checks are run on large files to keep analysis fast.",
+        example_fix: "Raise max_analysis_bytes or exclude generated files from analysis.",
+    },
+    RuleExplanation {
+        id: "bug/blocking-in-async",
+        title: "Blocking call in async function",
+        default_severity: Severity::Medium,
+        languages: &["rust"],
+        detects: "Blocking calls such as std::thread::sleep inside an async function.",
+        rationale: "Blocking stalls the executor thread and every task scheduled on it.",
+        example_fix: "std::thread::sleep(d)  ->  tokio::time::sleep(d).await",
+    },
+    RuleExplanation {
+        id: "bug/loose-equality",
+        title: "Loose equality",
+        default_severity: Severity::High,
+        languages: &["javascript", "typescript"],
+        detects: "Comparisons with == in JavaScript and TypeScript.",
+        rationale: "== coerces its operands, so '0' == 0 is true.",
+        example_fix: "a == b  ->  a === b",
+    },
+    RuleExplanation {
+        id: "perf/collect-len",
+        title: "Collecting only to count",
+        default_severity: Severity::High,
+        languages: &["rust"],
+        detects: "Iterators collected into a Vec only to take its length.",
+        rationale: "Collecting allocates a vector that is immediately thrown away.",
+        example_fix: "iter.collect::<Vec<_>>().len()  ->  iter.count()",
+    },
+    RuleExplanation {
+        id: "practice/debug-print",
+        title: "Debug print",
+        default_severity: Severity::Medium,
+        languages: &[],
+        detects: "Debug output such as print(), console.log or println! left in code.",
+        rationale: "Debug output clutters logs and can leak data in production.",
+        example_fix: "print(value)  ->  logger.debug(value)",
+    },
+    RuleExplanation {
+        id: "practice/deep-nesting",
+        title: "Deeply nested expression",
+        default_severity: Severity::Medium,
+        languages: &["clojure"],
+        detects: "Expressions nested more than five levels deep.",
+        rationale: "Deep nesting has to be read inside out and hides the order of operations.",
+        example_fix: "(f (g (h x)))  ->  (-> x h g f)",
+    },
+    RuleExplanation {
+        id: "practice/high-complexity",
+        title: "High complexity",
+        default_severity: Severity::Medium,
+        languages: &[
+            "c",
+            "cpp",
+            "dart",
+            "go",
+            "java",
+            "objc",
+            "php",
+            "ruby",
+            "scala",
+            "swift",
+            "typescript",
+        ],
+        detects: "Functions and methods with a high cyclomatic complexity.",
+        rationale: "Every extra branch is another path to understand and to test.",
+        example_fix: "Move independent branches into smaller, named functions.",
+    },
+    RuleExplanation {
+        id: "practice/missing-shebang",
+        title: "Missing shebang",
+        default_severity: Severity::Low,
+        languages: &["shell"],
+        detects: "Shell scripts that don't start with a #! line.",
+        rationale: "Without a shebang the script runs in whatever shell invokes it.",
+        example_fix: "Add #!/bin/sh or #!/bin/bash as the first line.",
+    },
+    RuleExplanation {
+        id: "practice/missing-type-annotation",
+        title: "Missing type annotations",
+        default_severity: Severity::Low,
+        languages: &["typescript"],
+        detects: "TypeScript functions without any type annotation.",
+        rationale: "Unannotated parameters default to any and escape type checking.",
+        example_fix: "function add(a, b)  ->  function add(a: number, b: number): number",
+    },
+    RuleExplanation {
+        id: "practice/panic-density",
+        title: "Dense panicking calls",
+        default_severity: Severity::Low,
+        languages: &["rust"],
+        detects: "Functions with many unwrap, expect or panic! calls for their length.",
+        rationale: "Each call is a place the program can crash instead of handling an error.",
+        example_fix: "let x = parse(s).unwrap();  ->  let x = parse(s)?;",
+    },
+    RuleExplanation {
+        id: "practice/todo-comment",
+        title: "TODO comment",
+        default_severity: Severity::Medium,
+        languages: &[],
+        detects: "TODO and FIXME markers in comments.",
+        rationale: "Markers record unfinished work that is easy to forget.",
+        example_fix: "Finish the work, or track it in an issue and remove the marker.",
+    },
+    RuleExplanation {
+        id: "practice/var-usage",
+        title: "var declaration",
+        default_severity: Severity::Medium,
+        languages: &["javascript", "typescript"],
+        detects: "Variables declared with var.",
+        rationale: "var is function-scoped and hoisted, which hides bugs.",
+        example_fix: "var count = 0;  ->  let count = 0;",
+    },
+    RuleExplanation {
+        id: "practice/wildcard-import",
+        title: "Wildcard import",
+        default_severity: Severity::Medium,
+        languages: &["python"],
+        detects: "from module import * statements.",
+        rationale: "Wildcard imports hide where names come from and can shadow existing ones.",
+        example_fix: "from os.path import *  ->  from os.path import join, exists",
+    },
+    RuleExplanation {
+        id: "style/line-length",
+        title: "Line too long",
+        default_severity: Severity::Medium,
+        languages: &[],
+        detects: "Lines longer than the configured maximum line length.",
+        rationale: "Long lines are hard to read and to review side by side.",
+        example_fix: "Break the expression over several lines.",
+    },
+    RuleExplanation {
+        id: "style/multiple-blank-lines",
+        title: "Multiple blank lines",
+        default_severity: Severity::Low,
+        languages: &[],
+        detects: "More than one blank line in a row.",
+        rationale: "Extra blank lines spread code out without separating anything.",
+        example_fix: "Keep a single blank line between blocks.",
+    },
+    RuleExplanation {
+        id: "style/naming-convention",
+        title: "Naming convention",
+        default_severity: Severity::Medium,
+        languages: &[
+            "clojure",
+            "dart",
+            "haskell",
+            "lua",
+            "objc",
+            "php",
+            "r",
+            "scala",
+            "typescript",
+        ],
+        detects:
+            "Type, class and function names that don't follow the language's naming convention.",
+        rationale: "Conventional names tell readers what kind of item a name refers to.",
+        example_fix: "class userAccount  ->  class UserAccount",
+    },
+    RuleExplanation {
+        id: "style/pattern-matching",
+        title: "Case instead of pattern matching",
+        default_severity: Severity::Low,
+        languages: &["haskell"],
+        detects: "Haskell functions whose body is a case expression.",
+        rationale:
+            "Matching in the function's equations is shorter and reads like its specification.",
+        example_fix: "f x = case x of 0 -> a; _ -> b  ->  f 0 = a; f _ = b",
+    },
+    RuleExplanation {
+        id: "style/trailing-whitespace",
+        title: "Trailing whitespace",
+        default_severity: Severity::Low,
+        languages: &[],
+        detects: "Spaces or tabs at the end of a line.",
+        rationale: "Trailing whitespace causes noisy diffs.",
+        example_fix: "Remove the whitespace, or let the editor trim it on save.",
+    },
+    RuleExplanation {
+        id: "syntax/syntax-error",
+        title: "Syntax error",
+        default_severity: Severity::High,
+        languages: &[],
+        detects: "Code the parser could not parse.",
+        rationale: "Code with syntax errors does not compile or run.",
+        example_fix: "Fix the reported construct, such as a missing bracket.",
+    },
+];
+
+/// Codes external compilers and linters use for the problem a built-in rule reports
+///
+/// A diagnostic with one of these codes supersedes the rule's issue on the same line.
+pub const EXTERNAL_EQUIVALENTS: &[(&str, &[&str])] = &[
+    ("bug/loose-equality", &["eqeqeq"]),
+    (
+        "perf/collect-len",
+        &["clippy::needless_collect", "needless_collect"],
+    ),
+    ("practice/debug-print", &["T201"]),
+    ("practice/todo-comment", &["no-warning-comments"]),
+    ("practice/var-usage", &["no-var"]),
+    ("practice/wildcard-import", &["F403"]),
+    ("style/line-length", &["max-len", "E501"]),
+    (
+        "style/multiple-blank-lines",
+        &["no-multiple-empty-lines", "E303"],
+    ),
+    ("style/trailing-whitespace", &["no-trailing-spaces", "W291"]),
+];
+
+/// Whether an external tool's diagnostic `code` reports the same problem as
+/// the built-in rule `rule_id`
+pub fn is_external_equivalent(rule_id: &str, code: &str) -> bool {
+    EXTERNAL_EQUIVALENTS
+        .iter()
+        .any(|(rule, codes)| *rule == rule_id && codes.contains(&code))
+}
+
+/// All rules, for tooling that lists or configures them
+pub fn rule_catalog() -> Vec<RuleInfo> {
+    RULES
+        .iter()
+        .map(|rule| RuleInfo {
+            id: rule.id.to_string(),
+            default_severity: rule.default_severity.clone(),
+            languages: rule.languages.iter().map(|l| l.to_string()).collect(),
+            description: rule.detects.to_string(),
+        })
+        .collect()
+}
+
+/// Look up a rule by its id
+///
+/// The name without its category is accepted too, with `-` or `_` between
+/// words, so `collect_len` finds `perf/collect-len`.
+pub fn find_rule(id: &str) -> Option<&'static RuleExplanation> {
+    let name = id.replace('_', "-");
+    RULES.iter().find(|rule| rule.id == id).or_else(|| {
+        RULES
+            .iter()
+            .find(|rule| rule.id.split('/').nth(1) == Some(&*name))
+    })
+}
+
+/// Explanation of a rule for display, or a message listing the known rules if
+/// there is no rule with that id
+pub fn explain_rule(id: &str) -> String {
+    match find_rule(id) {
+        Some(rule) => format!(
+            "{} ({})\n\nDetects: {}\nWhy: {}\nFix: {}",
+            rule.title, rule.id, rule.detects, rule.rationale, rule.example_fix
+        ),
+        None => {
+            let known: Vec<&str> = RULES.iter().map(|rule| rule.id).collect();
+            format!("No such rule: {}. Known rules: {}", id, known.join(", "))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_explain_known_and_unknown_rules() {
+        let explanation = explain_rule("collect_len");
+        assert!(explanation.starts_with("Collecting only to count (perf/collect-len)"));
+        assert!(explanation.contains("Fix: iter.collect::<Vec<_>>().len()  ->  iter.count()"));
+        assert_eq!(explain_rule("perf/collect-len"), explanation);
+
+        let unknown = explain_rule("no_such_thing");
+        assert!(unknown.starts_with("No such rule: no_such_thing."));
+        assert!(unknown.contains("perf/collect-len"));
+    }
+
+    #[test]
+    fn test_catalog_lists_known_rules() {
+        let catalog = rule_catalog();
+        for id in [
+            "style/line-length",
+            "perf/collect-len",
+            "bug/loose-equality",
+            "practice/todo-comment",
+        ] {
+            assert!(catalog.iter().any(|rule| rule.id == id), "missing {}", id);
+        }
+
+        let loose_equality = catalog
+            .iter()
+            .find(|rule| rule.id == "bug/loose-equality")
+            .unwrap();
+        assert_eq!(loose_equality.default_severity, Severity::High);
+        assert_eq!(loose_equality.languages, vec!["javascript", "typescript"]);
+    }
+
+    #[test]
+    fn test_external_equivalents_name_known_rules() {
+        for (rule_id, _) in EXTERNAL_EQUIVALENTS {
+            assert!(find_rule(rule_id).is_some(), "unknown rule {}", rule_id);
+        }
+        assert!(is_external_equivalent("practice/var-usage", "no-var"));
+        assert!(!is_external_equivalent(
+            "practice/todo-comment",
+            "unused_variables"
+        ));
+    }
+}
@@ -12,7 +12,7 @@ use std::collections::HashMap;
 use tracing::{debug, error, info};
 use uuid::Uuid;
 
-use crate::CodeFile;
+use crate::{CodeFile, CodeSuggestion, Severity, SuggestionType};
 
 /// Code complexity metrics
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -420,6 +420,76 @@ impl SemanticAnalysisEngine {
         Ok(analysis)
     }
 
+    /// Suggest clearer names for variables in a Rust file
+    ///
+    /// Flags `let` bindings with single-letter or placeholder names, and bindings
+    /// whose name contradicts their inferred type (a `Vec` named `item`). Only
+    /// bindings with an inferable type get a suggestion, and loop counters,
+    /// `_`-prefixed names and generics are never flagged.
+    pub fn suggest_renames(&self, file: &CodeFile) -> Result<Vec<CodeSuggestion>> {
+        if file.language != "rust" {
+            return Ok(Vec::new());
+        }
+
+        let mut suggestions = Vec::new();
+        for (idx, line) in file.content.lines().enumerate() {
+            let Some((name, binding_type)) = parse_let_binding(line) else {
+                continue;
+            };
+            if name.starts_with('_') || LOOP_COUNTER_NAMES.contains(&name.as_str()) {
+                continue;
+            }
+            let Some(inferred) = infer_binding_type(&binding_type) else {
+                continue;
+            };
+
+            let (proposed, reason, confidence) =
+                if name.len() == 1 || PLACEHOLDER_NAMES.contains(&name.as_str()) {
+                    (
+                        inferred.default_name().to_string(),
+                        format!(
+                            "'{}' doesn't describe the {} it holds",
+                            name,
+                            inferred.label()
+                        ),
+                        0.6,
+                    )
+                } else if inferred.is_collection() && SINGULAR_NAMES.contains(&name.as_str()) {
+                    (
+                        format!("{}s", name),
+                        format!(
+                            "'{}' names a single value but holds a {}",
+                            name,
+                            inferred.label()
+                        ),
+                        0.7,
+                    )
+                } else {
+                    continue;
+                };
+
+            suggestions.push(CodeSuggestion::complete(
+                Uuid::new_v4(),
+                SuggestionType::Rename,
+                format!("Rename '{}' to '{}'", name, proposed),
+                reason,
+                Some(proposed),
+                confidence,
+                file.path.clone(),
+                Some(idx + 1),
+                Severity::Info,
+                false,
+            ));
+        }
+
+        debug!(
+            "Found {} rename suggestions in {}",
+            suggestions.len(),
+            file.path
+        );
+        Ok(suggestions)
+    }
+
     /// Calculate complexity metrics for code
     fn calculate_complexity_metrics(&self, file: &CodeFile) -> Result<ComplexityMetrics> {
         let loc = file.content.lines().count();
@@ -1125,6 +1195,106 @@ impl SemanticAnalysisEngine {
     }
 }
 
+/// Loop counter names that are never flagged as non-descriptive
+const LOOP_COUNTER_NAMES: &[&str] = &["i", "j", "k"];
+
+/// Placeholder names that say nothing about the value
+const PLACEHOLDER_NAMES: &[&str] = &["foo", "bar", "baz", "tmp", "temp", "thing", "stuff"];
+
+/// Names of a single element that shouldn't hold a collection
+const SINGULAR_NAMES: &[&str] = &["item", "element", "elem", "entry", "value", "record"];
+
+/// Type of a binding inferred from its annotation or initializer
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum InferredType {
+    List,
+    Map,
+    Set,
+    Text,
+}
+
+impl InferredType {
+    /// Name proposed for a binding of this type
+    fn default_name(&self) -> &'static str {
+        match self {
+            InferredType::List => "list",
+            InferredType::Map => "map",
+            InferredType::Set => "set",
+            InferredType::Text => "text",
+        }
+    }
+
+    /// Human-readable name of the type
+    fn label(&self) -> &'static str {
+        match self {
+            InferredType::List => "list",
+            InferredType::Map => "map",
+            InferredType::Set => "set",
+            InferredType::Text => "string",
+        }
+    }
+
+    fn is_collection(&self) -> bool {
+        !matches!(self, InferredType::Text)
+    }
+}
+
+/// Extract the name and the annotation plus initializer of a simple `let` binding
+///
+/// Destructuring patterns return None.
+fn parse_let_binding(line: &str) -> Option<(String, String)> {
+    let rest = line.trim().strip_prefix("let ")?;
+    let rest = rest.strip_prefix("mut ").unwrap_or(rest).trim_start();
+
+    let name: String = rest
+        .chars()
+        .take_while(|c| c.is_alphanumeric() || *c == '_')
+        .collect();
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let after_name = rest[name.len()..].trim_start();
+    if !after_name.starts_with(':') && !after_name.starts_with('=') {
+        return None;
+    }
+    Some((name, after_name.to_string()))
+}
+
+/// Infer the type of a binding from `: Type = init`
+fn infer_binding_type(binding: &str) -> Option<InferredType> {
+    let binding = binding.replace(' ', "");
+    let patterns: [(&[&str], InferredType); 4] = [
+        (
+            &[
+                ":Vec<",
+                "vec![",
+                "Vec::new(",
+                "Vec::with_capacity(",
+                "collect::<Vec",
+            ],
+            InferredType::List,
+        ),
+        (
+            &[":HashMap<", ":BTreeMap<", "HashMap::new(", "BTreeMap::new("],
+            InferredType::Map,
+        ),
+        (
+            &[":HashSet<", ":BTreeSet<", "HashSet::new(", "BTreeSet::new("],
+            InferredType::Set,
+        ),
+        (
+            &[":String", "String::new(", "String::from(", "format!("],
+            InferredType::Text,
+        ),
+    ];
+
+    patterns
+        .iter()
+        .find(|(markers, _)| markers.iter().any(|marker| binding.contains(marker)))
+        .map(|(_, inferred)| *inferred)
+}
+
 impl Default for SemanticAnalysisEngine {
     fn default() -> Self {
         Self::new()
@@ -1160,6 +1330,8 @@ fn simple_function(x: i32) -> i32 {
             .to_string(),
             language: "rust".to_string(),
             modified: chrono::Utc::now(),
+            encoding: Default::default(),
+            line_ending: Default::default(),
         };
 
         let result = engine.analyze(&code_file).await;
@@ -1202,6 +1374,8 @@ fn complex_function(x: i32) -> i32 {
             .to_string(),
             language: "rust".to_string(),
             modified: chrono::Utc::now(),
+            encoding: Default::default(),
+            line_ending: Default::default(),
         };
 
         let result = engine.analyze(&code_file).await.unwrap();
@@ -1225,6 +1399,8 @@ fn function_with_many_params(a: i32, b: i32, c: i32, d: i32, e: i32, f: i32, g:
             .to_string(),
             language: "rust".to_string(),
             modified: chrono::Utc::now(),
+            encoding: Default::default(),
+            line_ending: Default::default(),
         };
 
         let result = engine.analyze(&code_file).await.unwrap();
@@ -1244,4 +1420,44 @@ fn function_with_many_params(a: i32, b: i32, c: i32, d: i32, e: i32, f: i32, g:
             .collect();
         assert!(!code_smells.is_empty());
     }
+
+    #[tokio::test]
+    async fn test_suggest_renames() {
+        let code_engine = crate::CodeEngine::new().unwrap();
+        let content = r#"
+fn collect_scores() -> usize {
+    let l = vec![1, 2, 3];
+    let mut item = Vec::new();
+    let _ = String::new();
+    let i = Vec::<u8>::new();
+    let name = String::from("scores");
+    for j in 0..l.len() {
+        item.push(j);
+    }
+    item.len() + name.len() + i.len()
+}
+"#;
+        let file_id = code_engine
+            .load_file(
+                "renames.rs".to_string(),
+                content.to_string(),
+                "rust".to_string(),
+            )
+            .await
+            .unwrap();
+
+        let suggestions = code_engine.suggest_renames(file_id).await.unwrap();
+        let renames: Vec<_> = suggestions
+            .iter()
+            .map(|s| (s.line_number.unwrap(), s.code_snippet.clone().unwrap()))
+            .collect();
+
+        assert_eq!(
+            renames,
+            vec![(3, "list".to_string()), (4, "items".to_string())]
+        );
+        assert!(suggestions
+            .iter()
+            .all(|s| s.suggestion_type == SuggestionType::Rename));
+    }
 }
@@ -0,0 +1,193 @@
+//! Suggestion Patch Module
+//!
+//! This module turns auto-fixes into a unified diff that `git apply` accepts, so
+//! fixes can be reviewed before they touch a file. A fix replaces one line of the
+//! file with the suggestion's code snippet, ending like the line it replaces.
+
+use crate::line_ending::split_terminated;
+
+/// Number of unchanged lines shown around each change
+const CONTEXT_LINES: usize = 3;
+
+/// Replacement of a single line of a file
+#[derive(Debug, Clone, PartialEq)]
+pub struct LineFix {
+    /// 1-based number of the replaced line
+    pub line_number: usize,
+    /// Text replacing the line, without its line terminator; may span lines
+    pub replacement: String,
+}
+
+/// A line of a file split from its terminator
+#[derive(Clone, Copy)]
+struct Line<'a> {
+    /// Text without the terminator
+    text: &'a str,
+    /// `\n`, `\r\n`, or empty for a final line without one
+    terminator: &'a str,
+}
+
+fn split_lines(content: &str) -> Vec<Line<'_>> {
+    split_terminated(content)
+        .into_iter()
+        .map(|(text, terminator)| Line { text, terminator })
+        .collect()
+}
+
+/// Lines replacing `line`, which end like it; the last keeps its exact terminator
+fn replacement_lines<'a>(line: &Line<'a>, replacement: &'a str) -> Vec<Line<'a>> {
+    let texts: Vec<&str> = replacement.split('\n').collect();
+    let last = texts.len() - 1;
+    let inner = if line.terminator.is_empty() {
+        "\n"
+    } else {
+        line.terminator
+    };
+    texts
+        .into_iter()
+        .enumerate()
+        .map(|(i, text)| Line {
+            text: text.strip_suffix('\r').unwrap_or(text),
+            terminator: if i < last { inner } else { line.terminator },
+        })
+        .collect()
+}
+
+fn push_line(out: &mut String, prefix: char, line: &Line<'_>) {
+    out.push(prefix);
+    out.push_str(line.text);
+    if line.terminator.is_empty() {
+        out.push_str("\n\\ No newline at end of file\n");
+    } else {
+        out.push_str(line.terminator);
+    }
+}
+
+/// Sort fixes by line, dropping fixes of lines outside the content and all but
+/// the first fix of a line
+fn normalize_fixes(content: &str, fixes: &[LineFix]) -> Vec<LineFix> {
+    let line_count = split_lines(content).len();
+    let mut fixes: Vec<LineFix> = fixes
+        .iter()
+        .filter(|fix| (1..=line_count).contains(&fix.line_number))
+        .cloned()
+        .collect();
+    fixes.sort_by_key(|fix| fix.line_number);
+    fixes.dedup_by_key(|fix| fix.line_number);
+    fixes
+}
+
+/// Content after applying the fixes
+pub fn apply_line_fixes(content: &str, fixes: &[LineFix]) -> String {
+    let fixes = normalize_fixes(content, fixes);
+    let mut out = String::with_capacity(content.len());
+
+    for (i, line) in split_lines(content).iter().enumerate() {
+        let replaced = match fixes.iter().find(|fix| fix.line_number == i + 1) {
+            Some(fix) => replacement_lines(line, &fix.replacement),
+            None => vec![*line],
+        };
+        for line in replaced {
+            out.push_str(line.text);
+            out.push_str(line.terminator);
+        }
+    }
+    out
+}
+
+/// Render the fixes as a unified diff of `path`, without applying them
+///
+/// Returns an empty string when no fix changes the content.
+pub fn line_fixes_to_patch(path: &str, content: &str, fixes: &[LineFix]) -> String {
+    let lines = split_lines(content);
+    let fixes: Vec<LineFix> = normalize_fixes(content, fixes)
+        .into_iter()
+        .filter(|fix| lines[fix.line_number - 1].text != fix.replacement)
+        .collect();
+    if fixes.is_empty() {
+        return String::new();
+    }
+
+    // Group fixes whose context would overlap into one hunk
+    let mut groups: Vec<Vec<&LineFix>> = Vec::new();
+    for fix in &fixes {
+        match groups.last_mut() {
+            Some(group)
+                if fix.line_number - group.last().unwrap().line_number <= 2 * CONTEXT_LINES =>
+            {
+                group.push(fix)
+            }
+            _ => groups.push(vec![fix]),
+        }
+    }
+
+    // git apply wants paths relative to the repository root
+    let path = path.trim_start_matches('/');
+    let mut out = format!("--- a/{}\n+++ b/{}\n", path, path);
+    let mut offset: isize = 0;
+
+    for group in groups {
+        let start = group[0].line_number.saturating_sub(1 + CONTEXT_LINES);
+        let end = (group.last().unwrap().line_number + CONTEXT_LINES).min(lines.len());
+
+        let mut body = String::new();
+        let mut new_len = 0;
+        for (i, line) in lines.iter().enumerate().take(end).skip(start) {
+            match group.iter().find(|fix| fix.line_number == i + 1) {
+                Some(fix) => {
+                    push_line(&mut body, '-', line);
+                    for new_line in replacement_lines(line, &fix.replacement) {
+                        push_line(&mut body, '+', &new_line);
+                        new_len += 1;
+                    }
+                }
+                None => {
+                    push_line(&mut body, ' ', line);
+                    new_len += 1;
+                }
+            }
+        }
+
+        let old_len = end - start;
+        out.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n",
+            start + 1,
+            old_len,
+            (start as isize + offset + 1),
+            new_len
+        ));
+        out.push_str(&body);
+        offset += new_len as isize - old_len as isize;
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_multi_line_fix_shifts_later_hunks() {
+        let content: String = (1..=20).map(|i| format!("line {}\n", i)).collect();
+        let fixes = vec![
+            LineFix {
+                line_number: 2,
+                replacement: "line 2a\nline 2b".to_string(),
+            },
+            LineFix {
+                line_number: 18,
+                replacement: "line eighteen".to_string(),
+            },
+        ];
+
+        let patch = line_fixes_to_patch("src/lib.rs", &content, &fixes);
+        assert!(patch.starts_with("--- a/src/lib.rs\n+++ b/src/lib.rs\n"));
+        assert!(patch.contains("@@ -1,5 +1,6 @@\n line 1\n-line 2\n+line 2a\n+line 2b\n"));
+        assert!(patch.contains("@@ -15,6 +16,6 @@\n"));
+
+        let fixed = apply_line_fixes(&content, &fixes);
+        assert!(fixed.contains("line 2a\nline 2b\nline 3\n"));
+        assert!(fixed.ends_with("line eighteen\nline 19\nline 20\n"));
+    }
+}
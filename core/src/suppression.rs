@@ -0,0 +1,181 @@
+//! Suppression Module
+//!
+//! This module reads inline `odincode:ignore` comments, which silence false
+//! positives on a single line without disabling a rule everywhere:
+//!
+//! - `// odincode:ignore` suppresses every issue on its line
+//! - `// odincode:ignore[style/line-length]` suppresses only the listed rules,
+//!   separated by commas
+//! - `// odincode:ignore-next-line` suppresses issues on the following line,
+//!   and takes a rule list too
+//!
+//! `#` and `--` comments work as well.
+
+use std::collections::HashMap;
+
+use crate::CodeIssue;
+
+/// Marker that starts a suppression comment
+pub const IGNORE_MARKER: &str = "odincode:ignore";
+
+/// Comment tokens a suppression marker may follow
+const COMMENT_TOKENS: &[&str] = &["//", "/*", "#", "--"];
+
+/// Issues suppressed on one line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Suppression {
+    /// Every issue
+    All,
+    /// Issues of these rule ids
+    Rules(Vec<String>),
+}
+
+impl Suppression {
+    /// Whether this suppresses the issue
+    pub fn suppresses(&self, issue: &CodeIssue) -> bool {
+        match self {
+            Suppression::All => true,
+            Suppression::Rules(rules) => issue
+                .rule_id
+                .as_ref()
+                .is_some_and(|rule_id| rules.contains(rule_id)),
+        }
+    }
+
+    fn merge(self, other: Suppression) -> Suppression {
+        match (self, other) {
+            (Suppression::Rules(mut rules), Suppression::Rules(more)) => {
+                rules.extend(more);
+                Suppression::Rules(rules)
+            }
+            _ => Suppression::All,
+        }
+    }
+}
+
+/// Parse the suppression comment of a line, if any
+///
+/// Returns whether it targets the next line, and what it suppresses.
+fn parse_line(line: &str) -> Option<(bool, Suppression)> {
+    let start = line.find(IGNORE_MARKER)?;
+    let before = line[..start].trim_end();
+    if !COMMENT_TOKENS.iter().any(|token| before.ends_with(token)) {
+        return None;
+    }
+
+    let rest = &line[start + IGNORE_MARKER.len()..];
+    let (next_line, rest) = match rest.strip_prefix("-next-line") {
+        Some(rest) => (true, rest),
+        None => (false, rest),
+    };
+    let suppression = match rest.strip_prefix('[') {
+        Some(list) => {
+            let (list, _) = list.split_once(']')?;
+            Suppression::Rules(
+                list.split(',')
+                    .map(str::trim)
+                    .filter(|rule| !rule.is_empty())
+                    .map(str::to_string)
+                    .collect(),
+            )
+        }
+        // Something like `odincode:ignored` is not a marker
+        None if rest.starts_with(|c: char| c.is_alphanumeric() || c == '-' || c == '_') => {
+            return None
+        }
+        None => Suppression::All,
+    };
+    Some((next_line, suppression))
+}
+
+/// Suppressions of some content, keyed by the 1-based line they apply to
+pub fn parse_suppressions(content: &str) -> HashMap<usize, Suppression> {
+    let mut suppressions: HashMap<usize, Suppression> = HashMap::new();
+    for (index, line) in content.lines().enumerate() {
+        if let Some((next_line, suppression)) = parse_line(line) {
+            let line_number = if next_line { index + 2 } else { index + 1 };
+            let merged = match suppressions.remove(&line_number) {
+                Some(existing) => existing.merge(suppression),
+                None => suppression,
+            };
+            suppressions.insert(line_number, merged);
+        }
+    }
+    suppressions
+}
+
+/// Drop the issues suppressed by comments in `content`
+pub fn filter_suppressed(content: &str, issues: Vec<CodeIssue>) -> Vec<CodeIssue> {
+    let suppressions = parse_suppressions(content);
+    if suppressions.is_empty() {
+        return issues;
+    }
+    issues
+        .into_iter()
+        .filter(|issue| {
+            !suppressions
+                .get(&issue.line_number)
+                .is_some_and(|suppression| suppression.suppresses(issue))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{IssueType, Severity};
+    use uuid::Uuid;
+
+    fn issue(line_number: usize, rule_id: &str) -> CodeIssue {
+        CodeIssue {
+            id: Uuid::new_v4(),
+            issue_type: IssueType::Style,
+            severity: Severity::Low,
+            description: rule_id.to_string(),
+            line_number,
+            column_number: 0,
+            suggestion: None,
+            rule_id: Some(rule_id.to_string()),
+            source: None,
+        }
+    }
+
+    fn remaining(content: &str, issues: Vec<CodeIssue>) -> Vec<(usize, String)> {
+        filter_suppressed(content, issues)
+            .into_iter()
+            .map(|issue| (issue.line_number, issue.description))
+            .collect()
+    }
+
+    #[test]
+    fn test_rule_scoped_ignore_keeps_other_rules() {
+        let content = "let a = 1; // odincode:ignore[style/line-length, perf/collect-len]\n";
+        let issues = vec![
+            issue(1, "style/line-length"),
+            issue(1, "style/trailing-whitespace"),
+        ];
+        assert_eq!(
+            remaining(content, issues),
+            vec![(1, "style/trailing-whitespace".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_next_line_ignore() {
+        let content = "# odincode:ignore-next-line\nprint(x)  \nprint(y)  \n";
+        let issues = vec![
+            issue(2, "practice/debug-print"),
+            issue(2, "style/trailing-whitespace"),
+            issue(3, "practice/debug-print"),
+        ];
+        assert_eq!(
+            remaining(content, issues),
+            vec![(3, "practice/debug-print".to_string())]
+        );
+
+        // Markers outside comments or misspelled are not suppressions
+        assert!(
+            parse_suppressions("let s = \"odincode:ignore\";\n// odincode:ignored\n").is_empty()
+        );
+    }
+}
@@ -252,6 +252,21 @@ impl SymbolTableManager {
             .await
     }
 
+    /// Replace the symbols of a file with those extracted from its AST
+    ///
+    /// Runs in one transaction, so on failure the file keeps its previous symbols.
+    pub async fn replace_file_symbols(
+        &self,
+        tree: &Tree,
+        file_content: &str,
+        file_path: &str,
+        language: &str,
+    ) -> Result<Vec<Symbol>> {
+        self.ast_extractor
+            .replace_file_symbols(tree, file_content, file_path, language)
+            .await
+    }
+
     /// Get symbol references
     pub async fn get_symbol_references(
         &self,
@@ -270,6 +285,65 @@ impl SymbolTableManager {
         self.reference_manager.get_references_by_name(name).await
     }
 
+    /// Index the calls in a file as references to already-extracted symbols
+    pub async fn index_references(
+        &self,
+        tree: &Tree,
+        file_content: &str,
+        file_path: &str,
+    ) -> Result<Vec<crate::symbol_table::core::SymbolReference>> {
+        self.reference_manager
+            .index_call_references(tree, file_content, file_path)
+            .await
+    }
+
+    /// Index the calls made by each function of a file as `calls` relationships,
+    /// queried with [`Self::find_callers`] and [`Self::find_callees`]
+    pub async fn index_calls(
+        &self,
+        tree: &Tree,
+        file_content: &str,
+        file_path: &str,
+    ) -> Result<Vec<crate::symbol_table::core::SymbolRelationship>> {
+        self.relationship_manager
+            .index_call_relationships(tree, file_content, file_path)
+            .await
+    }
+
+    /// Find every reference to a symbol name across the indexed codebase
+    ///
+    /// References to all symbols sharing the name are returned; each carries the
+    /// `symbol_id` of the definition it resolves to, whose file can be looked up
+    /// with [`Self::get_symbol_by_id`] to tell them apart.
+    pub async fn find_references(
+        &self,
+        symbol_name: &str,
+    ) -> Result<Vec<crate::symbol_table::core::SymbolReference>> {
+        self.reference_manager
+            .get_references_by_name(symbol_name)
+            .await
+    }
+
+    /// Find the definition of a symbol name, ignoring imports
+    ///
+    /// When several symbols share the name, the first by file path and line is returned.
+    pub async fn find_definition(&self, symbol_name: &str) -> Result<Option<Symbol>> {
+        let row = sqlx::query(
+            r#"
+            SELECT *
+            FROM symbols
+            WHERE name = ? AND kind != 'import'
+            ORDER BY file_path, line
+            LIMIT 1
+            "#,
+        )
+        .bind(symbol_name)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        row.map(|row| self.row_to_symbol(row)).transpose()
+    }
+
     /// Create symbol relationship
     pub async fn create_relationship(
         &self,
@@ -463,3 +537,85 @@ impl SymbolTableManager {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::language_parsing::{LanguageParser, SupportedLanguage};
+
+    #[tokio::test]
+    async fn test_find_references_across_files() -> Result<()> {
+        let pool = SqlitePool::connect("sqlite::memory:").await?;
+        let manager = SymbolTableManager::new(pool);
+        manager.init().await?;
+
+        let files = [
+            (
+                "src/math.rs",
+                "pub fn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n\n\
+                 fn double(a: i32) -> i32 {\n    add(a, a)\n}\n",
+            ),
+            (
+                "src/main.rs",
+                "fn main() {\n    let sum = math::add(1, 2);\n}\n",
+            ),
+        ];
+
+        let mut parser = LanguageParser::new()?;
+        let mut trees = Vec::new();
+        for (path, content) in files {
+            let tree = parser.parse(content, &SupportedLanguage::Rust)?;
+            manager
+                .extract_symbols_from_ast(&tree, content, path, "rust")
+                .await?;
+            trees.push(tree);
+        }
+        for ((path, content), tree) in files.iter().zip(&trees) {
+            manager.index_references(tree, content, path).await?;
+        }
+
+        let definition = manager.find_definition("add").await?.unwrap();
+        assert_eq!(definition.file_path, "src/math.rs");
+        assert_eq!(definition.line, 1);
+
+        let references = manager.find_references("add").await?;
+        assert_eq!(references.len(), 2);
+        assert!(references.iter().all(|r| r.symbol_id == definition.id));
+        let mut locations: Vec<_> = references
+            .iter()
+            .map(|r| (r.file_path.as_str(), r.line))
+            .collect();
+        locations.sort();
+        assert_eq!(locations, vec![("src/main.rs", 2), ("src/math.rs", 6)]);
+
+        // Indexing a file again replaces its references
+        manager
+            .index_references(&trees[1], files[1].1, files[1].0)
+            .await?;
+        assert_eq!(manager.find_references("add").await?.len(), 2);
+
+        assert!(manager.find_definition("missing").await?.is_none());
+
+        for ((path, content), tree) in files.iter().zip(&trees) {
+            manager.index_calls(tree, content, path).await?;
+        }
+        let double = manager.find_definition("double").await?.unwrap();
+        let callees = manager.find_callees(&double.id).await?;
+        assert_eq!(callees.len(), 1);
+        assert_eq!(callees[0].id, definition.id);
+        let callers: Vec<_> = manager
+            .find_callers("add")
+            .await?
+            .into_iter()
+            .map(|symbol| symbol.name)
+            .collect();
+        assert_eq!(callers, vec!["main", "double"]);
+
+        // Indexing a file again replaces its calls
+        manager
+            .index_calls(&trees[0], files[0].1, files[0].0)
+            .await?;
+        assert_eq!(manager.find_callees(&double.id).await?.len(), 1);
+        Ok(())
+    }
+}
@@ -2,7 +2,7 @@
 
 use crate::symbol_table::core::{Symbol, SymbolKind, Visibility};
 use anyhow::Result;
-use sqlx::SqlitePool;
+use sqlx::{SqliteConnection, SqlitePool};
 use std::collections::HashMap;
 use tree_sitter::{Node, Tree};
 use uuid::Uuid;
@@ -25,6 +25,52 @@ impl ASTExtractor {
         file_content: &str,
         file_path: &str,
         language: &str,
+    ) -> Result<Vec<Symbol>> {
+        let symbols = self.collect_symbols(tree, file_content, file_path, language)?;
+
+        // Store symbols in database
+        let mut conn = self.pool.acquire().await?;
+        for symbol in &symbols {
+            Self::store_symbol(&mut conn, symbol).await?;
+        }
+
+        Ok(symbols)
+    }
+
+    /// Replace the stored symbols of a file with those extracted from its AST
+    ///
+    /// The old symbols are deleted and the new ones stored in one transaction,
+    /// so on failure the file keeps its previous symbols.
+    pub async fn replace_file_symbols(
+        &self,
+        tree: &Tree,
+        file_content: &str,
+        file_path: &str,
+        language: &str,
+    ) -> Result<Vec<Symbol>> {
+        let symbols = self.collect_symbols(tree, file_content, file_path, language)?;
+
+        // Dropping the transaction on error rolls it back
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM symbols WHERE file_path = ?")
+            .bind(file_path)
+            .execute(&mut *tx)
+            .await?;
+        for symbol in &symbols {
+            Self::store_symbol(&mut tx, symbol).await?;
+        }
+        tx.commit().await?;
+
+        Ok(symbols)
+    }
+
+    /// Extract the symbols of an AST without storing them
+    fn collect_symbols(
+        &self,
+        tree: &Tree,
+        file_content: &str,
+        file_path: &str,
+        language: &str,
     ) -> Result<Vec<Symbol>> {
         let mut symbols = Vec::new();
         let root_node = tree.root_node();
@@ -38,11 +84,6 @@ impl ASTExtractor {
             &mut String::new(),
         )?;
 
-        // Store symbols in database
-        for symbol in &symbols {
-            self.store_symbol(symbol).await?;
-        }
-
         Ok(symbols)
     }
 
@@ -342,7 +383,7 @@ impl ASTExtractor {
         let name = self
             .extract_node_name(node, file_content)?
             .ok_or_else(|| anyhow::anyhow!("Failed to extract function name"))?;
-        let signature = self.extract_function_signature(node, file_content)?;
+        let signature = self.extract_source(node, file_content)?;
 
         Ok(Some(Symbol {
             id: Uuid::new_v4().to_string(),
@@ -377,7 +418,7 @@ impl ASTExtractor {
         let name = self
             .extract_node_name(node, file_content)?
             .ok_or_else(|| anyhow::anyhow!("Failed to extract name"))?;
-        let signature = self.extract_function_signature(node, file_content)?;
+        let signature = self.extract_source(node, file_content)?;
 
         Ok(Some(Symbol {
             id: Uuid::new_v4().to_string(),
@@ -427,7 +468,7 @@ impl ASTExtractor {
             },
             visibility: self.extract_visibility(node, file_content)?,
             language: language.to_string(),
-            signature: None,
+            signature: self.extract_source(node, file_content)?,
             documentation: self.extract_documentation(node, file_content)?,
             created_at: chrono::Utc::now().timestamp(),
             updated_at: chrono::Utc::now().timestamp(),
@@ -461,7 +502,7 @@ impl ASTExtractor {
             },
             visibility: self.extract_visibility(node, file_content)?,
             language: language.to_string(),
-            signature: None,
+            signature: self.extract_source(node, file_content)?,
             documentation: self.extract_documentation(node, file_content)?,
             created_at: chrono::Utc::now().timestamp(),
             updated_at: chrono::Utc::now().timestamp(),
@@ -495,7 +536,7 @@ impl ASTExtractor {
             },
             visibility: self.extract_visibility(node, file_content)?,
             language: language.to_string(),
-            signature: None,
+            signature: self.extract_source(node, file_content)?,
             documentation: self.extract_documentation(node, file_content)?,
             created_at: chrono::Utc::now().timestamp(),
             updated_at: chrono::Utc::now().timestamp(),
@@ -529,7 +570,7 @@ impl ASTExtractor {
             },
             visibility: self.extract_visibility(node, file_content)?,
             language: language.to_string(),
-            signature: None,
+            signature: self.extract_source(node, file_content)?,
             documentation: self.extract_documentation(node, file_content)?,
             created_at: chrono::Utc::now().timestamp(),
             updated_at: chrono::Utc::now().timestamp(),
@@ -585,12 +626,8 @@ impl ASTExtractor {
         Ok(None)
     }
 
-    /// Extract function signature
-    fn extract_function_signature(
-        &self,
-        node: &Node,
-        file_content: &str,
-    ) -> Result<Option<String>> {
+    /// Extract the full source of a function or type definition
+    fn extract_source(&self, node: &Node, file_content: &str) -> Result<Option<String>> {
         let start = node.start_byte();
         let end = node.end_byte();
         Ok(Some(file_content[start..end].to_string()))
@@ -629,7 +666,7 @@ impl ASTExtractor {
     }
 
     /// Store symbol in database
-    async fn store_symbol(&self, symbol: &Symbol) -> Result<()> {
+    async fn store_symbol(conn: &mut SqliteConnection, symbol: &Symbol) -> Result<()> {
         sqlx::query(
             r#"
             INSERT INTO symbols (
@@ -651,7 +688,7 @@ impl ASTExtractor {
         .bind(&symbol.documentation)
         .bind(symbol.created_at)
         .bind(symbol.updated_at)
-        .execute(&self.pool)
+        .execute(conn)
         .await?;
 
         Ok(())
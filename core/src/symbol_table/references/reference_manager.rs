@@ -3,6 +3,7 @@
 use crate::symbol_table::core::{ReferenceType, SymbolReference};
 use anyhow::Result;
 use sqlx::{Row, SqlitePool};
+use tree_sitter::{Node, Tree};
 use uuid::Uuid;
 
 /// Manager for symbol references
@@ -127,8 +128,86 @@ impl ReferenceManager {
         Ok(references)
     }
 
+    /// Record a call reference for every call in a file to an indexed function or method
+    ///
+    /// Calls are resolved by name, so a call to a name shared by several symbols is
+    /// recorded against each of them. The call references previously recorded for
+    /// the file are replaced.
+    pub async fn index_call_references(
+        &self,
+        tree: &Tree,
+        file_content: &str,
+        file_path: &str,
+    ) -> Result<Vec<SymbolReference>> {
+        sqlx::query("DELETE FROM symbol_references WHERE file_path = ? AND reference_type = ?")
+            .bind(file_path)
+            .bind(ReferenceType::Call.as_str())
+            .execute(&self.pool)
+            .await?;
+
+        let mut calls = Vec::new();
+        collect_calls(tree.root_node(), file_content, &mut calls);
+
+        let mut references = Vec::new();
+        for (name, line, column) in calls {
+            let symbol_ids: Vec<String> = sqlx::query_scalar(
+                "SELECT id FROM symbols WHERE name = ? AND kind IN ('function', 'method')",
+            )
+            .bind(&name)
+            .fetch_all(&self.pool)
+            .await?;
+
+            for symbol_id in symbol_ids {
+                let reference = SymbolReference {
+                    id: Self::generate_reference_id(),
+                    symbol_id,
+                    file_path: file_path.to_string(),
+                    line,
+                    column,
+                    reference_type: ReferenceType::Call,
+                    created_at: chrono::Utc::now().timestamp(),
+                };
+                self.create_reference(reference.clone()).await?;
+                references.push(reference);
+            }
+        }
+
+        Ok(references)
+    }
+
     /// Generate a unique reference ID
     pub fn generate_reference_id() -> String {
         Uuid::new_v4().to_string()
     }
 }
+
+/// Collect the callee name and position of every call under a node
+pub(super) fn collect_calls(node: Node, file_content: &str, calls: &mut Vec<(String, u32, u32)>) {
+    let callee = match node.kind() {
+        "call_expression" | "call" => node.child_by_field_name("function"),
+        "method_invocation" => node.child_by_field_name("name"),
+        _ => None,
+    };
+
+    // Qualified and method calls resolve to their last segment
+    let name_node = callee.map(|callee| {
+        ["name", "field", "property", "attribute"]
+            .iter()
+            .find_map(|field| callee.child_by_field_name(field))
+            .unwrap_or(callee)
+    });
+    if let Some(name_node) = name_node {
+        let name = &file_content[name_node.start_byte()..name_node.end_byte()];
+        if name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            calls.push((
+                name.to_string(),
+                name_node.start_position().row as u32 + 1,
+                name_node.start_position().column as u32 + 1,
+            ));
+        }
+    }
+
+    for child in node.children(&mut node.walk()) {
+        collect_calls(child, file_content, calls);
+    }
+}
@@ -1,8 +1,11 @@
 //! Symbol relationship management
 
+use super::reference_manager::collect_calls;
+use crate::is_function_node;
 use crate::symbol_table::core::{RelationshipType, Symbol, SymbolRelationship};
 use anyhow::Result;
 use sqlx::{Row, SqlitePool};
+use tree_sitter::Tree;
 use uuid::Uuid;
 
 /// Manager for symbol relationships
@@ -36,6 +39,80 @@ impl RelationshipManager {
         Ok(())
     }
 
+    /// Record a `calls` relationship from every indexed function or method of a file
+    /// to each indexed function or method it calls
+    ///
+    /// Calls are resolved by name, so a call to a name shared by several symbols
+    /// relates the caller to each of them. The `calls` relationships previously
+    /// recorded from the file's symbols are replaced.
+    pub async fn index_call_relationships(
+        &self,
+        tree: &Tree,
+        file_content: &str,
+        file_path: &str,
+    ) -> Result<Vec<SymbolRelationship>> {
+        sqlx::query(
+            r#"
+            DELETE FROM symbol_relationships
+            WHERE relationship_type = ?
+              AND from_symbol_id IN (SELECT id FROM symbols WHERE file_path = ?)
+            "#,
+        )
+        .bind(RelationshipType::Calls.as_str())
+        .bind(file_path)
+        .execute(&self.pool)
+        .await?;
+
+        let callers: Vec<(String, u32)> = sqlx::query_as(
+            "SELECT id, line FROM symbols WHERE file_path = ? AND kind IN ('function', 'method')",
+        )
+        .bind(file_path)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut relationships = Vec::new();
+        let mut stack = vec![tree.root_node()];
+        while let Some(node) = stack.pop() {
+            stack.extend(node.children(&mut node.walk()));
+            if !is_function_node(node.kind()) {
+                continue;
+            }
+            let line = node.start_position().row as u32 + 1;
+            let Some((caller_id, _)) = callers.iter().find(|(_, start)| *start == line) else {
+                continue;
+            };
+
+            let mut calls = Vec::new();
+            collect_calls(node, file_content, &mut calls);
+            let mut names: Vec<String> = calls.into_iter().map(|(name, _, _)| name).collect();
+            names.sort();
+            names.dedup();
+
+            for name in names {
+                let callee_ids: Vec<String> = sqlx::query_scalar(
+                    "SELECT id FROM symbols WHERE name = ? AND kind IN ('function', 'method')",
+                )
+                .bind(&name)
+                .fetch_all(&self.pool)
+                .await?;
+
+                for callee_id in callee_ids {
+                    let relationship = SymbolRelationship {
+                        id: Self::generate_relationship_id(),
+                        from_symbol_id: caller_id.clone(),
+                        to_symbol_id: callee_id,
+                        relationship_type: RelationshipType::Calls,
+                        created_at: chrono::Utc::now().timestamp(),
+                    };
+                    self.create_relationship(relationship.clone()).await?;
+                    relationships.push(relationship);
+                }
+            }
+        }
+
+        Ok(relationships)
+    }
+
     /// Get relationships for a symbol
     pub async fn get_symbol_relationships(
         &self,
@@ -0,0 +1,434 @@
+//! System Configuration Module
+//!
+//! This module loads the system-wide OdinCode configuration from a TOML file:
+//! analysis thresholds, database connections, the LLM backend, API bind
+//! settings, agent defaults and formatters. `ODINCODE_*` environment variables override
+//! values from the file, and validation reports every problem at once.
+
+use crate::analysis_config::AnalysisConfig;
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+
+/// LLM backends accepted in `llm.backend`
+pub const KNOWN_LLM_BACKENDS: &[&str] = &["openai", "anthropic", "ollama", "mock"];
+
+/// System-wide configuration
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SystemConfig {
+    /// Log level filter, e.g. `info` or `debug`
+    pub log_level: String,
+    /// Code analysis thresholds
+    pub analysis: AnalysisConfig,
+    /// Database connection strings
+    pub database: DatabaseSettings,
+    /// LLM backend selection
+    pub llm: LlmSettings,
+    /// API server bind settings
+    pub api: ApiSettings,
+    /// Defaults for agent execution
+    pub agents: AgentSettings,
+    /// Refuse tools that write files or run project code
+    pub safe_mode: bool,
+    /// Formatter run by `odincode format`, by language
+    pub formatters: HashMap<String, FormatterSettings>,
+}
+
+impl Default for SystemConfig {
+    fn default() -> Self {
+        Self {
+            log_level: "info".to_string(),
+            analysis: AnalysisConfig::default(),
+            database: DatabaseSettings::default(),
+            llm: LlmSettings::default(),
+            api: ApiSettings::default(),
+            agents: AgentSettings::default(),
+            safe_mode: false,
+            formatters: HashMap::new(),
+        }
+    }
+}
+
+/// Database connection strings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DatabaseSettings {
+    /// SQLite connection string for the main database
+    pub url: String,
+    /// Optional Neo4j URI for the graph database
+    pub neo4j_uri: Option<String>,
+    /// Optional Redis URL for caching
+    pub redis_url: Option<String>,
+}
+
+impl Default for DatabaseSettings {
+    fn default() -> Self {
+        Self {
+            url: "sqlite://odincode.db".to_string(),
+            neo4j_uri: None,
+            redis_url: None,
+        }
+    }
+}
+
+/// LLM backend selection
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LlmSettings {
+    /// Backend name, one of [`KNOWN_LLM_BACKENDS`]
+    pub backend: String,
+    /// Model name passed to the backend
+    pub model: String,
+    /// Optional base URL overriding the backend's default endpoint
+    pub base_url: Option<String>,
+}
+
+impl Default for LlmSettings {
+    fn default() -> Self {
+        Self {
+            backend: "ollama".to_string(),
+            model: "llama2".to_string(),
+            base_url: None,
+        }
+    }
+}
+
+/// API server bind settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ApiSettings {
+    /// Address to bind to
+    pub host: String,
+    /// Port to bind to; kept wider than `u16` so out-of-range values are reported
+    pub port: u32,
+    /// Number of worker threads
+    pub workers: usize,
+}
+
+impl Default for ApiSettings {
+    fn default() -> Self {
+        Self {
+            host: "0.0.0.0".to_string(),
+            port: 8080,
+            workers: 4,
+        }
+    }
+}
+
+/// Defaults for agent execution
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AgentSettings {
+    /// Maximum number of agents running at once
+    pub max_concurrent: usize,
+    /// Timeout for a single agent task, in seconds
+    pub timeout_seconds: u64,
+}
+
+impl Default for AgentSettings {
+    fn default() -> Self {
+        Self {
+            max_concurrent: 4,
+            timeout_seconds: 300,
+        }
+    }
+}
+
+/// Formatter command for one language, e.g. `[formatters.rust]`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FormatterSettings {
+    /// Command that reads the source on stdin and writes the formatted source to stdout
+    pub command: String,
+    /// Arguments passed to the command
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+/// Every problem found while validating a configuration
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigValidationError {
+    /// One message per invalid field
+    pub errors: Vec<String>,
+}
+
+impl fmt::Display for ConfigValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid configuration:")?;
+        for error in &self.errors {
+            write!(f, "\n  - {}", error)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ConfigValidationError {}
+
+impl SystemConfig {
+    /// Load the configuration from a TOML file, applying environment overrides
+    pub fn load(path: &Path) -> Result<Self> {
+        Self::load_with_env(path, |name| std::env::var(name).ok())
+    }
+
+    /// Load the configuration, reading overrides through `env` instead of the
+    /// process environment
+    pub fn load_with_env(path: &Path, env: impl Fn(&str) -> Option<String>) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| anyhow!("Failed to read config file {}: {}", path.display(), e))?;
+        Self::from_toml_str(&content, env)
+    }
+
+    /// Parse the configuration from TOML, applying overrides and validating it
+    pub fn from_toml_str(content: &str, env: impl Fn(&str) -> Option<String>) -> Result<Self> {
+        let mut config: Self =
+            toml::from_str(content).map_err(|e| anyhow!("Invalid config file: {}", e))?;
+
+        let mut errors = config.apply_env_overrides(env);
+        errors.extend(config.validation_errors());
+        if !errors.is_empty() {
+            return Err(ConfigValidationError { errors }.into());
+        }
+        Ok(config)
+    }
+
+    /// Validate the configuration, reporting every invalid field
+    pub fn validate(&self) -> std::result::Result<(), ConfigValidationError> {
+        let errors = self.validation_errors();
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ConfigValidationError { errors })
+        }
+    }
+
+    /// Apply `ODINCODE_*` overrides, returning errors for unparseable values
+    fn apply_env_overrides(&mut self, env: impl Fn(&str) -> Option<String>) -> Vec<String> {
+        let mut errors = Vec::new();
+
+        let strings: [(&str, &mut String); 5] = [
+            ("ODINCODE_LOG_LEVEL", &mut self.log_level),
+            ("ODINCODE_DATABASE_URL", &mut self.database.url),
+            ("ODINCODE_LLM_BACKEND", &mut self.llm.backend),
+            ("ODINCODE_LLM_MODEL", &mut self.llm.model),
+            ("ODINCODE_API_HOST", &mut self.api.host),
+        ];
+        for (name, field) in strings {
+            if let Some(value) = env(name) {
+                *field = value;
+            }
+        }
+
+        let optional: [(&str, &mut Option<String>); 3] = [
+            ("ODINCODE_NEO4J_URI", &mut self.database.neo4j_uri),
+            ("ODINCODE_REDIS_URL", &mut self.database.redis_url),
+            ("ODINCODE_LLM_BASE_URL", &mut self.llm.base_url),
+        ];
+        for (name, field) in optional {
+            if let Some(value) = env(name) {
+                *field = Some(value);
+            }
+        }
+
+        override_parsed(&env, "ODINCODE_API_PORT", &mut self.api.port, &mut errors);
+        override_parsed(
+            &env,
+            "ODINCODE_API_WORKERS",
+            &mut self.api.workers,
+            &mut errors,
+        );
+        override_parsed(
+            &env,
+            "ODINCODE_MAX_ANALYSIS_BYTES",
+            &mut self.analysis.max_analysis_bytes,
+            &mut errors,
+        );
+        override_parsed(
+            &env,
+            "ODINCODE_MAX_PANIC_DENSITY",
+            &mut self.analysis.max_panic_density,
+            &mut errors,
+        );
+        override_parsed(
+            &env,
+            "ODINCODE_AGENT_MAX_CONCURRENT",
+            &mut self.agents.max_concurrent,
+            &mut errors,
+        );
+        override_parsed(
+            &env,
+            "ODINCODE_AGENT_TIMEOUT_SECONDS",
+            &mut self.agents.timeout_seconds,
+            &mut errors,
+        );
+
+        errors
+    }
+
+    /// Messages for every invalid field
+    fn validation_errors(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+
+        if self.analysis.max_analysis_bytes == 0 {
+            errors.push("analysis.max_analysis_bytes must be positive".to_string());
+        }
+        let density = self.analysis.max_panic_density;
+        if density.is_nan() || density <= 0.0 {
+            errors.push(format!(
+                "analysis.max_panic_density must be positive, got {}",
+                density
+            ));
+        }
+        if self.database.url.trim().is_empty() {
+            errors.push("database.url must not be empty".to_string());
+        }
+        if !KNOWN_LLM_BACKENDS.contains(&self.llm.backend.to_lowercase().as_str()) {
+            errors.push(format!(
+                "llm.backend '{}' is not one of: {}",
+                self.llm.backend,
+                KNOWN_LLM_BACKENDS.join(", ")
+            ));
+        }
+        if self.llm.model.trim().is_empty() {
+            errors.push("llm.model must not be empty".to_string());
+        }
+        if !(1..=65535).contains(&self.api.port) {
+            errors.push(format!(
+                "api.port must be between 1 and 65535, got {}",
+                self.api.port
+            ));
+        }
+        if self.api.workers == 0 {
+            errors.push("api.workers must be positive".to_string());
+        }
+        if self.agents.max_concurrent == 0 {
+            errors.push("agents.max_concurrent must be positive".to_string());
+        }
+        if self.agents.timeout_seconds == 0 {
+            errors.push("agents.timeout_seconds must be positive".to_string());
+        }
+        let mut languages: Vec<&String> = self.formatters.keys().collect();
+        languages.sort();
+        for language in languages {
+            if self.formatters[language].command.trim().is_empty() {
+                errors.push(format!("formatters.{}.command must not be empty", language));
+            }
+        }
+
+        errors
+    }
+}
+
+/// Override a parsed field from an environment variable, recording parse errors
+fn override_parsed<T: std::str::FromStr>(
+    env: &impl Fn(&str) -> Option<String>,
+    name: &str,
+    field: &mut T,
+    errors: &mut Vec<String>,
+) {
+    let Some(value) = env(name) else {
+        return;
+    };
+    match value.trim().parse() {
+        Ok(parsed) => *field = parsed,
+        Err(_) => errors.push(format!("{} has invalid value '{}'", name, value)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    const VALID: &str = r#"
+        log_level = "debug"
+
+        [analysis]
+        max_analysis_bytes = 2048
+        max_panic_density = 0.5
+
+        [database]
+        url = "sqlite://test.db"
+
+        [llm]
+        backend = "anthropic"
+        model = "claude-3-haiku"
+
+        [api]
+        host = "127.0.0.1"
+        port = 9000
+
+        [formatters.rust]
+        command = "rustfmt"
+        args = ["--emit", "stdout"]
+    "#;
+
+    fn no_env(_: &str) -> Option<String> {
+        None
+    }
+
+    #[test]
+    fn test_load_valid_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("odincode.toml");
+        std::fs::write(&path, VALID).unwrap();
+
+        let config = SystemConfig::load_with_env(&path, no_env).unwrap();
+        assert_eq!(config.log_level, "debug");
+        assert_eq!(config.analysis.max_analysis_bytes, 2048);
+        assert_eq!(config.database.url, "sqlite://test.db");
+        assert_eq!(config.llm.backend, "anthropic");
+        assert_eq!(config.api.host, "127.0.0.1");
+        assert_eq!(config.api.port, 9000);
+        // Unspecified fields keep their defaults
+        assert_eq!(config.api.workers, 4);
+        assert_eq!(config.agents, AgentSettings::default());
+        assert_eq!(config.formatters["rust"].command, "rustfmt");
+        assert_eq!(config.formatters["rust"].args, vec!["--emit", "stdout"]);
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_all_invalid_fields_are_reported() {
+        let content = r#"
+            [llm]
+            backend = "gpt-local"
+
+            [api]
+            port = 70000
+        "#;
+
+        let error = SystemConfig::from_toml_str(content, no_env).unwrap_err();
+        let error = error.downcast::<ConfigValidationError>().unwrap();
+        assert_eq!(error.errors.len(), 2);
+        assert!(error.errors[0].contains("llm.backend 'gpt-local'"));
+        assert!(error.errors[1].contains("api.port must be between 1 and 65535"));
+    }
+
+    #[test]
+    fn test_env_overrides_take_precedence() {
+        let env: HashMap<&str, &str> = [
+            ("ODINCODE_API_PORT", "9100"),
+            ("ODINCODE_LLM_BACKEND", "mock"),
+            ("ODINCODE_MAX_PANIC_DENSITY", "0.1"),
+        ]
+        .into_iter()
+        .collect();
+        let lookup = |name: &str| env.get(name).map(|v| v.to_string());
+
+        let config = SystemConfig::from_toml_str(VALID, lookup).unwrap();
+        assert_eq!(config.api.port, 9100);
+        assert_eq!(config.llm.backend, "mock");
+        assert_eq!(config.analysis.max_panic_density, 0.1);
+        assert_eq!(config.api.host, "127.0.0.1");
+
+        let error = SystemConfig::from_toml_str(VALID, |name: &str| {
+            (name == "ODINCODE_API_PORT").then(|| "eighty".to_string())
+        })
+        .unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("ODINCODE_API_PORT has invalid value"));
+    }
+}
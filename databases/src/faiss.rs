@@ -219,6 +219,11 @@ impl FaissManager {
         }
     }
 
+    /// Dimension of the vectors the index holds
+    pub fn dimension(&self) -> usize {
+        self.config.dimension
+    }
+
     /// Add a vector embedding to the index
     pub async fn add_embedding(&self, embedding: VectorEmbedding) -> Result<()> {
         let start_time = std::time::Instant::now();
@@ -23,7 +23,9 @@ pub use neo4j::{
     PatternRelationship, RelationshipType,
 };
 pub use redis::{RedisConfig, RedisKeyPatterns, RedisManager, RedisStats};
-pub use sqlite::{DatabaseStats, LearningPattern, SQLiteManager, UserInteraction};
+pub use sqlite::{
+    DatabaseStats, LearningPattern, PatternAccessStats, SQLiteManager, UserInteraction,
+};
 
 /// Database type enumeration
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
@@ -501,6 +503,9 @@ mod tests {
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
             tags: vec!["integration".to_string(), "test".to_string()],
+            created_by: None,
+            file_id: None,
+            session_id: None,
         };
 
         sqlite_manager
@@ -1015,6 +1020,9 @@ mod tests {
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
             tags: vec!["cross_db".to_string(), "test".to_string()],
+            created_by: None,
+            file_id: None,
+            session_id: None,
         };
 
         sqlite_manager
@@ -551,6 +551,92 @@ impl Neo4jManager {
         }
     }
 
+    /// Create a learning pattern node unless one with the id already exists
+    ///
+    /// Unlike `create_learning_pattern_node` this never duplicates a node, so it is
+    /// safe for patterns that may already be in the graph.
+    pub async fn merge_learning_pattern_node(
+        &self,
+        pattern_id: &str,
+        pattern_type: &str,
+        pattern_data: &str,
+        source: &str,
+        confidence: f64,
+    ) -> Result<()> {
+        let query = "MERGE (p:LearningPattern {id: $id}) \
+             ON CREATE SET p.pattern_type = $pattern_type, p.pattern_data = $pattern_data, p.source = $source, p.confidence = $confidence, p.created_at = $created_at, p.updated_at = $created_at"
+            .to_string();
+
+        let params = vec![
+            ("id", pattern_id.into()),
+            ("pattern_type", pattern_type.into()),
+            ("pattern_data", pattern_data.into()),
+            ("source", source.into()),
+            ("confidence", confidence.into()),
+            ("created_at", Utc::now().timestamp().into()),
+        ];
+
+        let mut query_obj = neo4rs::query(&query);
+        for (key, value) in params {
+            query_obj = query_obj.param::<BoltType>(key, value);
+        }
+
+        self.graph
+            .run(query_obj)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to merge learning pattern node: {e}"))?;
+
+        // Update statistics
+        {
+            let mut stats = self.stats.write().await;
+            stats.queries_executed += 1;
+            stats.last_updated = Utc::now();
+        }
+
+        debug!("Merged learning pattern node: {pattern_id}");
+        Ok(())
+    }
+
+    /// Delete a learning pattern node together with all of its relationships
+    ///
+    /// Returns whether the node existed.
+    pub async fn delete_learning_pattern_node(&self, pattern_id: &str) -> Result<bool> {
+        let query = neo4rs::query(
+            "MATCH (p:LearningPattern {id: $id}) DETACH DELETE p RETURN count(p) AS deleted",
+        )
+        .param("id", pattern_id);
+
+        let mut result = self
+            .graph
+            .execute(query)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to delete learning pattern node: {e}"))?;
+
+        let row = result
+            .next()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to read learning pattern deletion result: {e}"))?;
+        let deleted: i64 = match row {
+            Some(row) => row
+                .get("deleted")
+                .map_err(|e| anyhow::anyhow!("Failed to get deleted count from result: {e}"))?,
+            None => 0,
+        };
+
+        // Update statistics
+        {
+            let mut stats = self.stats.write().await;
+            stats.queries_executed += 1;
+            stats.last_updated = Utc::now();
+        }
+
+        debug!(
+            "Deleted learning pattern node: {pattern_id} (existed: {})",
+            deleted > 0
+        );
+        Ok(deleted > 0)
+    }
+
     /// Create a pattern relationship between two learning patterns
     pub async fn create_pattern_relationship(
         &self,
@@ -738,6 +824,54 @@ impl Neo4jManager {
         Ok(relationships)
     }
 
+    /// Find the code entities with the most incoming `CALLS` and `IMPORTS` relationships
+    ///
+    /// Returns up to `limit` entities, named by their `name` property (falling
+    /// back to `id`), with their in-degree, most depended-upon first. Degrees
+    /// are counted per node, so entities sharing a name are listed separately.
+    pub async fn most_depended_upon(&self, limit: usize) -> Result<Vec<(String, usize)>> {
+        let query = neo4rs::query(
+            "MATCH ()-[r]->(entity)
+             WHERE type(r) IN ['CALLS', 'IMPORTS']
+             WITH entity, count(r) AS in_degree
+             RETURN coalesce(entity.name, entity.id, toString(id(entity))) AS name, in_degree
+             ORDER BY in_degree DESC, name ASC
+             LIMIT $limit",
+        )
+        .param("limit", limit as i64);
+
+        let mut result = self
+            .graph
+            .execute(query)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to find most depended-upon entities: {e}"))?;
+
+        let mut entities = Vec::new();
+        while let Some(row) = result
+            .next()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to read most depended-upon entities: {e}"))?
+        {
+            let name: String = row
+                .get("name")
+                .map_err(|e| anyhow::anyhow!("Failed to get entity name from result: {e}"))?;
+            let in_degree: i64 = row
+                .get("in_degree")
+                .map_err(|e| anyhow::anyhow!("Failed to get in-degree from result: {e}"))?;
+            entities.push((name, in_degree as usize));
+        }
+
+        // Update statistics
+        {
+            let mut stats = self.stats.write().await;
+            stats.queries_executed += 1;
+            stats.last_updated = Utc::now();
+        }
+
+        debug!("Found {} most depended-upon entities", entities.len());
+        Ok(entities)
+    }
+
     /// Get statistics for the Neo4j manager
     pub async fn get_stats(&self) -> Result<Neo4jStats> {
         let stats = self.stats.read().await;
@@ -898,4 +1032,50 @@ mod tests {
             .unwrap();
         assert!(!relationships.is_empty());
     }
+
+    #[tokio::test]
+    #[ignore] // Integration test requiring Neo4j
+    async fn test_most_depended_upon() {
+        let manager = Neo4jManager::new().await.unwrap();
+
+        // Names unique to this run, so other data in the graph can be told apart
+        let prefix = format!("test_{}_", uuid::Uuid::new_v4().simple());
+        let query = format!(
+            "CREATE (hub:CodeElement {{name: '{p}parse'}}),
+                    (util:CodeElement {{name: '{p}util'}}),
+                    (a:CodeElement {{name: '{p}a'}}),
+                    (b:CodeElement {{name: '{p}b'}}),
+                    (c:CodeElement {{name: '{p}c'}}),
+                    (dup1:CodeElement {{name: '{p}dup'}}),
+                    (dup2:CodeElement {{name: '{p}dup'}}),
+                    (a)-[:CALLS]->(hub), (b)-[:CALLS]->(hub), (c)-[:CALLS]->(hub),
+                    (util)-[:CALLS]->(hub), (a)-[:IMPORTS]->(util),
+                    (a)-[:CALLS]->(dup1), (a)-[:CALLS]->(dup2)",
+            p = prefix
+        );
+        manager.execute_query(&query).await.unwrap();
+
+        let ranked = manager.most_depended_upon(1000).await.unwrap();
+        manager
+            .execute_query(&format!(
+                "MATCH (n:CodeElement) WHERE n.name STARTS WITH '{prefix}' DETACH DELETE n"
+            ))
+            .await
+            .unwrap();
+
+        let ranked: Vec<_> = ranked
+            .into_iter()
+            .filter(|(name, _)| name.starts_with(&prefix))
+            .collect();
+        // The two entities named dup are counted separately, not as one with 2
+        assert_eq!(
+            ranked,
+            vec![
+                (format!("{prefix}parse"), 4),
+                (format!("{prefix}dup"), 1),
+                (format!("{prefix}dup"), 1),
+                (format!("{prefix}util"), 1),
+            ]
+        );
+    }
 }
@@ -5,6 +5,7 @@
 
 use anyhow::Result;
 use chrono::{DateTime, Utc};
+use futures::stream::{BoxStream, StreamExt};
 use redis::{aio::ConnectionManager, AsyncCommands, Client};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
@@ -642,6 +643,48 @@ impl RedisManager {
         .await
     }
 
+    /// Publish a message on a channel, returning how many subscribers received it
+    pub async fn publish(&self, channel: &str, message: &str) -> Result<usize> {
+        let channel = channel.to_string();
+        let message = message.to_string();
+
+        self.execute_with_retry(|mut conn| {
+            Box::pin({
+                let channel = channel.clone();
+                let message = message.clone();
+                async move {
+                    let receivers: usize = conn
+                        .publish(&channel, &message)
+                        .await
+                        .map_err(|e| anyhow::anyhow!("Redis error: {e}"))?;
+                    Ok(receivers)
+                }
+            })
+        })
+        .await
+    }
+
+    /// Subscribe to a channel, returning the stream of messages published on it
+    ///
+    /// The subscription uses its own connection, which is closed when the stream
+    /// is dropped. Messages whose payload is not a string are skipped.
+    pub async fn subscribe(&self, channel: &str) -> Result<BoxStream<'static, String>> {
+        let mut pubsub = self
+            .client
+            .get_async_pubsub()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to open Redis pub/sub connection: {e}"))?;
+        pubsub
+            .subscribe(channel)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to subscribe to {channel}: {e}"))?;
+
+        Ok(pubsub
+            .into_on_message()
+            .filter_map(|msg| async move { msg.get_payload::<String>().ok() })
+            .boxed())
+    }
+
     /// Get Redis statistics
     pub async fn get_stats(&self) -> Result<RedisStats> {
         let stats = self.stats.read().await;
@@ -2,20 +2,27 @@
 //!
 //! This module provides real SQLite database operations for the LTMC system,
 //! including connection management, schema creation, and CRUD operations.
+//! Queries run on connections from a pool, with the database in WAL mode so
+//! that readers do not block on a writer.
 
 use anyhow::Result;
 use chrono::{DateTime, Utc};
-use rusqlite::{params, Connection, OptionalExtension};
+use r2d2::{Pool, PooledConnection};
+use r2d2_sqlite::SqliteConnectionManager;
+use rusqlite::{params, OptionalExtension};
 use serde::{Deserialize, Serialize};
 use std::path::Path;
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
 
+/// Default number of pooled connections per database
+pub const DEFAULT_POOL_SIZE: u32 = 8;
+
 /// SQLite connection manager
 pub struct SQLiteManager {
-    /// Database connection
-    connection: Arc<Mutex<Connection>>,
+    /// Pool of database connections
+    pool: Pool<SqliteConnectionManager>,
     /// Database path
     db_path: String,
     /// Connection status
@@ -41,8 +48,36 @@ pub struct LearningPattern {
     pub updated_at: DateTime<Utc>,
     /// Tags for categorization
     pub tags: Vec<String>,
+    /// Agent or tool that created the pattern, as `agent:<name>` or `tool:<name>`
+    #[serde(default)]
+    pub created_by: Option<String>,
+    /// File the pattern was learned from
+    #[serde(default)]
+    pub file_id: Option<String>,
+    /// Learning session the pattern was recorded in
+    #[serde(default)]
+    pub session_id: Option<String>,
+}
+
+/// How often and how recently a learning pattern was accessed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatternAccessStats {
+    /// Number of accesses
+    pub access_count: u32,
+    /// Time of the latest access, if any
+    pub last_accessed: Option<DateTime<Utc>>,
 }
 
+/// Columns added to `learning_patterns` after its first release, with their
+/// definitions
+const LEARNING_PATTERN_ADDED_COLUMNS: [(&str, &str); 5] = [
+    ("created_by", "TEXT"),
+    ("file_id", "TEXT"),
+    ("session_id", "TEXT"),
+    ("access_count", "INTEGER NOT NULL DEFAULT 0"),
+    ("last_accessed", "TEXT"),
+];
+
 /// Sequential thinking step
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SequentialThinkingStep {
@@ -76,39 +111,71 @@ pub struct UserInteraction {
 }
 
 impl SQLiteManager {
-    /// Create a new SQLite manager
+    /// Create a new SQLite manager with the default pool size
     pub fn new<P: AsRef<Path>>(db_path: P) -> Result<Self> {
+        Self::with_pool_size(db_path, DEFAULT_POOL_SIZE)
+    }
+
+    /// Create a new SQLite manager with up to `pool_size` open connections
+    ///
+    /// An in-memory database (`:memory:`) is private to its connection, so it
+    /// always gets a single connection.
+    pub fn with_pool_size<P: AsRef<Path>>(db_path: P, pool_size: u32) -> Result<Self> {
         let path = db_path.as_ref().to_string_lossy().to_string();
+        let in_memory = path == ":memory:";
+        let pool_size = if in_memory { 1 } else { pool_size.max(1) };
 
-        info!("Creating SQLite manager with database path: {path}");
+        info!("Creating SQLite manager with database path: {path} (pool size {pool_size})");
 
-        // Create or open the database connection
-        let conn = Connection::open(&path)
+        let manager = if in_memory {
+            SqliteConnectionManager::memory()
+        } else {
+            SqliteConnectionManager::file(&path)
+        }
+        .with_init(|conn| {
+            // Enable foreign keys and wait for locks instead of failing right away
+            conn.execute_batch("PRAGMA foreign_keys = ON;")?;
+            conn.busy_timeout(std::time::Duration::from_secs(30))
+        });
+
+        let pool = Pool::builder()
+            .max_size(pool_size)
+            .build(manager)
             .map_err(|e| anyhow::anyhow!("Failed to open SQLite database: {e}"))?;
 
-        // Enable foreign keys
-        conn.execute("PRAGMA foreign_keys = ON;", [])
-            .map_err(|e| anyhow::anyhow!("Failed to enable foreign keys: {e}"))?;
-
-        // Set busy timeout
-        conn.busy_timeout(std::time::Duration::from_secs(30))
-            .map_err(|e| anyhow::anyhow!("Failed to set busy timeout: {e}"))?;
+        // WAL lets readers proceed while a write is in progress; it is persistent,
+        // so setting it once per database is enough
+        if !in_memory {
+            let conn = pool
+                .get()
+                .map_err(|e| anyhow::anyhow!("Failed to open SQLite database: {e}"))?;
+            let mode: String = conn
+                .pragma_update_and_check(None, "journal_mode", "WAL", |row| row.get(0))
+                .map_err(|e| anyhow::anyhow!("Failed to enable WAL mode: {e}"))?;
+            if !mode.eq_ignore_ascii_case("wal") {
+                warn!("SQLite database {path} is in {mode} journal mode instead of WAL");
+            }
+        }
 
         Ok(Self {
-            connection: Arc::new(Mutex::new(conn)),
+            pool,
             db_path: path,
             is_connected: Arc::new(RwLock::new(true)),
         })
     }
 
+    /// Take a connection from the pool
+    fn connection(&self) -> Result<PooledConnection<SqliteConnectionManager>> {
+        self.pool
+            .get()
+            .map_err(|e| anyhow::anyhow!("Failed to get a pooled SQLite connection: {e}"))
+    }
+
     /// Initialize database schema
     pub async fn initialize_schema(&self) -> Result<()> {
         info!("Initializing SQLite database schema");
 
-        let conn = self
-            .connection
-            .lock()
-            .map_err(|e| anyhow::anyhow!("Failed to acquire connection lock: {e}"))?;
+        let conn = self.connection()?;
 
         // Create learning patterns table
         conn.execute(
@@ -120,12 +187,19 @@ impl SQLiteManager {
                 confidence REAL NOT NULL,
                 created_at TEXT NOT NULL,
                 updated_at TEXT NOT NULL,
-                tags TEXT
+                tags TEXT,
+                created_by TEXT,
+                file_id TEXT,
+                session_id TEXT,
+                access_count INTEGER NOT NULL DEFAULT 0,
+                last_accessed TEXT
             );",
             [],
         )
         .map_err(|e| anyhow::anyhow!("Failed to create learning_patterns table: {e}"))?;
 
+        Self::migrate_learning_patterns(&conn)?;
+
         // Create sequential thinking table
         conn.execute(
             "CREATE TABLE IF NOT EXISTS sequential_thinking (
@@ -181,6 +255,12 @@ impl SQLiteManager {
         )
         .map_err(|e| anyhow::anyhow!("Failed to create patterns_source index: {e}"))?;
 
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_patterns_file ON learning_patterns(file_id);",
+            [],
+        )
+        .map_err(|e| anyhow::anyhow!("Failed to create patterns_file index: {e}"))?;
+
         conn.execute(
             "CREATE INDEX IF NOT EXISTS idx_thinking_session ON sequential_thinking(session_id);",
             [],
@@ -204,15 +284,39 @@ impl SQLiteManager {
         Ok(())
     }
 
+    /// Add the columns in `LEARNING_PATTERN_ADDED_COLUMNS` to a `learning_patterns`
+    /// table created before they existed
+    fn migrate_learning_patterns(conn: &rusqlite::Connection) -> Result<()> {
+        let mut stmt = conn
+            .prepare("PRAGMA table_info(learning_patterns);")
+            .map_err(|e| anyhow::anyhow!("Failed to read learning_patterns columns: {e}"))?;
+        let existing = stmt
+            .query_map([], |row| row.get::<_, String>(1))
+            .and_then(|rows| rows.collect::<Result<Vec<_>, _>>())
+            .map_err(|e| anyhow::anyhow!("Failed to read learning_patterns columns: {e}"))?;
+        drop(stmt);
+
+        for (column, definition) in LEARNING_PATTERN_ADDED_COLUMNS {
+            if existing.iter().any(|name| name == column) {
+                continue;
+            }
+            info!("Adding column {column} to learning_patterns");
+            conn.execute(
+                &format!("ALTER TABLE learning_patterns ADD COLUMN {column} {definition};"),
+                [],
+            )
+            .map_err(|e| anyhow::anyhow!("Failed to add learning_patterns.{column}: {e}"))?;
+        }
+
+        Ok(())
+    }
+
     /// Test database connection
     pub async fn test_connection(&self) -> Result<bool> {
         debug!("Testing SQLite connection");
 
         let result = {
-            let conn = self
-                .connection
-                .lock()
-                .map_err(|e| anyhow::anyhow!("Failed to acquire connection lock: {e}"))?;
+            let conn = self.connection()?;
 
             // Execute a simple query to test connection
             conn.query_row("SELECT 1;", [], |_| Ok(()))
@@ -249,17 +353,14 @@ impl SQLiteManager {
     pub async fn create_learning_pattern(&self, pattern: &LearningPattern) -> Result<()> {
         debug!("Creating learning pattern: {}", pattern.id);
 
-        let conn = self
-            .connection
-            .lock()
-            .map_err(|e| anyhow::anyhow!("Failed to acquire connection lock: {e}"))?;
+        let conn = self.connection()?;
 
         let tags_json = serde_json::to_string(&pattern.tags)
             .map_err(|e| anyhow::anyhow!("Failed to serialize tags: {e}"))?;
 
         conn.execute(
-            "INSERT INTO learning_patterns (id, pattern_type, pattern_data, source, confidence, created_at, updated_at, tags) 
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8);",
+            "INSERT INTO learning_patterns (id, pattern_type, pattern_data, source, confidence, created_at, updated_at, tags, created_by, file_id, session_id) 
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11);",
             params![
                 pattern.id,
                 pattern.pattern_type,
@@ -268,7 +369,10 @@ impl SQLiteManager {
                 pattern.confidence,
                 pattern.created_at.to_rfc3339(),
                 pattern.updated_at.to_rfc3339(),
-                tags_json
+                tags_json,
+                pattern.created_by,
+                pattern.file_id,
+                pattern.session_id
             ],
         ).map_err(|e| anyhow::anyhow!("Failed to insert learning pattern: {e}"))?;
 
@@ -282,13 +386,10 @@ impl SQLiteManager {
     pub async fn get_learning_pattern(&self, id: &str) -> Result<Option<LearningPattern>> {
         debug!("Getting learning pattern: {id}");
 
-        let conn = self
-            .connection
-            .lock()
-            .map_err(|e| anyhow::anyhow!("Failed to acquire connection lock: {e}"))?;
+        let conn = self.connection()?;
 
         let pattern = conn.query_row(
-            "SELECT id, pattern_type, pattern_data, source, confidence, created_at, updated_at, tags 
+            "SELECT id, pattern_type, pattern_data, source, confidence, created_at, updated_at, tags, created_by, file_id, session_id 
              FROM learning_patterns WHERE id = ?1;",
             params![id],
             |row| {
@@ -309,6 +410,9 @@ impl SQLiteManager {
                         .unwrap()
                         .with_timezone(&Utc),
                     tags,
+                    created_by: row.get(8)?,
+                    file_id: row.get(9)?,
+                    session_id: row.get(10)?,
                 })
             }
         ).optional()
@@ -328,10 +432,7 @@ impl SQLiteManager {
     pub async fn update_learning_pattern(&self, pattern: &LearningPattern) -> Result<bool> {
         debug!("Updating learning pattern: {}", pattern.id);
 
-        let conn = self
-            .connection
-            .lock()
-            .map_err(|e| anyhow::anyhow!("Failed to acquire connection lock: {e}"))?;
+        let conn = self.connection()?;
 
         let tags_json = serde_json::to_string(&pattern.tags)
             .map_err(|e| anyhow::anyhow!("Failed to serialize tags: {e}"))?;
@@ -340,7 +441,7 @@ impl SQLiteManager {
             .execute(
                 "UPDATE learning_patterns 
              SET pattern_type = ?2, pattern_data = ?3, source = ?4, confidence = ?5, 
-                 updated_at = ?6, tags = ?7 
+                 updated_at = ?6, tags = ?7, created_by = ?8, file_id = ?9, session_id = ?10 
              WHERE id = ?1;",
                 params![
                     pattern.id,
@@ -349,7 +450,10 @@ impl SQLiteManager {
                     pattern.source,
                     pattern.confidence,
                     pattern.updated_at.to_rfc3339(),
-                    tags_json
+                    tags_json,
+                    pattern.created_by,
+                    pattern.file_id,
+                    pattern.session_id
                 ],
             )
             .map_err(|e| anyhow::anyhow!("Failed to update learning pattern: {e}"))?;
@@ -366,14 +470,94 @@ impl SQLiteManager {
         Ok(success)
     }
 
+    /// Add batched access counts to learning patterns in one transaction
+    ///
+    /// Each entry is a pattern id, the number of accesses to add and the time of
+    /// the latest one. Returns how many patterns were updated.
+    pub async fn record_learning_pattern_accesses(
+        &self,
+        accesses: &[(String, u32, DateTime<Utc>)],
+    ) -> Result<usize> {
+        debug!("Recording accesses to {} learning patterns", accesses.len());
+
+        let mut conn = self.connection()?;
+        let tx = conn
+            .transaction()
+            .map_err(|e| anyhow::anyhow!("Failed to begin transaction: {e}"))?;
+
+        let mut updated = 0;
+        for (id, count, last_accessed) in accesses {
+            updated += tx
+                .execute(
+                    "UPDATE learning_patterns
+                     SET access_count = access_count + ?2, last_accessed = ?3
+                     WHERE id = ?1;",
+                    params![id, count, last_accessed.to_rfc3339()],
+                )
+                .map_err(|e| anyhow::anyhow!("Failed to record pattern access: {e}"))?;
+        }
+
+        tx.commit()
+            .map_err(|e| anyhow::anyhow!("Failed to commit pattern accesses: {e}"))?;
+        drop(conn);
+
+        Ok(updated)
+    }
+
+    /// Get the access statistics of a learning pattern
+    pub async fn get_learning_pattern_access_stats(
+        &self,
+        id: &str,
+    ) -> Result<Option<PatternAccessStats>> {
+        let conn = self.connection()?;
+
+        conn.query_row(
+            "SELECT access_count, last_accessed FROM learning_patterns WHERE id = ?1;",
+            params![id],
+            |row| {
+                Ok(PatternAccessStats {
+                    access_count: row.get(0)?,
+                    last_accessed: row
+                        .get::<_, Option<String>>(1)?
+                        .and_then(|time| DateTime::parse_from_rfc3339(&time).ok())
+                        .map(|time| time.with_timezone(&Utc)),
+                })
+            },
+        )
+        .optional()
+        .map_err(|e| anyhow::anyhow!("Failed to query pattern access statistics: {e}"))
+    }
+
+    /// Ids of the `limit` most accessed learning patterns, most accessed first
+    ///
+    /// Ties go to the most recently accessed pattern.
+    pub async fn list_most_accessed_learning_pattern_ids(
+        &self,
+        limit: usize,
+    ) -> Result<Vec<String>> {
+        let conn = self.connection()?;
+
+        let mut stmt = conn
+            .prepare(
+                "SELECT id FROM learning_patterns
+                 ORDER BY access_count DESC, last_accessed DESC LIMIT ?1;",
+            )
+            .map_err(|e| anyhow::anyhow!("Failed to prepare statement: {e}"))?;
+
+        let ids = stmt
+            .query_map(params![limit as i64], |row| row.get(0))
+            .map_err(|e| anyhow::anyhow!("Failed to query most accessed patterns: {e}"))?
+            .collect::<Result<Vec<String>, _>>()
+            .map_err(|e| anyhow::anyhow!("Failed to collect most accessed patterns: {e}"))?;
+
+        Ok(ids)
+    }
+
     /// Delete a learning pattern
     pub async fn delete_learning_pattern(&self, id: &str) -> Result<bool> {
         debug!("Deleting learning pattern: {id}");
 
-        let conn = self
-            .connection
-            .lock()
-            .map_err(|e| anyhow::anyhow!("Failed to acquire connection lock: {e}"))?;
+        let conn = self.connection()?;
 
         let result = conn
             .execute("DELETE FROM learning_patterns WHERE id = ?1;", params![id])
@@ -391,6 +575,32 @@ impl SQLiteManager {
         Ok(success)
     }
 
+    /// Delete all learning patterns from a source, returning the deleted ids
+    pub async fn delete_learning_patterns_by_source(&self, source: &str) -> Result<Vec<String>> {
+        debug!("Deleting learning patterns from source: {source}");
+
+        let conn = self.connection()?;
+
+        let mut stmt = conn
+            .prepare("DELETE FROM learning_patterns WHERE source = ?1 RETURNING id;")
+            .map_err(|e| anyhow::anyhow!("Failed to prepare statement: {e}"))?;
+
+        let ids = stmt
+            .query_map(params![source], |row| row.get(0))
+            .map_err(|e| anyhow::anyhow!("Failed to delete learning patterns: {e}"))?
+            .collect::<Result<Vec<String>, _>>()
+            .map_err(|e| anyhow::anyhow!("Failed to collect deleted pattern ids: {e}"))?;
+
+        drop(stmt);
+        drop(conn);
+
+        debug!(
+            "Deleted {} learning patterns from source: {source}",
+            ids.len()
+        );
+        Ok(ids)
+    }
+
     /// List learning patterns by type
     pub async fn list_learning_patterns_by_type(
         &self,
@@ -398,13 +608,10 @@ impl SQLiteManager {
     ) -> Result<Vec<LearningPattern>> {
         debug!("Listing learning patterns by type: {pattern_type}");
 
-        let conn = self
-            .connection
-            .lock()
-            .map_err(|e| anyhow::anyhow!("Failed to acquire connection lock: {e}"))?;
+        let conn = self.connection()?;
 
         let mut stmt = conn.prepare(
-            "SELECT id, pattern_type, pattern_data, source, confidence, created_at, updated_at, tags 
+            "SELECT id, pattern_type, pattern_data, source, confidence, created_at, updated_at, tags, created_by, file_id, session_id 
              FROM learning_patterns WHERE pattern_type = ?1 ORDER BY created_at DESC;"
         ).map_err(|e| anyhow::anyhow!("Failed to prepare statement: {e}"))?;
 
@@ -427,6 +634,9 @@ impl SQLiteManager {
                         .unwrap()
                         .with_timezone(&Utc),
                     tags,
+                    created_by: row.get(8)?,
+                    file_id: row.get(9)?,
+                    session_id: row.get(10)?,
                 })
             })
             .map_err(|e| anyhow::anyhow!("Failed to query learning patterns: {e}"))?
@@ -452,16 +662,13 @@ impl SQLiteManager {
     ) -> Result<Vec<LearningPattern>> {
         debug!("Searching learning patterns with query: {query}, limit: {limit}");
 
-        let conn = self
-            .connection
-            .lock()
-            .map_err(|e| anyhow::anyhow!("Failed to acquire connection lock: {e}"))?;
+        let conn = self.connection()?;
 
         // Use LIKE for simple keyword search (in production, would use FTS)
         let search_pattern = format!("%{}%", query);
 
         let mut stmt = conn.prepare(
-            "SELECT id, pattern_type, pattern_data, source, confidence, created_at, updated_at, tags 
+            "SELECT id, pattern_type, pattern_data, source, confidence, created_at, updated_at, tags, created_by, file_id, session_id 
              FROM learning_patterns 
              WHERE pattern_data LIKE ?1 OR source LIKE ?1
              ORDER BY confidence DESC, created_at DESC
@@ -490,6 +697,9 @@ impl SQLiteManager {
                         .unwrap()
                         .with_timezone(&Utc),
                     tags,
+                    created_by: row.get(8)?,
+                    file_id: row.get(9)?,
+                    session_id: row.get(10)?,
                 })
             })
             .map_err(|e| anyhow::anyhow!("Failed to search learning patterns: {e}"))?
@@ -515,10 +725,7 @@ impl SQLiteManager {
     ) -> Result<()> {
         debug!("Creating thinking session: {session_id}");
 
-        let conn = self
-            .connection
-            .lock()
-            .map_err(|e| anyhow::anyhow!("Failed to acquire connection lock: {e}"))?;
+        let conn = self.connection()?;
 
         let now = Utc::now();
 
@@ -548,10 +755,7 @@ impl SQLiteManager {
     ) -> Result<()> {
         debug!("Creating sequential thinking step: {}", step.id);
 
-        let conn = self
-            .connection
-            .lock()
-            .map_err(|e| anyhow::anyhow!("Failed to acquire connection lock: {e}"))?;
+        let conn = self.connection()?;
 
         conn.execute(
             "INSERT INTO sequential_thinking (id, session_id, step_number, description, step_data, created_at) 
@@ -579,10 +783,7 @@ impl SQLiteManager {
     ) -> Result<Vec<SequentialThinkingStep>> {
         debug!("Getting sequential thinking steps for session: {session_id}");
 
-        let conn = self
-            .connection
-            .lock()
-            .map_err(|e| anyhow::anyhow!("Failed to acquire connection lock: {e}"))?;
+        let conn = self.connection()?;
 
         let mut stmt = conn
             .prepare(
@@ -625,10 +826,7 @@ impl SQLiteManager {
     ) -> Result<Option<SequentialThinkingStep>> {
         debug!("Getting sequential thinking step: {id}");
 
-        let conn = self
-            .connection
-            .lock()
-            .map_err(|e| anyhow::anyhow!("Failed to acquire connection lock: {e}"))?;
+        let conn = self.connection()?;
 
         let step = conn
             .query_row(
@@ -668,10 +866,7 @@ impl SQLiteManager {
     ) -> Result<bool> {
         debug!("Updating sequential thinking step: {}", step.id);
 
-        let conn = self
-            .connection
-            .lock()
-            .map_err(|e| anyhow::anyhow!("Failed to acquire connection lock: {e}"))?;
+        let conn = self.connection()?;
 
         let result = conn
             .execute(
@@ -704,10 +899,7 @@ impl SQLiteManager {
     pub async fn delete_sequential_thinking_step(&self, id: &str) -> Result<bool> {
         debug!("Deleting sequential thinking step: {id}");
 
-        let conn = self
-            .connection
-            .lock()
-            .map_err(|e| anyhow::anyhow!("Failed to acquire connection lock: {e}"))?;
+        let conn = self.connection()?;
 
         let result = conn
             .execute(
@@ -736,10 +928,7 @@ impl SQLiteManager {
     ) -> Result<bool> {
         debug!("Updating thinking session status: {session_id} -> {status}");
 
-        let conn = self
-            .connection
-            .lock()
-            .map_err(|e| anyhow::anyhow!("Failed to acquire connection lock: {e}"))?;
+        let conn = self.connection()?;
 
         let result = conn
             .execute(
@@ -764,10 +953,7 @@ impl SQLiteManager {
     pub async fn create_user_interaction(&self, interaction: &UserInteraction) -> Result<()> {
         debug!("Creating user interaction: {}", interaction.id);
 
-        let conn = self
-            .connection
-            .lock()
-            .map_err(|e| anyhow::anyhow!("Failed to acquire connection lock: {e}"))?;
+        let conn = self.connection()?;
 
         conn.execute(
             "INSERT INTO user_interactions (id, user_id, interaction_type, interaction_data, created_at) 
@@ -791,10 +977,7 @@ impl SQLiteManager {
     pub async fn get_database_stats(&self) -> Result<DatabaseStats> {
         debug!("Getting database statistics");
 
-        let conn = self
-            .connection
-            .lock()
-            .map_err(|e| anyhow::anyhow!("Failed to acquire connection lock: {e}"))?;
+        let conn = self.connection()?;
 
         let learning_patterns_count: i64 = conn
             .query_row("SELECT COUNT(*) FROM learning_patterns;", [], |row| {
@@ -891,6 +1074,9 @@ mod tests {
             created_at: Utc::now(),
             updated_at: Utc::now(),
             tags: vec!["rust".to_string(), "async".to_string()],
+            created_by: None,
+            file_id: None,
+            session_id: None,
         };
 
         // Create
@@ -928,6 +1114,172 @@ mod tests {
         assert!(not_found.is_none());
     }
 
+    #[tokio::test]
+    async fn test_learning_patterns_table_is_migrated_to_provenance() {
+        let temp_file = NamedTempFile::new().unwrap();
+
+        // A table and row from before the provenance columns existed
+        let old = rusqlite::Connection::open(temp_file.path()).unwrap();
+        old.execute(
+            "CREATE TABLE learning_patterns (
+                id TEXT PRIMARY KEY,
+                pattern_type TEXT NOT NULL,
+                pattern_data TEXT NOT NULL,
+                source TEXT NOT NULL,
+                confidence REAL NOT NULL,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                tags TEXT
+            );",
+            [],
+        )
+        .unwrap();
+        let now = Utc::now().to_rfc3339();
+        old.execute(
+            "INSERT INTO learning_patterns VALUES ('old', 'code_pattern', '{}', 'lib.rs', 0.5, ?1, ?1, '[]');",
+            params![now],
+        )
+        .unwrap();
+        drop(old);
+
+        let manager = SQLiteManager::new(temp_file.path()).unwrap();
+        manager.initialize_schema().await.unwrap();
+        // Running the migration again is a no-op
+        manager.initialize_schema().await.unwrap();
+
+        let migrated = manager.get_learning_pattern("old").await.unwrap().unwrap();
+        assert_eq!(migrated.created_by, None);
+        assert_eq!(migrated.file_id, None);
+
+        let file_id = Uuid::new_v4().to_string();
+        let pattern = LearningPattern {
+            id: Uuid::new_v4().to_string(),
+            created_by: Some("tool:clippy".to_string()),
+            file_id: Some(file_id.clone()),
+            session_id: None,
+            ..migrated
+        };
+        manager.create_learning_pattern(&pattern).await.unwrap();
+
+        let stored = manager
+            .get_learning_pattern(&pattern.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.created_by.as_deref(), Some("tool:clippy"));
+        assert_eq!(stored.file_id, Some(file_id));
+    }
+
+    #[tokio::test]
+    async fn test_pattern_accesses_are_accumulated() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let manager = SQLiteManager::new(temp_file.path()).unwrap();
+        manager.initialize_schema().await.unwrap();
+
+        let pattern = LearningPattern {
+            id: Uuid::new_v4().to_string(),
+            pattern_type: "code_pattern".to_string(),
+            pattern_data: "{}".to_string(),
+            source: "src/lib.rs".to_string(),
+            confidence: 0.5,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            tags: vec![],
+            created_by: None,
+            file_id: None,
+            session_id: None,
+        };
+        manager.create_learning_pattern(&pattern).await.unwrap();
+        let stats = manager
+            .get_learning_pattern_access_stats(&pattern.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stats.access_count, 0);
+        assert_eq!(stats.last_accessed, None);
+
+        let batch = [
+            (pattern.id.clone(), 3, Utc::now()),
+            ("missing".to_string(), 1, Utc::now()),
+        ];
+        assert_eq!(
+            manager
+                .record_learning_pattern_accesses(&batch)
+                .await
+                .unwrap(),
+            1
+        );
+        manager
+            .record_learning_pattern_accesses(&batch[..1])
+            .await
+            .unwrap();
+        let stats = manager
+            .get_learning_pattern_access_stats(&pattern.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stats.access_count, 6);
+        assert!(stats.last_accessed.is_some());
+        assert_eq!(
+            manager
+                .list_most_accessed_learning_pattern_ids(10)
+                .await
+                .unwrap(),
+            vec![pattern.id.clone()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_delete_learning_patterns_by_source() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let manager = SQLiteManager::new(temp_file.path()).unwrap();
+        manager.initialize_schema().await.unwrap();
+
+        let mut ids = Vec::new();
+        for source in ["src/user.rs", "src/user.rs", "src/other.rs"] {
+            let pattern = LearningPattern {
+                id: Uuid::new_v4().to_string(),
+                pattern_type: "code_pattern".to_string(),
+                pattern_data: "{}".to_string(),
+                source: source.to_string(),
+                confidence: 0.5,
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+                tags: vec![],
+                created_by: None,
+                file_id: None,
+                session_id: None,
+            };
+            manager.create_learning_pattern(&pattern).await.unwrap();
+            ids.push(pattern.id);
+        }
+
+        let mut deleted = manager
+            .delete_learning_patterns_by_source("src/user.rs")
+            .await
+            .unwrap();
+        deleted.sort();
+        let mut expected = ids[..2].to_vec();
+        expected.sort();
+        assert_eq!(deleted, expected);
+
+        assert!(manager
+            .get_learning_pattern(&ids[0])
+            .await
+            .unwrap()
+            .is_none());
+        assert!(manager
+            .get_learning_pattern(&ids[2])
+            .await
+            .unwrap()
+            .is_some());
+        assert!(manager
+            .delete_learning_patterns_by_source("src/user.rs")
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
     #[tokio::test]
     async fn test_learning_patterns_by_type() {
         let temp_file = NamedTempFile::new().unwrap();
@@ -944,6 +1296,9 @@ mod tests {
             created_at: Utc::now(),
             updated_at: Utc::now(),
             tags: vec!["rust".to_string()],
+            created_by: None,
+            file_id: None,
+            session_id: None,
         };
 
         let pattern2 = LearningPattern {
@@ -955,6 +1310,9 @@ mod tests {
             created_at: Utc::now(),
             updated_at: Utc::now(),
             tags: vec!["python".to_string()],
+            created_by: None,
+            file_id: None,
+            session_id: None,
         };
 
         let pattern3 = LearningPattern {
@@ -966,6 +1324,9 @@ mod tests {
             created_at: Utc::now(),
             updated_at: Utc::now(),
             tags: vec!["architecture".to_string()],
+            created_by: None,
+            file_id: None,
+            session_id: None,
         };
 
         manager.create_learning_pattern(&pattern1).await.unwrap();
@@ -1177,6 +1538,9 @@ mod tests {
             created_at: Utc::now(),
             updated_at: Utc::now(),
             tags: vec!["test".to_string()],
+            created_by: None,
+            file_id: None,
+            session_id: None,
         };
         manager.create_learning_pattern(&pattern).await.unwrap();
 
@@ -1217,6 +1581,53 @@ mod tests {
         assert_eq!(stats.user_interactions_count, 1);
     }
 
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_reads_and_writes() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let manager = Arc::new(SQLiteManager::with_pool_size(temp_file.path(), 4).unwrap());
+        manager.initialize_schema().await.unwrap();
+
+        let tasks: Vec<_> = (0..32)
+            .map(|i| {
+                let manager = Arc::clone(&manager);
+                tokio::spawn(async move {
+                    let pattern = LearningPattern {
+                        id: format!("pattern-{i}"),
+                        pattern_type: "concurrency".to_string(),
+                        pattern_data: "{}".to_string(),
+                        source: "test".to_string(),
+                        confidence: 0.5,
+                        created_at: Utc::now(),
+                        updated_at: Utc::now(),
+                        tags: vec![],
+                        created_by: None,
+                        file_id: None,
+                        session_id: None,
+                    };
+                    manager.create_learning_pattern(&pattern).await?;
+                    let found = manager.get_learning_pattern(&pattern.id).await?;
+                    manager.get_database_stats().await?;
+                    anyhow::Ok(found.is_some())
+                })
+            })
+            .collect();
+
+        for task in tasks {
+            let result = task.await.unwrap();
+            assert!(matches!(result, Ok(true)), "task failed: {result:?}");
+        }
+
+        let mode: String = manager
+            .connection()
+            .unwrap()
+            .query_row("PRAGMA journal_mode", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(mode.to_lowercase(), "wal");
+
+        let stats = manager.get_database_stats().await.unwrap();
+        assert_eq!(stats.learning_patterns_count, 32);
+    }
+
     #[tokio::test]
     async fn test_error_handling() {
         let temp_file = NamedTempFile::new().unwrap();
@@ -1237,6 +1648,9 @@ mod tests {
             created_at: Utc::now(),
             updated_at: Utc::now(),
             tags: vec![],
+            created_by: None,
+            file_id: None,
+            session_id: None,
         };
         let not_updated = manager.update_learning_pattern(&pattern).await.unwrap();
         assert!(!not_updated);
@@ -69,6 +69,8 @@ fn main() {
         content: "fn main() { println!(\"Hello, world!\"); }".to_string(),
         language: "rust".to_string(),
         modified: chrono::Utc::now(),
+        encoding: Default::default(),
+        line_ending: Default::default(),
     };
 
     println!("✓ CodeFile created successfully");
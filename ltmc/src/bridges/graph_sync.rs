@@ -0,0 +1,168 @@
+//! LTMC Graph Sync Bridge
+//!
+//! This module keeps the Neo4j knowledge graph growing with the pattern store:
+//! once a pattern has an embedding in the vector index, its nearest neighbours
+//! above a similarity threshold are linked to it with `SimilarTo` relationships.
+
+use anyhow::Result;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use tokio::sync::{Mutex, RwLock};
+use tracing::debug;
+use uuid::Uuid;
+
+use odincode_databases::{FaissManager, Neo4jManager, PatternRelationship, RelationshipType};
+
+use crate::config::LTMCConfig;
+use crate::models::LearningPattern;
+
+/// Thresholds for linking similar patterns
+#[derive(Debug, Clone)]
+pub struct GraphSyncConfig {
+    /// Lowest similarity, from 0.0 to 1.0, that gets a relationship
+    pub min_similarity: f32,
+    /// Most relationships created for one pattern
+    pub max_links: usize,
+}
+
+impl Default for GraphSyncConfig {
+    fn default() -> Self {
+        Self {
+            min_similarity: 0.8,
+            max_links: 5,
+        }
+    }
+}
+
+/// Bridge linking similar patterns in Neo4j
+#[derive(Clone)]
+pub struct GraphSyncBridge {
+    /// Graph the relationships are created in
+    graph: Arc<Neo4jManager>,
+    /// Linking thresholds
+    config: GraphSyncConfig,
+    /// Patterns already linked, so a pattern is only linked once
+    linked: Arc<Mutex<HashSet<Uuid>>>,
+}
+
+impl GraphSyncBridge {
+    /// Create a bridge linking patterns in the given graph
+    pub fn new(graph: Arc<Neo4jManager>, config: GraphSyncConfig) -> Self {
+        Self {
+            graph,
+            config,
+            linked: Arc::new(Mutex::new(HashSet::new())),
+        }
+    }
+
+    /// Create a bridge if graph sync is enabled in the configuration
+    pub fn from_config(config: &LTMCConfig, graph: Arc<Neo4jManager>) -> Option<Self> {
+        config
+            .features
+            .graph_sync
+            .then(|| Self::new(graph, GraphSyncConfig::default()))
+    }
+
+    /// Similarity from 0.0 to 1.0 of two embeddings an L2 distance apart
+    pub fn similarity(distance: f32) -> f32 {
+        1.0 / (1.0 + distance.max(0.0))
+    }
+
+    /// Link a pattern to its most similar cached patterns
+    ///
+    /// Does nothing for a pattern without an embedding or one linked before.
+    /// Neighbours missing from `patterns` are skipped, since their nodes could not
+    /// be described. Returns how many relationships were created.
+    pub async fn sync_pattern(
+        &self,
+        id: Uuid,
+        vector_index: &FaissManager,
+        patterns: &RwLock<HashMap<Uuid, LearningPattern>>,
+    ) -> Result<usize> {
+        let pattern_id = id.to_string();
+        if self.linked.lock().await.contains(&id)
+            || vector_index.get_embedding(&pattern_id).await?.is_none()
+        {
+            return Ok(0);
+        }
+
+        // One extra result, as the pattern may find itself
+        let neighbours = vector_index
+            .find_similar_patterns(&pattern_id, f32::MAX, self.config.max_links + 1)
+            .await?;
+
+        let (pattern, similar) = {
+            let patterns = patterns.read().await;
+            let Some(pattern) = patterns.get(&id).cloned() else {
+                return Ok(0);
+            };
+            let similar: Vec<(LearningPattern, f32)> = neighbours
+                .into_iter()
+                .map(|(neighbour, distance)| (neighbour, Self::similarity(distance)))
+                .filter(|(_, similarity)| *similarity >= self.config.min_similarity)
+                .filter_map(|(neighbour, similarity)| {
+                    let neighbour = Uuid::parse_str(&neighbour).ok()?;
+                    Some((patterns.get(&neighbour)?.clone(), similarity))
+                })
+                .take(self.config.max_links)
+                .collect();
+            (pattern, similar)
+        };
+
+        if !similar.is_empty() {
+            self.merge_node(&pattern).await?;
+        }
+        for (neighbour, similarity) in &similar {
+            self.merge_node(neighbour).await?;
+
+            let mut metadata = HashMap::new();
+            metadata.insert("linked_by".to_string(), "graph_sync".to_string());
+            self.graph
+                .create_pattern_relationship(&PatternRelationship {
+                    id: Uuid::new_v4().to_string(),
+                    source_pattern_id: pattern_id.clone(),
+                    target_pattern_id: neighbour.id.to_string(),
+                    relationship_type: RelationshipType::SimilarTo,
+                    strength: *similarity as f64,
+                    metadata,
+                    created_at: chrono::Utc::now(),
+                    updated_at: chrono::Utc::now(),
+                })
+                .await?;
+        }
+
+        self.linked.lock().await.insert(id);
+        debug!(
+            "Linked pattern {} to {} similar patterns",
+            id,
+            similar.len()
+        );
+        Ok(similar.len())
+    }
+
+    /// Make sure a pattern has a node to attach relationships to
+    async fn merge_node(&self, pattern: &LearningPattern) -> Result<()> {
+        self.graph
+            .merge_learning_pattern_node(
+                &pattern.id.to_string(),
+                pattern.pattern_type.as_str(),
+                &pattern.content,
+                pattern.source().unwrap_or_default(),
+                pattern.confidence as f64,
+            )
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_similarity_falls_with_distance() {
+        assert_eq!(GraphSyncBridge::similarity(0.0), 1.0);
+        assert!(GraphSyncBridge::similarity(0.1) > GraphSyncBridge::similarity(1.0));
+        assert!(GraphSyncBridge::similarity(0.25) >= GraphSyncConfig::default().min_similarity);
+        assert!(GraphSyncBridge::similarity(0.5) < GraphSyncConfig::default().min_similarity);
+    }
+}
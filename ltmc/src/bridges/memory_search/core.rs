@@ -5,11 +5,11 @@
 use anyhow::Result;
 use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::sync::{OnceCell, RwLock};
 use tracing::{error, info};
 use uuid::Uuid;
 
-use odincode_databases::{DatabaseManager, DatabaseType};
+use odincode_databases::{DatabaseManager, DatabaseType, Neo4jManager};
 
 /// Memory search bridge for LTMC system
 #[derive(Clone)]
@@ -20,6 +20,8 @@ pub struct MemorySearchBridge {
     pub connection_ids: Arc<RwLock<HashMap<DatabaseType, Uuid>>>,
     /// Flag indicating if the bridge is initialized
     pub initialized: bool,
+    /// Neo4j manager for the registered Neo4j connection, created on first use
+    pub(crate) neo4j: Arc<OnceCell<Arc<Neo4jManager>>>,
 }
 
 impl MemorySearchBridge {
@@ -29,6 +31,18 @@ impl MemorySearchBridge {
             database_manager: Arc::new(database_manager),
             connection_ids: Arc::new(RwLock::new(HashMap::new())),
             initialized: false,
+            neo4j: Arc::new(OnceCell::new()),
+        }
+    }
+
+    /// Use an already configured Neo4j manager instead of connecting one to the
+    /// registered Neo4j connection
+    pub fn with_neo4j_manager(self, neo4j_manager: Arc<Neo4jManager>) -> Self {
+        let neo4j = OnceCell::new();
+        let _ = neo4j.set(neo4j_manager);
+        Self {
+            neo4j: Arc::new(neo4j),
+            ..self
         }
     }
 
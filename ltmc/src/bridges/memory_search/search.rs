@@ -3,12 +3,13 @@
 //! This module contains search-related functionality for the memory search bridge.
 
 use anyhow::Result;
-use odincode_databases::DatabaseType;
+use odincode_databases::{DatabaseType, PatternAccessStats};
 use std::collections::HashMap;
 use tracing::{debug, info, warn};
 
 use crate::bridges::memory_search::core::MemorySearchBridge;
-use crate::models::{LearningPattern, PatternType};
+use crate::models::{LearningPattern, PatternProvenance, PatternType};
+use crate::search::embedding::{text_embedding, text_hash};
 use odincode_databases::faiss::VectorSearchResult;
 use odincode_databases::neo4j::GraphNode;
 
@@ -191,8 +192,7 @@ impl MemorySearchBridge {
             connection.name, connection.id
         );
 
-        // Create Neo4j manager from connection
-        let neo4j_manager = odincode_databases::neo4j::Neo4jManager::new().await?;
+        let neo4j_manager = self.neo4j_manager().await?;
 
         // Build Cypher query based on pattern type
         let query = if let Some(pattern_type) = pattern_type {
@@ -270,21 +270,13 @@ impl MemorySearchBridge {
             .search_learning_patterns(query, limit)
             .await?;
 
-        // Convert SQLite patterns to LTMC patterns
+        // Convert SQLite patterns to LTMC patterns, with their access statistics
         let mut patterns = Vec::new();
         for sqlite_pattern in sqlite_patterns {
-            let pattern = LearningPattern {
-                id: uuid::Uuid::parse_str(&sqlite_pattern.id)
-                    .unwrap_or_else(|_| uuid::Uuid::new_v4()),
-                pattern_type: self.parse_pattern_type(&sqlite_pattern.pattern_type),
-                content: sqlite_pattern.pattern_data,
-                context: HashMap::new(), // Would be populated from additional metadata
-                created: sqlite_pattern.created_at,
-                last_accessed: chrono::Utc::now(),
-                access_count: 0,
-                confidence: sqlite_pattern.confidence as f32,
-            };
-            patterns.push(pattern);
+            let stats = sqlite_manager
+                .get_learning_pattern_access_stats(&sqlite_pattern.id)
+                .await?;
+            patterns.push(self.pattern_from_sqlite(sqlite_pattern, stats));
         }
 
         info!(
@@ -296,38 +288,78 @@ impl MemorySearchBridge {
         Ok(patterns)
     }
 
-    /// Convert text query to vector embedding (simplified implementation)
-    async fn text_to_embedding(&self, text: &str) -> Result<Vec<f32>> {
-        // In a real implementation, this would use an embedding model like BERT, OpenAI embeddings, etc.
-        // For now, we'll create a simple hash-based embedding as a placeholder
-        let words: Vec<&str> = text.split_whitespace().collect();
-        let dimension = 768; // Standard embedding dimension
-        let mut embedding = vec![0.0; dimension];
-
-        for word in words.iter() {
-            let hash = self.simple_hash(word) as usize;
-            let index = hash % dimension;
-            embedding[index] += 1.0;
+    /// The `n` most accessed patterns in SQLite, most accessed first
+    pub async fn most_accessed_patterns(&self, n: usize) -> Result<Vec<LearningPattern>> {
+        if !self.initialized {
+            return Err(anyhow::anyhow!("MemorySearchBridge not initialized"));
         }
 
-        // Normalize the embedding
-        let norm: f32 = embedding.iter().map(|x| x * x).sum::<f32>().sqrt();
-        if norm > 0.0 {
-            for val in &mut embedding {
-                *val /= norm;
-            }
+        let connection = self.connection_for(DatabaseType::SQLite).await?;
+        let sqlite_manager =
+            odincode_databases::sqlite::SQLiteManager::new(&connection.connection_string)?;
+
+        let mut patterns = Vec::new();
+        for id in sqlite_manager
+            .list_most_accessed_learning_pattern_ids(n)
+            .await?
+        {
+            let Some(sqlite_pattern) = sqlite_manager.get_learning_pattern(&id).await? else {
+                continue;
+            };
+            let stats = sqlite_manager
+                .get_learning_pattern_access_stats(&id)
+                .await?;
+            patterns.push(self.pattern_from_sqlite(sqlite_pattern, stats));
         }
 
-        Ok(embedding)
+        Ok(patterns)
+    }
+
+    /// Convert a SQLite pattern and its access statistics to an LTMC pattern
+    fn pattern_from_sqlite(
+        &self,
+        sqlite_pattern: odincode_databases::sqlite::LearningPattern,
+        stats: Option<PatternAccessStats>,
+    ) -> LearningPattern {
+        let stats = stats.unwrap_or(PatternAccessStats {
+            access_count: 0,
+            last_accessed: None,
+        });
+
+        LearningPattern {
+            id: uuid::Uuid::parse_str(&sqlite_pattern.id).unwrap_or_else(|_| uuid::Uuid::new_v4()),
+            pattern_type: self.parse_pattern_type(&sqlite_pattern.pattern_type),
+            content: sqlite_pattern.pattern_data,
+            context: HashMap::new(), // Would be populated from additional metadata
+            created: sqlite_pattern.created_at,
+            last_accessed: stats.last_accessed.unwrap_or(sqlite_pattern.created_at),
+            access_count: stats.access_count,
+            confidence: sqlite_pattern.confidence as f32,
+            provenance: PatternProvenance {
+                created_by: sqlite_pattern
+                    .created_by
+                    .as_deref()
+                    .and_then(|creator| creator.parse().ok()),
+                file_id: sqlite_pattern
+                    .file_id
+                    .as_deref()
+                    .and_then(|id| uuid::Uuid::parse_str(id).ok()),
+                session_id: sqlite_pattern
+                    .session_id
+                    .as_deref()
+                    .and_then(|id| uuid::Uuid::parse_str(id).ok()),
+            },
+        }
+    }
+
+    /// Convert text query to vector embedding (simplified implementation)
+    async fn text_to_embedding(&self, text: &str) -> Result<Vec<f32>> {
+        Ok(text_embedding(text, 768)) // Standard embedding dimension
     }
 
     /// Simple hash function for text
     fn simple_hash(&self, text: &str) -> u32 {
-        let mut hash: u32 = 5381;
-        for byte in text.as_bytes() {
-            hash = hash.wrapping_mul(33).wrapping_add(*byte as u32);
-        }
-        hash
+        text_hash(text)
     }
 
     /// Parse pattern type from string
@@ -3,12 +3,13 @@
 //! This module contains storage-related functionality for the memory search bridge.
 
 use anyhow::Result;
-use odincode_databases::DatabaseType;
+use odincode_databases::{DatabaseConnection, DatabaseType, Neo4jConfig, Neo4jManager};
+use std::sync::Arc;
 use tracing::{debug, info};
 use uuid::Uuid;
 
 use crate::bridges::memory_search::core::MemorySearchBridge;
-use crate::models::LearningPattern;
+use crate::models::{LearningPattern, PatternAccess};
 
 impl MemorySearchBridge {
     /// Store a learning pattern in all databases atomically
@@ -178,6 +179,144 @@ impl MemorySearchBridge {
         Ok(())
     }
 
+    /// Look up the registered connection for a database
+    pub(crate) async fn connection_for(
+        &self,
+        database_type: DatabaseType,
+    ) -> Result<DatabaseConnection> {
+        let id = {
+            let ids = self.connection_ids.read().await;
+            *ids.get(&database_type)
+                .ok_or_else(|| anyhow::anyhow!("{:?} connection not found", database_type))?
+        };
+
+        self.database_manager
+            .get_connection(id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("{:?} connection not found", database_type))
+    }
+
+    /// Neo4j manager for the registered Neo4j connection
+    ///
+    /// The manager is connected once, to the connection's URI, and shared by
+    /// every later graph operation of the bridge and its clones.
+    pub(crate) async fn neo4j_manager(&self) -> Result<Arc<Neo4jManager>> {
+        self.neo4j
+            .get_or_try_init(|| async {
+                let connection = self.connection_for(DatabaseType::Neo4j).await?;
+                debug!(
+                    "Connecting to Neo4j connection: {} ({})",
+                    connection.name, connection.id
+                );
+                let config = Neo4jConfig {
+                    uri: connection.connection_string.clone(),
+                    ..Default::default()
+                };
+                Ok::<_, anyhow::Error>(Arc::new(Neo4jManager::with_config(config).await?))
+            })
+            .await
+            .cloned()
+    }
+
+    /// Delete a learning pattern from all databases
+    ///
+    /// Every database is visited even if the pattern is missing from some, so a
+    /// partially stored pattern is removed completely. Returns whether any
+    /// database held the pattern.
+    pub async fn delete_pattern_atomically(&self, id: Uuid) -> Result<bool> {
+        if !self.initialized {
+            return Err(anyhow::anyhow!("MemorySearchBridge not initialized"));
+        }
+
+        debug!("Deleting pattern from all databases: {}", id);
+
+        let in_sqlite = self.delete_pattern_from_sqlite(id).await?;
+        let in_others = self.delete_pattern_from_indexes(id).await?;
+
+        debug!("Pattern deleted from all databases: {}", id);
+        Ok(in_sqlite || in_others)
+    }
+
+    /// Delete every learning pattern from a source in all databases
+    ///
+    /// SQLite is the record of which patterns came from the source; the ids it
+    /// deletes are then removed from the other databases. Returns those ids.
+    pub async fn delete_patterns_by_source(&self, source: &str) -> Result<Vec<Uuid>> {
+        if !self.initialized {
+            return Err(anyhow::anyhow!("MemorySearchBridge not initialized"));
+        }
+
+        debug!("Deleting patterns from source in all databases: {}", source);
+
+        let connection = self.connection_for(DatabaseType::SQLite).await?;
+        let sqlite_manager =
+            odincode_databases::sqlite::SQLiteManager::new(&connection.connection_string)?;
+        let ids: Vec<Uuid> = sqlite_manager
+            .delete_learning_patterns_by_source(source)
+            .await?
+            .iter()
+            .filter_map(|id| Uuid::parse_str(id).ok())
+            .collect();
+
+        for id in &ids {
+            self.delete_pattern_from_indexes(*id).await?;
+        }
+
+        info!("Deleted {} patterns from source {}", ids.len(), source);
+        Ok(ids)
+    }
+
+    /// Delete a pattern from SQLite
+    async fn delete_pattern_from_sqlite(&self, id: Uuid) -> Result<bool> {
+        let connection = self.connection_for(DatabaseType::SQLite).await?;
+        let sqlite_manager =
+            odincode_databases::sqlite::SQLiteManager::new(&connection.connection_string)?;
+        sqlite_manager
+            .delete_learning_pattern(&id.to_string())
+            .await
+    }
+
+    /// Delete a pattern from the stores derived from its SQLite record: the
+    /// FAISS index, the Neo4j graph and the Redis cache
+    async fn delete_pattern_from_indexes(&self, id: Uuid) -> Result<bool> {
+        let in_faiss = self.delete_pattern_from_faiss(id).await?;
+
+        // Detach-deleting the node removes its relationships with it
+        let in_neo4j = self
+            .neo4j_manager()
+            .await?
+            .delete_learning_pattern_node(&id.to_string())
+            .await?;
+
+        let connection = self.connection_for(DatabaseType::Redis).await?;
+        let redis_config = odincode_databases::redis::RedisConfig {
+            url: connection.connection_string.clone(),
+            ..Default::default()
+        };
+        let redis_manager = odincode_databases::redis::RedisManager::new(redis_config)?;
+        let in_redis = redis_manager
+            .delete_learning_pattern(&id.to_string())
+            .await?;
+
+        Ok(in_faiss || in_neo4j || in_redis)
+    }
+
+    /// Delete a pattern's embedding from FAISS
+    async fn delete_pattern_from_faiss(&self, id: Uuid) -> Result<bool> {
+        let connection = self.connection_for(DatabaseType::FAISS).await?;
+
+        debug!(
+            "Using FAISS connection: {} ({})",
+            connection.name, connection.id
+        );
+
+        // Pattern embeddings are not persisted to the FAISS connection yet (see
+        // store_pattern_in_faiss), so there is nothing to remove here; patterns
+        // embedded in the manager's own vector index are removed by the manager
+        info!("Pattern {} would be removed from FAISS vector database", id);
+        Ok(false)
+    }
+
     /// Get pattern from cache (Redis)
     pub async fn get_pattern_from_cache(&self, id: Uuid) -> Result<Option<LearningPattern>> {
         if !self.initialized {
@@ -217,20 +356,32 @@ impl MemorySearchBridge {
         Ok(None)
     }
 
-    /// Update pattern access statistics
-    pub async fn update_pattern_access_stats(&self, id: Uuid) -> Result<()> {
+    /// Persist a batch of pattern accesses to SQLite
+    pub async fn update_pattern_access_stats(&self, accesses: &[PatternAccess]) -> Result<()> {
         if !self.initialized {
             return Err(anyhow::anyhow!("MemorySearchBridge not initialized"));
         }
 
-        debug!("Updating access statistics for pattern: {}", id);
-
-        // In a real implementation, this would:
-        // 1. Update access count and timestamp in all databases
-        // 2. Update cache with new statistics
-
-        // For now, simulate the process
-        info!("Access statistics would be updated for pattern: {}", id);
+        debug!("Updating access statistics for {} patterns", accesses.len());
+
+        let connection = self.connection_for(DatabaseType::SQLite).await?;
+        let sqlite_manager =
+            odincode_databases::sqlite::SQLiteManager::new(&connection.connection_string)?;
+        let batch: Vec<_> = accesses
+            .iter()
+            .map(|access| {
+                (
+                    access.pattern_id.to_string(),
+                    access.count,
+                    access.last_accessed,
+                )
+            })
+            .collect();
+        let updated = sqlite_manager
+            .record_learning_pattern_accesses(&batch)
+            .await?;
+
+        debug!("Updated access statistics for {} patterns", updated);
         Ok(())
     }
 }
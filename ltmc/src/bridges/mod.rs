@@ -3,6 +3,16 @@
 //! This module contains bridges between the LTMC system and external systems,
 //! including database connections and other integrations.
 
+pub mod graph_sync;
 pub mod memory_search;
+#[cfg(feature = "ltmc-redis")]
+pub mod pattern_sync;
+#[cfg(feature = "ltmc-otel")]
+pub mod telemetry;
 
+pub use graph_sync::{GraphSyncBridge, GraphSyncConfig};
 pub use memory_search::MemorySearchBridge;
+#[cfg(feature = "ltmc-redis")]
+pub use pattern_sync::{PatternSyncBridge, PATTERN_SYNC_CHANNEL};
+#[cfg(feature = "ltmc-otel")]
+pub use telemetry::{log_tracer_provider, LogSpanExporter, TelemetryBridge};
@@ -0,0 +1,160 @@
+//! LTMC Pattern Sync Bridge
+//!
+//! This module shares learned patterns between OdinCode instances using the
+//! same Redis: stored patterns are published on a channel, and patterns other
+//! instances publish are merged into the local cache. It is only compiled with
+//! the `ltmc-redis` feature.
+
+use anyhow::Result;
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use odincode_databases::RedisManager;
+
+use crate::models::LearningPattern;
+
+/// Channel patterns are published on by default
+pub const PATTERN_SYNC_CHANNEL: &str = "ltmc:patterns";
+
+/// Wait before the first attempt to resubscribe after a subscription ends
+const RESUBSCRIBE_INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Longest wait between attempts to resubscribe
+const RESUBSCRIBE_MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// A pattern published on the channel, tagged with the instance that stored it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PatternMessage {
+    /// Instance id of the publisher
+    origin: Uuid,
+    /// The stored pattern
+    pattern: LearningPattern,
+}
+
+/// Bridge sharing stored patterns over Redis pub/sub
+#[derive(Clone)]
+pub struct PatternSyncBridge {
+    /// Redis the patterns are published on
+    redis: Arc<RedisManager>,
+    /// Channel the patterns are published on
+    channel: String,
+    /// Id of this instance, so it skips the patterns it published itself
+    instance_id: Uuid,
+}
+
+impl PatternSyncBridge {
+    /// Create a bridge on the default channel with a new instance id
+    pub fn new(redis: Arc<RedisManager>) -> Self {
+        Self::with_channel(redis, PATTERN_SYNC_CHANNEL)
+    }
+
+    /// Create a bridge on the given channel with a new instance id
+    pub fn with_channel(redis: Arc<RedisManager>, channel: impl Into<String>) -> Self {
+        Self {
+            redis,
+            channel: channel.into(),
+            instance_id: Uuid::new_v4(),
+        }
+    }
+
+    /// Id this instance tags the patterns it publishes with
+    pub fn instance_id(&self) -> Uuid {
+        self.instance_id
+    }
+
+    /// Publish a stored pattern to the other instances
+    pub async fn publish(&self, pattern: &LearningPattern) -> Result<()> {
+        let message = serde_json::to_string(&PatternMessage {
+            origin: self.instance_id,
+            pattern: pattern.clone(),
+        })?;
+        let receivers = self.redis.publish(&self.channel, &message).await?;
+        debug!(
+            "Published learning pattern {} to {} subscribers",
+            pattern.id, receivers
+        );
+        Ok(())
+    }
+
+    /// Merge the patterns other instances publish into `patterns`
+    ///
+    /// Returns once subscribed; messages are then handled in the background until
+    /// the returned task is aborted. If the subscription ends, for instance when
+    /// the Redis connection drops, the task resubscribes with a growing backoff.
+    /// Patterns this instance published and messages that are not patterns are
+    /// skipped.
+    pub async fn subscribe(
+        &self,
+        patterns: Arc<RwLock<HashMap<Uuid, LearningPattern>>>,
+    ) -> Result<JoinHandle<()>> {
+        let mut messages = self.redis.subscribe(&self.channel).await?;
+        let bridge = self.clone();
+
+        Ok(tokio::spawn(async move {
+            loop {
+                while let Some(payload) = messages.next().await {
+                    bridge.merge_message(&payload, &patterns).await;
+                }
+                warn!("Pattern sync subscription to {} ended", bridge.channel);
+                messages = bridge.resubscribe().await;
+            }
+        }))
+    }
+
+    /// Subscribe to the channel again, retrying with exponential backoff until
+    /// it succeeds
+    async fn resubscribe(&self) -> BoxStream<'static, String> {
+        let mut backoff = RESUBSCRIBE_INITIAL_BACKOFF;
+        loop {
+            tokio::time::sleep(backoff).await;
+            match self.redis.subscribe(&self.channel).await {
+                Ok(messages) => {
+                    info!("Resubscribed to pattern sync channel {}", self.channel);
+                    return messages;
+                }
+                Err(e) => {
+                    warn!(
+                        "Failed to resubscribe to {}, retrying in {:?}: {}",
+                        self.channel, backoff, e
+                    );
+                    backoff = (backoff * 2).min(RESUBSCRIBE_MAX_BACKOFF);
+                }
+            }
+        }
+    }
+
+    /// Merge one published message into `patterns`, unless it is malformed or
+    /// this instance published it
+    async fn merge_message(
+        &self,
+        payload: &str,
+        patterns: &RwLock<HashMap<Uuid, LearningPattern>>,
+    ) {
+        let message: PatternMessage = match serde_json::from_str(payload) {
+            Ok(message) => message,
+            Err(e) => {
+                warn!("Skipping malformed pattern sync message: {}", e);
+                return;
+            }
+        };
+        if message.origin == self.instance_id {
+            return;
+        }
+        debug!(
+            "Received learning pattern {} from instance {}",
+            message.pattern.id, message.origin
+        );
+        patterns
+            .write()
+            .await
+            .insert(message.pattern.id, message.pattern);
+    }
+}
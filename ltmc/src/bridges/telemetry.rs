@@ -0,0 +1,167 @@
+//! LTMC Telemetry Bridge
+//!
+//! This module emits stored learning patterns and tool executions as
+//! OpenTelemetry spans, so they show up in a tracing backend alongside request
+//! spans. It is only compiled with the `ltmc-otel` feature.
+
+use futures::future::BoxFuture;
+use opentelemetry::trace::{Span, Tracer, TracerProvider as _};
+use opentelemetry::KeyValue;
+use opentelemetry_sdk::export::trace::{ExportResult, SpanData, SpanExporter};
+use opentelemetry_sdk::trace::{Tracer as SdkTracer, TracerProvider};
+use std::time::Duration;
+use tracing::info;
+
+use crate::config::LTMCConfig;
+use crate::models::LearningPattern;
+
+/// Instrumentation name of the spans emitted by the bridge
+pub const TELEMETRY_TRACER_NAME: &str = "odincode-ltmc";
+
+/// Span exporter that writes finished spans to the `tracing` log
+///
+/// Used when no tracing backend is set up, so the spans still reach the
+/// application's log output.
+#[derive(Debug, Default)]
+pub struct LogSpanExporter;
+
+impl SpanExporter for LogSpanExporter {
+    fn export(&mut self, batch: Vec<SpanData>) -> BoxFuture<'static, ExportResult> {
+        for span in batch {
+            let duration = span
+                .end_time
+                .duration_since(span.start_time)
+                .unwrap_or_default();
+            let attributes: Vec<String> = span
+                .attributes
+                .iter()
+                .map(|kv| format!("{}={}", kv.key, kv.value))
+                .collect();
+            info!(
+                target: TELEMETRY_TRACER_NAME,
+                "{} finished in {}ms: {}",
+                span.name,
+                duration.as_millis(),
+                attributes.join(" ")
+            );
+        }
+        Box::pin(std::future::ready(Ok(())))
+    }
+}
+
+/// Tracer provider that writes every finished span to the `tracing` log
+pub fn log_tracer_provider() -> TracerProvider {
+    TracerProvider::builder()
+        .with_simple_exporter(LogSpanExporter)
+        .build()
+}
+
+/// Bridge from LTMC events to OpenTelemetry spans
+#[derive(Clone)]
+pub struct TelemetryBridge {
+    /// Tracer the spans are started on
+    tracer: SdkTracer,
+    /// Provider of the tracer, which only holds a weak reference to it
+    _provider: TracerProvider,
+}
+
+impl TelemetryBridge {
+    /// Create a bridge emitting spans through the given tracer provider
+    pub fn new(provider: &TracerProvider) -> Self {
+        Self {
+            tracer: provider.tracer(TELEMETRY_TRACER_NAME),
+            _provider: provider.clone(),
+        }
+    }
+
+    /// Create a bridge if telemetry is enabled in the configuration
+    pub fn from_config(config: &LTMCConfig, provider: &TracerProvider) -> Option<Self> {
+        config.features.telemetry.then(|| Self::new(provider))
+    }
+
+    /// Emit a span for a stored learning pattern
+    pub fn record_pattern(&self, pattern: &LearningPattern) {
+        let mut span = self.tracer.start("ltmc.store_pattern");
+        span.set_attribute(KeyValue::new("ltmc.pattern.id", pattern.id.to_string()));
+        span.set_attribute(KeyValue::new(
+            "ltmc.pattern.type",
+            pattern.pattern_type.as_str(),
+        ));
+        span.set_attribute(KeyValue::new(
+            "ltmc.pattern.confidence",
+            pattern.confidence as f64,
+        ));
+        span.end();
+    }
+
+    /// Emit a span for a finished tool execution
+    pub fn record_tool_execution(&self, tool_name: &str, success: bool, duration: Duration) {
+        let mut span = self.tracer.start("ltmc.tool_execution");
+        span.set_attribute(KeyValue::new("ltmc.tool.name", tool_name.to_string()));
+        span.set_attribute(KeyValue::new("ltmc.tool.success", success));
+        span.set_attribute(KeyValue::new(
+            "ltmc.tool.duration_ms",
+            duration.as_millis() as i64,
+        ));
+        span.end();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manager::LTMManager;
+    use crate::models::PatternType;
+    use opentelemetry::Value;
+    use opentelemetry_sdk::testing::trace::InMemorySpanExporter;
+
+    /// Value of a span attribute by key
+    fn attribute(span: &opentelemetry_sdk::export::trace::SpanData, key: &str) -> Option<Value> {
+        span.attributes
+            .iter()
+            .find(|kv| kv.key.as_str() == key)
+            .map(|kv| kv.value.clone())
+    }
+
+    #[tokio::test]
+    async fn test_storing_pattern_emits_span() {
+        let exporter = InMemorySpanExporter::default();
+        let provider = TracerProvider::builder()
+            .with_simple_exporter(exporter.clone())
+            .build();
+
+        let mut config = LTMCConfig::default();
+        assert!(TelemetryBridge::from_config(&config, &provider).is_none());
+        config.features.telemetry = true;
+
+        let mut manager = LTMManager::new();
+        manager.set_telemetry_bridge(TelemetryBridge::from_config(&config, &provider).unwrap());
+
+        let pattern = LearningPattern {
+            id: uuid::Uuid::new_v4(),
+            pattern_type: PatternType::ErrorSolution,
+            content: "Use tokio::fs in async code".to_string(),
+            context: std::collections::HashMap::new(),
+            created: chrono::Utc::now(),
+            last_accessed: chrono::Utc::now(),
+            access_count: 0,
+            confidence: 0.5,
+            provenance: Default::default(),
+        };
+        manager.store_pattern(pattern).await.unwrap();
+        // The simple processor exports on its own thread
+        provider.force_flush();
+
+        let spans = exporter.get_finished_spans().unwrap();
+        assert_eq!(spans.len(), 1);
+        assert_eq!(spans[0].name, "ltmc.store_pattern");
+        assert_eq!(
+            attribute(&spans[0], "ltmc.pattern.type"),
+            Some(Value::from("error_solution"))
+        );
+        assert_eq!(
+            attribute(&spans[0], "ltmc.pattern.confidence"),
+            Some(Value::F64(0.5))
+        );
+    }
+}
@@ -109,6 +109,19 @@ pub struct FeatureConfig {
 
     /// Enable cross-database coordination
     pub cross_database: bool,
+
+    /// Emit OpenTelemetry spans for patterns and tool executions (requires the
+    /// `ltmc-otel` feature)
+    #[serde(default)]
+    pub telemetry: bool,
+
+    /// Link newly embedded patterns to similar ones in Neo4j
+    #[serde(default = "default_graph_sync")]
+    pub graph_sync: bool,
+}
+
+fn default_graph_sync() -> bool {
+    true
 }
 
 /// Performance configuration
@@ -155,6 +168,8 @@ impl Default for FeatureConfig {
             sequential_thinking: true,
             user_tracking: true,
             cross_database: false,
+            telemetry: false,
+            graph_sync: default_graph_sync(),
         }
     }
 }
@@ -262,6 +277,8 @@ impl LTMCConfigManager {
             "sequential_thinking" => self.config.features.sequential_thinking,
             "user_tracking" => self.config.features.user_tracking,
             "cross_database" => self.config.features.cross_database,
+            "telemetry" => self.config.features.telemetry,
+            "graph_sync" => self.config.features.graph_sync,
             _ => false,
         }
     }
@@ -273,6 +290,8 @@ impl LTMCConfigManager {
             "sequential_thinking" => self.config.features.sequential_thinking = enabled,
             "user_tracking" => self.config.features.user_tracking = enabled,
             "cross_database" => self.config.features.cross_database = enabled,
+            "telemetry" => self.config.features.telemetry = enabled,
+            "graph_sync" => self.config.features.graph_sync = enabled,
             _ => return Err(anyhow!("Unknown feature: {feature}")),
         }
         info!(
@@ -183,6 +183,14 @@ impl FeatureDetector {
                 dependencies: vec!["sqlite".to_string()],
                 category: FeatureCategory::Utility,
             },
+            FeatureInfo {
+                name: "telemetry".to_string(),
+                description: "OpenTelemetry spans for patterns and tool executions".to_string(),
+                available: cfg!(feature = "ltmc-otel"),
+                enabled: false, // Will be set by runtime config
+                dependencies: vec![],
+                category: FeatureCategory::Utility,
+            },
         ];
 
         let capabilities = SystemCapabilities {
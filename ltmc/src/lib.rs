@@ -22,6 +22,8 @@ pub use search::*;
 #[cfg(test)]
 mod tests {
     use super::*;
+    use odincode_databases::{FaissConfig, FaissManager, FaissMetricType};
+    use std::sync::Arc;
     use uuid::Uuid;
 
     #[tokio::test]
@@ -45,6 +47,7 @@ mod tests {
             last_accessed: chrono::Utc::now(),
             access_count: 0,
             confidence: 0.8,
+            provenance: Default::default(),
         };
 
         // Store pattern in memory cache only
@@ -111,4 +114,136 @@ mod tests {
         assert!(session.is_some());
         assert_eq!(session.unwrap().thoughts.len(), 1);
     }
+
+    fn pattern_from(file_path: &str) -> LearningPattern {
+        let mut context = std::collections::HashMap::new();
+        context.insert("file_path".to_string(), file_path.to_string());
+        LearningPattern {
+            id: Uuid::new_v4(),
+            pattern_type: PatternType::CodePattern,
+            content: format!("Pattern learned from {}", file_path),
+            context,
+            created: chrono::Utc::now(),
+            last_accessed: chrono::Utc::now(),
+            access_count: 0,
+            confidence: 0.8,
+            provenance: Default::default(),
+        }
+    }
+
+    async fn manager_with_vector_index() -> (LTMManager, Arc<FaissManager>) {
+        let index = FaissManager::with_config(FaissConfig {
+            index_description: "Flat".to_string(),
+            dimension: 4,
+            metric_type: FaissMetricType::L2,
+            nlist: None,
+            nprobe: None,
+            index_path: None,
+            use_gpu: false,
+            max_vectors: None,
+        })
+        .await
+        .unwrap();
+        let index = Arc::new(index);
+
+        let mut manager = LTMManager::new();
+        manager.set_vector_index(index.clone());
+        (manager, index)
+    }
+
+    #[tokio::test]
+    async fn test_deleted_pattern_is_gone_from_cache_and_vector_index() {
+        let (manager, index) = manager_with_vector_index().await;
+
+        // Storing a pattern embeds it in the vector index
+        let id = manager
+            .store_pattern(pattern_from("src/user.rs"))
+            .await
+            .unwrap();
+        assert!(index
+            .get_embedding(&id.to_string())
+            .await
+            .unwrap()
+            .is_some());
+
+        assert!(manager.delete_pattern(id).await.unwrap());
+        assert!(manager.get_pattern(id).await.unwrap().is_none());
+        assert!(index
+            .get_embedding(&id.to_string())
+            .await
+            .unwrap()
+            .is_none());
+        assert_eq!(index.get_vector_count().await, 0);
+
+        // Deleting again reports that nothing was there
+        assert!(!manager.delete_pattern(id).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_delete_patterns_by_source() {
+        let (manager, index) = manager_with_vector_index().await;
+
+        let mut ids = Vec::new();
+        for file_path in ["src/user.rs", "src/user.rs", "src/other.rs"] {
+            let id = manager
+                .store_pattern(pattern_from(file_path))
+                .await
+                .unwrap();
+            ids.push(id);
+        }
+
+        assert_eq!(
+            manager
+                .delete_patterns_by_source("src/user.rs")
+                .await
+                .unwrap(),
+            2
+        );
+        assert!(manager.get_pattern(ids[0]).await.unwrap().is_none());
+        assert!(manager.get_pattern(ids[1]).await.unwrap().is_none());
+        assert!(manager.get_pattern(ids[2]).await.unwrap().is_some());
+        assert_eq!(
+            index.get_all_embedding_ids().await.unwrap(),
+            vec![ids[2].to_string()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_most_accessed_pattern_ranks_first() {
+        let manager = LTMManager::new();
+        let popular = manager
+            .store_pattern(pattern_from("src/popular.rs"))
+            .await
+            .unwrap();
+        let other = manager
+            .store_pattern(pattern_from("src/other.rs"))
+            .await
+            .unwrap();
+
+        for _ in 0..5 {
+            manager.get_pattern(popular).await.unwrap();
+        }
+        manager.get_pattern(other).await.unwrap();
+
+        // Accesses are batched until a flush
+        assert_eq!(manager.pattern_cache.read().await[&popular].access_count, 0);
+
+        let top = manager.top_patterns(2).await.unwrap();
+        assert_eq!(
+            top.iter().map(|p| p.id).collect::<Vec<_>>(),
+            vec![popular, other]
+        );
+        assert_eq!(top[0].access_count, 5);
+        assert_eq!(top[1].access_count, 1);
+        assert_eq!(manager.top_patterns(1).await.unwrap().len(), 1);
+
+        // Enough pending accesses flush on their own
+        for _ in 0..ACCESS_FLUSH_THRESHOLD {
+            manager.get_pattern(other).await.unwrap();
+        }
+        assert_eq!(
+            manager.pattern_cache.read().await[&other].access_count as usize,
+            ACCESS_FLUSH_THRESHOLD + 1
+        );
+    }
 }
@@ -3,16 +3,36 @@
 //! This module contains the main LTMC manager functionality.
 
 use anyhow::Result;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::time::Duration;
+use tokio::sync::{Mutex, RwLock};
 use tracing::{debug, error, info};
 use uuid::Uuid;
 
-use crate::bridges::MemorySearchBridge;
+#[cfg(feature = "ltmc-redis")]
+use crate::bridges::PatternSyncBridge;
+#[cfg(feature = "ltmc-otel")]
+use crate::bridges::TelemetryBridge;
+use crate::bridges::{GraphSyncBridge, MemorySearchBridge};
 use crate::models::{
-    LearningPattern, PatternType, ReasoningType, SequentialThinkingSession, Thought, ThoughtType,
+    LearningPattern, PatternAccess, PatternType, ReasoningType, SequentialThinkingSession, Thought,
+    ThoughtType,
 };
+use crate::search::text_embedding;
+use odincode_databases::{FaissManager, VectorEmbedding};
+
+/// Number of pending pattern accesses after which they are flushed
+pub const ACCESS_FLUSH_THRESHOLD: usize = 64;
+
+/// Pattern accesses recorded since the last flush
+#[derive(Debug, Default)]
+struct AccessLog {
+    /// Pending accesses by pattern
+    pending: HashMap<Uuid, PatternAccess>,
+    /// Number of accesses recorded since the last flush
+    recorded: usize,
+}
 
 /// Main LTMC (Learning Through Meta-Cognition) manager
 #[derive(Clone)]
@@ -23,6 +43,18 @@ pub struct LTMManager {
     pub session_cache: Arc<RwLock<HashMap<Uuid, SequentialThinkingSession>>>,
     /// Memory search bridge for database operations
     pub memory_search_bridge: Option<MemorySearchBridge>,
+    /// Vector index holding pattern embeddings, keyed by pattern id
+    pub vector_index: Option<Arc<FaissManager>>,
+    /// Bridge linking similar patterns in Neo4j as they are stored
+    pub graph_sync_bridge: Option<GraphSyncBridge>,
+    /// Pattern accesses not yet applied to the cache and databases
+    access_log: Arc<Mutex<AccessLog>>,
+    /// Bridge emitting patterns and tool executions as OpenTelemetry spans
+    #[cfg(feature = "ltmc-otel")]
+    pub telemetry_bridge: Option<TelemetryBridge>,
+    /// Bridge sharing stored patterns with other instances over Redis
+    #[cfg(feature = "ltmc-redis")]
+    pub pattern_sync_bridge: Option<PatternSyncBridge>,
 }
 
 impl Default for LTMManager {
@@ -38,7 +70,95 @@ impl LTMManager {
             pattern_cache: Arc::new(RwLock::new(HashMap::new())),
             session_cache: Arc::new(RwLock::new(HashMap::new())),
             memory_search_bridge: None,
+            vector_index: None,
+            graph_sync_bridge: None,
+            access_log: Arc::new(Mutex::new(AccessLog::default())),
+            #[cfg(feature = "ltmc-otel")]
+            telemetry_bridge: None,
+            #[cfg(feature = "ltmc-redis")]
+            pattern_sync_bridge: None,
+        }
+    }
+
+    /// Set the bridge that emits OpenTelemetry spans
+    #[cfg(feature = "ltmc-otel")]
+    pub fn set_telemetry_bridge(&mut self, bridge: TelemetryBridge) {
+        self.telemetry_bridge = Some(bridge);
+    }
+
+    /// Set the vector index that pattern embeddings are stored in
+    pub fn set_vector_index(&mut self, index: Arc<FaissManager>) {
+        self.vector_index = Some(index);
+    }
+
+    /// Set the bridge that links similar patterns in Neo4j
+    pub fn set_graph_sync_bridge(&mut self, bridge: GraphSyncBridge) {
+        self.graph_sync_bridge = Some(bridge);
+    }
+
+    /// Share stored patterns with other instances through the given bridge
+    ///
+    /// Patterns the other instances publish are merged into the pattern cache
+    /// from then on.
+    #[cfg(feature = "ltmc-redis")]
+    pub async fn start_pattern_sync(&mut self, bridge: PatternSyncBridge) -> Result<()> {
+        bridge.subscribe(self.pattern_cache.clone()).await?;
+        self.pattern_sync_bridge = Some(bridge);
+        Ok(())
+    }
+
+    /// Publish a stored pattern to the other instances in the background
+    ///
+    /// Does nothing without a pattern sync bridge. Failures are logged, never
+    /// returned, so storing a pattern does not wait on Redis.
+    #[cfg(feature = "ltmc-redis")]
+    fn spawn_pattern_publish(&self, pattern: &LearningPattern) {
+        let Some(bridge) = &self.pattern_sync_bridge else {
+            return;
+        };
+        let bridge = bridge.clone();
+        let pattern = pattern.clone();
+
+        tokio::spawn(async move {
+            if let Err(e) = bridge.publish(&pattern).await {
+                error!("Failed to publish learning pattern {}: {}", pattern.id, e);
+            }
+        });
+    }
+
+    /// Link a pattern to similar patterns in the background
+    ///
+    /// Does nothing without a graph sync bridge and a vector index. Failures are
+    /// logged, never returned, so storing a pattern does not wait on Neo4j.
+    fn spawn_graph_sync(&self, id: Uuid) {
+        let (Some(bridge), Some(index)) = (&self.graph_sync_bridge, &self.vector_index) else {
+            return;
+        };
+        let (bridge, index) = (bridge.clone(), index.clone());
+        let patterns = self.pattern_cache.clone();
+
+        tokio::spawn(async move {
+            if let Err(e) = bridge.sync_pattern(id, &index, &patterns).await {
+                error!("Failed to link similar patterns for {}: {}", id, e);
+            }
+        });
+    }
+
+    /// Record a finished tool execution
+    ///
+    /// Only emits a span when a telemetry bridge is set; otherwise does nothing.
+    pub fn record_tool_execution(&self, tool_name: &str, success: bool, duration: Duration) {
+        #[cfg(feature = "ltmc-otel")]
+        if let Some(bridge) = &self.telemetry_bridge {
+            bridge.record_tool_execution(tool_name, success, duration);
         }
+
+        debug!(
+            "Tool {} finished in {}ms, success: {}",
+            tool_name,
+            duration.as_millis(),
+            success
+        );
     }
 
     /// Initialize database connections
@@ -59,14 +179,28 @@ impl LTMManager {
     }
 
     /// Store a learning pattern
+    ///
+    /// With a vector index set, the pattern's content is embedded in it too.
     pub async fn store_pattern(&self, pattern: LearningPattern) -> Result<Uuid> {
         let id = pattern.id;
+        let vector = self
+            .vector_index
+            .as_ref()
+            .map(|index| text_embedding(&pattern.content, index.dimension()));
 
         // Store in cache
         let mut cache = self.pattern_cache.write().await;
         cache.insert(id, pattern.clone());
         drop(cache);
 
+        #[cfg(feature = "ltmc-otel")]
+        if let Some(bridge) = &self.telemetry_bridge {
+            bridge.record_pattern(&pattern);
+        }
+
+        #[cfg(feature = "ltmc-redis")]
+        self.spawn_pattern_publish(&pattern);
+
         // Store in databases using the bridge if available
         if let Some(bridge) = &self.memory_search_bridge {
             if bridge.is_initialized() {
@@ -83,28 +217,125 @@ impl LTMManager {
             }
         }
 
+        // Storing the embedding links the pattern to similar ones
+        match vector {
+            Some(vector) => {
+                if let Err(e) = self.store_pattern_embedding(id, vector).await {
+                    error!("Failed to embed learning pattern: {} - {}", id, e);
+                }
+            }
+            None => self.spawn_graph_sync(id),
+        }
+
         debug!("Stored learning pattern: {}", id);
         Ok(id)
     }
 
+    /// Store the embedding of a learning pattern in the vector index
+    pub async fn store_pattern_embedding(&self, id: Uuid, vector: Vec<f32>) -> Result<()> {
+        let index = self
+            .vector_index
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("No vector index configured"))?;
+
+        let mut metadata = HashMap::new();
+        metadata.insert("pattern_id".to_string(), id.to_string());
+
+        index
+            .add_embedding(VectorEmbedding {
+                id: id.to_string(),
+                vector,
+                metadata,
+                created_at: chrono::Utc::now(),
+                updated_at: chrono::Utc::now(),
+            })
+            .await?;
+
+        self.spawn_graph_sync(id);
+
+        debug!("Stored embedding for learning pattern: {}", id);
+        Ok(())
+    }
+
+    /// Delete a learning pattern from every store
+    ///
+    /// Removes the pattern from the cache, the vector index and, when the database
+    /// bridge is initialized, from SQLite, FAISS, Neo4j and Redis. The local copies
+    /// are removed first; a database failure is returned so the deletion can be
+    /// retried. Returns whether the pattern existed anywhere.
+    pub async fn delete_pattern(&self, id: Uuid) -> Result<bool> {
+        let locally = self.delete_local_pattern(id).await?;
+
+        let in_databases = match &self.memory_search_bridge {
+            Some(bridge) if bridge.is_initialized() => bridge.delete_pattern_atomically(id).await?,
+            _ => false,
+        };
+
+        let existed = locally || in_databases;
+        debug!("Deleted learning pattern: {} (existed: {})", id, existed);
+        Ok(existed)
+    }
+
+    /// Delete a learning pattern from the cache and the vector index, returning
+    /// whether either held it
+    async fn delete_local_pattern(&self, id: Uuid) -> Result<bool> {
+        let in_cache = self.pattern_cache.write().await.remove(&id).is_some();
+
+        let in_index = match &self.vector_index {
+            Some(index) => index.remove_embedding(&id.to_string()).await?,
+            None => false,
+        };
+
+        Ok(in_cache || in_index)
+    }
+
+    /// Delete every learning pattern from a source, in every store
+    ///
+    /// A pattern's source is its `file_path` context entry, which is stored as the
+    /// SQLite `source` column. Patterns the bridge deleted by source only need
+    /// their local copies removed; cached patterns it did not know of are
+    /// deleted everywhere. Returns how many patterns were deleted.
+    pub async fn delete_patterns_by_source(&self, source: &str) -> Result<usize> {
+        let mut ids: HashSet<Uuid> = self
+            .pattern_cache
+            .read()
+            .await
+            .values()
+            .filter(|pattern| pattern.source() == Some(source))
+            .map(|pattern| pattern.id)
+            .collect();
+
+        let mut deleted_from_databases = HashSet::new();
+        if let Some(bridge) = &self.memory_search_bridge {
+            if bridge.is_initialized() {
+                deleted_from_databases.extend(bridge.delete_patterns_by_source(source).await?);
+            }
+        }
+        ids.extend(deleted_from_databases.iter().copied());
+
+        for id in &ids {
+            if deleted_from_databases.contains(id) {
+                self.delete_local_pattern(*id).await?;
+            } else {
+                self.delete_pattern(*id).await?;
+            }
+        }
+
+        info!(
+            "Deleted {} learning patterns from source {}",
+            ids.len(),
+            source
+        );
+        Ok(ids.len())
+    }
+
     /// Retrieve a learning pattern by ID
     pub async fn get_pattern(&self, id: Uuid) -> Result<Option<LearningPattern>> {
         // Check cache first
-        {
-            let cache = self.pattern_cache.read().await;
-            if let Some(pattern) = cache.get(&id) {
-                // Update access statistics
-                let mut mutable_pattern = pattern.clone();
-                mutable_pattern.last_accessed = chrono::Utc::now();
-                mutable_pattern.access_count += 1;
-
-                // Update cache with new stats
-                let mut cache_write = self.pattern_cache.write().await;
-                cache_write.insert(id, mutable_pattern);
-                drop(cache_write);
-
-                return Ok(Some(pattern.clone()));
-            }
+        let cached = self.pattern_cache.read().await.get(&id).cloned();
+        if let Some(pattern) = cached {
+            self.record_accesses([id]).await;
+            return Ok(Some(pattern));
         }
 
         // Try to get from bridge cache if available
@@ -117,6 +348,7 @@ impl LTMManager {
                         cache.insert(id, pattern.clone());
                         drop(cache);
 
+                        self.record_accesses([id]).await;
                         return Ok(Some(pattern));
                     }
                     Ok(None) => {
@@ -149,6 +381,7 @@ impl LTMManager {
                 {
                     Ok(results) => {
                         debug!("Retrieved {} patterns from hybrid search", results.len());
+                        self.record_accesses(results.iter().map(|p| p.id)).await;
                         return Ok(results);
                     }
                     Err(e) => {
@@ -178,10 +411,76 @@ impl LTMManager {
                 results.push(pattern.clone());
             }
         }
+        drop(cache);
 
+        self.record_accesses(results.iter().map(|p| p.id)).await;
         Ok(results)
     }
 
+    /// Find error-solution patterns similar to the given error text
+    ///
+    /// Uses FAISS vector search over stored error solutions when the database bridge
+    /// is available, and falls back to word-overlap text search over cached patterns
+    /// when no embeddings are available.
+    pub async fn find_similar_error_solutions(
+        &self,
+        error_text: &str,
+        k: usize,
+    ) -> Result<Vec<LearningPattern>> {
+        if let Some(bridge) = &self.memory_search_bridge {
+            if bridge.is_initialized() {
+                match bridge.search_patterns_semantic(error_text, k * 4).await {
+                    Ok(results) => {
+                        let solutions: Vec<LearningPattern> = results
+                            .into_iter()
+                            .filter(|p| p.pattern_type == PatternType::ErrorSolution)
+                            .take(k)
+                            .collect();
+                        if !solutions.is_empty() {
+                            debug!(
+                                "Found {} similar error solutions via vector search",
+                                solutions.len()
+                            );
+                            self.record_accesses(solutions.iter().map(|p| p.id)).await;
+                            return Ok(solutions);
+                        }
+                    }
+                    Err(e) => {
+                        error!("Vector search for similar errors failed: {}", e);
+                        // Fall back to text search
+                    }
+                }
+            }
+        }
+
+        // Fall back to text search over cached error solutions
+        let query_words = error_words(error_text);
+        let cache = self.pattern_cache.read().await;
+        let mut scored: Vec<(f32, LearningPattern)> = cache
+            .values()
+            .filter(|p| p.pattern_type == PatternType::ErrorSolution)
+            .filter_map(|p| {
+                let score = word_overlap(&query_words, &error_words(&p.content));
+                (score > 0.0).then(|| (score, p.clone()))
+            })
+            .collect();
+        drop(cache);
+
+        scored.sort_by(|a, b| {
+            b.0.partial_cmp(&a.0)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| {
+                    b.1.confidence
+                        .partial_cmp(&a.1.confidence)
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                })
+        });
+
+        let solutions: Vec<LearningPattern> = scored.into_iter().take(k).map(|(_, p)| p).collect();
+        self.record_accesses(solutions.iter().map(|p| p.id)).await;
+        Ok(solutions)
+    }
+
     /// Start a new sequential thinking session
     pub async fn start_sequential_thinking_session(
         &self,
@@ -281,7 +580,148 @@ impl LTMManager {
             .filter(|pattern| pattern.pattern_type == pattern_type)
             .cloned()
             .collect();
+        drop(cache);
 
+        self.record_accesses(results.iter().map(|p| p.id)).await;
         Ok(results)
     }
+
+    /// Record that patterns were returned to a caller
+    ///
+    /// Accesses are batched rather than written per read: they reach the cache and
+    /// the databases on the next flush, which happens on its own once
+    /// `ACCESS_FLUSH_THRESHOLD` accesses are pending.
+    async fn record_accesses(&self, ids: impl IntoIterator<Item = Uuid>) {
+        let now = chrono::Utc::now();
+        let should_flush = {
+            let mut guard = self.access_log.lock().await;
+            let log = &mut *guard;
+            for id in ids {
+                let access = log.pending.entry(id).or_insert(PatternAccess {
+                    pattern_id: id,
+                    count: 0,
+                    last_accessed: now,
+                });
+                access.count += 1;
+                access.last_accessed = now;
+                log.recorded += 1;
+            }
+            log.recorded >= ACCESS_FLUSH_THRESHOLD
+        };
+
+        if should_flush {
+            if let Err(e) = self.flush_access_stats().await {
+                error!("Failed to flush pattern access statistics: {}", e);
+            }
+        }
+    }
+
+    /// Apply pending pattern accesses, through the bridge, to the databases and
+    /// then to the cache
+    ///
+    /// If the databases cannot be updated, the accesses stay pending for the
+    /// next flush. Returns how many patterns had pending accesses.
+    pub async fn flush_access_stats(&self) -> Result<usize> {
+        let accesses: Vec<PatternAccess> = {
+            let mut log = self.access_log.lock().await;
+            log.recorded = 0;
+            log.pending.drain().map(|(_, access)| access).collect()
+        };
+        if accesses.is_empty() {
+            return Ok(0);
+        }
+
+        if let Some(bridge) = &self.memory_search_bridge {
+            if bridge.is_initialized() {
+                if let Err(e) = bridge.update_pattern_access_stats(&accesses).await {
+                    self.requeue_accesses(accesses).await;
+                    return Err(e);
+                }
+            }
+        }
+
+        {
+            let mut cache = self.pattern_cache.write().await;
+            for access in &accesses {
+                if let Some(pattern) = cache.get_mut(&access.pattern_id) {
+                    pattern.access_count = pattern.access_count.saturating_add(access.count);
+                    pattern.last_accessed = pattern.last_accessed.max(access.last_accessed);
+                }
+            }
+        }
+
+        debug!("Flushed access statistics for {} patterns", accesses.len());
+        Ok(accesses.len())
+    }
+
+    /// Put accesses that could not be flushed back into the pending log, merged
+    /// with any recorded since
+    async fn requeue_accesses(&self, accesses: Vec<PatternAccess>) {
+        let mut guard = self.access_log.lock().await;
+        let log = &mut *guard;
+        for access in accesses {
+            log.recorded += access.count as usize;
+            log.pending
+                .entry(access.pattern_id)
+                .and_modify(|pending| {
+                    pending.count = pending.count.saturating_add(access.count);
+                    pending.last_accessed = pending.last_accessed.max(access.last_accessed);
+                })
+                .or_insert(access);
+        }
+    }
+
+    /// The `n` most accessed patterns, most accessed first
+    ///
+    /// Pending accesses are flushed first so the ranking is current, and the
+    /// most accessed patterns in the databases are ranked with the cached ones,
+    /// so rankings survive a restart. Ties go to the most recently accessed
+    /// pattern.
+    pub async fn top_patterns(&self, n: usize) -> Result<Vec<LearningPattern>> {
+        self.flush_access_stats().await?;
+
+        let mut patterns: HashMap<Uuid, LearningPattern> = self.pattern_cache.read().await.clone();
+        if let Some(bridge) = &self.memory_search_bridge {
+            if bridge.is_initialized() {
+                for stored in bridge.most_accessed_patterns(n).await? {
+                    match patterns.get_mut(&stored.id) {
+                        Some(cached) => {
+                            cached.access_count = cached.access_count.max(stored.access_count);
+                            cached.last_accessed = cached.last_accessed.max(stored.last_accessed);
+                        }
+                        None => {
+                            patterns.insert(stored.id, stored);
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut patterns: Vec<LearningPattern> = patterns.into_values().collect();
+        patterns.sort_by(|a, b| {
+            b.access_count
+                .cmp(&a.access_count)
+                .then_with(|| b.last_accessed.cmp(&a.last_accessed))
+        });
+        patterns.truncate(n);
+
+        Ok(patterns)
+    }
+}
+
+/// Split error text into lowercase words, ignoring punctuation and very short tokens
+fn error_words(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric() && c != '_')
+        .filter(|w| w.len() > 2)
+        .map(|w| w.to_lowercase())
+        .collect()
+}
+
+/// Jaccard similarity between two word sets
+fn word_overlap(a: &HashSet<String>, b: &HashSet<String>) -> f32 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(b).count() as f32 / union as f32
 }
@@ -0,0 +1,306 @@
+//! LTMC Models Module
+//!
+//! This module contains the data models used by the LTMC system: learning patterns
+//! and sequential thinking sessions.
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use uuid::Uuid;
+
+/// A learned pattern stored in the LTMC system
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LearningPattern {
+    /// Unique identifier
+    pub id: Uuid,
+    /// Type of the pattern
+    pub pattern_type: PatternType,
+    /// Pattern content
+    pub content: String,
+    /// Context in which the pattern was learned
+    pub context: HashMap<String, String>,
+    /// Creation timestamp
+    pub created: DateTime<Utc>,
+    /// Last access timestamp
+    pub last_accessed: DateTime<Utc>,
+    /// Number of times the pattern has been accessed
+    pub access_count: u32,
+    /// Confidence level in the pattern
+    pub confidence: f32,
+    /// Where the pattern came from
+    #[serde(default)]
+    pub provenance: PatternProvenance,
+}
+
+impl LearningPattern {
+    /// Source the pattern was learned from: its `file_path` context entry
+    pub fn source(&self) -> Option<&str> {
+        self.context.get("file_path").map(String::as_str)
+    }
+}
+
+/// Accesses to a learning pattern that have not been written back yet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatternAccess {
+    /// Pattern that was accessed
+    pub pattern_id: Uuid,
+    /// Number of accesses
+    pub count: u32,
+    /// Time of the latest access
+    pub last_accessed: DateTime<Utc>,
+}
+
+/// Origin of a learning pattern: what created it, for which file and session
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PatternProvenance {
+    /// Agent or tool that created the pattern
+    pub created_by: Option<PatternCreator>,
+    /// File the pattern was learned from
+    pub file_id: Option<Uuid>,
+    /// Learning session the pattern was recorded in
+    pub session_id: Option<Uuid>,
+}
+
+impl PatternProvenance {
+    /// Provenance of a pattern recorded by an agent
+    pub fn agent(name: impl Into<String>, file_id: Option<Uuid>, session_id: Option<Uuid>) -> Self {
+        Self {
+            created_by: Some(PatternCreator::Agent(name.into())),
+            file_id,
+            session_id,
+        }
+    }
+
+    /// Provenance of a pattern recorded by a tool run
+    pub fn tool(name: impl Into<String>, file_id: Option<Uuid>) -> Self {
+        Self {
+            created_by: Some(PatternCreator::Tool(name.into())),
+            file_id,
+            session_id: None,
+        }
+    }
+}
+
+/// Creator of a learning pattern
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "name", rename_all = "snake_case")]
+pub enum PatternCreator {
+    /// An agent, by agent type
+    Agent(String),
+    /// A tool, by tool name
+    Tool(String),
+}
+
+impl PatternCreator {
+    /// Name of the agent or tool
+    pub fn name(&self) -> &str {
+        match self {
+            PatternCreator::Agent(name) | PatternCreator::Tool(name) => name,
+        }
+    }
+}
+
+impl fmt::Display for PatternCreator {
+    /// Formats as `agent:<name>` or `tool:<name>`, the form stored in SQLite
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PatternCreator::Agent(name) => write!(f, "agent:{}", name),
+            PatternCreator::Tool(name) => write!(f, "tool:{}", name),
+        }
+    }
+}
+
+impl FromStr for PatternCreator {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.split_once(':') {
+            Some(("agent", name)) if !name.is_empty() => {
+                Ok(PatternCreator::Agent(name.to_string()))
+            }
+            Some(("tool", name)) if !name.is_empty() => Ok(PatternCreator::Tool(name.to_string())),
+            _ => Err(anyhow!("Unknown pattern creator: {}", s)),
+        }
+    }
+}
+
+/// Type of learning pattern
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PatternType {
+    /// Architectural decision
+    ArchitecturalDecision,
+    /// Code pattern or idiom
+    CodePattern,
+    /// Research finding
+    ResearchFinding,
+    /// Performance data
+    PerformanceData,
+    /// Solution to an error
+    ErrorSolution,
+    /// User interaction
+    UserInteraction,
+    /// Sequential thinking session data
+    SequentialThinking,
+    /// Model training data
+    ModelTraining,
+}
+
+impl PatternType {
+    /// All pattern types, in declaration order
+    pub const ALL: [PatternType; 8] = [
+        PatternType::ArchitecturalDecision,
+        PatternType::CodePattern,
+        PatternType::ResearchFinding,
+        PatternType::PerformanceData,
+        PatternType::ErrorSolution,
+        PatternType::UserInteraction,
+        PatternType::SequentialThinking,
+        PatternType::ModelTraining,
+    ];
+
+    /// Canonical snake_case name of the pattern type
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PatternType::ArchitecturalDecision => "architectural_decision",
+            PatternType::CodePattern => "code_pattern",
+            PatternType::ResearchFinding => "research_finding",
+            PatternType::PerformanceData => "performance_data",
+            PatternType::ErrorSolution => "error_solution",
+            PatternType::UserInteraction => "user_interaction",
+            PatternType::SequentialThinking => "sequential_thinking",
+            PatternType::ModelTraining => "model_training",
+        }
+    }
+}
+
+impl fmt::Display for PatternType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for PatternType {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let normalized = s.trim().to_lowercase();
+        PatternType::ALL
+            .iter()
+            .find(|pattern_type| pattern_type.as_str() == normalized)
+            .cloned()
+            .ok_or_else(|| anyhow!("Unknown pattern type: {}", s))
+    }
+}
+
+/// A sequential thinking session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SequentialThinkingSession {
+    /// Unique identifier
+    pub id: Uuid,
+    /// Context the session reasons about
+    pub context: String,
+    /// Type of reasoning used
+    pub reasoning_type: ReasoningType,
+    /// Thoughts recorded so far, in order
+    pub thoughts: Vec<Thought>,
+    /// Creation timestamp
+    pub created: DateTime<Utc>,
+    /// Completion timestamp
+    pub completed: Option<DateTime<Utc>>,
+    /// Summary written on completion
+    pub summary: Option<String>,
+}
+
+/// Type of reasoning in a sequential thinking session
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ReasoningType {
+    /// Step-by-step reasoning
+    Sequential,
+    /// Reasoning aimed at solving a concrete problem
+    ProblemSolving,
+}
+
+/// A single thought in a sequential thinking session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Thought {
+    /// Unique identifier
+    pub id: Uuid,
+    /// Previous thought in the chain
+    pub previous_thought_id: Option<Uuid>,
+    /// Thought content
+    pub content: String,
+    /// Type of thought
+    pub thought_type: ThoughtType,
+    /// Creation timestamp
+    pub created: DateTime<Utc>,
+    /// Additional metadata
+    pub metadata: HashMap<String, String>,
+}
+
+/// Type of a thought
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ThoughtType {
+    /// First thought of a session
+    Initial,
+    /// Analysis step
+    Analysis,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pattern_type_round_trip() {
+        for pattern_type in PatternType::ALL.iter() {
+            let name = pattern_type.to_string();
+            let parsed: PatternType = name.parse().unwrap();
+            assert_eq!(&parsed, pattern_type);
+        }
+    }
+
+    #[test]
+    fn test_pattern_type_parse() {
+        assert_eq!(
+            "error_solution".parse::<PatternType>().unwrap(),
+            PatternType::ErrorSolution
+        );
+        assert_eq!(
+            "Code_Pattern".parse::<PatternType>().unwrap(),
+            PatternType::CodePattern
+        );
+        assert!("not_a_pattern".parse::<PatternType>().is_err());
+    }
+
+    #[test]
+    fn test_patterns_without_provenance_still_deserialize() {
+        let json = serde_json::json!({
+            "id": Uuid::new_v4(),
+            "pattern_type": "CodePattern",
+            "content": "use ? over unwrap",
+            "context": {},
+            "created": Utc::now(),
+            "last_accessed": Utc::now(),
+            "access_count": 0,
+            "confidence": 0.5,
+        });
+        let pattern: LearningPattern = serde_json::from_value(json).unwrap();
+        assert_eq!(pattern.provenance, PatternProvenance::default());
+
+        let file_id = Uuid::new_v4();
+        let provenance = PatternProvenance::tool("clippy", Some(file_id));
+        let round_trip: PatternProvenance =
+            serde_json::from_value(serde_json::to_value(&provenance).unwrap()).unwrap();
+        assert_eq!(round_trip, provenance);
+        let creator = round_trip.created_by.unwrap();
+        assert_eq!(creator.name(), "clippy");
+        assert_eq!(
+            creator.to_string().parse::<PatternCreator>().unwrap(),
+            creator
+        );
+        assert!("linter".parse::<PatternCreator>().is_err());
+    }
+}
@@ -0,0 +1,52 @@
+//! LTMC Text Embeddings
+//!
+//! Hash-based embeddings shared by pattern storage and semantic search, so that
+//! stored patterns and search queries land in the same vector space.
+
+/// Embed text as normalized word counts hashed into `dimension` buckets
+pub fn text_embedding(text: &str, dimension: usize) -> Vec<f32> {
+    // In a real implementation, this would use an embedding model like BERT, OpenAI embeddings, etc.
+    let mut embedding = vec![0.0; dimension];
+    if dimension == 0 {
+        return embedding;
+    }
+
+    for word in text.split_whitespace() {
+        embedding[text_hash(word) as usize % dimension] += 1.0;
+    }
+
+    // Normalize the embedding
+    let norm: f32 = embedding.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        for val in &mut embedding {
+            *val /= norm;
+        }
+    }
+
+    embedding
+}
+
+/// Simple hash function for text
+pub(crate) fn text_hash(text: &str) -> u32 {
+    let mut hash: u32 = 5381;
+    for byte in text.as_bytes() {
+        hash = hash.wrapping_mul(33).wrapping_add(*byte as u32);
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_text_embedding_is_normalized_and_stable() {
+        let embedding = text_embedding("fn main fn", 16);
+        assert_eq!(embedding.len(), 16);
+
+        let norm: f32 = embedding.iter().map(|x| x * x).sum::<f32>().sqrt();
+        assert!((norm - 1.0).abs() < 1e-6);
+        assert_eq!(embedding, text_embedding("fn main fn", 16));
+        assert!(text_embedding("", 16).iter().all(|x| *x == 0.0));
+    }
+}
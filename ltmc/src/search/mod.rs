@@ -4,11 +4,13 @@
 
 pub mod by_type;
 pub mod core;
+pub mod embedding;
 pub mod examples;
 pub mod ltmc;
 pub mod rust;
 
 pub use by_type::SearchByTypeUtils;
 pub use core::CoreSearchUtils;
+pub use embedding::text_embedding;
 pub use ltmc::LtmcStructureSearchUtils;
 pub use rust::RustSearchUtils;
@@ -0,0 +1,57 @@
+//! Similar Error Solution Integration Tests
+//!
+//! These tests exercise the error-solution lookup against the full database stack
+//! and require SQLite, Neo4j, Redis and FAISS to be available.
+
+use chrono::Utc;
+use odincode_databases::DatabaseManager;
+use odincode_ltmc::{LTMManager, LearningPattern, PatternType};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+fn error_solution(content: &str) -> LearningPattern {
+    LearningPattern {
+        id: Uuid::new_v4(),
+        pattern_type: PatternType::ErrorSolution,
+        content: content.to_string(),
+        context: HashMap::new(),
+        created: Utc::now(),
+        last_accessed: Utc::now(),
+        access_count: 0,
+        confidence: 0.9,
+        provenance: Default::default(),
+    }
+}
+
+#[tokio::test]
+#[ignore] // Integration test requiring the LTMC databases
+async fn test_find_similar_error_solutions() {
+    let mut manager = LTMManager::new();
+    manager
+        .initialize(DatabaseManager::new())
+        .await
+        .expect("LTMC databases must be running for this test");
+
+    let borrow_fix = error_solution(
+        "error[E0502]: cannot borrow `items` as mutable because it is also borrowed as immutable. \
+         Fix: clone the value before the mutable borrow or limit the scope of the immutable borrow",
+    );
+    let import_fix = error_solution(
+        "error[E0433]: failed to resolve: use of undeclared crate or module `serde_json`. \
+         Fix: add serde_json to Cargo.toml dependencies",
+    );
+    manager.store_pattern(borrow_fix.clone()).await.unwrap();
+    manager.store_pattern(import_fix).await.unwrap();
+
+    let results = manager
+        .find_similar_error_solutions(
+            "cannot borrow `list` as mutable since it is already borrowed as immutable",
+            1,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].id, borrow_fix.id);
+    assert_eq!(results[0].pattern_type, PatternType::ErrorSolution);
+}
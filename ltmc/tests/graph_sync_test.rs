@@ -0,0 +1,96 @@
+//! Graph Sync Integration Tests
+//!
+//! These tests check that storing similar patterns links them in Neo4j and
+//! require a Neo4j server to be available.
+
+use chrono::Utc;
+use odincode_databases::{
+    FaissConfig, FaissManager, FaissMetricType, Neo4jManager, RelationshipType,
+};
+use odincode_ltmc::{GraphSyncBridge, GraphSyncConfig, LTMManager, LearningPattern, PatternType};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+fn code_pattern(content: &str) -> LearningPattern {
+    LearningPattern {
+        id: Uuid::new_v4(),
+        pattern_type: PatternType::CodePattern,
+        content: content.to_string(),
+        context: HashMap::new(),
+        created: Utc::now(),
+        last_accessed: Utc::now(),
+        access_count: 0,
+        confidence: 0.9,
+        provenance: Default::default(),
+    }
+}
+
+#[tokio::test]
+#[ignore] // Integration test requiring Neo4j
+async fn test_similar_patterns_are_linked_in_graph() {
+    let graph = Arc::new(
+        Neo4jManager::new()
+            .await
+            .expect("Neo4j must be running for this test"),
+    );
+    let index = FaissManager::with_config(FaissConfig {
+        index_description: "Flat".to_string(),
+        dimension: 4,
+        metric_type: FaissMetricType::L2,
+        nlist: None,
+        nprobe: None,
+        index_path: None,
+        use_gpu: false,
+        max_vectors: None,
+    })
+    .await
+    .unwrap();
+
+    let mut manager = LTMManager::new();
+    manager.set_vector_index(Arc::new(index));
+    manager.set_graph_sync_bridge(GraphSyncBridge::new(
+        graph.clone(),
+        GraphSyncConfig::default(),
+    ));
+
+    let first = code_pattern("Prefer iterators over index loops");
+    let second = code_pattern("Prefer iterator adaptors over manual index loops");
+    for (pattern, vector) in [
+        (&first, vec![1.0, 0.0, 0.0, 0.0]),
+        (&second, vec![1.0, 0.1, 0.0, 0.0]),
+    ] {
+        manager.store_pattern(pattern.clone()).await.unwrap();
+        manager
+            .store_pattern_embedding(pattern.id, vector)
+            .await
+            .unwrap();
+    }
+
+    // Linking happens in the background, so poll for the relationship
+    let mut linked = false;
+    for _ in 0..50 {
+        let relationships = graph
+            .get_pattern_relationships(&second.id.to_string())
+            .await
+            .unwrap();
+        linked = relationships.iter().any(|r| {
+            r.relationship_type == RelationshipType::SimilarTo
+                && (r.source_pattern_id == first.id.to_string()
+                    || r.target_pattern_id == first.id.to_string())
+        });
+        if linked {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    for pattern in [&first, &second] {
+        graph
+            .delete_learning_pattern_node(&pattern.id.to_string())
+            .await
+            .unwrap();
+    }
+    assert!(linked, "similar patterns were not linked in Neo4j");
+}
@@ -0,0 +1,65 @@
+//! Pattern Sync Integration Tests
+//!
+//! These tests check that a pattern stored by one manager reaches another
+//! through Redis pub/sub and require a Redis server to be available.
+
+#![cfg(feature = "ltmc-redis")]
+
+use chrono::Utc;
+use odincode_databases::RedisManager;
+use odincode_ltmc::{LTMManager, LearningPattern, PatternSyncBridge, PatternType};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+async fn synced_manager(channel: &str) -> LTMManager {
+    let redis = RedisManager::from_connection_string("redis://localhost:6379").unwrap();
+    redis
+        .initialize()
+        .await
+        .expect("Redis must be running for this test");
+
+    let mut manager = LTMManager::new();
+    manager
+        .start_pattern_sync(PatternSyncBridge::with_channel(Arc::new(redis), channel))
+        .await
+        .unwrap();
+    manager
+}
+
+#[tokio::test]
+#[ignore] // Integration test requiring Redis
+async fn test_stored_pattern_reaches_other_instance() {
+    // A channel of its own, so concurrent runs do not see each other's patterns
+    let channel = format!("ltmc:patterns:test:{}", Uuid::new_v4());
+    let first = synced_manager(&channel).await;
+    let second = synced_manager(&channel).await;
+
+    let pattern = LearningPattern {
+        id: Uuid::new_v4(),
+        pattern_type: PatternType::CodePattern,
+        content: "Prefer iterators over index loops".to_string(),
+        context: HashMap::new(),
+        created: Utc::now(),
+        last_accessed: Utc::now(),
+        access_count: 0,
+        confidence: 0.9,
+        provenance: Default::default(),
+    };
+    first.store_pattern(pattern.clone()).await.unwrap();
+
+    // Publishing happens in the background, so poll for the pattern
+    let mut received = None;
+    for _ in 0..50 {
+        received = second.pattern_cache.read().await.get(&pattern.id).cloned();
+        if received.is_some() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    let received = received.expect("pattern did not reach the other instance");
+    assert_eq!(received.content, pattern.content);
+    assert_eq!(first.pattern_cache.read().await.len(), 1);
+}
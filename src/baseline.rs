@@ -0,0 +1,219 @@
+//! Check Baseline
+//!
+//! A baseline records the issues a codebase already has, so `check --baseline`
+//! only fails on issues that are new. Issues are matched by file, rule and a
+//! fingerprint of the text of their line instead of the line number, so issues
+//! on lines that merely moved up or down stay matched.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+use odincode_core::CodeIssue;
+
+/// Version of the baseline file format
+pub const BASELINE_VERSION: u32 = 1;
+
+/// One grandfathered issue
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BaselineEntry {
+    /// Path of the file, relative to the checked root
+    pub path: String,
+    /// Rule id of the issue, or its description if it has none
+    pub rule: String,
+    /// Fingerprint of the text of the issue's line
+    pub fingerprint: String,
+    /// Line the issue was on when the baseline was written, for reference only
+    pub line_number: usize,
+}
+
+/// Issues that existed when the baseline was written
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Baseline {
+    /// File format version
+    pub version: u32,
+    /// The grandfathered issues
+    pub entries: Vec<BaselineEntry>,
+}
+
+impl Default for Baseline {
+    fn default() -> Self {
+        Self {
+            version: BASELINE_VERSION,
+            entries: Vec::new(),
+        }
+    }
+}
+
+impl Baseline {
+    /// Read a baseline file
+    pub fn load(path: &Path) -> Result<Self> {
+        let json = std::fs::read_to_string(path)
+            .with_context(|| format!("Cannot read baseline {}", path.display()))?;
+        serde_json::from_str(&json).with_context(|| format!("Invalid baseline {}", path.display()))
+    }
+
+    /// Write the baseline file
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, json + "\n")
+            .with_context(|| format!("Cannot write baseline {}", path.display()))
+    }
+
+    /// Record the issues of a file with the given content
+    pub fn add(&mut self, path: &str, content: &str, issues: &[CodeIssue]) {
+        self.entries
+            .extend(issues.iter().map(|issue| BaselineEntry {
+                path: path.to_string(),
+                rule: rule_key(issue).to_string(),
+                fingerprint: fingerprint(content, issue.line_number),
+                line_number: issue.line_number,
+            }));
+    }
+
+    /// Issues of a file that are not in the baseline
+    ///
+    /// Each baseline entry matches at most one issue, so a second copy of a
+    /// grandfathered issue is reported as new.
+    pub fn new_issues(&self, path: &str, content: &str, issues: Vec<CodeIssue>) -> Vec<CodeIssue> {
+        let mut remaining: HashMap<(String, String), usize> = HashMap::new();
+        for entry in self.entries.iter().filter(|entry| entry.path == path) {
+            *remaining
+                .entry((entry.rule.clone(), entry.fingerprint.clone()))
+                .or_default() += 1;
+        }
+
+        issues
+            .into_iter()
+            .filter(|issue| {
+                let key = (
+                    rule_key(issue).to_string(),
+                    fingerprint(content, issue.line_number),
+                );
+                match remaining.get_mut(&key) {
+                    Some(count) if *count > 0 => {
+                        *count -= 1;
+                        false
+                    }
+                    _ => true,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Rule an issue is matched by
+fn rule_key(issue: &CodeIssue) -> &str {
+    issue.rule_id.as_deref().unwrap_or(&issue.description)
+}
+
+/// Fingerprint of the 1-based line of `content`, ignoring indentation and
+/// whitespace changes
+///
+/// This is a 64-bit FNV-1a hash, which is stable across builds unlike the
+/// standard library's hasher.
+fn fingerprint(content: &str, line_number: usize) -> String {
+    let line = line_number
+        .checked_sub(1)
+        .and_then(|index| content.lines().nth(index))
+        .unwrap_or("");
+    let normalized = line.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in normalized.bytes() {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    format!("{:016x}", hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use odincode_core::{IssueType, Severity};
+    use uuid::Uuid;
+
+    fn issue(line_number: usize, rule_id: &str) -> CodeIssue {
+        CodeIssue {
+            id: Uuid::new_v4(),
+            issue_type: IssueType::Style,
+            severity: Severity::Low,
+            description: rule_id.to_string(),
+            line_number,
+            column_number: 0,
+            suggestion: None,
+            rule_id: Some(rule_id.to_string()),
+            source: None,
+        }
+    }
+
+    #[test]
+    fn test_baselined_issue_is_suppressed_after_shifting() {
+        let original = "fn main() {\n    let a = 1; \n}\n";
+        let mut baseline = Baseline::default();
+        baseline.add(
+            "main.rs",
+            original,
+            &[issue(2, "style/trailing-whitespace")],
+        );
+
+        let unchanged = baseline.new_issues(
+            "main.rs",
+            original,
+            vec![issue(2, "style/trailing-whitespace")],
+        );
+        assert!(unchanged.is_empty());
+
+        // Two lines inserted above move the issue to line 4
+        let shifted = "// header\n\nfn main() {\n    let a = 1; \n}\n";
+        let moved = baseline.new_issues(
+            "main.rs",
+            shifted,
+            vec![issue(4, "style/trailing-whitespace")],
+        );
+        assert!(moved.is_empty());
+    }
+
+    #[test]
+    fn test_new_issue_is_reported() {
+        let original = "fn main() {\n    let a = 1; \n}\n";
+        let mut baseline = Baseline::default();
+        baseline.add(
+            "main.rs",
+            original,
+            &[issue(2, "style/trailing-whitespace")],
+        );
+
+        let changed = "fn main() {\n    let a = 1; \n    let b = 2; \n}\n";
+        let issues = vec![
+            issue(2, "style/trailing-whitespace"),
+            issue(3, "style/trailing-whitespace"),
+            issue(2, "style/line-length"),
+        ];
+        let new: Vec<(usize, String)> = baseline
+            .new_issues("main.rs", changed, issues)
+            .into_iter()
+            .map(|issue| (issue.line_number, issue.description))
+            .collect();
+        assert_eq!(
+            new,
+            vec![
+                (3, "style/trailing-whitespace".to_string()),
+                (2, "style/line-length".to_string()),
+            ]
+        );
+
+        // The same issue in another file is new too
+        assert_eq!(
+            baseline
+                .new_issues(
+                    "other.rs",
+                    original,
+                    vec![issue(2, "style/trailing-whitespace")]
+                )
+                .len(),
+            1
+        );
+    }
+}
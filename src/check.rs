@@ -0,0 +1,466 @@
+//! Check Mode
+//!
+//! Analyzes the files of a directory tree through the `CodeEngine` and prints
+//! their issues. With `--since <ref>` only files changed since the git ref are
+//! analyzed, and only issues on the lines the change touched are reported.
+//! With `--format jsonl` each file's issues are written as one JSON line as soon
+//! as the file is analyzed. With `--baseline <file>` only issues missing from
+//! the baseline are reported, and the check fails if there are any.
+
+use anyhow::{anyhow, Result};
+use clap::ValueEnum;
+use ignore::WalkBuilder;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::Path;
+use std::sync::Arc;
+use tracing::debug;
+use uuid::Uuid;
+
+use crate::baseline::Baseline;
+use odincode_core::{CodeEngine, CodeIssue};
+use odincode_tools::{git_changed_lines, LineRange};
+
+/// Analyze one file and return its engine id, content and issues, or `None` if
+/// it cannot be analyzed
+///
+/// The file stays loaded in the engine until the caller unloads it.
+async fn analyze_path(
+    engine: &CodeEngine,
+    root: &Path,
+    path: &str,
+) -> Result<Option<(Uuid, String, Vec<CodeIssue>)>> {
+    let content = match tokio::fs::read_to_string(root.join(path)).await {
+        Ok(content) => content,
+        Err(e) => {
+            debug!("Skipping unreadable file {}: {}", path, e);
+            return Ok(None);
+        }
+    };
+
+    let file_id = match engine
+        .load_file_with_detection(path.to_string(), content.clone())
+        .await
+    {
+        Ok(id) => id,
+        // Unsupported extension, nothing to analyze
+        Err(_) => return Ok(None),
+    };
+
+    match engine.analyze_file(file_id).await {
+        Ok(Some(result)) => Ok(Some((file_id, content, result.issues))),
+        Ok(None) => {
+            engine.unload_file(file_id).await;
+            Ok(None)
+        }
+        Err(e) => {
+            engine.unload_file(file_id).await;
+            Err(e)
+        }
+    }
+}
+
+/// Keep only the issues whose line falls within one of the changed ranges
+pub fn filter_to_changed_lines(issues: Vec<CodeIssue>, ranges: &[LineRange]) -> Vec<CodeIssue> {
+    issues
+        .into_iter()
+        .filter(|issue| ranges.iter().any(|range| range.contains(issue.line_number)))
+        .collect()
+}
+
+/// Output format of `check`
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// One human-readable line per issue (default)
+    Text,
+    /// One JSON object per file, written as soon as the file is analyzed
+    Jsonl,
+}
+
+/// One line of `--format jsonl` output
+#[derive(Serialize)]
+struct FileReport<'a> {
+    /// Path of the file, relative to the checked root
+    path: &'a str,
+    /// Issues found in the file
+    issues: &'a [CodeIssue],
+}
+
+/// Paths, relative to `root`, of the files under `root` that are not ignored
+pub fn walk_files(root: &Path) -> Vec<String> {
+    WalkBuilder::new(root)
+        .build()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_some_and(|t| t.is_file()))
+        .filter_map(|entry| {
+            entry
+                .path()
+                .strip_prefix(root)
+                .ok()
+                .map(|path| path.to_string_lossy().to_string())
+        })
+        .collect()
+}
+
+/// Check the files of `root` one at a time, limited to lines changed since
+/// `since` if given
+///
+/// `on_file` is called with each analyzed file's path, content and issues as
+/// soon as the file is done, so results never need to be held for the whole tree.
+pub async fn check_each<F>(
+    engine: &CodeEngine,
+    root: &str,
+    since: Option<&str>,
+    mut on_file: F,
+) -> Result<()>
+where
+    F: FnMut(&str, &str, Vec<CodeIssue>) -> Result<()>,
+{
+    let root_path = Path::new(root);
+    if !root_path.is_dir() {
+        return Err(anyhow!("Cannot check {}: not a directory", root));
+    }
+
+    let changed: Option<HashMap<String, Vec<LineRange>>> = match since {
+        Some(since) => Some(git_changed_lines(root, since)?),
+        None => None,
+    };
+
+    let mut paths: Vec<String> = match &changed {
+        Some(changed) => changed.keys().cloned().collect(),
+        None => walk_files(root_path),
+    };
+    paths.sort();
+
+    for path in paths {
+        let Some((file_id, content, mut issues)) = analyze_path(engine, root_path, &path).await?
+        else {
+            continue;
+        };
+        if let Some(ranges) = changed.as_ref().and_then(|changed| changed.get(&path)) {
+            issues = filter_to_changed_lines(issues, ranges);
+        }
+
+        issues.sort_by_key(|issue| (issue.line_number, issue.column_number));
+        let reported = on_file(&path, &content, issues);
+        // The file is done with, so the engine does not grow with the tree
+        engine.unload_file(file_id).await;
+        reported?;
+    }
+
+    Ok(())
+}
+
+/// Run `check`, writing its report to `out` in the given format
+///
+/// JSON lines are flushed one file at a time, so consumers can process them
+/// while the rest of the tree is still being analyzed. Issues in `baseline` are
+/// left out. Returns the number of issues reported.
+pub async fn write_check<W: Write>(
+    engine: &CodeEngine,
+    root: &str,
+    since: Option<&str>,
+    format: OutputFormat,
+    baseline: Option<&Baseline>,
+    out: &mut W,
+) -> Result<usize> {
+    let mut total = 0;
+    check_each(engine, root, since, |path, content, issues| {
+        let issues = match baseline {
+            Some(baseline) => baseline.new_issues(path, content, issues),
+            None => issues,
+        };
+        total += issues.len();
+        match format {
+            OutputFormat::Text => {
+                for issue in &issues {
+                    write!(
+                        out,
+                        "{}:{}:{}: {:?}: {}",
+                        path,
+                        issue.line_number,
+                        issue.column_number,
+                        issue.severity,
+                        issue.description
+                    )?;
+                    match &issue.rule_id {
+                        Some(rule_id) => writeln!(out, " [{}]", rule_id)?,
+                        None => writeln!(out)?,
+                    }
+                }
+            }
+            OutputFormat::Jsonl => {
+                let report = FileReport {
+                    path,
+                    issues: &issues,
+                };
+                serde_json::to_writer(&mut *out, &report)?;
+                writeln!(out)?;
+                out.flush()?;
+            }
+        }
+        Ok(())
+    })
+    .await?;
+
+    if format == OutputFormat::Text {
+        match since {
+            Some(since) => writeln!(out, "{} issues on lines changed since {}", total, since)?,
+            None => writeln!(out, "{} issues", total)?,
+        }
+    }
+    Ok(total)
+}
+
+/// Check the files of `root` and record every issue found in a baseline
+pub async fn build_baseline(engine: &CodeEngine, root: &str) -> Result<Baseline> {
+    let mut baseline = Baseline::default();
+    check_each(engine, root, None, |path, content, issues| {
+        baseline.add(path, content, &issues);
+        Ok(())
+    })
+    .await?;
+    Ok(baseline)
+}
+
+/// How `check` uses a baseline file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BaselineMode {
+    /// Fail only on issues missing from the baseline at this path
+    Compare(String),
+    /// Write the current issues to a baseline at this path
+    Write(String),
+}
+
+/// Run `check` and print its report to stdout
+///
+/// When comparing against a baseline, fails if any issue is not in it.
+pub async fn run_check(
+    engine: Arc<CodeEngine>,
+    root: &str,
+    since: Option<&str>,
+    format: OutputFormat,
+    baseline: Option<BaselineMode>,
+) -> Result<()> {
+    let baseline = match baseline {
+        Some(BaselineMode::Write(path)) => {
+            let baseline = build_baseline(&engine, root).await?;
+            baseline.save(Path::new(&path))?;
+            println!(
+                "Wrote {} issues to baseline {}",
+                baseline.entries.len(),
+                path
+            );
+            return Ok(());
+        }
+        Some(BaselineMode::Compare(path)) => Some((Baseline::load(Path::new(&path))?, path)),
+        None => None,
+    };
+
+    let stdout = std::io::stdout();
+    let reported = write_check(
+        &engine,
+        root,
+        since,
+        format,
+        baseline.as_ref().map(|(baseline, _)| baseline),
+        &mut stdout.lock(),
+    )
+    .await?;
+
+    match baseline {
+        Some((_, path)) if reported > 0 => {
+            Err(anyhow!("{} issues are not in baseline {}", reported, path))
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+
+    /// An issue found in a checked file
+    #[derive(Debug, Clone)]
+    struct FileIssue {
+        /// Path of the file, relative to the checked root
+        path: String,
+        /// The issue
+        issue: CodeIssue,
+    }
+
+    /// Collect every issue reported by `check_each`
+    async fn check(engine: &CodeEngine, root: &str, since: Option<&str>) -> Result<Vec<FileIssue>> {
+        let mut found = Vec::new();
+        check_each(engine, root, since, |path, _, issues| {
+            found.extend(issues.into_iter().map(|issue| FileIssue {
+                path: path.to_string(),
+                issue,
+            }));
+            Ok(())
+        })
+        .await?;
+
+        Ok(found)
+    }
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {:?} failed", args);
+    }
+
+    #[tokio::test]
+    async fn test_since_reports_only_changed_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        git(dir.path(), &["init", "-q"]);
+        git(dir.path(), &["config", "user.email", "dev@example.com"]);
+        git(dir.path(), &["config", "user.name", "Dev"]);
+
+        // Lines 2 and 4 both carry trailing whitespace
+        let original =
+            "fn main() {\n    let a = 1; \n    println!(\"{}\", a);\n    let b = 2; \n}\n";
+        std::fs::write(dir.path().join("main.rs"), original).unwrap();
+        git(dir.path(), &["add", "main.rs"]);
+        git(dir.path(), &["commit", "-q", "-m", "initial"]);
+
+        std::fs::write(
+            dir.path().join("main.rs"),
+            original.replace("let b = 2; ", "let b = 3; "),
+        )
+        .unwrap();
+
+        let engine = CodeEngine::new().unwrap();
+        let root = dir.path().to_str().unwrap();
+
+        let all = check(&engine, root, None).await.unwrap();
+        let trailing = |issues: &[FileIssue]| -> Vec<usize> {
+            issues
+                .iter()
+                .filter(|found| found.issue.description.contains("Trailing whitespace"))
+                .map(|found| found.issue.line_number)
+                .collect()
+        };
+        assert_eq!(trailing(&all), vec![2, 4]);
+
+        let changed = check(&engine, root, Some("HEAD")).await.unwrap();
+        assert_eq!(trailing(&changed), vec![4]);
+        assert!(changed.iter().all(|found| found.issue.line_number == 4));
+        assert!(changed.iter().all(|found| found.path == "main.rs"));
+    }
+
+    /// Writer that records its contents at every flush
+    #[derive(Default)]
+    struct FlushRecorder {
+        buffer: Vec<u8>,
+        flushes: Vec<String>,
+    }
+
+    impl Write for FlushRecorder {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.buffer.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            self.flushes
+                .push(String::from_utf8_lossy(&self.buffer).to_string());
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_jsonl_output_is_streamed_per_file() {
+        let dir = tempfile::tempdir().unwrap();
+        for (name, content) in [
+            ("a.rs", "fn a() {} \n"),
+            ("b.rs", "fn b() {}\n"),
+            ("c.js", "if (x == 1) {}\n"),
+        ] {
+            std::fs::write(dir.path().join(name), content).unwrap();
+        }
+
+        let engine = CodeEngine::new().unwrap();
+        let mut out = FlushRecorder::default();
+        write_check(
+            &engine,
+            dir.path().to_str().unwrap(),
+            None,
+            OutputFormat::Jsonl,
+            None,
+            &mut out,
+        )
+        .await
+        .unwrap();
+
+        // Each file was flushed as its own line before the next was analyzed
+        assert_eq!(out.flushes.len(), 3);
+        for (i, flushed) in out.flushes.iter().enumerate() {
+            assert_eq!(flushed.lines().count(), i + 1);
+        }
+
+        let lines: Vec<serde_json::Value> = String::from_utf8(out.buffer)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        let paths: Vec<&str> = lines.iter().map(|l| l["path"].as_str().unwrap()).collect();
+        assert_eq!(paths, vec!["a.rs", "b.rs", "c.js"]);
+        assert!(lines.iter().all(|l| l["issues"].is_array()));
+        assert!(!lines[0]["issues"].as_array().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_baseline_fails_only_on_new_issues() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("src");
+        std::fs::create_dir(&root).unwrap();
+        std::fs::write(root.join("main.rs"), "fn main() {\n    let a = 1; \n}\n").unwrap();
+        let root = root.to_str().unwrap();
+        let baseline_path = dir.path().join("baseline.json");
+        let baseline_file = baseline_path.to_str().unwrap().to_string();
+
+        let engine = Arc::new(CodeEngine::new().unwrap());
+        run_check(
+            Arc::clone(&engine),
+            root,
+            None,
+            OutputFormat::Text,
+            Some(BaselineMode::Write(baseline_file.clone())),
+        )
+        .await
+        .unwrap();
+        let compare = || {
+            run_check(
+                Arc::clone(&engine),
+                root,
+                None,
+                OutputFormat::Text,
+                Some(BaselineMode::Compare(baseline_file.clone())),
+            )
+        };
+        compare().await.unwrap();
+
+        // The grandfathered issue moved down a line
+        std::fs::write(
+            dir.path().join("src/main.rs"),
+            "// entry point\nfn main() {\n    let a = 1; \n}\n",
+        )
+        .unwrap();
+        compare().await.unwrap();
+
+        std::fs::write(
+            dir.path().join("src/main.rs"),
+            "// entry point\nfn main() {\n    let a = 1; \n    let b = 2; \n}\n",
+        )
+        .unwrap();
+        let error = compare().await.unwrap_err();
+        assert!(error.to_string().contains("not in baseline"));
+    }
+}
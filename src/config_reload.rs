@@ -0,0 +1,356 @@
+//! Configuration Hot Reload
+//!
+//! Watches the system configuration file and applies the settings that are safe
+//! to change at runtime: analysis thresholds, approval policies and the log
+//! level. Bind address and database changes are only logged, since they need a
+//! restart. A reloaded file that fails to parse or validate is rejected and the
+//! previous configuration stays in effect.
+
+use anyhow::{anyhow, Result};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::sync::{mpsc, watch};
+use tracing::{info, warn};
+
+use odincode_core::{CodeEngine, SystemConfig};
+use odincode_tui::ApprovalConfig;
+
+use crate::watch::{Debouncer, DEFAULT_DEBOUNCE};
+
+/// Applies a new log level filter, e.g. `debug` or `odincode=trace`
+pub type LogLevelSetter = Box<dyn Fn(&str) -> Result<()> + Send + Sync>;
+
+/// Reloads the system configuration and applies runtime-safe changes
+pub struct ConfigReloader {
+    /// System configuration file
+    config_path: PathBuf,
+    /// Configuration currently in effect
+    current: SystemConfig,
+    /// Engine whose analysis thresholds are updated
+    engine: Arc<CodeEngine>,
+    /// Approval policy file and the channel its reloaded policies are sent on
+    approval: Option<(PathBuf, watch::Sender<ApprovalConfig>)>,
+    /// Applies a changed log level
+    log_level: Option<LogLevelSetter>,
+}
+
+impl ConfigReloader {
+    /// Create a reloader for `config_path`, starting from the loaded `current`
+    pub fn new(config_path: &Path, current: SystemConfig, engine: Arc<CodeEngine>) -> Self {
+        Self {
+            config_path: config_path.to_path_buf(),
+            current,
+            engine,
+            approval: None,
+            log_level: None,
+        }
+    }
+
+    /// Also reload approval policies from `path`, sending them on `sender`
+    pub fn with_approval(mut self, path: PathBuf, sender: watch::Sender<ApprovalConfig>) -> Self {
+        self.approval = Some((path, sender));
+        self
+    }
+
+    /// Apply log level changes through `setter`
+    pub fn with_log_level(mut self, setter: LogLevelSetter) -> Self {
+        self.log_level = Some(setter);
+        self
+    }
+
+    /// Files whose changes trigger a reload
+    pub fn watched_paths(&self) -> Vec<PathBuf> {
+        let mut paths = vec![self.config_path.clone()];
+        if let Some((path, _)) = &self.approval {
+            paths.push(path.clone());
+        }
+        paths
+    }
+
+    /// Reload after a change to `path`
+    ///
+    /// Errors are logged and the previous configuration is kept.
+    pub async fn handle_change(&mut self, path: &Path) {
+        let result = if same_file(path, &self.config_path) {
+            self.reload().await
+        } else {
+            self.reload_approval()
+        };
+
+        if let Err(e) = result {
+            warn!("Keeping previous configuration: {}", e);
+        }
+    }
+
+    /// Reload the system configuration file and apply runtime-safe changes
+    pub async fn reload(&mut self) -> Result<()> {
+        let new = SystemConfig::load(&self.config_path)?;
+
+        for setting in restart_required_changes(&self.current, &new) {
+            warn!(
+                "Changing {} in {} requires a restart; keeping the running value",
+                setting,
+                self.config_path.display()
+            );
+        }
+
+        // The log level is the only setting that can still be rejected, so it is
+        // applied first and a rejected file changes nothing
+        if new.log_level != self.current.log_level {
+            if let Some(setter) = &self.log_level {
+                setter(&new.log_level)
+                    .map_err(|e| anyhow!("Invalid log level '{}': {}", new.log_level, e))?;
+                info!("Log level changed to {}", new.log_level);
+            }
+        }
+
+        if new.analysis != self.current.analysis {
+            self.engine.set_analysis_config(new.analysis.clone()).await;
+            info!("Reloaded analysis thresholds");
+        }
+
+        // Restart-only settings keep describing what is actually running
+        self.current = SystemConfig {
+            api: self.current.api.clone(),
+            database: self.current.database.clone(),
+            safe_mode: self.current.safe_mode,
+            ..new
+        };
+        Ok(())
+    }
+
+    /// Reload the approval policy file, if one is watched
+    pub fn reload_approval(&self) -> Result<()> {
+        let Some((path, sender)) = &self.approval else {
+            return Ok(());
+        };
+
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| anyhow!("Failed to read {}: {}", path.display(), e))?;
+        let approval = ApprovalConfig::from_toml_str(&content)?;
+        sender.send_replace(approval);
+        info!("Reloaded approval policies from {}", path.display());
+        Ok(())
+    }
+}
+
+/// Settings that changed between two configurations but need a restart to apply
+pub fn restart_required_changes(old: &SystemConfig, new: &SystemConfig) -> Vec<&'static str> {
+    let mut changed = Vec::new();
+    if old.api.host != new.api.host || old.api.port != new.api.port {
+        changed.push("api bind address");
+    }
+    if old.api.workers != new.api.workers {
+        changed.push("api.workers");
+    }
+    if old.database != new.database {
+        changed.push("database");
+    }
+    if old.safe_mode != new.safe_mode {
+        changed.push("safe_mode");
+    }
+    changed
+}
+
+/// Whether two paths name the same existing file
+fn same_file(a: &Path, b: &Path) -> bool {
+    a == b
+        || match (std::fs::canonicalize(a), std::fs::canonicalize(b)) {
+            (Ok(a), Ok(b)) => a == b,
+            _ => false,
+        }
+}
+
+/// Watch the reloader's files and apply changes until the task is dropped
+///
+/// The parent directories are watched rather than the files themselves, so
+/// editors that save by replacing the file are still seen.
+pub async fn watch_config(mut reloader: ConfigReloader) -> Result<()> {
+    let watched: Vec<PathBuf> = reloader
+        .watched_paths()
+        .into_iter()
+        .map(|path| std::fs::canonicalize(&path).unwrap_or(path))
+        .collect();
+
+    let (event_tx, mut event_rx) = mpsc::unbounded_channel::<Event>();
+    let mut watcher: RecommendedWatcher =
+        notify::recommended_watcher(move |res: notify::Result<Event>| match res {
+            Ok(event) => {
+                let _ = event_tx.send(event);
+            }
+            Err(e) => warn!("Config watch error: {}", e),
+        })?;
+    let mut dirs: Vec<&Path> = watched.iter().filter_map(|path| path.parent()).collect();
+    dirs.sort();
+    dirs.dedup();
+    for dir in dirs {
+        if dir.is_dir() {
+            watcher.watch(dir, RecursiveMode::NonRecursive)?;
+        } else {
+            warn!("Not watching {}: directory does not exist", dir.display());
+        }
+    }
+
+    let mut debouncer = Debouncer::new(DEFAULT_DEBOUNCE);
+    let mut tick = tokio::time::interval(DEFAULT_DEBOUNCE / 2);
+    info!("Watching configuration files {:?}", watched);
+
+    loop {
+        tokio::select! {
+            Some(event) = event_rx.recv() => {
+                if !matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+                    continue;
+                }
+                for path in event.paths {
+                    if let Some(target) = watched.iter().find(|w| same_file(&path, w)) {
+                        debouncer.record(target.clone(), Instant::now());
+                    }
+                }
+            }
+            _ = tick.tick() => {
+                for path in debouncer.drain_ready(Instant::now()) {
+                    reloader.handle_change(&path).await;
+                }
+            }
+        }
+    }
+}
+
+/// Load the system configuration, falling back to defaults if the file is absent
+pub fn load_or_default(path: &Path) -> Result<SystemConfig> {
+    if path.exists() {
+        SystemConfig::load(path)
+    } else {
+        info!(
+            "No configuration file at {}, using defaults",
+            path.display()
+        );
+        Ok(SystemConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    /// Time to wait for a watched change to be applied
+    const APPLY_TIMEOUT: Duration = Duration::from_secs(5);
+
+    const CONFIG: &str = r#"
+        [analysis]
+        max_panic_density = 0.25
+
+        [api]
+        port = 8080
+    "#;
+
+    /// Poll the engine until its panic density threshold equals `expected`
+    async fn wait_for_density(engine: &CodeEngine, expected: f64) -> bool {
+        let deadline = Instant::now() + APPLY_TIMEOUT;
+        while Instant::now() < deadline {
+            if engine.get_analysis_config().await.max_panic_density == expected {
+                return true;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        false
+    }
+
+    #[tokio::test]
+    async fn test_watched_config_changes_are_applied() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = std::fs::canonicalize(dir.path())
+            .unwrap()
+            .join("config.toml");
+        std::fs::write(&path, CONFIG).unwrap();
+
+        let engine = Arc::new(CodeEngine::new().unwrap());
+        let config = SystemConfig::load(&path).unwrap();
+        engine.set_analysis_config(config.analysis.clone()).await;
+        let reloader = ConfigReloader::new(&path, config, Arc::clone(&engine));
+        let watch_task = tokio::spawn(watch_config(reloader));
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        std::fs::write(&path, CONFIG.replace("0.25", "0.5")).unwrap();
+        assert!(wait_for_density(&engine, 0.5).await);
+
+        // An invalid edit is rejected and the previous thresholds stay in effect
+        std::fs::write(&path, CONFIG.replace("0.25", "-1.0")).unwrap();
+        tokio::time::sleep(DEFAULT_DEBOUNCE * 4).await;
+        assert_eq!(engine.get_analysis_config().await.max_panic_density, 0.5);
+
+        watch_task.abort();
+    }
+
+    #[tokio::test]
+    async fn test_restart_only_settings_are_kept() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(&path, CONFIG).unwrap();
+
+        let engine = Arc::new(CodeEngine::new().unwrap());
+        let mut reloader = ConfigReloader::new(&path, SystemConfig::load(&path).unwrap(), engine);
+
+        std::fs::write(&path, CONFIG.replace("8080", "9090").replace("0.25", "0.3")).unwrap();
+        reloader.reload().await.unwrap();
+        assert_eq!(reloader.current.api.port, 8080);
+        assert_eq!(reloader.current.analysis.max_panic_density, 0.3);
+
+        let mut new = reloader.current.clone();
+        new.api.port = 9090;
+        assert_eq!(
+            restart_required_changes(&reloader.current, &new),
+            vec!["api bind address"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rejected_log_level_changes_nothing() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(&path, CONFIG).unwrap();
+
+        let engine = Arc::new(CodeEngine::new().unwrap());
+        let config = SystemConfig::load(&path).unwrap();
+        engine.set_analysis_config(config.analysis.clone()).await;
+        let mut reloader = ConfigReloader::new(&path, config, Arc::clone(&engine))
+            .with_log_level(Box::new(|_| Err(anyhow!("unknown level"))));
+
+        let edited = CONFIG.replace("0.25", "0.5");
+        std::fs::write(&path, format!("log_level = \"loud\"\n{}", edited)).unwrap();
+        assert!(reloader.reload().await.is_err());
+        assert_eq!(engine.get_analysis_config().await.max_panic_density, 0.25);
+        assert_eq!(reloader.current.analysis.max_panic_density, 0.25);
+        assert_ne!(reloader.current.log_level, "loud");
+    }
+
+    #[tokio::test]
+    async fn test_approval_policies_are_reloaded() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        let approval_path = dir.path().join("approval.toml");
+        std::fs::write(&path, CONFIG).unwrap();
+        std::fs::write(&approval_path, "default = \"always_allow\"").unwrap();
+
+        let (sender, receiver) = watch::channel(ApprovalConfig::default());
+        let engine = Arc::new(CodeEngine::new().unwrap());
+        let reloader = ConfigReloader::new(&path, SystemConfig::default(), engine)
+            .with_approval(approval_path.clone(), sender);
+
+        reloader.reload_approval().unwrap();
+        assert_eq!(
+            receiver.borrow().policy_for("shell"),
+            odincode_tui::ApprovalPolicy::AlwaysAllow
+        );
+
+        std::fs::write(&approval_path, "default = \"sometimes\"").unwrap();
+        assert!(reloader.reload_approval().is_err());
+        assert_eq!(
+            receiver.borrow().policy_for("shell"),
+            odincode_tui::ApprovalPolicy::AlwaysAllow
+        );
+    }
+}
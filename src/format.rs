@@ -0,0 +1,184 @@
+//! Format Mode
+//!
+//! Runs the formatter the tool manager's format policy assigns to each file's
+//! language over the files of a directory tree, so the same formatters apply
+//! here as to edited files. The policy is loaded from `[formatters.<language>]`
+//! in the system config. With `--since <ref>` only files changed since the git ref are
+//! formatted. With `--check` no file is written; the files that would change
+//! are reported instead, and the run fails if there are any.
+
+use anyhow::{anyhow, Result};
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::Path;
+use tracing::debug;
+
+use crate::check::walk_files;
+use odincode_core::CodeEngine;
+use odincode_tools::{git_changed_lines, FormatterCommand, ToolManager};
+
+/// Format the files of `root`, or only those changed since `since`
+///
+/// Files without a formatter for their language are skipped, and with `check`
+/// set no file is written. Returns the paths, relative to `root`, of the files
+/// whose formatted content differs from the content on disk.
+pub async fn format_files(
+    engine: &CodeEngine,
+    root: &str,
+    since: Option<&str>,
+    formatters: &HashMap<String, FormatterCommand>,
+    check: bool,
+) -> Result<Vec<String>> {
+    let root_path = Path::new(root);
+    if !root_path.is_dir() {
+        return Err(anyhow!("Cannot format {}: not a directory", root));
+    }
+
+    let mut paths: Vec<String> = match since {
+        Some(since) => git_changed_lines(root, since)?.into_keys().collect(),
+        None => walk_files(root_path),
+    };
+    paths.sort();
+
+    let mut changed = Vec::new();
+    for path in paths {
+        let Ok(language) = engine.detect_language_from_path(&path).await else {
+            continue;
+        };
+        let Some(formatter) = formatters.get(&language) else {
+            debug!("No formatter configured for {} ({})", path, language);
+            continue;
+        };
+        let full_path = root_path.join(&path);
+        let content = match tokio::fs::read_to_string(&full_path).await {
+            Ok(content) => content,
+            Err(e) => {
+                debug!("Skipping unreadable file {}: {}", path, e);
+                continue;
+            }
+        };
+
+        let formatted = formatter
+            .format(&content)
+            .await
+            .map_err(|e| anyhow!("Failed to format {}: {}", path, e))?;
+        if formatted == content {
+            continue;
+        }
+        if !check {
+            tokio::fs::write(&full_path, &formatted)
+                .await
+                .map_err(|e| anyhow!("Failed to write {}: {}", path, e))?;
+        }
+        changed.push(path);
+    }
+
+    Ok(changed)
+}
+
+/// Run `format`, writing one line per changed file and a summary to `out`
+///
+/// In check mode, fails if any file is not formatted.
+pub async fn write_format<W: Write>(
+    engine: &CodeEngine,
+    root: &str,
+    since: Option<&str>,
+    formatters: &HashMap<String, FormatterCommand>,
+    check: bool,
+    out: &mut W,
+) -> Result<()> {
+    let changed = format_files(engine, root, since, formatters, check).await?;
+    if check {
+        for path in &changed {
+            writeln!(out, "Would reformat {}", path)?;
+        }
+        writeln!(out, "{} files would be reformatted", changed.len())?;
+        if !changed.is_empty() {
+            return Err(anyhow!("{} files are not formatted", changed.len()));
+        }
+    } else {
+        for path in &changed {
+            writeln!(out, "Formatted {}", path)?;
+        }
+        writeln!(out, "{} files formatted", changed.len())?;
+    }
+    Ok(())
+}
+
+/// Run `format` with the tool manager's formatters and print its report to stdout
+pub async fn run_format(
+    tools: &ToolManager,
+    root: &str,
+    since: Option<&str>,
+    check: bool,
+) -> Result<()> {
+    let formatters = tools.resolve_formatters().await;
+    let stdout = std::io::stdout();
+    write_format(
+        &tools.core_engine,
+        root,
+        since,
+        &formatters,
+        check,
+        &mut stdout.lock(),
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_check_reports_only_unformatted_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let bad = "fn main() {   \n    let a = 1;  \n}\n";
+        std::fs::write(dir.path().join("bad.rs"), bad).unwrap();
+        std::fs::write(dir.path().join("good.rs"), "fn good() {}\n").unwrap();
+        // No formatter is configured for Python
+        std::fs::write(dir.path().join("skipped.py"), "x = 1   \n").unwrap();
+
+        // Strips trailing whitespace, reading stdin and writing stdout
+        let formatters: HashMap<String, FormatterCommand> = [(
+            "rust".to_string(),
+            FormatterCommand {
+                tool_name: "rust formatter".to_string(),
+                command: "sed".to_string(),
+                args: vec!["s/[[:space:]]*$//".to_string()],
+            },
+        )]
+        .into_iter()
+        .collect();
+
+        let engine = CodeEngine::new().unwrap();
+        let root = dir.path().to_str().unwrap();
+        let mut out = Vec::new();
+        let result = write_format(&engine, root, None, &formatters, true, &mut out).await;
+
+        assert!(result.is_err());
+        let report = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = report.lines().collect();
+        assert_eq!(
+            lines,
+            vec!["Would reformat bad.rs", "1 files would be reformatted"]
+        );
+        // Check mode leaves the file as it was
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("bad.rs")).unwrap(),
+            bad
+        );
+
+        let mut out = Vec::new();
+        write_format(&engine, root, None, &formatters, false, &mut out)
+            .await
+            .unwrap();
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("bad.rs")).unwrap(),
+            "fn main() {\n    let a = 1;\n}\n"
+        );
+        let mut out = Vec::new();
+        write_format(&engine, root, None, &formatters, true, &mut out)
+            .await
+            .unwrap();
+    }
+}
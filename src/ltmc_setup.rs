@@ -0,0 +1,108 @@
+//! LTMC manager setup
+//!
+//! Builds the LTMC manager from the configuration kept in the database root,
+//! attaching the vector index and the graph sync, pattern sync and telemetry
+//! bridges it enables.
+
+use std::path::Path;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use odincode_databases::{FaissConfig, FaissManager, FaissMetricType, Neo4jManager};
+use odincode_ltmc::{GraphSyncBridge, LTMCConfig, LTMCConfigManager, LTMManager};
+
+/// File name of the LTMC configuration inside the database root
+pub const LTMC_CONFIG_FILE_NAME: &str = "ltmc.json";
+
+/// Load the LTMC configuration from `db_root`, or the defaults if it cannot be read
+pub async fn load_config(db_root: &Path) -> LTMCConfig {
+    let mut config_manager =
+        LTMCConfigManager::with_config_file(db_root.join(LTMC_CONFIG_FILE_NAME));
+    if let Err(e) = config_manager.load_config().await {
+        warn!("Using the default LTMC configuration: {}", e);
+    }
+    config_manager.get_config().clone()
+}
+
+/// Create an LTMC manager with the databases its configuration enables
+///
+/// A database that cannot be opened is logged and left out, so the manager
+/// still works from its cache.
+pub async fn build_manager(config: &LTMCConfig) -> LTMManager {
+    let mut manager = LTMManager::new();
+    if !config.enabled {
+        return manager;
+    }
+
+    if let Some(faiss) = &config.databases.faiss {
+        let index = FaissManager::with_config(FaissConfig {
+            index_description: faiss.index_type.clone(),
+            dimension: faiss.dimension,
+            metric_type: FaissMetricType::L2,
+            nlist: None,
+            nprobe: None,
+            index_path: Some(faiss.index_path.to_string_lossy().into_owned()),
+            use_gpu: false,
+            max_vectors: None,
+        })
+        .await;
+        match index {
+            Ok(index) => manager.set_vector_index(Arc::new(index)),
+            Err(e) => warn!("Patterns will not be embedded, failed to open FAISS: {}", e),
+        }
+    }
+
+    if let (true, Some(neo4j)) = (config.features.graph_sync, &config.databases.neo4j) {
+        let graph = Neo4jManager::with_config(odincode_databases::Neo4jConfig {
+            uri: neo4j.uri.clone(),
+            username: neo4j.username.clone(),
+            password: neo4j.password.clone(),
+            pool_size: neo4j.pool_size as usize,
+            ..Default::default()
+        })
+        .await;
+        match graph {
+            Ok(graph) => {
+                if let Some(bridge) = GraphSyncBridge::from_config(config, Arc::new(graph)) {
+                    manager.set_graph_sync_bridge(bridge);
+                    info!("Linking similar patterns in Neo4j at {}", neo4j.uri);
+                }
+            }
+            Err(e) => warn!(
+                "Similar patterns will not be linked, failed to open Neo4j: {}",
+                e
+            ),
+        }
+    }
+
+    #[cfg(feature = "ltmc-redis")]
+    if let Some(redis) = &config.databases.redis {
+        let sync = odincode_databases::RedisManager::new(odincode_databases::RedisConfig {
+            url: redis.url.clone(),
+            pool_size: redis.pool_size as usize,
+            default_ttl: redis.default_ttl,
+            ..Default::default()
+        })
+        .map(|redis| odincode_ltmc::PatternSyncBridge::new(Arc::new(redis)));
+        let started = match sync {
+            Ok(bridge) => manager.start_pattern_sync(bridge).await,
+            Err(e) => Err(e),
+        };
+        match started {
+            Ok(()) => info!("Sharing learned patterns over Redis at {}", redis.url),
+            Err(e) => warn!(
+                "Learned patterns will not be shared, failed to open Redis: {}",
+                e
+            ),
+        }
+    }
+
+    #[cfg(feature = "ltmc-otel")]
+    if let Some(bridge) =
+        odincode_ltmc::TelemetryBridge::from_config(config, &odincode_ltmc::log_tracer_provider())
+    {
+        manager.set_telemetry_bridge(bridge);
+    }
+
+    manager
+}
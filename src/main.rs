@@ -3,18 +3,32 @@
 //! The main entry point for the OdinCode application.
 
 use anyhow::Result;
-use clap::Parser;
+use clap::{Parser, Subcommand, ValueEnum};
+use std::collections::HashMap;
 use std::sync::Arc;
-use tracing::{error, info};
+use tracing::{error, info, warn};
+use tracing_subscriber::fmt::MakeWriter;
 use tracing_subscriber::EnvFilter;
 use uuid::Uuid;
 
 use odincode_agents::AgentCoordinator;
+use odincode_core::action_history::ActionHistoryManager;
 use odincode_core::llm_integration::LLMIntegrationManager;
+use odincode_core::system_config::FormatterSettings;
 use odincode_core::CodeEngine;
-#[cfg(not(feature = "simple-ltmc"))]
 use odincode_ltmc::LTMManager;
 
+mod baseline;
+mod check;
+mod config_reload;
+mod format;
+mod ltmc_setup;
+mod plan;
+mod watch;
+
+/// File name of the agent execution history inside the database root
+const ACTION_HISTORY_FILE_NAME: &str = "actions.db";
+
 /// Command line interface for OdinCode
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -38,6 +52,150 @@ struct Args {
     /// Run in CLI mode (commands)
     #[arg(long, conflicts_with = "server")]
     cli: bool,
+
+    /// Log output format
+    #[arg(long, value_enum, env = "ODINCODE_LOG_FORMAT", default_value_t = LogFormat::Text)]
+    log_format: LogFormat,
+
+    /// Seconds a tool may run before it is reported as timed out (0 disables the timeout)
+    #[arg(long, default_value_t = 60)]
+    tool_timeout_secs: u64,
+
+    /// Refuse tools that write files or run project code, for exploring untrusted code
+    #[arg(long)]
+    safe: bool,
+
+    /// Directory holding the action history, symbol index and TUI settings
+    #[arg(long, default_value = odincode_tui::DEFAULT_DB_ROOT)]
+    db_root: std::path::PathBuf,
+
+    /// Subcommand to run instead of one of the modes
+    #[command(subcommand)]
+    command: Option<Commands>,
+}
+
+/// CLI subcommands
+#[derive(Subcommand, Debug)]
+enum Commands {
+    /// Watch a directory and re-analyze files as they change
+    Watch {
+        /// Directory to watch
+        #[arg(default_value = ".")]
+        path: String,
+    },
+    /// Analyze a directory and print the issues found
+    Check {
+        /// Directory to check
+        #[arg(default_value = ".")]
+        path: String,
+        /// Only report issues on lines changed since this git ref
+        #[arg(long)]
+        since: Option<String>,
+        /// Output format
+        #[arg(long, value_enum, default_value_t = check::OutputFormat::Text)]
+        format: check::OutputFormat,
+        /// Only report and fail on issues missing from this baseline file
+        #[arg(long)]
+        baseline: Option<String>,
+        /// Write the current issues to the --baseline file instead of checking
+        #[arg(long, requires = "baseline")]
+        write_baseline: bool,
+    },
+    /// Run the configured formatters over a directory
+    Format {
+        /// Directory to format
+        #[arg(default_value = ".")]
+        path: String,
+        /// Report the files that would change without writing them, failing if there are any
+        #[arg(long)]
+        check: bool,
+        /// Only format files changed since this git ref
+        #[arg(long)]
+        since: Option<String>,
+    },
+    /// Apply an edit plan from a JSON file
+    Plan {
+        /// Path of the plan file
+        file: String,
+        /// Print the combined unified diff of the plan and exit without applying it
+        #[arg(long)]
+        diff: bool,
+        /// Print what each step of the plan would do without applying it
+        #[arg(long, conflicts_with = "diff")]
+        dry_run: bool,
+        /// Leave completed steps in place when a step fails instead of offering to roll back
+        #[arg(long)]
+        no_rollback: bool,
+        /// Apply a multi-file plan, and roll back a failed one, without asking
+        #[arg(long, short = 'y')]
+        yes: bool,
+    },
+    /// Explain what an analysis rule detects and how to fix it
+    Explain {
+        /// Rule id, as shown with each reported issue
+        rule: String,
+    },
+}
+
+impl Args {
+    /// Tool timeout from the command line, `None` when disabled
+    fn tool_timeout(&self) -> Option<std::time::Duration> {
+        match self.tool_timeout_secs {
+            0 => None,
+            secs => Some(std::time::Duration::from_secs(secs)),
+        }
+    }
+}
+
+/// Output format for log records
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+enum LogFormat {
+    /// Human-readable text (default)
+    Text,
+    /// One JSON object per line, for log aggregation
+    Json,
+}
+
+/// Build the tracing subscriber for the selected log format
+///
+/// Also returns a setter that replaces the subscriber's filter, used to apply a
+/// changed log level from a reloaded configuration.
+fn build_subscriber<W>(
+    format: LogFormat,
+    filter: EnvFilter,
+    writer: W,
+) -> (
+    Box<dyn tracing::Subscriber + Send + Sync>,
+    config_reload::LogLevelSetter,
+)
+where
+    W: for<'a> MakeWriter<'a> + Send + Sync + 'static,
+{
+    match format {
+        LogFormat::Text => {
+            let builder = tracing_subscriber::fmt()
+                .with_env_filter(filter)
+                .with_writer(writer)
+                .with_filter_reloading();
+            let handle = builder.reload_handle();
+            let setter: config_reload::LogLevelSetter =
+                Box::new(move |level| Ok(handle.reload(EnvFilter::try_new(level)?)?));
+            (Box::new(builder.finish()), setter)
+        }
+        LogFormat::Json => {
+            let builder = tracing_subscriber::fmt()
+                .json()
+                .with_current_span(true)
+                .with_span_list(true)
+                .with_env_filter(filter)
+                .with_writer(writer)
+                .with_filter_reloading();
+            let handle = builder.reload_handle();
+            let setter: config_reload::LogLevelSetter =
+                Box::new(move |level| Ok(handle.reload(EnvFilter::try_new(level)?)?));
+            (Box::new(builder.finish()), setter)
+        }
+    }
 }
 
 /// Main application structure
@@ -45,7 +203,6 @@ pub struct OdinCodeApp {
     /// The core code engine
     core_engine: Arc<CodeEngine>,
     /// The LTMC manager for persistent learning
-    #[cfg(not(feature = "simple-ltmc"))]
     ltmc_manager: Arc<LTMManager>,
     /// The LTMC integration for agent learning
     #[cfg(not(feature = "simple-ltmc"))]
@@ -59,8 +216,8 @@ pub struct OdinCodeApp {
 }
 
 impl OdinCodeApp {
-    /// Create a new OdinCode application instance
-    pub async fn new() -> Result<Self> {
+    /// Create a new OdinCode application instance, keeping its databases in `db_root`
+    pub async fn new(db_root: &std::path::Path) -> Result<Self> {
         // Create ML integration configuration
         let ml_config = odincode_core::ml_integration::MLIntegrationConfig {
             model_name: "odincode_ml_v1".to_string(),
@@ -97,11 +254,12 @@ impl OdinCodeApp {
         let llm_manager = LLMIntegrationManager::new()?;
         let llm_manager = Arc::new(llm_manager);
 
+        // One LTMC manager, configured from the database root, serves every component
+        let ltmc_config = ltmc_setup::load_config(db_root).await;
+        let ltmc_manager = Arc::new(ltmc_setup::build_manager(&ltmc_config).await);
+
         #[cfg(not(feature = "simple-ltmc"))]
         {
-            // Full LTMC mode - use external LTMC manager
-            let ltmc_manager = Arc::new(LTMManager::new());
-
             // Create core engine with ML and LLM integration
             let core_engine = Arc::new(
                 CodeEngine::new_with_ml_and_llm(
@@ -126,6 +284,12 @@ impl OdinCodeApp {
                 Arc::clone(&ltmc_integration),
             )
             .await;
+            let agent_coordinator = match open_action_history(db_root).await {
+                Some(action_history) => agent_coordinator.with_action_history(action_history),
+                None => agent_coordinator,
+            };
+            // Agents load their prompt overrides from the same database root
+            let agent_coordinator = agent_coordinator.with_prompt_root(db_root.to_path_buf());
 
             // Now that we have the complete app, let's initialize the semantic analysis engine
             // in the ML integration manager if it exists
@@ -159,21 +323,18 @@ impl OdinCodeApp {
 
         #[cfg(feature = "simple-ltmc")]
         {
-            // Simple LTMC mode - use dummy LTMC manager since SimpleLTMCManager doesn't exist
-            // We'll use the basic LTMManager as a placeholder
-            let simple_ltmc_manager = Arc::new(odincode_ltmc::LTMManager::new());
+            // Simple LTMC mode - SimpleLTMCManager doesn't exist, so the LTMManager is used
 
-            // Create core engine with simple LTMC integration
+            // Create core engine with simple LTMC integration, without ML if it fails to start
             let engine =
-                CodeEngine::new_with_ml(ml_config.clone(), Arc::clone(&simple_ltmc_manager))
+                CodeEngine::new_with_optional_ml(ml_config.clone(), Arc::clone(&ltmc_manager))
                     .await?;
             let core_engine = Arc::new(engine);
 
-            // Create a dummy LTMC manager for agent coordinator compatibility
-            let dummy_ltmc_manager = Arc::new(odincode_ltmc::LTMManager::new());
+            // Create a dummy LTMC integration for agent coordinator compatibility
             let dummy_ltmc_integration =
                 Arc::new(odincode_agents::ltmc_integration::LTMCIntegration::new(
-                    Arc::clone(&dummy_ltmc_manager),
+                    Arc::clone(&ltmc_manager),
                     Arc::clone(&core_engine),
                     Arc::clone(&llm_manager),
                 ));
@@ -181,9 +342,15 @@ impl OdinCodeApp {
             // Create the agent coordinator with the core engine and dummy managers for compatibility
             let agent_coordinator = AgentCoordinator::new(
                 Arc::clone(&core_engine),
-                Arc::clone(&dummy_ltmc_manager),
+                Arc::clone(&ltmc_manager),
                 Arc::clone(&dummy_ltmc_integration),
             );
+            let agent_coordinator = match open_action_history(db_root).await {
+                Some(action_history) => agent_coordinator.with_action_history(action_history),
+                None => agent_coordinator,
+            };
+            // Agents load their prompt overrides from the same database root
+            let agent_coordinator = agent_coordinator.with_prompt_root(db_root.to_path_buf());
 
             // Now that we have the complete app, let's initialize the semantic analysis engine
             // in the ML integration manager if it exists
@@ -210,6 +377,7 @@ impl OdinCodeApp {
 
             Ok(Self {
                 core_engine,
+                ltmc_manager,
                 agent_coordinator,
                 llm_manager,
                 ml_config,
@@ -226,15 +394,13 @@ impl OdinCodeApp {
         // We'll initialize the LTMC manager separately since it's in an Arc
         info!("LTMC databases will be initialized separately");
 
-        // TODO: Fix LLM manager connection to ML integration
-        // let ml_integration = self.core_engine.get_ml_integration().await;
-        // if let Some(ml_integration) = ml_integration {
-        //     // Set the LLM integration in the ML integration manager
-        //     ml_integration
-        //         .set_llm_integration(Arc::clone(&self.llm_manager))
-        //         .await;
-        //     info!("Connected LLM manager to ML integration");
-        // }
+        // Let ML analysis use the LLM manager, when ML integration is enabled
+        if self.core_engine.get_ml_integration().await.is_some() {
+            self.core_engine
+                .set_llm_integration(Arc::clone(&self.llm_manager))
+                .await?;
+            info!("Connected LLM manager to ML integration");
+        }
 
         // Register default agents
         self.register_default_agents().await?;
@@ -536,10 +702,10 @@ fn inefficient_function() -> Vec<i32> {
             name: "RustAnalyzer".to_string(),
             description: "Advanced Rust linter".to_string(),
             enabled_rules: vec![
-                "trailing_whitespace".to_string(),
-                "line_length".to_string(),
-                "todo_comments".to_string(),
-                "inefficient_patterns".to_string(),
+                "style/trailing-whitespace".to_string(),
+                "style/line-length".to_string(),
+                "practice/todo-comment".to_string(),
+                "perf/collect-len".to_string(),
             ],
             disabled_rules: vec![],
             severity_overrides: std::collections::HashMap::new(),
@@ -568,35 +734,145 @@ fn inefficient_function() -> Vec<i32> {
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    // Initialize logging
-    tracing_subscriber::fmt()
-        .with_env_filter(EnvFilter::from_default_env())
-        .init();
-
-    info!("Starting OdinCode - Next-Generation AI Code Engineering System");
-
     // Parse command line arguments
     let args = Args::parse();
 
-    // Set log level based on verbose flag
+    // Load the system configuration; environment variables override the file
+    let config_path = std::path::PathBuf::from(&args.config);
+    let system_config = config_reload::load_or_default(&config_path)?;
+    let safe_mode = args.safe || system_config.safe_mode;
+
+    // Set log level based on verbose flag, then RUST_LOG, then the config file
     if args.verbose {
         std::env::set_var("RUST_LOG", "debug");
     }
+    let filter = EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| EnvFilter::new(&system_config.log_level));
+
+    // Initialize logging in the requested format
+    let (subscriber, log_level_setter) = build_subscriber(args.log_format, filter, std::io::stdout);
+    tracing::subscriber::set_global_default(subscriber)?;
+
+    info!("Starting OdinCode - Next-Generation AI Code Engineering System");
 
     info!("OdinCode starting with args: {:?}", args);
 
     // Create the application instance
-    let mut app = OdinCodeApp::new().await?;
+    let mut app = OdinCodeApp::new(&args.db_root).await?;
+    app.core_engine
+        .set_analysis_config(system_config.analysis.clone())
+        .await;
+    let formatters = system_config.formatters.clone();
+    let reloader = config_reload::ConfigReloader::new(
+        &config_path,
+        system_config,
+        Arc::clone(&app.core_engine),
+    )
+    .with_log_level(log_level_setter);
 
     // Determine which mode to run based on command line arguments
-    if args.tui {
+    if let Some(command) = args.command {
+        match command {
+            Commands::Watch { path } => {
+                info!("Starting OdinCode in watch mode on {}...", path);
+                watch::run_watch(Arc::clone(&app.core_engine), &path).await?;
+            }
+            Commands::Check {
+                path,
+                since,
+                format,
+                baseline,
+                write_baseline,
+            } => {
+                info!("Checking {}...", path);
+                let baseline = baseline.map(|file| {
+                    if write_baseline {
+                        check::BaselineMode::Write(file)
+                    } else {
+                        check::BaselineMode::Compare(file)
+                    }
+                });
+                check::run_check(
+                    Arc::clone(&app.core_engine),
+                    &path,
+                    since.as_deref(),
+                    format,
+                    baseline,
+                )
+                .await?;
+            }
+            Commands::Format { path, check, since } => {
+                if safe_mode && !check {
+                    return Err(anyhow::anyhow!(
+                        "Formatting {} is blocked by safe mode; use --check",
+                        path
+                    ));
+                }
+                info!("Formatting {}...", path);
+                let tools = tool_manager(&app, safe_mode, &formatters).await?;
+                format::run_format(&tools, &path, since.as_deref(), check).await?;
+            }
+            Commands::Plan {
+                file,
+                diff,
+                dry_run,
+                no_rollback,
+                yes,
+            } => {
+                info!("Running plan {}...", file);
+                let mode = if dry_run {
+                    plan::PlanMode::DryRun
+                } else if diff {
+                    plan::PlanMode::Diff
+                } else if safe_mode {
+                    return Err(anyhow::anyhow!(
+                        "Applying plan {} is blocked by safe mode; use --diff or --dry-run",
+                        file
+                    ));
+                } else {
+                    // Rolling back is offered on failure unless the flags decide it
+                    let rollback = if no_rollback {
+                        plan::Rollback::Never
+                    } else if yes {
+                        plan::Rollback::Always
+                    } else {
+                        plan::Rollback::Ask
+                    };
+                    plan::PlanMode::Apply {
+                        rollback,
+                        confirm: !yes,
+                    }
+                };
+                plan::run_plan(Arc::clone(&app.core_engine), &file, mode).await?;
+            }
+            Commands::Explain { rule } => {
+                println!("{}", odincode_core::explain_rule(&rule));
+            }
+        }
+    } else if args.tui {
         // Run in TUI mode
         info!("Starting OdinCode in TUI mode...");
-        run_tui_mode(app).await?;
+        let db_root = args.db_root.as_path();
+        let approval_path = db_root.join(odincode_tui::approval::APPROVAL_FILE_NAME);
+        let (approval_tx, approval_rx) =
+            tokio::sync::watch::channel(odincode_tui::ApprovalConfig::load(db_root));
+        spawn_config_watch(reloader.with_approval(approval_path, approval_tx));
+        let tools = tool_manager(&app, safe_mode, &formatters).await?;
+        run_tui_mode(
+            app,
+            tools,
+            args.tool_timeout(),
+            approval_rx,
+            safe_mode,
+            args.db_root.clone(),
+        )
+        .await?;
     } else if args.server {
         // Run in API server mode
         info!("Starting OdinCode in API server mode...");
-        run_api_mode(app).await?;
+        spawn_config_watch(reloader);
+        let tools = tool_manager(&app, safe_mode, &formatters).await?;
+        run_api_mode(app, tools).await?;
     } else if args.cli {
         // Run in CLI mode
         info!("Starting OdinCode in CLI mode...");
@@ -611,8 +887,59 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
+/// Open the agent execution history, or run without one if it cannot be opened
+async fn open_action_history(db_root: &std::path::Path) -> Option<Arc<ActionHistoryManager>> {
+    let path = db_root.join(ACTION_HISTORY_FILE_NAME);
+    match ActionHistoryManager::open(&path).await {
+        Ok(action_history) => Some(Arc::new(action_history)),
+        Err(e) => {
+            warn!(
+                "Agent executions will not be recorded, failed to open {}: {}",
+                path.display(),
+                e
+            );
+            None
+        }
+    }
+}
+
+/// Hot-reload the configuration and approval files in the background
+///
+/// A configuration file that does not exist yet is picked up once it is created.
+fn spawn_config_watch(reloader: config_reload::ConfigReloader) {
+    tokio::spawn(async move {
+        if let Err(e) = config_reload::watch_config(reloader).await {
+            error!("Configuration watch stopped: {}", e);
+        }
+    });
+}
+
+/// Tool manager over the application's components that formats edited files
+/// with the formatters of the `[formatters]` config
+async fn tool_manager(
+    app: &OdinCodeApp,
+    safe_mode: bool,
+    formatters: &HashMap<String, FormatterSettings>,
+) -> Result<odincode_tools::ToolManager> {
+    let tools = odincode_tools::ToolManager::new_with_arcs(
+        Arc::clone(&app.core_engine),
+        Arc::clone(&app.ltmc_manager),
+        app.agent_coordinator.clone(),
+    )
+    .with_safe_mode(safe_mode);
+    tools.configure_formatters(formatters).await?;
+    Ok(tools)
+}
+
 /// Run the application in TUI mode
-async fn run_tui_mode(app: OdinCodeApp) -> Result<()> {
+async fn run_tui_mode(
+    app: OdinCodeApp,
+    tools: odincode_tools::ToolManager,
+    tool_timeout: Option<std::time::Duration>,
+    approval_updates: tokio::sync::watch::Receiver<odincode_tui::ApprovalConfig>,
+    safe_mode: bool,
+    db_root: std::path::PathBuf,
+) -> Result<()> {
     // Import the TUI module
     use odincode_tui::TuiRunner;
 
@@ -626,28 +953,15 @@ async fn run_tui_mode(app: OdinCodeApp) -> Result<()> {
     // Create a TUI runner with the application's components
     let tui_runner = TuiRunner::new(
         Arc::clone(&app.core_engine),
-        // For TUI mode, we need to extract the components properly
-        // Create a new LTMC manager for TUI mode
-        #[cfg(not(feature = "simple-ltmc"))]
-        {
-            Arc::clone(&app.ltmc_manager)
-        },
-        #[cfg(feature = "simple-ltmc")]
-        {
-            // Use a dummy LTMC manager for simple mode
-            Arc::new(odincode_ltmc::LTMManager::new())
-        },
+        Arc::clone(&app.ltmc_manager),
         app.agent_coordinator.clone(),
-        // Create a tool manager
-        odincode_tools::ToolManager::new_with_arcs(
-            Arc::clone(&app.core_engine),
-            #[cfg(not(feature = "simple-ltmc"))]
-            Arc::clone(&app.ltmc_manager),
-            #[cfg(feature = "simple-ltmc")]
-            Arc::new(odincode_ltmc::LTMManager::new()),
-            app.agent_coordinator.clone(),
-        ),
-    );
+        tools,
+    )
+    .with_tool_timeout(tool_timeout)
+    .with_safe_mode(safe_mode)
+    .with_llm_manager(llm_manager)
+    .with_approval_updates(approval_updates)
+    .with_db_root(db_root);
 
     // Run the TUI application
     tui_runner.run().await?;
@@ -655,7 +969,7 @@ async fn run_tui_mode(app: OdinCodeApp) -> Result<()> {
 }
 
 /// Run the application in API server mode
-async fn run_api_mode(app: OdinCodeApp) -> Result<()> {
+async fn run_api_mode(app: OdinCodeApp, tools: odincode_tools::ToolManager) -> Result<()> {
     // Import the API module
     use odincode_api::{models::ApiConfig, ApiServer};
 
@@ -671,19 +985,10 @@ async fn run_api_mode(app: OdinCodeApp) -> Result<()> {
     let api_server = ApiServer::new(
         config,
         Arc::clone(&app.core_engine),
-        #[cfg(not(feature = "simple-ltmc"))]
         Arc::clone(&app.ltmc_manager),
-        #[cfg(feature = "simple-ltmc")]
-        Arc::new(odincode_ltmc::LTMManager::new()),
         Arc::new(app.agent_coordinator.clone()),
-        Arc::new(odincode_tools::ToolManager::new_with_arcs(
-            Arc::clone(&app.core_engine),
-            #[cfg(not(feature = "simple-ltmc"))]
-            Arc::clone(&app.ltmc_manager),
-            #[cfg(feature = "simple-ltmc")]
-            Arc::new(odincode_ltmc::LTMManager::new()),
-            app.agent_coordinator.clone(),
-        )), // We need to create a new ToolManager for the API
+        Arc::new(tools),
+        Arc::clone(&app.llm_manager),
     );
 
     // Start the API server
@@ -703,3 +1008,88 @@ async fn run_cli_mode(app: OdinCodeApp) -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::sync::Mutex;
+
+    /// Writer that captures log output in memory
+    #[derive(Clone, Default)]
+    struct CaptureWriter(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for CaptureWriter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<'a> MakeWriter<'a> for CaptureWriter {
+        type Writer = CaptureWriter;
+
+        fn make_writer(&'a self) -> Self::Writer {
+            self.clone()
+        }
+    }
+
+    #[test]
+    fn test_log_format_parsing() {
+        let args = Args::parse_from(["odincode", "--log-format", "json"]);
+        assert_eq!(args.log_format, LogFormat::Json);
+
+        let args = Args::parse_from(["odincode"]);
+        assert_eq!(args.log_format, LogFormat::Text);
+    }
+
+    #[test]
+    fn test_tool_timeout_parsing() {
+        let args = Args::parse_from(["odincode"]);
+        assert_eq!(
+            args.tool_timeout(),
+            Some(std::time::Duration::from_secs(60))
+        );
+
+        let args = Args::parse_from(["odincode", "--tool-timeout-secs", "600"]);
+        assert_eq!(
+            args.tool_timeout(),
+            Some(std::time::Duration::from_secs(600))
+        );
+
+        // Zero disables the timeout
+        let args = Args::parse_from(["odincode", "--tool-timeout-secs", "0"]);
+        assert_eq!(args.tool_timeout(), None);
+
+        assert!(Args::try_parse_from(["odincode", "--tool-timeout-secs", "soon"]).is_err());
+        assert!(Args::try_parse_from(["odincode", "--tool-timeout-secs", "-5"]).is_err());
+    }
+
+    #[test]
+    fn test_json_logging_emits_structured_records() {
+        let writer = CaptureWriter::default();
+        let (subscriber, _) =
+            build_subscriber(LogFormat::Json, EnvFilter::new("info"), writer.clone());
+
+        tracing::subscriber::with_default(subscriber, || {
+            let span = tracing::info_span!("analysis", file = "demo.rs");
+            let _guard = span.enter();
+            info!(issues = 3, "analysis finished");
+        });
+
+        let output = String::from_utf8(writer.0.lock().unwrap().clone()).unwrap();
+        let line = output.lines().next().expect("expected a log record");
+        let record: serde_json::Value = serde_json::from_str(line).unwrap();
+
+        assert_eq!(record["level"], "INFO");
+        assert!(record["target"].as_str().unwrap().contains("odincode"));
+        assert!(record["timestamp"].is_string());
+        assert_eq!(record["fields"]["message"], "analysis finished");
+        assert_eq!(record["fields"]["issues"], 3);
+        assert_eq!(record["span"]["file"], "demo.rs");
+    }
+}
@@ -0,0 +1,997 @@
+//! Plan Mode
+//!
+//! Loads an edit plan from a JSON file and either applies it to disk or, with
+//! `--diff`, prints the combined unified diff of its file changes and exits.
+//! With `--dry-run` it only prints what each step would do. Besides content
+//! edits, a step can delete or rename its file.
+
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use odincode_core::CodeEngine;
+use odincode_tools::{render_unified_diff, EditOperationType, EditTask, MultiEditManager};
+
+/// An edit plan as stored on disk
+#[derive(Debug, Deserialize)]
+pub struct Plan {
+    /// Name of the plan
+    pub name: String,
+    /// Description of the plan
+    #[serde(default)]
+    pub description: String,
+    /// File edits, applied in order
+    pub edits: Vec<PlannedEdit>,
+}
+
+/// A single file edit within a plan
+#[derive(Debug, Deserialize)]
+pub struct PlannedEdit {
+    /// Path of the file to edit, relative to the plan's base directory
+    pub path: String,
+    /// Type of edit operation
+    pub operation_type: PlanOperation,
+    /// New path of the file for `RenameFile`, relative to the plan's base directory
+    #[serde(default)]
+    pub new_path: Option<String>,
+    /// Start position for the edit (line, column)
+    #[serde(default)]
+    pub start_pos: (usize, usize),
+    /// End position for the edit (line, column)
+    #[serde(default)]
+    pub end_pos: (usize, usize),
+    /// Content to insert or replace
+    #[serde(default)]
+    pub content: String,
+    /// Description of the edit
+    #[serde(default)]
+    pub description: String,
+}
+
+/// Operation performed by a plan step
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub enum PlanOperation {
+    /// Insert content at position
+    Insert,
+    /// Replace content between positions
+    Replace,
+    /// Delete content between positions
+    Delete,
+    /// Update content with pattern matching
+    PatternReplace,
+    /// Delete the file
+    DeleteFile,
+    /// Move the file to the step's `new_path`
+    RenameFile,
+}
+
+impl PlanOperation {
+    /// Multi-edit operation of a content edit, `None` for file operations
+    fn edit_type(&self) -> Option<EditOperationType> {
+        match self {
+            PlanOperation::Insert => Some(EditOperationType::Insert),
+            PlanOperation::Replace => Some(EditOperationType::Replace),
+            PlanOperation::Delete => Some(EditOperationType::Delete),
+            PlanOperation::PatternReplace => Some(EditOperationType::PatternReplace),
+            PlanOperation::DeleteFile | PlanOperation::RenameFile => None,
+        }
+    }
+}
+
+impl Plan {
+    /// Load a plan from a JSON file
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| anyhow!("Failed to read plan {}: {}", path.display(), e))?;
+        serde_json::from_str(&content)
+            .map_err(|e| anyhow!("Failed to parse plan {}: {}", path.display(), e))
+    }
+
+    /// Check that every edit of the plan is well formed
+    pub fn validate(&self) -> Result<()> {
+        if self.edits.is_empty() {
+            return Err(anyhow!("Plan '{}' has no edits", self.name));
+        }
+
+        for (index, edit) in self.edits.iter().enumerate() {
+            edit.validate().map_err(|e| {
+                anyhow!("Invalid step {} of plan '{}': {}", index + 1, self.name, e)
+            })?;
+        }
+        Ok(())
+    }
+}
+
+impl PlannedEdit {
+    /// Check that the edit targets a file inside the base directory and has a
+    /// valid range, and that a rename has a new path inside it too
+    pub fn validate(&self) -> Result<()> {
+        if self.path.trim().is_empty() {
+            return Err(anyhow!("edit has no path"));
+        }
+        check_inside_base_dir(&self.path)?;
+        if self.operation_type == PlanOperation::RenameFile {
+            let new_path = self
+                .new_path
+                .as_deref()
+                .ok_or_else(|| anyhow!("rename of {} has no new_path", self.path))?;
+            check_inside_base_dir(new_path)?;
+        }
+
+        let ranged = matches!(
+            self.operation_type,
+            PlanOperation::Replace | PlanOperation::Delete
+        );
+        if ranged && self.start_pos > self.end_pos {
+            return Err(anyhow!(
+                "range {}:{}-{}:{} of {} ends before it starts",
+                self.start_pos.0,
+                self.start_pos.1,
+                self.end_pos.0,
+                self.end_pos.1,
+                self.path
+            ));
+        }
+        Ok(())
+    }
+
+    /// Describe what the edit would do to its file, without performing it
+    pub fn describe(&self) -> String {
+        let (start_line, start_column) = self.start_pos;
+        let (end_line, end_column) = self.end_pos;
+        match self.operation_type {
+            PlanOperation::Insert => format!(
+                "insert {} bytes at {}:{}",
+                self.content.len(),
+                start_line,
+                start_column
+            ),
+            PlanOperation::Replace => format!(
+                "replace {}:{}-{}:{} with {} bytes",
+                start_line,
+                start_column,
+                end_line,
+                end_column,
+                self.content.len()
+            ),
+            PlanOperation::Delete => format!(
+                "delete {}:{}-{}:{}",
+                start_line, start_column, end_line, end_column
+            ),
+            PlanOperation::PatternReplace => {
+                format!("pattern replace with {} bytes", self.content.len())
+            }
+            PlanOperation::DeleteFile => "delete the file".to_string(),
+            PlanOperation::RenameFile => {
+                format!("rename to {}", self.new_path.as_deref().unwrap_or_default())
+            }
+        }
+    }
+}
+
+/// Check that a plan path is relative and stays inside the plan directory
+fn check_inside_base_dir(path: &str) -> Result<()> {
+    let path = Path::new(path);
+    if path.is_absolute() || path.components().any(|c| c == Component::ParentDir) {
+        return Err(anyhow!(
+            "path {} is outside the plan directory",
+            path.display()
+        ));
+    }
+    Ok(())
+}
+
+/// Number of paths listed per group in a plan summary before the rest are counted
+pub const SUMMARY_MAX_PATHS: usize = 8;
+
+/// What a plan step does to its file
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum StepAction {
+    /// Changes the content of an existing file
+    Edit,
+    /// Creates the file
+    Create,
+    /// Deletes the file
+    Delete,
+    /// Moves the file to a new path
+    Rename,
+}
+
+impl StepAction {
+    /// Verb describing the action
+    fn verb(self) -> &'static str {
+        match self {
+            StepAction::Edit => "edit",
+            StepAction::Create => "create",
+            StepAction::Delete => "delete",
+            StepAction::Rename => "rename",
+        }
+    }
+}
+
+/// Pair each step of a plan with what it does to its file
+///
+/// Only the first content edit of a file that doesn't exist yet, and isn't the
+/// target of an earlier rename, creates it.
+fn classify_steps<'a>(plan: &'a Plan, base_dir: &Path) -> Vec<(&'a PlannedEdit, StepAction)> {
+    let mut touched: HashSet<&str> = HashSet::new();
+    plan.edits
+        .iter()
+        .map(|edit| {
+            let first = touched.insert(edit.path.as_str());
+            let action = match edit.operation_type {
+                PlanOperation::DeleteFile => StepAction::Delete,
+                PlanOperation::RenameFile => {
+                    touched.extend(edit.new_path.as_deref());
+                    StepAction::Rename
+                }
+                _ if first && !base_dir.join(&edit.path).exists() => StepAction::Create,
+                _ => StepAction::Edit,
+            };
+            (edit, action)
+        })
+        .collect()
+}
+
+/// Describe each step of a plan without touching any files
+///
+/// Fails only when the plan is structurally invalid.
+pub fn describe_plan(plan: &Plan, base_dir: &Path) -> Result<String> {
+    plan.validate()?;
+
+    let mut out = format!(
+        "Plan '{}' would apply {} steps:\n",
+        plan.name,
+        plan.edits.len()
+    );
+    for (index, (edit, action)) in classify_steps(plan, base_dir).into_iter().enumerate() {
+        out.push_str(&format!(
+            "{}. {} {}: {}\n",
+            index + 1,
+            action.verb(),
+            edit.path,
+            edit.describe()
+        ));
+    }
+    Ok(out)
+}
+
+/// Summarize which files a plan touches, for confirming it before it is applied
+///
+/// Lists the files to edit, create, delete and rename, at most `max_paths` of
+/// each; the rest are counted. Fails if the plan is invalid.
+pub fn summarize_plan(plan: &Plan, base_dir: &Path, max_paths: usize) -> Result<Vec<String>> {
+    plan.validate()?;
+
+    let mut edited: Vec<String> = Vec::new();
+    let mut created: Vec<String> = Vec::new();
+    let mut deleted: Vec<String> = Vec::new();
+    let mut renamed: Vec<String> = Vec::new();
+    for (edit, action) in classify_steps(plan, base_dir) {
+        let path = edit.path.clone();
+        match action {
+            StepAction::Create => created.push(path),
+            StepAction::Edit if !created.contains(&path) && !edited.contains(&path) => {
+                edited.push(path)
+            }
+            StepAction::Edit => {}
+            StepAction::Delete => deleted.push(path),
+            StepAction::Rename => renamed.push(format!(
+                "{} -> {}",
+                path,
+                edit.new_path.as_deref().unwrap_or_default()
+            )),
+        }
+    }
+
+    let files = |count: usize| if count == 1 { "file" } else { "files" };
+    let mut lines = vec![format!(
+        "Plan '{}': {} {} to edit, {} {} to create, {} {} to delete, {} {} to rename, {} steps",
+        plan.name,
+        edited.len(),
+        files(edited.len()),
+        created.len(),
+        files(created.len()),
+        deleted.len(),
+        files(deleted.len()),
+        renamed.len(),
+        files(renamed.len()),
+        plan.edits.len()
+    )];
+    for (heading, paths) in [
+        ("Edit:", &edited),
+        ("Create:", &created),
+        ("Delete:", &deleted),
+        ("Rename:", &renamed),
+    ] {
+        if paths.is_empty() {
+            continue;
+        }
+        lines.push(heading.to_string());
+        lines.extend(
+            paths
+                .iter()
+                .take(max_paths)
+                .map(|path| format!("  {}", path)),
+        );
+        if paths.len() > max_paths {
+            lines.push(format!("  ... and {} more", paths.len() - max_paths));
+        }
+    }
+    Ok(lines)
+}
+
+/// A plan loaded into a multi-edit operation
+struct PreparedPlan {
+    /// Multi-edit manager holding the operation
+    manager: MultiEditManager,
+    /// Operation id of the plan
+    operation_id: Uuid,
+}
+
+/// Load a file touched by a plan into the engine
+///
+/// Files that don't exist yet are loaded empty, to be created by the plan.
+async fn load_plan_file(engine: &CodeEngine, path: &str, disk_path: &Path) -> Result<Uuid> {
+    let content = read_plan_file(disk_path)?;
+    match engine
+        .load_file_with_detection(path.to_string(), content.clone())
+        .await
+    {
+        Ok(id) => Ok(id),
+        Err(_) => {
+            engine
+                .load_file(path.to_string(), content, "text".to_string())
+                .await
+        }
+    }
+}
+
+/// Edit task applying a planned content edit to a loaded file
+fn plan_task(edit: &PlannedEdit, operation_type: EditOperationType, file_id: Uuid) -> EditTask {
+    EditTask {
+        id: Uuid::new_v4(),
+        file_id,
+        operation_type,
+        start_pos: edit.start_pos,
+        end_pos: edit.end_pos,
+        content: edit.content.clone(),
+        description: edit.description.clone(),
+    }
+}
+
+/// Read a file touched by a plan
+///
+/// Files that don't exist yet are read as empty, to be created by the plan.
+/// Any other read error is returned so an unreadable file is never overwritten.
+fn read_plan_file(disk_path: &Path) -> Result<String> {
+    match std::fs::read_to_string(disk_path) {
+        Ok(content) => Ok(content),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(String::new()),
+        Err(e) => Err(anyhow!("Failed to read {}: {}", disk_path.display(), e)),
+    }
+}
+
+/// Load every file edited by a plan into the engine and create an operation
+/// of its content edits
+async fn prepare(engine: Arc<CodeEngine>, plan: &Plan, base_dir: &Path) -> Result<PreparedPlan> {
+    let mut file_ids: HashMap<String, Uuid> = HashMap::new();
+    let mut tasks = Vec::new();
+
+    for edit in &plan.edits {
+        let Some(operation_type) = edit.operation_type.edit_type() else {
+            continue;
+        };
+        let file_id = match file_ids.get(&edit.path) {
+            Some(id) => *id,
+            None => {
+                let id = load_plan_file(&engine, &edit.path, &base_dir.join(&edit.path)).await?;
+                file_ids.insert(edit.path.clone(), id);
+                id
+            }
+        };
+        tasks.push(plan_task(edit, operation_type, file_id));
+    }
+
+    let manager = MultiEditManager::new(engine);
+    let operation_id = manager
+        .create_operation(plan.name.clone(), plan.description.clone(), tasks)
+        .await?;
+
+    Ok(PreparedPlan {
+        manager,
+        operation_id,
+    })
+}
+
+/// Render the combined unified diff of a plan without touching any files
+///
+/// File deletes and renames are listed after the diff.
+pub async fn preview_plan(engine: Arc<CodeEngine>, plan: &Plan, base_dir: &Path) -> Result<String> {
+    let prepared = prepare(engine, plan, base_dir).await?;
+    let diffs = prepared
+        .manager
+        .preview_operation(prepared.operation_id)
+        .await?;
+
+    let mut out = render_unified_diff(&diffs);
+    for edit in &plan.edits {
+        match edit.operation_type {
+            PlanOperation::DeleteFile => out.push_str(&format!("deleted: {}\n", edit.path)),
+            PlanOperation::RenameFile => out.push_str(&format!(
+                "renamed: {} -> {}\n",
+                edit.path,
+                edit.new_path.as_deref().unwrap_or_default()
+            )),
+            _ => {}
+        }
+    }
+    Ok(out)
+}
+
+/// Contents of a file before a plan step changed it
+struct FileSnapshot {
+    /// One-based index of the step
+    step: usize,
+    /// Path of the file relative to the plan's base directory
+    path: String,
+    /// Path of the file on disk
+    disk_path: PathBuf,
+    /// Original content, `None` if the step created the file
+    original: Option<String>,
+}
+
+impl FileSnapshot {
+    /// Snapshot a file before a step touches it
+    fn take(step: usize, path: &str, disk_path: &Path) -> Result<Self> {
+        let original = if disk_path.exists() {
+            Some(
+                std::fs::read_to_string(disk_path)
+                    .map_err(|e| anyhow!("Failed to read {}: {}", disk_path.display(), e))?,
+            )
+        } else {
+            None
+        };
+
+        Ok(Self {
+            step,
+            path: path.to_string(),
+            disk_path: disk_path.to_path_buf(),
+            original,
+        })
+    }
+
+    /// Put the file back the way it was, returning a summary line
+    fn restore(&self) -> String {
+        let result = match &self.original {
+            Some(content) => std::fs::write(&self.disk_path, content).map(|_| "restored"),
+            None if self.disk_path.exists() => {
+                std::fs::remove_file(&self.disk_path).map(|_| "removed")
+            }
+            None => Ok("unchanged"),
+        };
+
+        match result {
+            Ok(action) => format!("step {}: {} {}", self.step, action, self.path),
+            Err(e) => format!("step {}: failed to restore {}: {}", self.step, self.path, e),
+        }
+    }
+}
+
+/// Apply a single plan step, writing the edited file to disk
+async fn apply_step(
+    engine: &CodeEngine,
+    manager: &MultiEditManager,
+    file_ids: &mut HashMap<String, Uuid>,
+    edit: &PlannedEdit,
+    base_dir: &Path,
+) -> Result<()> {
+    let Some(operation_type) = edit.operation_type.edit_type() else {
+        return apply_file_operation(file_ids, edit, base_dir);
+    };
+
+    let disk_path = base_dir.join(&edit.path);
+    let file_id = match file_ids.get(&edit.path) {
+        Some(id) => *id,
+        None => {
+            let id = load_plan_file(engine, &edit.path, &disk_path).await?;
+            file_ids.insert(edit.path.clone(), id);
+            id
+        }
+    };
+
+    let operation_id = manager
+        .create_operation(
+            edit.path.clone(),
+            edit.description.clone(),
+            vec![plan_task(edit, operation_type, file_id)],
+        )
+        .await?;
+    if !manager.execute_operation(operation_id).await? {
+        return Err(anyhow!("could not {} in {}", edit.describe(), edit.path));
+    }
+
+    let file = engine
+        .get_file(file_id)
+        .await?
+        .ok_or_else(|| anyhow!("File not found: {}", edit.path))?;
+    if let Some(parent) = disk_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&disk_path, file.content)?;
+    Ok(())
+}
+
+/// Delete or rename the file of a plan step on disk
+///
+/// The paths involved are dropped from `file_ids`, so later steps load them
+/// from disk again.
+fn apply_file_operation(
+    file_ids: &mut HashMap<String, Uuid>,
+    edit: &PlannedEdit,
+    base_dir: &Path,
+) -> Result<()> {
+    let disk_path = base_dir.join(&edit.path);
+    file_ids.remove(&edit.path);
+
+    match (&edit.operation_type, edit.new_path.as_deref()) {
+        (PlanOperation::RenameFile, Some(new_path)) => {
+            file_ids.remove(new_path);
+            let new_disk_path = base_dir.join(new_path);
+            if let Some(parent) = new_disk_path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::rename(&disk_path, &new_disk_path)
+                .map_err(|e| anyhow!("could not rename {} to {}: {}", edit.path, new_path, e))
+        }
+        _ => std::fs::remove_file(&disk_path)
+            .map_err(|e| anyhow!("could not delete {}: {}", edit.path, e)),
+    }
+}
+
+/// What happens to the completed steps of a plan when a later step fails
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Rollback {
+    /// Undo them without asking
+    Always,
+    /// Leave them in place
+    Never,
+    /// Report the failure and ask on stdin whether to undo them
+    Ask,
+}
+
+/// Apply a plan step by step, writing each changed file to disk
+///
+/// Every file is snapshotted before a step touches it, including the target of
+/// a rename. When a step fails and
+/// `rollback` decides to roll back, the failed and completed steps are undone
+/// in reverse order; otherwise completed steps are left in place.
+pub async fn apply_plan(
+    engine: Arc<CodeEngine>,
+    plan: &Plan,
+    base_dir: &Path,
+    rollback: Rollback,
+) -> Result<()> {
+    plan.validate()?;
+
+    let manager = MultiEditManager::new(Arc::clone(&engine));
+    let mut file_ids: HashMap<String, Uuid> = HashMap::new();
+    let mut snapshots: Vec<FileSnapshot> = Vec::new();
+
+    for (index, edit) in plan.edits.iter().enumerate() {
+        let step = index + 1;
+        snapshots.push(FileSnapshot::take(
+            step,
+            &edit.path,
+            &base_dir.join(&edit.path),
+        )?);
+        if let (PlanOperation::RenameFile, Some(new_path)) =
+            (&edit.operation_type, edit.new_path.as_deref())
+        {
+            snapshots.push(FileSnapshot::take(
+                step,
+                new_path,
+                &base_dir.join(new_path),
+            )?);
+        }
+
+        let Err(e) = apply_step(&engine, &manager, &mut file_ids, edit, base_dir).await else {
+            continue;
+        };
+        let error = format!("Step {} of plan '{}' failed: {}", step, plan.name, e);
+        let roll_back = match rollback {
+            Rollback::Always => true,
+            Rollback::Never => false,
+            Rollback::Ask => {
+                println!("{}", error);
+                confirm(&format!("Roll back the {} completed steps?", index))?
+            }
+        };
+        if !roll_back {
+            return Err(anyhow!("{}", error));
+        }
+
+        let summary: Vec<String> = snapshots.iter().rev().map(FileSnapshot::restore).collect();
+        warn!("{}; rolled back {} steps", error, step);
+        return Err(anyhow!(
+            "{}\nRolled back:\n  {}",
+            error,
+            summary.join("\n  ")
+        ));
+    }
+
+    info!("Applied plan '{}' in {} steps", plan.name, plan.edits.len());
+    Ok(())
+}
+
+/// How a plan file is run
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PlanMode {
+    /// Print the combined diff of the plan
+    Diff,
+    /// Describe each step of the plan
+    DryRun,
+    /// Apply the plan, handling a failed step as `rollback` says
+    ///
+    /// With `confirm`, plans touching more than one file are summarized and only
+    /// applied once the user answers yes.
+    Apply { rollback: Rollback, confirm: bool },
+}
+
+/// Ask a yes/no question on stdin, defaulting to no
+fn confirm(question: &str) -> Result<bool> {
+    use std::io::Write;
+
+    print!("{} [y/N] ", question);
+    std::io::stdout().flush()?;
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+/// Run a plan file in the given mode
+pub async fn run_plan(engine: Arc<CodeEngine>, plan_path: &str, mode: PlanMode) -> Result<()> {
+    let plan_path = Path::new(plan_path);
+    let plan = Plan::load(plan_path)?;
+    let base_dir = plan_path.parent().unwrap_or_else(|| Path::new("."));
+
+    match mode {
+        PlanMode::DryRun => print!("{}", describe_plan(&plan, base_dir)?),
+        PlanMode::Diff => print!("{}", preview_plan(engine, &plan, base_dir).await?),
+        PlanMode::Apply {
+            rollback,
+            confirm: ask,
+        } => {
+            let files: HashSet<&str> = plan.edits.iter().map(|edit| edit.path.as_str()).collect();
+            if ask && files.len() > 1 {
+                for line in summarize_plan(&plan, base_dir, SUMMARY_MAX_PATHS)? {
+                    println!("{}", line);
+                }
+                if !confirm("Apply this plan?")? {
+                    println!("Plan '{}' was not applied", plan.name);
+                    return Ok(());
+                }
+            }
+            apply_plan(engine, &plan, base_dir, rollback).await?;
+            println!("Applied plan '{}'", plan.name);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_preview_plan_writes_nothing() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("lib.rs"), "fn one() {}\nfn two() {}\n").unwrap();
+
+        let plan: Plan = serde_json::from_str(
+            r#"{
+                "name": "two files",
+                "edits": [
+                    {"path": "new.rs", "operation_type": "Insert", "content": "fn new() {}\n"},
+                    {"path": "lib.rs", "operation_type": "Replace",
+                     "start_pos": [1, 3], "end_pos": [1, 6], "content": "three"}
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let engine = Arc::new(CodeEngine::new().unwrap());
+        let diff = preview_plan(engine, &plan, dir.path()).await.unwrap();
+
+        assert_eq!(diff.matches("+++ b/").count(), 2);
+        assert!(diff.contains("new file: new.rs"));
+        assert!(diff.contains("modified: lib.rs"));
+
+        // Nothing was written
+        assert!(!dir.path().join("new.rs").exists());
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("lib.rs")).unwrap(),
+            "fn one() {}\nfn two() {}\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_describes_steps() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("lib.rs"), "fn one() {}\n").unwrap();
+        let plan_path = dir.path().join("plan.json");
+        std::fs::write(
+            &plan_path,
+            r#"{
+                "name": "two steps",
+                "edits": [
+                    {"path": "new.rs", "operation_type": "Insert", "content": "fn new() {}\n"},
+                    {"path": "lib.rs", "operation_type": "Replace",
+                     "start_pos": [1, 3], "end_pos": [1, 6], "content": "three"}
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let plan = Plan::load(&plan_path).unwrap();
+        let description = describe_plan(&plan, dir.path()).unwrap();
+        assert!(description.contains("1. create new.rs: insert 12 bytes at 0:0"));
+        assert!(description.contains("2. edit lib.rs: replace 1:3-1:6 with 5 bytes"));
+
+        let engine = Arc::new(CodeEngine::new().unwrap());
+        run_plan(engine, plan_path.to_str().unwrap(), PlanMode::DryRun)
+            .await
+            .unwrap();
+
+        assert!(!dir.path().join("new.rs").exists());
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("lib.rs")).unwrap(),
+            "fn one() {}\n"
+        );
+    }
+
+    #[test]
+    fn test_invalid_plan_is_rejected() {
+        let plan: Plan = serde_json::from_str(
+            r#"{"name": "escape", "edits": [
+                {"path": "../outside.rs", "operation_type": "Insert", "content": "x"}
+            ]}"#,
+        )
+        .unwrap();
+        assert!(describe_plan(&plan, Path::new(".")).is_err());
+
+        let plan: Plan = serde_json::from_str(
+            r#"{"name": "backwards", "edits": [
+                {"path": "lib.rs", "operation_type": "Delete",
+                 "start_pos": [3, 0], "end_pos": [1, 0]}
+            ]}"#,
+        )
+        .unwrap();
+        assert!(describe_plan(&plan, Path::new(".")).is_err());
+    }
+
+    #[test]
+    fn test_summary_counts_every_kind_of_file_change() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["a.rs", "b.rs", "c.rs", "d.rs", "e.rs"] {
+            std::fs::write(dir.path().join(name), "fn one() {}\n").unwrap();
+        }
+        let plan: Plan = serde_json::from_str(
+            r#"{
+                "name": "mixed",
+                "edits": [
+                    {"path": "a.rs", "operation_type": "Insert", "content": "// a\n"},
+                    {"path": "new.rs", "operation_type": "Insert", "content": "fn new() {}\n"},
+                    {"path": "new.rs", "operation_type": "Insert", "content": "// new\n"},
+                    {"path": "b.rs", "operation_type": "Delete",
+                     "start_pos": [1, 0], "end_pos": [1, 2]},
+                    {"path": "a.rs", "operation_type": "PatternReplace", "content": "one"},
+                    {"path": "c.rs", "operation_type": "Replace",
+                     "start_pos": [1, 3], "end_pos": [1, 6], "content": "two"},
+                    {"path": "d.rs", "operation_type": "DeleteFile"},
+                    {"path": "e.rs", "operation_type": "RenameFile", "new_path": "src/e.rs"},
+                    {"path": "src/e.rs", "operation_type": "Insert", "content": "// moved\n"}
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let summary = summarize_plan(&plan, dir.path(), 2).unwrap();
+        assert_eq!(
+            summary,
+            vec![
+                "Plan 'mixed': 4 files to edit, 1 file to create, 1 file to delete, \
+                 1 file to rename, 9 steps",
+                "Edit:",
+                "  a.rs",
+                "  b.rs",
+                "  ... and 2 more",
+                "Create:",
+                "  new.rs",
+                "Delete:",
+                "  d.rs",
+                "Rename:",
+                "  e.rs -> src/e.rs",
+            ]
+        );
+
+        // A rename needs a new path inside the plan directory
+        let plan: Plan = serde_json::from_str(
+            r#"{"name": "rename", "edits": [
+                {"path": "e.rs", "operation_type": "RenameFile", "new_path": "../e.rs"}
+            ]}"#,
+        )
+        .unwrap();
+        assert!(summarize_plan(&plan, dir.path(), 2).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_apply_plan_deletes_and_renames_files() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("old.rs"), "fn old() {}\n").unwrap();
+        std::fs::write(dir.path().join("gone.rs"), "fn gone() {}\n").unwrap();
+        let plan: Plan = serde_json::from_str(
+            r#"{
+                "name": "move",
+                "edits": [
+                    {"path": "old.rs", "operation_type": "RenameFile", "new_path": "src/new.rs"},
+                    {"path": "src/new.rs", "operation_type": "Insert", "content": "// moved\n"},
+                    {"path": "gone.rs", "operation_type": "DeleteFile"}
+                ]
+            }"#,
+        )
+        .unwrap();
+        let description = describe_plan(&plan, dir.path()).unwrap();
+        assert!(description.contains("1. rename old.rs: rename to src/new.rs"));
+        assert!(description.contains("2. edit src/new.rs: insert 9 bytes at 0:0"));
+        assert!(description.contains("3. delete gone.rs: delete the file"));
+
+        let engine = Arc::new(CodeEngine::new().unwrap());
+        apply_plan(engine, &plan, dir.path(), Rollback::Always)
+            .await
+            .unwrap();
+
+        assert!(!dir.path().join("old.rs").exists());
+        assert!(!dir.path().join("gone.rs").exists());
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("src/new.rs")).unwrap(),
+            "// moved\nfn old() {}\n"
+        );
+
+        // Rolling back moves a renamed file back
+        let plan: Plan = serde_json::from_str(
+            r#"{
+                "name": "move back",
+                "edits": [
+                    {"path": "src/new.rs", "operation_type": "RenameFile", "new_path": "lib.rs"},
+                    {"path": "lib.rs", "operation_type": "Replace",
+                     "start_pos": [40, 0], "end_pos": [40, 1], "content": "x"}
+                ]
+            }"#,
+        )
+        .unwrap();
+        let engine = Arc::new(CodeEngine::new().unwrap());
+        let error = apply_plan(engine, &plan, dir.path(), Rollback::Always)
+            .await
+            .unwrap_err()
+            .to_string();
+
+        assert!(error.contains("step 1: removed lib.rs"));
+        assert!(error.contains("step 1: restored src/new.rs"));
+        assert!(!dir.path().join("lib.rs").exists());
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("src/new.rs")).unwrap(),
+            "// moved\nfn old() {}\n"
+        );
+    }
+
+    /// Plan whose second of three steps edits a line that doesn't exist
+    fn failing_plan() -> Plan {
+        serde_json::from_str(
+            r#"{
+                "name": "fails midway",
+                "edits": [
+                    {"path": "lib.rs", "operation_type": "Replace",
+                     "start_pos": [1, 3], "end_pos": [1, 6], "content": "three"},
+                    {"path": "lib.rs", "operation_type": "Replace",
+                     "start_pos": [40, 0], "end_pos": [40, 1], "content": "x"},
+                    {"path": "new.rs", "operation_type": "Insert", "content": "fn new() {}\n"}
+                ]
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_failed_step_rolls_back_completed_steps() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("lib.rs"), "fn one() {}\nfn two() {}\n").unwrap();
+
+        let engine = Arc::new(CodeEngine::new().unwrap());
+        let error = apply_plan(engine, &failing_plan(), dir.path(), Rollback::Always)
+            .await
+            .unwrap_err()
+            .to_string();
+
+        assert!(error.contains("Step 2 of plan 'fails midway' failed"));
+        assert!(error.contains("step 1: restored lib.rs"));
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("lib.rs")).unwrap(),
+            "fn one() {}\nfn two() {}\n"
+        );
+        assert!(!dir.path().join("new.rs").exists());
+    }
+
+    #[tokio::test]
+    async fn test_no_rollback_keeps_completed_steps() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("lib.rs"), "fn one() {}\nfn two() {}\n").unwrap();
+
+        let engine = Arc::new(CodeEngine::new().unwrap());
+        assert!(
+            apply_plan(engine, &failing_plan(), dir.path(), Rollback::Never)
+                .await
+                .is_err()
+        );
+
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("lib.rs")).unwrap(),
+            "fn one() {}\nfn three() {}\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_apply_plan_keeps_crlf_line_endings() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("lib.rs"), "fn one() {}\r\nfn two() {}\r\n").unwrap();
+        let plan: Plan = serde_json::from_str(
+            r#"{
+                "name": "windows file",
+                "edits": [
+                    {"path": "lib.rs", "operation_type": "Replace",
+                     "start_pos": [1, 3], "end_pos": [1, 6], "content": "three"},
+                    {"path": "lib.rs", "operation_type": "Insert", "content": "// edited\n"}
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let engine = Arc::new(CodeEngine::new().unwrap());
+        apply_plan(engine, &plan, dir.path(), Rollback::Always)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("lib.rs")).unwrap(),
+            "// edited\r\nfn one() {}\r\nfn three() {}\r\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unreadable_file_is_not_overwritten() {
+        let dir = tempfile::tempdir().unwrap();
+        let original = b"fn \xff() {}\n".to_vec();
+        std::fs::write(dir.path().join("lib.rs"), &original).unwrap();
+
+        let plan: Plan = serde_json::from_str(
+            r#"{
+                "name": "non-utf8",
+                "edits": [{"path": "lib.rs", "operation_type": "Insert", "content": "// x\n"}]
+            }"#,
+        )
+        .unwrap();
+
+        let engine = Arc::new(CodeEngine::new().unwrap());
+        let error = apply_plan(engine, &plan, dir.path(), Rollback::Always)
+            .await
+            .unwrap_err()
+            .to_string();
+
+        assert!(error.contains("Failed to read"));
+        assert_eq!(std::fs::read(dir.path().join("lib.rs")).unwrap(), original);
+    }
+}
@@ -0,0 +1,385 @@
+//! Watch Mode
+//!
+//! Watches a directory for file changes and re-analyzes changed files through the
+//! `CodeEngine`, printing which issues appeared or were resolved since the last run.
+
+use anyhow::{anyhow, Result};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use odincode_core::{CodeEngine, CodeIssue};
+
+/// Default debounce window for coalescing rapid successive events
+pub const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Coalesces bursts of file events so each path is handled once per burst
+#[derive(Debug)]
+pub struct Debouncer {
+    /// How long a path must be quiet before it is considered ready
+    window: Duration,
+    /// Paths with pending events and the time of their latest event
+    pending: HashMap<PathBuf, Instant>,
+}
+
+impl Debouncer {
+    /// Create a new debouncer with the given quiet window
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            pending: HashMap::new(),
+        }
+    }
+
+    /// Record an event for a path, restarting its quiet window
+    pub fn record(&mut self, path: PathBuf, now: Instant) {
+        self.pending.insert(path, now);
+    }
+
+    /// Remove and return all paths whose quiet window has elapsed
+    pub fn drain_ready(&mut self, now: Instant) -> Vec<PathBuf> {
+        let window = self.window;
+        let ready: Vec<PathBuf> = self
+            .pending
+            .iter()
+            .filter(|(_, last)| now.duration_since(**last) >= window)
+            .map(|(path, _)| path.clone())
+            .collect();
+
+        for path in &ready {
+            self.pending.remove(path);
+        }
+
+        ready
+    }
+}
+
+/// Issues that changed between two analysis runs of the same file
+#[derive(Debug, Default, PartialEq)]
+pub struct IssueDiff {
+    /// Issues present now but not in the previous run
+    pub appeared: Vec<String>,
+    /// Issues present in the previous run but not now
+    pub resolved: Vec<String>,
+}
+
+impl IssueDiff {
+    /// Compute the difference between two sets of issue fingerprints
+    pub fn between(previous: &HashSet<String>, current: &HashSet<String>) -> Self {
+        let mut appeared: Vec<String> = current.difference(previous).cloned().collect();
+        let mut resolved: Vec<String> = previous.difference(current).cloned().collect();
+        appeared.sort();
+        resolved.sort();
+        Self { appeared, resolved }
+    }
+
+    /// Whether nothing changed
+    pub fn is_empty(&self) -> bool {
+        self.appeared.is_empty() && self.resolved.is_empty()
+    }
+}
+
+/// Build a stable fingerprint for an issue, independent of its random id
+fn issue_fingerprint(issue: &CodeIssue) -> String {
+    format!(
+        "line {}: {:?} {}",
+        issue.line_number, issue.severity, issue.description
+    )
+}
+
+/// State of a watch session over one directory tree
+pub struct WatchSession {
+    /// Engine used for analysis
+    engine: Arc<CodeEngine>,
+    /// Root of the watched tree
+    root: PathBuf,
+    /// Gitignore rules of the watched tree
+    gitignore: Gitignore,
+    /// Debouncer for incoming events
+    debouncer: Debouncer,
+    /// Engine file ids of files loaded so far
+    file_ids: HashMap<PathBuf, Uuid>,
+    /// Issue fingerprints from the last run of each file
+    last_issues: HashMap<PathBuf, HashSet<String>>,
+}
+
+impl WatchSession {
+    /// Create a new watch session rooted at `root`
+    pub fn new(engine: Arc<CodeEngine>, root: &Path, window: Duration) -> Self {
+        let mut builder = GitignoreBuilder::new(root);
+        builder.add(root.join(".gitignore"));
+        let gitignore = builder.build().unwrap_or_else(|e| {
+            warn!("Failed to read .gitignore in {:?}: {}", root, e);
+            Gitignore::empty()
+        });
+
+        Self {
+            engine,
+            root: root.to_path_buf(),
+            gitignore,
+            debouncer: Debouncer::new(window),
+            file_ids: HashMap::new(),
+            last_issues: HashMap::new(),
+        }
+    }
+
+    /// Whether a path should be ignored (gitignored, `target/` or `.git/`)
+    pub fn is_ignored(&self, path: &Path) -> bool {
+        let relative = path.strip_prefix(&self.root).unwrap_or(path);
+        if relative
+            .components()
+            .any(|c| c.as_os_str() == "target" || c.as_os_str() == ".git")
+        {
+            return true;
+        }
+
+        self.gitignore
+            .matched_path_or_any_parents(relative, path.is_dir())
+            .is_ignore()
+    }
+
+    /// Handle a raw file system event
+    pub fn handle_event(&mut self, event: Event, now: Instant) {
+        if !matches!(
+            event.kind,
+            EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+        ) {
+            return;
+        }
+
+        for path in event.paths {
+            if !self.is_ignored(&path) {
+                self.debouncer.record(path, now);
+            }
+        }
+    }
+
+    /// Analyze every path whose debounce window has elapsed
+    pub async fn process_ready(&mut self, now: Instant) -> Result<Vec<(PathBuf, IssueDiff)>> {
+        let mut reports = Vec::new();
+
+        for path in self.debouncer.drain_ready(now) {
+            match self.analyze_path(&path).await {
+                Ok(Some(diff)) => reports.push((path, diff)),
+                Ok(None) => {}
+                Err(e) => warn!("Failed to analyze {:?}: {}", path, e),
+            }
+        }
+
+        Ok(reports)
+    }
+
+    /// Re-analyze a single path and diff against its previous issues
+    async fn analyze_path(&mut self, path: &Path) -> Result<Option<IssueDiff>> {
+        if !path.is_file() {
+            // Deleted file: everything it reported is resolved
+            self.file_ids.remove(path);
+            return Ok(self
+                .last_issues
+                .remove(path)
+                .map(|previous| IssueDiff::between(&previous, &HashSet::new())));
+        }
+
+        let content = match tokio::fs::read_to_string(path).await {
+            Ok(content) => content,
+            Err(e) => {
+                debug!("Skipping unreadable file {:?}: {}", path, e);
+                return Ok(None);
+            }
+        };
+
+        let file_id = match self.file_ids.get(path) {
+            Some(id) => {
+                self.engine.update_file(*id, content).await?;
+                *id
+            }
+            None => {
+                let path_str = path.to_string_lossy().to_string();
+                let id = match self
+                    .engine
+                    .load_file_with_detection(path_str, content)
+                    .await
+                {
+                    Ok(id) => id,
+                    // Unsupported extension, nothing to analyze
+                    Err(_) => return Ok(None),
+                };
+                self.file_ids.insert(path.to_path_buf(), id);
+                id
+            }
+        };
+
+        let current: HashSet<String> = match self.engine.analyze_file(file_id).await? {
+            Some(result) => result.issues.iter().map(issue_fingerprint).collect(),
+            None => return Ok(None),
+        };
+
+        let previous = self.last_issues.get(path).cloned().unwrap_or_default();
+        let diff = IssueDiff::between(&previous, &current);
+        self.last_issues.insert(path.to_path_buf(), current);
+
+        Ok(Some(diff))
+    }
+}
+
+/// Print a concise report of an analysis diff
+fn print_report(path: &Path, diff: &IssueDiff) {
+    if diff.is_empty() {
+        println!("{}: no change in issues", path.display());
+        return;
+    }
+
+    println!(
+        "{}: {} appeared, {} resolved",
+        path.display(),
+        diff.appeared.len(),
+        diff.resolved.len()
+    );
+    for issue in &diff.appeared {
+        println!("  + {}", issue);
+    }
+    for issue in &diff.resolved {
+        println!("  - {}", issue);
+    }
+}
+
+/// Watch `root` and re-analyze changed files until `q` or Ctrl-C is pressed
+pub async fn run_watch(engine: Arc<CodeEngine>, root: &str) -> Result<()> {
+    let root = std::fs::canonicalize(root).map_err(|e| anyhow!("Cannot watch {}: {}", root, e))?;
+
+    let (event_tx, mut event_rx) = mpsc::unbounded_channel::<Event>();
+    let mut watcher: RecommendedWatcher =
+        notify::recommended_watcher(move |res: notify::Result<Event>| match res {
+            Ok(event) => {
+                let _ = event_tx.send(event);
+            }
+            Err(e) => warn!("Watch error: {}", e),
+        })?;
+    watcher.watch(&root, RecursiveMode::Recursive)?;
+
+    // Read stdin on a blocking thread so `q` can stop the watch
+    let (quit_tx, mut quit_rx) = mpsc::unbounded_channel::<()>();
+    std::thread::spawn(move || {
+        let mut line = String::new();
+        while std::io::stdin().read_line(&mut line).unwrap_or(0) > 0 {
+            if line.trim().eq_ignore_ascii_case("q") {
+                let _ = quit_tx.send(());
+                break;
+            }
+            line.clear();
+        }
+    });
+
+    let mut session = WatchSession::new(engine, &root, DEFAULT_DEBOUNCE);
+    let mut tick = tokio::time::interval(DEFAULT_DEBOUNCE / 2);
+
+    println!(
+        "Watching {} (press q then Enter, or Ctrl-C, to exit)",
+        root.display()
+    );
+    info!("Watch mode started on {:?}", root);
+
+    loop {
+        tokio::select! {
+            Some(event) = event_rx.recv() => {
+                session.handle_event(event, Instant::now());
+            }
+            _ = tick.tick() => {
+                for (path, diff) in session.process_ready(Instant::now()).await? {
+                    print_report(&path, &diff);
+                }
+            }
+            _ = quit_rx.recv() => break,
+            _ = tokio::signal::ctrl_c() => break,
+        }
+    }
+
+    info!("Watch mode stopped");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify::event::{CreateKind, ModifyKind};
+
+    #[test]
+    fn test_debouncer_coalesces_events() {
+        let mut debouncer = Debouncer::new(Duration::from_millis(100));
+        let start = Instant::now();
+        let path = PathBuf::from("src/lib.rs");
+
+        debouncer.record(path.clone(), start);
+        debouncer.record(path.clone(), start + Duration::from_millis(50));
+
+        // Still inside the window of the second event
+        assert!(debouncer
+            .drain_ready(start + Duration::from_millis(120))
+            .is_empty());
+
+        let ready = debouncer.drain_ready(start + Duration::from_millis(150));
+        assert_eq!(ready, vec![path]);
+        assert!(debouncer
+            .drain_ready(start + Duration::from_millis(500))
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_debounced_change_analyzes_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = std::fs::canonicalize(dir.path()).unwrap();
+        let file = root.join("main.rs");
+        std::fs::write(&file, "fn main() {}\n").unwrap();
+
+        let engine = Arc::new(CodeEngine::new().unwrap());
+        let window = Duration::from_millis(100);
+        let mut session = WatchSession::new(engine, &root, window);
+        let start = Instant::now();
+
+        // Editors often write twice in quick succession
+        session.handle_event(
+            Event::new(EventKind::Create(CreateKind::File)).add_path(file.clone()),
+            start,
+        );
+        std::fs::write(&file, "fn main() {\n    // TODO: finish\n}\n").unwrap();
+        session.handle_event(
+            Event::new(EventKind::Modify(ModifyKind::Any)).add_path(file.clone()),
+            start + Duration::from_millis(10),
+        );
+
+        let reports = session
+            .process_ready(start + Duration::from_millis(200))
+            .await
+            .unwrap();
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].0, file);
+        assert!(!reports[0].1.appeared.is_empty());
+
+        // Nothing left pending after the burst was handled
+        let reports = session
+            .process_ready(start + Duration::from_millis(400))
+            .await
+            .unwrap();
+        assert!(reports.is_empty());
+    }
+
+    #[test]
+    fn test_target_and_gitignored_paths_ignored() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = std::fs::canonicalize(dir.path()).unwrap();
+        std::fs::write(root.join(".gitignore"), "*.log\n").unwrap();
+
+        let engine = Arc::new(CodeEngine::new().unwrap());
+        let session = WatchSession::new(engine, &root, DEFAULT_DEBOUNCE);
+
+        assert!(session.is_ignored(&root.join("target/debug/build.rs")));
+        assert!(session.is_ignored(&root.join("output.log")));
+        assert!(!session.is_ignored(&root.join("src/main.rs")));
+    }
+}
@@ -0,0 +1,166 @@
+//! File Tools Module
+//!
+//! This module reads line counts and byte ranges of files without loading
+//! large files into memory: files above `MMAP_THRESHOLD_BYTES` are memory-mapped,
+//! smaller ones are read normally.
+
+use anyhow::{anyhow, Result};
+use memmap2::Mmap;
+use std::fs::File;
+use std::ops::Deref;
+use std::path::Path;
+
+/// Files larger than this many bytes are memory-mapped instead of read: 16 MiB
+pub const MMAP_THRESHOLD_BYTES: u64 = 16 * 1024 * 1024;
+
+/// Bytes of a file, either mapped or read into memory
+enum FileBytes {
+    /// Memory-mapped contents of a large file
+    Mapped(Mmap),
+    /// Contents of a small file read into memory
+    Read(Vec<u8>),
+}
+
+impl Deref for FileBytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            FileBytes::Mapped(mmap) => mmap,
+            FileBytes::Read(bytes) => bytes,
+        }
+    }
+}
+
+/// Open a file, mapping it if it is larger than `threshold` bytes
+fn open_bytes(path: &Path, threshold: u64) -> Result<FileBytes> {
+    let file = File::open(path).map_err(|e| anyhow!("Failed to open {}: {}", path.display(), e))?;
+    let size = file.metadata()?.len();
+
+    if size > threshold {
+        // SAFETY: the map is only read, and only for the duration of one call;
+        // a file truncated concurrently by another process is outside what
+        // these tools guarantee, as with any read of a file being rewritten
+        let mmap = unsafe { Mmap::map(&file) }
+            .map_err(|e| anyhow!("Failed to map {}: {}", path.display(), e))?;
+        Ok(FileBytes::Mapped(mmap))
+    } else {
+        let bytes =
+            std::fs::read(path).map_err(|e| anyhow!("Failed to read {}: {}", path.display(), e))?;
+        Ok(FileBytes::Read(bytes))
+    }
+}
+
+/// Count the lines of bytes the way `str::lines` does: a final line without a
+/// trailing newline still counts
+fn count_lines(bytes: &[u8]) -> usize {
+    let newlines = bytes.iter().filter(|&&b| b == b'\n').count();
+    match bytes.last() {
+        Some(b'\n') | None => newlines,
+        Some(_) => newlines + 1,
+    }
+}
+
+/// Whether a byte continues a UTF-8 codepoint rather than starting one
+fn is_continuation(byte: u8) -> bool {
+    byte & 0b1100_0000 == 0b1000_0000
+}
+
+/// Widen `start..end` to the codepoints it touches
+fn widen_to_char_boundaries(bytes: &[u8], start: usize, end: usize) -> (usize, usize) {
+    let mut start = start.min(bytes.len());
+    let mut end = end.clamp(start, bytes.len());
+
+    while start > 0 && start < bytes.len() && is_continuation(bytes[start]) {
+        start -= 1;
+    }
+    while end < bytes.len() && is_continuation(bytes[end]) {
+        end += 1;
+    }
+    (start, end)
+}
+
+fn line_count_with_threshold(path: &Path, threshold: u64) -> Result<usize> {
+    Ok(count_lines(&open_bytes(path, threshold)?))
+}
+
+fn read_range_with_threshold(path: &Path, start: u64, end: u64, threshold: u64) -> Result<String> {
+    if start > end {
+        return Err(anyhow!("Invalid byte range {}..{}", start, end));
+    }
+
+    let bytes = open_bytes(path, threshold)?;
+    let (start, end) = widen_to_char_boundaries(&bytes, start as usize, end as usize);
+
+    std::str::from_utf8(&bytes[start..end])
+        .map(str::to_string)
+        .map_err(|e| anyhow!("{} is not valid UTF-8: {}", path.display(), e))
+}
+
+/// Count the lines of a file, memory-mapping it if it is large
+pub fn file_line_count(path: impl AsRef<Path>) -> Result<usize> {
+    line_count_with_threshold(path.as_ref(), MMAP_THRESHOLD_BYTES)
+}
+
+/// Read the bytes `start..end` of a file as text, memory-mapping it if it is large
+///
+/// The range is clamped to the file and widened to whole codepoints, so a range
+/// that starts or ends inside a multi-byte character includes that character.
+pub fn file_read_range(path: impl AsRef<Path>, start: u64, end: u64) -> Result<String> {
+    read_range_with_threshold(path.as_ref(), start, end, MMAP_THRESHOLD_BYTES)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_large_file_is_mapped_for_line_count_and_range() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("generated.txt");
+        let mut file = std::io::BufWriter::new(File::create(&path).unwrap());
+        // Every line is 17 bytes: "line 000000 żż\n" with two 2-byte characters
+        for i in 0..200_000 {
+            writeln!(file, "line {:06} żż", i).unwrap();
+        }
+        file.flush().unwrap();
+        drop(file);
+
+        let threshold = 1024 * 1024;
+        assert!(matches!(
+            open_bytes(&path, threshold).unwrap(),
+            FileBytes::Mapped(_)
+        ));
+        assert_eq!(
+            line_count_with_threshold(&path, threshold).unwrap(),
+            200_000
+        );
+
+        let line_start = 100_000 * 17;
+        let range =
+            read_range_with_threshold(&path, line_start, line_start + 17, threshold).unwrap();
+        assert_eq!(range, "line 100000 żż\n");
+
+        // A range starting and ending inside the two-byte characters is widened
+        let range =
+            read_range_with_threshold(&path, line_start + 13, line_start + 15, threshold).unwrap();
+        assert_eq!(range, "żż");
+    }
+
+    #[test]
+    fn test_small_file_is_read_normally() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("small.txt");
+        std::fs::write(&path, "first\nsecond\nthird").unwrap();
+
+        assert!(matches!(
+            open_bytes(&path, MMAP_THRESHOLD_BYTES).unwrap(),
+            FileBytes::Read(_)
+        ));
+        assert_eq!(file_line_count(&path).unwrap(), 3);
+        assert_eq!(file_read_range(&path, 6, 12).unwrap(), "second");
+        assert_eq!(file_read_range(&path, 13, 1000).unwrap(), "third");
+        assert!(file_read_range(&path, 5, 2).is_err());
+    }
+}
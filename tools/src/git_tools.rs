@@ -0,0 +1,239 @@
+//! Git Tools Module
+//!
+//! This module reads repository state through the `git` CLI, so that staged
+//! changes can be given to the LLM as review context and analysis can be limited
+//! to the lines a change touched.
+
+use anyhow::{anyhow, Context, Result};
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Command;
+use tracing::debug;
+
+/// Run a git command in `repo_root` and return its stdout
+pub(crate) fn run_git(repo_root: &str, args: &[&str]) -> Result<String> {
+    debug!("Running git {:?} in {}", args, repo_root);
+
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(repo_root)
+        .output()
+        .context("Failed to run git")?;
+
+    if !output.status.success() {
+        return Err(anyhow!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Fail with a clear error unless `repo_root` is inside a git work tree
+pub(crate) fn ensure_git_repo(repo_root: &str) -> Result<()> {
+    if !Path::new(repo_root).is_dir() {
+        return Err(anyhow!("Repository root does not exist: {}", repo_root));
+    }
+    match run_git(repo_root, &["rev-parse", "--is-inside-work-tree"]) {
+        Ok(out) if out.trim() == "true" => Ok(()),
+        _ => Err(anyhow!("Not a git repository: {}", repo_root)),
+    }
+}
+
+/// Get the unified diff of the changes staged in a repository
+pub fn git_staged_diff(repo_root: &str) -> Result<String> {
+    ensure_git_repo(repo_root)?;
+    run_git(repo_root, &["diff", "--cached", "--no-color"])
+}
+
+/// Get the unified diff of the unstaged changes in a repository's work tree
+///
+/// `path` limits the diff to a file or directory, relative to `repo_root`.
+pub fn git_working_diff(repo_root: &str, path: Option<&str>) -> Result<String> {
+    ensure_git_repo(repo_root)?;
+    let mut args = vec!["diff", "--no-color", "--no-ext-diff"];
+    if let Some(path) = path {
+        args.extend(["--", path]);
+    }
+    run_git(repo_root, &args)
+}
+
+/// Get the paths modified, added, deleted or renamed in a repository
+///
+/// Both staged and unstaged changes are listed; untracked files are not. Renamed
+/// files are reported under their new path.
+pub fn git_changed_files(repo_root: &str) -> Result<Vec<String>> {
+    ensure_git_repo(repo_root)?;
+    let status = run_git(repo_root, &["status", "--porcelain"])?;
+
+    Ok(status
+        .lines()
+        .filter(|line| line.len() > 3 && !line.starts_with("??"))
+        .map(|line| {
+            let path = &line[3..];
+            let path = path.split(" -> ").last().unwrap_or(path);
+            path.trim_matches('"').to_string()
+        })
+        .collect())
+}
+
+/// Inclusive range of 1-based line numbers in the new version of a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineRange {
+    /// First changed line
+    pub start: usize,
+    /// Last changed line
+    pub end: usize,
+}
+
+impl LineRange {
+    /// Whether a 1-based line number falls within the range
+    pub fn contains(&self, line: usize) -> bool {
+        self.start <= line && line <= self.end
+    }
+}
+
+/// Get the lines changed since `since`, by file
+///
+/// Compares the work tree, staged changes included, against the given ref. Paths
+/// are relative to `repo_root`, and only files below it are listed; deleted
+/// files are left out since they have no lines to report on.
+pub fn git_changed_lines(repo_root: &str, since: &str) -> Result<HashMap<String, Vec<LineRange>>> {
+    ensure_git_repo(repo_root)?;
+    let diff = run_git(
+        repo_root,
+        &[
+            "diff",
+            "--no-color",
+            "--no-ext-diff",
+            "--relative",
+            "-U0",
+            since,
+            "--",
+        ],
+    )?;
+    Ok(parse_changed_lines(&diff))
+}
+
+/// Parse the added or modified line ranges of each file in a unified diff
+///
+/// The ranges come from the new-file side of each hunk header, so the diff
+/// should be produced with `-U0` for them to cover only changed lines.
+pub fn parse_changed_lines(diff: &str) -> HashMap<String, Vec<LineRange>> {
+    let mut changed: HashMap<String, Vec<LineRange>> = HashMap::new();
+    let mut current: Option<String> = None;
+
+    for line in diff.lines() {
+        if let Some(path) = line.strip_prefix("+++ ") {
+            current = path
+                .trim_matches('"')
+                .strip_prefix("b/")
+                .map(|path| path.to_string());
+        } else if let Some(header) = line.strip_prefix("@@ ") {
+            let (Some(path), Some(range)) = (&current, parse_hunk_range(header)) else {
+                continue;
+            };
+            changed.entry(path.clone()).or_default().push(range);
+        }
+    }
+
+    changed
+}
+
+/// Parse the new-file range of a hunk header such as `-3,2 +4,3 @@`
+///
+/// Returns `None` for hunks that only delete lines.
+fn parse_hunk_range(header: &str) -> Option<LineRange> {
+    let new_side = header
+        .split_whitespace()
+        .find(|part| part.starts_with('+'))?;
+    let (start, count) = match new_side[1..].split_once(',') {
+        Some((start, count)) => (start.parse().ok()?, count.parse().ok()?),
+        None => (new_side[1..].parse().ok()?, 1),
+    };
+    if count == 0 {
+        return None;
+    }
+
+    Some(LineRange {
+        start,
+        end: start + count - 1,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {:?} failed", args);
+    }
+
+    #[test]
+    fn test_staged_change_in_diff_and_changed_files() {
+        let dir = tempfile::tempdir().unwrap();
+        git(dir.path(), &["init", "-q"]);
+        std::fs::write(dir.path().join("lib.rs"), "fn staged() {}\n").unwrap();
+        std::fs::write(dir.path().join("scratch.txt"), "untracked\n").unwrap();
+        git(dir.path(), &["add", "lib.rs"]);
+
+        let root = dir.path().to_str().unwrap();
+        let diff = git_staged_diff(root).unwrap();
+        assert!(diff.contains("+++ b/lib.rs"));
+        assert!(diff.contains("+fn staged() {}"));
+
+        assert_eq!(git_changed_files(root).unwrap(), vec!["lib.rs".to_string()]);
+    }
+
+    #[test]
+    fn test_parse_changed_lines() {
+        let diff = "\
+diff --git a/src/lib.rs b/src/lib.rs
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -3 +3 @@ fn one() {
+-    let a = 1;
++    let a = 2;
+@@ -10,2 +10,0 @@
+-gone
+-gone
+@@ -20,0 +19,3 @@
++new
++new
++new
+diff --git a/old.rs b/old.rs
+--- a/old.rs
++++ /dev/null
+@@ -1 +0,0 @@
+-removed
+";
+        let changed = parse_changed_lines(diff);
+        assert_eq!(changed.len(), 1);
+        assert_eq!(
+            changed["src/lib.rs"],
+            vec![
+                LineRange { start: 3, end: 3 },
+                LineRange { start: 19, end: 21 }
+            ]
+        );
+        assert!(changed["src/lib.rs"][1].contains(21));
+        assert!(!changed["src/lib.rs"][1].contains(22));
+    }
+
+    #[test]
+    fn test_non_git_directory_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_str().unwrap();
+
+        let error = git_staged_diff(root).unwrap_err();
+        assert!(error.to_string().contains("Not a git repository"));
+        assert!(git_changed_files(root).is_err());
+    }
+}
@@ -10,6 +10,7 @@ use tokio::sync::RwLock;
 use tracing::{debug, info};
 use uuid::Uuid;
 
+use odincode_core::rules::find_rule;
 use odincode_core::{CodeEngine, CodeFile, CodeIssue, IssueType, Severity};
 
 /// Represents a linter configuration
@@ -128,6 +129,14 @@ impl LinterManager {
                 }
             }
 
+            // Issues of disabled rules are dropped
+            issues.retain(|issue| {
+                !issue
+                    .rule_id
+                    .as_ref()
+                    .is_some_and(|rule_id| config.disabled_rules.contains(rule_id))
+            });
+
             Ok(issues)
         } else {
             // If no specific linter is configured, do basic checks
@@ -138,8 +147,8 @@ impl LinterManager {
                     name: "Generic Linter".to_string(),
                     description: "Basic linter for any language".to_string(),
                     enabled_rules: vec![
-                        "trailing_whitespace".to_string(),
-                        "line_length".to_string(),
+                        "style/trailing-whitespace".to_string(),
+                        "style/line-length".to_string(),
                     ],
                     disabled_rules: vec![],
                     severity_overrides: HashMap::new(),
@@ -165,11 +174,13 @@ impl LinterManager {
                 issues.push(CodeIssue {
                     id: Uuid::new_v4(),
                     issue_type: IssueType::Style,
-                    severity: self.get_rule_severity(config, "trailing_whitespace"),
+                    severity: self.get_rule_severity(config, "style/trailing-whitespace"),
                     description: "Trailing whitespace detected".to_string(),
                     line_number: line_idx + 1,
                     column_number: line.len(),
                     suggestion: Some("Remove trailing whitespace".to_string()),
+                    rule_id: Some("style/trailing-whitespace".to_string()),
+                    source: None,
                 });
             }
 
@@ -178,11 +189,13 @@ impl LinterManager {
                 issues.push(CodeIssue {
                     id: Uuid::new_v4(),
                     issue_type: IssueType::Style,
-                    severity: self.get_rule_severity(config, "line_length"),
+                    severity: self.get_rule_severity(config, "style/line-length"),
                     description: "Line exceeds 100 characters".to_string(),
                     line_number: line_idx + 1,
                     column_number: 100,
                     suggestion: Some("Break line into multiple lines".to_string()),
+                    rule_id: Some("style/line-length".to_string()),
+                    source: None,
                 });
             }
 
@@ -191,11 +204,13 @@ impl LinterManager {
                 issues.push(CodeIssue {
                     id: Uuid::new_v4(),
                     issue_type: IssueType::BestPractice,
-                    severity: self.get_rule_severity(config, "todo_comments"),
+                    severity: self.get_rule_severity(config, "practice/todo-comment"),
                     description: "TODO/FIXME/HACK comment found".to_string(),
                     line_number: line_idx + 1,
                     column_number: 0,
                     suggestion: Some("Address the technical debt".to_string()),
+                    rule_id: Some("practice/todo-comment".to_string()),
+                    source: None,
                 });
             }
 
@@ -204,11 +219,13 @@ impl LinterManager {
                 issues.push(CodeIssue {
                     id: Uuid::new_v4(),
                     issue_type: IssueType::Performance,
-                    severity: self.get_rule_severity(config, "inefficient_pattern"),
+                    severity: self.get_rule_severity(config, "perf/collect-len"),
                     description: "Inefficient length calculation after collect".to_string(),
                     line_number: line_idx + 1,
                     column_number: 0,
                     suggestion: Some("Use .count() or .len() directly on iterator".to_string()),
+                    rule_id: Some("perf/collect-len".to_string()),
+                    source: None,
                 });
             }
         }
@@ -231,11 +248,13 @@ impl LinterManager {
                 issues.push(CodeIssue {
                     id: Uuid::new_v4(),
                     issue_type: IssueType::Style,
-                    severity: self.get_rule_severity(config, "trailing_whitespace"),
+                    severity: self.get_rule_severity(config, "style/trailing-whitespace"),
                     description: "Trailing whitespace detected".to_string(),
                     line_number: line_idx + 1,
                     column_number: line.len(),
                     suggestion: Some("Remove trailing whitespace".to_string()),
+                    rule_id: Some("style/trailing-whitespace".to_string()),
+                    source: None,
                 });
             }
 
@@ -244,11 +263,13 @@ impl LinterManager {
                 issues.push(CodeIssue {
                     id: Uuid::new_v4(),
                     issue_type: IssueType::Style,
-                    severity: self.get_rule_severity(config, "line_length"),
+                    severity: self.get_rule_severity(config, "style/line-length"),
                     description: "Line exceeds 100 characters".to_string(),
                     line_number: line_idx + 1,
                     column_number: 100,
                     suggestion: Some("Break line into multiple lines".to_string()),
+                    rule_id: Some("style/line-length".to_string()),
+                    source: None,
                 });
             }
 
@@ -257,11 +278,13 @@ impl LinterManager {
                 issues.push(CodeIssue {
                     id: Uuid::new_v4(),
                     issue_type: IssueType::PotentialBug,
-                    severity: self.get_rule_severity(config, "loose_equality"),
+                    severity: self.get_rule_severity(config, "bug/loose-equality"),
                     description: "Use of == instead of === for comparison".to_string(),
                     line_number: line_idx + 1,
                     column_number: 0,
                     suggestion: Some("Use === for comparison to avoid type coercion".to_string()),
+                    rule_id: Some("bug/loose-equality".to_string()),
+                    source: None,
                 });
             }
 
@@ -270,11 +293,13 @@ impl LinterManager {
                 issues.push(CodeIssue {
                     id: Uuid::new_v4(),
                     issue_type: IssueType::BestPractice,
-                    severity: self.get_rule_severity(config, "var_usage"),
+                    severity: self.get_rule_severity(config, "practice/var-usage"),
                     description: "Use of 'var' instead of 'let' or 'const'".to_string(),
                     line_number: line_idx + 1,
                     column_number: 0,
                     suggestion: Some("Use 'let' or 'const' instead of 'var'".to_string()),
+                    rule_id: Some("practice/var-usage".to_string()),
+                    source: None,
                 });
             }
         }
@@ -297,11 +322,13 @@ impl LinterManager {
                 issues.push(CodeIssue {
                     id: Uuid::new_v4(),
                     issue_type: IssueType::Style,
-                    severity: self.get_rule_severity(config, "trailing_whitespace"),
+                    severity: self.get_rule_severity(config, "style/trailing-whitespace"),
                     description: "Trailing whitespace detected".to_string(),
                     line_number: line_idx + 1,
                     column_number: line.len(),
                     suggestion: Some("Remove trailing whitespace".to_string()),
+                    rule_id: Some("style/trailing-whitespace".to_string()),
+                    source: None,
                 });
             }
 
@@ -310,11 +337,13 @@ impl LinterManager {
                 issues.push(CodeIssue {
                     id: Uuid::new_v4(),
                     issue_type: IssueType::Style,
-                    severity: self.get_rule_severity(config, "line_length"),
+                    severity: self.get_rule_severity(config, "style/line-length"),
                     description: "Line exceeds 100 characters".to_string(),
                     line_number: line_idx + 1,
                     column_number: 100,
                     suggestion: Some("Break line into multiple lines".to_string()),
+                    rule_id: Some("style/line-length".to_string()),
+                    source: None,
                 });
             }
 
@@ -323,11 +352,13 @@ impl LinterManager {
                 issues.push(CodeIssue {
                     id: Uuid::new_v4(),
                     issue_type: IssueType::BestPractice,
-                    severity: self.get_rule_severity(config, "debug_print"),
+                    severity: self.get_rule_severity(config, "practice/debug-print"),
                     description: "Debug print statement found".to_string(),
                     line_number: line_idx + 1,
                     column_number: 0,
                     suggestion: Some("Remove debug print statements before production".to_string()),
+                    rule_id: Some("practice/debug-print".to_string()),
+                    source: None,
                 });
             }
         }
@@ -350,11 +381,13 @@ impl LinterManager {
                 issues.push(CodeIssue {
                     id: Uuid::new_v4(),
                     issue_type: IssueType::Style,
-                    severity: self.get_rule_severity(config, "trailing_whitespace"),
+                    severity: self.get_rule_severity(config, "style/trailing-whitespace"),
                     description: "Trailing whitespace detected".to_string(),
                     line_number: line_idx + 1,
                     column_number: line.len(),
                     suggestion: Some("Remove trailing whitespace".to_string()),
+                    rule_id: Some("style/trailing-whitespace".to_string()),
+                    source: None,
                 });
             }
 
@@ -363,11 +396,13 @@ impl LinterManager {
                 issues.push(CodeIssue {
                     id: Uuid::new_v4(),
                     issue_type: IssueType::Style,
-                    severity: self.get_rule_severity(config, "line_length"),
+                    severity: self.get_rule_severity(config, "style/line-length"),
                     description: "Line exceeds 100 characters".to_string(),
                     line_number: line_idx + 1,
                     column_number: 100,
                     suggestion: Some("Break line into multiple lines".to_string()),
+                    rule_id: Some("style/line-length".to_string()),
+                    source: None,
                 });
             }
         }
@@ -376,19 +411,17 @@ impl LinterManager {
     }
 
     /// Get the severity for a rule, considering overrides
+    ///
+    /// Rules without an override have their catalog default severity.
     fn get_rule_severity(&self, config: &LinterConfig, rule_id: &str) -> Severity {
         // Check if there's an override for this rule
         if let Some(severity) = config.severity_overrides.get(rule_id) {
             return severity.clone();
         }
 
-        // If the rule is disabled, return Low severity (effectively ignoring it)
-        if config.disabled_rules.contains(&rule_id.to_string()) {
-            return Severity::Low;
-        }
-
-        // Default severity for all rules is Medium
-        Severity::Medium
+        find_rule(rule_id)
+            .map(|rule| rule.default_severity.clone())
+            .unwrap_or(Severity::Medium)
     }
 
     /// Get all registered linters
@@ -405,3 +438,61 @@ impl LinterManager {
         Ok(result)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use odincode_core::rule_catalog;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_disabled_catalog_rule_is_suppressed() {
+        let loose_equality_rule = rule_catalog()
+            .into_iter()
+            .find(|rule| rule.id == "bug/loose-equality")
+            .expect("loose equality is in the catalog");
+
+        let core_engine = Arc::new(CodeEngine::new().unwrap());
+        let file_id = core_engine
+            .load_file(
+                "app.js".to_string(),
+                "var same = a == b; \n".to_string(),
+                "javascript".to_string(),
+            )
+            .await
+            .unwrap();
+        let manager = LinterManager::new(Arc::clone(&core_engine));
+        let config = |disabled_rules: Vec<String>| LinterConfig {
+            language: "javascript".to_string(),
+            name: "JavaScript Linter".to_string(),
+            description: "Lints JavaScript".to_string(),
+            enabled_rules: vec![],
+            disabled_rules,
+            severity_overrides: HashMap::new(),
+            custom_params: HashMap::new(),
+        };
+
+        manager.register_linter(config(vec![])).await.unwrap();
+        let issues = manager.lint_file(file_id).await.unwrap();
+        let loose_equality = issues
+            .iter()
+            .find(|issue| issue.rule_id.as_deref() == Some("bug/loose-equality"))
+            .expect("loose equality is reported");
+        assert_eq!(loose_equality.severity, Severity::High);
+
+        manager
+            .register_linter(config(vec![loose_equality_rule.id]))
+            .await
+            .unwrap();
+        let rule_ids: Vec<String> = manager
+            .lint_file(file_id)
+            .await
+            .unwrap()
+            .into_iter()
+            .filter_map(|issue| issue.rule_id)
+            .collect();
+        assert!(!rule_ids.contains(&"bug/loose-equality".to_string()));
+        assert!(rule_ids.contains(&"practice/var-usage".to_string()));
+        assert!(rule_ids.contains(&"style/trailing-whitespace".to_string()));
+    }
+}
@@ -0,0 +1,376 @@
+//! LSP Tools Module
+//!
+//! This module converts engine `CodeIssue`s into LSP-shaped diagnostics, so
+//! editor plugins can consume them as JSON in the same form as language-server
+//! diagnostics. Diagnostics from external tools such as compilers convert the
+//! other way and can be merged into the engine's analysis results.
+
+use anyhow::{anyhow, Result};
+use serde::{Serialize, Serializer};
+use uuid::Uuid;
+
+use odincode_core::{CodeEngine, CodeIssue, IssueType, Severity};
+
+/// Source reported on every diagnostic converted from a `CodeIssue`
+pub const DIAGNOSTIC_SOURCE: &str = "odincode";
+
+/// Severity of a diagnostic, serialized as the LSP numeric code
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagnosticSeverity {
+    /// Reported as an error
+    Error = 1,
+    /// Reported as a warning
+    Warning = 2,
+    /// Reported as information
+    Information = 3,
+    /// Reported as a hint
+    Hint = 4,
+}
+
+impl From<&Severity> for DiagnosticSeverity {
+    fn from(severity: &Severity) -> Self {
+        match severity {
+            Severity::Critical | Severity::High => Self::Error,
+            Severity::Medium | Severity::Warning => Self::Warning,
+            Severity::Low => Self::Information,
+            Severity::Info => Self::Hint,
+        }
+    }
+}
+
+impl Serialize for DiagnosticSeverity {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_u8(*self as u8)
+    }
+}
+
+/// Position in a file; both fields are 0-based
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Position {
+    /// Line number
+    pub line: usize,
+    /// Character offset within the line
+    pub character: usize,
+}
+
+/// Range in a file between two positions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Range {
+    /// Start position
+    pub start: Position,
+    /// End position
+    pub end: Position,
+}
+
+/// An editor-facing diagnostic
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Diagnostic {
+    /// File the diagnostic belongs to
+    pub file_path: String,
+    /// Range the diagnostic covers
+    pub range: Range,
+    /// Severity of the diagnostic
+    pub severity: DiagnosticSeverity,
+    /// Kind of issue, e.g. `Style` or `Security`
+    pub code: String,
+    /// Tool that produced the diagnostic
+    pub source: String,
+    /// Description of the issue, followed by the suggested fix if any
+    pub message: String,
+}
+
+/// Convert engine issues of one file into diagnostics
+///
+/// `CodeIssue` lines are 1-based and become 0-based diagnostic lines; columns
+/// are already 0-based and are kept as they are.
+pub fn issues_to_diagnostics(file_path: &str, issues: &[CodeIssue]) -> Vec<Diagnostic> {
+    issues
+        .iter()
+        .map(|issue| {
+            let position = Position {
+                line: issue.line_number.saturating_sub(1),
+                character: issue.column_number,
+            };
+            let message = match &issue.suggestion {
+                Some(suggestion) => format!("{}\n{}", issue.description, suggestion),
+                None => issue.description.clone(),
+            };
+
+            Diagnostic {
+                file_path: file_path.to_string(),
+                range: Range {
+                    start: position,
+                    end: position,
+                },
+                severity: DiagnosticSeverity::from(&issue.severity),
+                code: format!("{:?}", issue.issue_type),
+                source: DIAGNOSTIC_SOURCE.to_string(),
+                message,
+            }
+        })
+        .collect()
+}
+
+/// Convert diagnostics reported by an external tool into engine issues
+///
+/// Diagnostic lines are 0-based and become 1-based issue lines. A message's
+/// first line becomes the description and the rest the suggestion. Each issue
+/// keeps the diagnostic's `source`, and its `code` as the rule id.
+pub fn diagnostics_to_issues(diagnostics: &[Diagnostic]) -> Vec<CodeIssue> {
+    diagnostics
+        .iter()
+        .map(|diagnostic| {
+            let (description, suggestion) = match diagnostic.message.split_once('\n') {
+                Some((description, suggestion)) => (description, Some(suggestion.to_string())),
+                None => (diagnostic.message.as_str(), None),
+            };
+            let (issue_type, severity) = match diagnostic.severity {
+                DiagnosticSeverity::Error => (IssueType::PotentialBug, Severity::High),
+                DiagnosticSeverity::Warning => (IssueType::BestPractice, Severity::Warning),
+                DiagnosticSeverity::Information => (IssueType::BestPractice, Severity::Low),
+                DiagnosticSeverity::Hint => (IssueType::Style, Severity::Info),
+            };
+
+            CodeIssue {
+                id: Uuid::new_v4(),
+                issue_type,
+                severity,
+                description: description.to_string(),
+                line_number: diagnostic.range.start.line + 1,
+                column_number: diagnostic.range.start.character,
+                suggestion,
+                rule_id: (!diagnostic.code.is_empty()).then(|| diagnostic.code.clone()),
+                source: Some(diagnostic.source.clone()),
+            }
+        })
+        .collect()
+}
+
+/// Merge diagnostics reported by the external tool `source`, such as `rustc`
+/// for `cargo check`, into a file's latest analysis result
+///
+/// Every diagnostic must come from `source`. See
+/// [`CodeEngine::merge_external_issues`] for how they replace earlier and
+/// overlapping issues; an empty list clears the tool's earlier diagnostics.
+pub async fn merge_external_diagnostics(
+    engine: &CodeEngine,
+    file_id: Uuid,
+    source: &str,
+    diagnostics: Vec<Diagnostic>,
+) -> Result<()> {
+    engine
+        .merge_external_issues(file_id, source, diagnostics_to_issues(&diagnostics))
+        .await
+}
+
+/// Export diagnostics as a JSON array
+pub fn diagnostics_to_json(diagnostics: &[Diagnostic]) -> Result<String> {
+    serde_json::to_string_pretty(diagnostics)
+        .map_err(|e| anyhow!("Failed to serialize diagnostics: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn issue(severity: Severity, line_number: usize, column_number: usize) -> CodeIssue {
+        CodeIssue {
+            id: uuid::Uuid::new_v4(),
+            issue_type: IssueType::Security,
+            severity,
+            description: "Hardcoded credential".to_string(),
+            line_number,
+            column_number,
+            suggestion: Some("Load the secret from the environment".to_string()),
+            rule_id: None,
+            source: None,
+        }
+    }
+
+    #[test]
+    fn test_critical_issue_maps_to_error() {
+        let diagnostics = issues_to_diagnostics("src/lib.rs", &[issue(Severity::Critical, 12, 4)]);
+
+        assert_eq!(diagnostics.len(), 1);
+        let diagnostic = &diagnostics[0];
+        assert_eq!(diagnostic.severity, DiagnosticSeverity::Error);
+        assert_eq!(diagnostic.file_path, "src/lib.rs");
+        assert_eq!(
+            diagnostic.range.start,
+            Position {
+                line: 11,
+                character: 4
+            }
+        );
+        assert_eq!(diagnostic.code, "Security");
+        assert_eq!(diagnostic.source, DIAGNOSTIC_SOURCE);
+        assert!(diagnostic
+            .message
+            .ends_with("Load the secret from the environment"));
+    }
+
+    #[test]
+    fn test_severity_mapping_and_json_export() {
+        let issues = [
+            issue(Severity::High, 1, 0),
+            issue(Severity::Medium, 2, 0),
+            issue(Severity::Low, 3, 0),
+            issue(Severity::Info, 0, 0),
+        ];
+        let diagnostics = issues_to_diagnostics("main.rs", &issues);
+        let severities: Vec<DiagnosticSeverity> = diagnostics.iter().map(|d| d.severity).collect();
+        assert_eq!(
+            severities,
+            vec![
+                DiagnosticSeverity::Error,
+                DiagnosticSeverity::Warning,
+                DiagnosticSeverity::Information,
+                DiagnosticSeverity::Hint
+            ]
+        );
+        // Line 0 is not a valid 1-based line and is clamped to the first line
+        assert_eq!(diagnostics[3].range.start.line, 0);
+
+        let json: serde_json::Value =
+            serde_json::from_str(&diagnostics_to_json(&diagnostics).unwrap()).unwrap();
+        assert_eq!(json[0]["severity"], 1);
+        assert_eq!(json[1]["range"]["start"]["line"], 1);
+        assert_eq!(json[2]["source"], "odincode");
+    }
+
+    #[tokio::test]
+    async fn test_merged_compiler_diagnostic_keeps_unrelated_heuristic_issue() {
+        let engine = CodeEngine::new().unwrap();
+        let content = "fn main() {\n    let count = 1; // TODO use count\n}\n";
+        let file_id = engine
+            .load_file(
+                "src/main.rs".to_string(),
+                content.to_string(),
+                "rust".to_string(),
+            )
+            .await
+            .unwrap();
+        engine.analyze_file(file_id).await.unwrap();
+        let is_todo_on_line_2 = |issue: &CodeIssue| {
+            issue.source.is_none()
+                && issue.line_number == 2
+                && issue.rule_id.as_deref() == Some("practice/todo-comment")
+        };
+        let issues = engine
+            .get_issues_for_file(file_id, None, None)
+            .await
+            .unwrap();
+        assert!(issues.iter().any(is_todo_on_line_2));
+
+        let position = Position {
+            line: 1,
+            character: 8,
+        };
+        let diagnostic = Diagnostic {
+            file_path: "src/main.rs".to_string(),
+            range: Range {
+                start: position,
+                end: position,
+            },
+            severity: DiagnosticSeverity::Warning,
+            code: "unused_variables".to_string(),
+            source: "rustc".to_string(),
+            message: "unused variable: `count`\nprefix it with an underscore: `_count`".to_string(),
+        };
+        merge_external_diagnostics(&engine, file_id, "rustc", vec![diagnostic.clone()])
+            .await
+            .unwrap();
+        // Merging the same tool's diagnostics again replaces the earlier ones
+        merge_external_diagnostics(&engine, file_id, "rustc", vec![diagnostic])
+            .await
+            .unwrap();
+
+        let issues = engine
+            .get_issues_for_file(file_id, None, None)
+            .await
+            .unwrap();
+        let compiler: Vec<&CodeIssue> = issues
+            .iter()
+            .filter(|issue| issue.source.as_deref() == Some("rustc"))
+            .collect();
+        assert_eq!(compiler.len(), 1);
+        assert_eq!(compiler[0].line_number, 2);
+        assert_eq!(compiler[0].description, "unused variable: `count`");
+        assert_eq!(compiler[0].rule_id.as_deref(), Some("unused_variables"));
+        assert_eq!(compiler[0].severity, Severity::Warning);
+        // The unused variable is a different problem from the TODO on its line
+        assert!(issues.iter().any(is_todo_on_line_2));
+    }
+
+    #[tokio::test]
+    async fn test_clean_external_run_clears_its_diagnostics() {
+        let engine = CodeEngine::new().unwrap();
+        let content = "fn main() {\n    let count = 1; // TODO use count\n}\n";
+        let file_id = engine
+            .load_file(
+                "src/main.rs".to_string(),
+                content.to_string(),
+                "rust".to_string(),
+            )
+            .await
+            .unwrap();
+        engine.analyze_file(file_id).await.unwrap();
+        let is_heuristic_todo = |issue: &CodeIssue| {
+            issue.source.is_none() && issue.rule_id.as_deref() == Some("practice/todo-comment")
+        };
+
+        let position = Position {
+            line: 1,
+            character: 20,
+        };
+        let diagnostic = |code: &str, source: &str| Diagnostic {
+            file_path: "src/main.rs".to_string(),
+            range: Range {
+                start: position,
+                end: position,
+            },
+            severity: DiagnosticSeverity::Warning,
+            code: code.to_string(),
+            source: source.to_string(),
+            message: "Unexpected 'todo' comment".to_string(),
+        };
+        merge_external_diagnostics(
+            &engine,
+            file_id,
+            "eslint",
+            vec![diagnostic("no-warning-comments", "eslint")],
+        )
+        .await
+        .unwrap();
+        let issues = engine
+            .get_issues_for_file(file_id, None, None)
+            .await
+            .unwrap();
+        assert!(issues
+            .iter()
+            .any(|issue| issue.source.as_deref() == Some("eslint")));
+        assert!(!issues.iter().any(is_heuristic_todo));
+
+        // A diagnostic from another tool is refused
+        assert!(merge_external_diagnostics(
+            &engine,
+            file_id,
+            "eslint",
+            vec![diagnostic("E0425", "rustc")],
+        )
+        .await
+        .is_err());
+
+        // The comment was fixed and the tool now reports nothing
+        merge_external_diagnostics(&engine, file_id, "eslint", Vec::new())
+            .await
+            .unwrap();
+        let issues = engine
+            .get_issues_for_file(file_id, None, None)
+            .await
+            .unwrap();
+        assert!(!issues.iter().any(|issue| issue.source.is_some()));
+        // The heuristic issue hidden behind the external one is reported again
+        assert!(issues.iter().any(is_heuristic_todo));
+    }
+}
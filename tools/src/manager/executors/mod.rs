@@ -10,7 +10,7 @@ use uuid::Uuid;
 
 use crate::tool_models::ToolIntegration;
 use odincode_core::CodeFile;
-use odincode_ltmc::{LTMManager, LearningPattern, PatternType};
+use odincode_ltmc::{LTMManager, LearningPattern, PatternProvenance, PatternType};
 
 pub mod subprocess;
 use subprocess::SubprocessExecutor;
@@ -96,6 +96,7 @@ impl ToolExecutors {
                     last_accessed: chrono::Utc::now(),
                     access_count: 0,
                     confidence: 0.8,
+                    provenance: PatternProvenance::tool(&tool.name, Some(file.id)),
                 };
 
                 ltmc_manager.store_pattern(pattern).await?;
@@ -129,6 +130,7 @@ impl ToolExecutors {
                     last_accessed: chrono::Utc::now(),
                     access_count: 0,
                     confidence: 0.8,
+                    provenance: PatternProvenance::tool(&tool.name, Some(file.id)),
                 };
 
                 ltmc_manager.store_pattern(pattern).await?;
@@ -213,6 +215,7 @@ impl ToolExecutors {
                     last_accessed: chrono::Utc::now(),
                     access_count: 0,
                     confidence: 0.8,
+                    provenance: PatternProvenance::tool(&tool.name, Some(file.id)),
                 };
 
                 ltmc_manager.store_pattern(pattern).await?;
@@ -246,6 +249,7 @@ impl ToolExecutors {
                     last_accessed: chrono::Utc::now(),
                     access_count: 0,
                     confidence: 0.8,
+                    provenance: PatternProvenance::tool(&tool.name, Some(file.id)),
                 };
 
                 ltmc_manager.store_pattern(pattern).await?;
@@ -334,6 +338,7 @@ impl ToolExecutors {
                     last_accessed: chrono::Utc::now(),
                     access_count: 0,
                     confidence: 0.8,
+                    provenance: PatternProvenance::tool(&tool.name, Some(file.id)),
                 };
 
                 ltmc_manager.store_pattern(pattern).await?;
@@ -367,6 +372,7 @@ impl ToolExecutors {
                     last_accessed: chrono::Utc::now(),
                     access_count: 0,
                     confidence: 0.8,
+                    provenance: PatternProvenance::tool(&tool.name, Some(file.id)),
                 };
 
                 ltmc_manager.store_pattern(pattern).await?;
@@ -458,6 +464,7 @@ impl ToolExecutors {
                     last_accessed: chrono::Utc::now(),
                     access_count: 0,
                     confidence: 0.8,
+                    provenance: PatternProvenance::tool(&tool.name, Some(file.id)),
                 };
 
                 ltmc_manager.store_pattern(pattern).await?;
@@ -491,6 +498,7 @@ impl ToolExecutors {
                     last_accessed: chrono::Utc::now(),
                     access_count: 0,
                     confidence: 0.8,
+                    provenance: PatternProvenance::tool(&tool.name, Some(file.id)),
                 };
 
                 ltmc_manager.store_pattern(pattern).await?;
@@ -582,6 +590,7 @@ impl ToolExecutors {
                         last_accessed: chrono::Utc::now(),
                         access_count: 0,
                         confidence: 0.8,
+                        provenance: PatternProvenance::tool(&tool.name, Some(file.id)),
                     };
 
                 ltmc_manager.store_pattern(pattern).await?;
@@ -615,6 +624,7 @@ impl ToolExecutors {
                     last_accessed: chrono::Utc::now(),
                     access_count: 0,
                     confidence: 0.8,
+                    provenance: PatternProvenance::tool(&tool.name, Some(file.id)),
                 };
 
                 ltmc_manager.store_pattern(pattern).await?;
@@ -707,6 +717,7 @@ impl ToolExecutors {
                     last_accessed: chrono::Utc::now(),
                     access_count: 0,
                     confidence: 0.8,
+                    provenance: PatternProvenance::tool(&tool.name, Some(file.id)),
                 };
 
                 ltmc_manager.store_pattern(pattern).await?;
@@ -740,6 +751,7 @@ impl ToolExecutors {
                     last_accessed: chrono::Utc::now(),
                     access_count: 0,
                     confidence: 0.8,
+                    provenance: PatternProvenance::tool(&tool.name, Some(file.id)),
                 };
 
                 ltmc_manager.store_pattern(pattern).await?;
@@ -838,6 +850,7 @@ impl ToolExecutors {
                         last_accessed: chrono::Utc::now(),
                         access_count: 0,
                         confidence: 0.8,
+                        provenance: PatternProvenance::tool(&tool.name, Some(file.id)),
                     };
 
                 ltmc_manager.store_pattern(pattern).await?;
@@ -871,6 +884,7 @@ impl ToolExecutors {
                     last_accessed: chrono::Utc::now(),
                     access_count: 0,
                     confidence: 0.8,
+                    provenance: PatternProvenance::tool(&tool.name, Some(file.id)),
                 };
 
                 ltmc_manager.store_pattern(pattern).await?;
@@ -968,6 +982,7 @@ impl ToolExecutors {
                         last_accessed: chrono::Utc::now(),
                         access_count: 0,
                         confidence: 0.8,
+                        provenance: PatternProvenance::tool(&tool.name, Some(file.id)),
                     };
 
                 ltmc_manager.store_pattern(pattern).await?;
@@ -1001,6 +1016,7 @@ impl ToolExecutors {
                     last_accessed: chrono::Utc::now(),
                     access_count: 0,
                     confidence: 0.8,
+                    provenance: PatternProvenance::tool(&tool.name, Some(file.id)),
                 };
 
                 ltmc_manager.store_pattern(pattern).await?;
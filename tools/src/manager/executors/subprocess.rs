@@ -6,6 +6,7 @@ use anyhow::{Context, Result};
 use std::collections::HashMap;
 use std::path::Path;
 use std::process::Stdio;
+use tokio::io::AsyncWriteExt;
 use tokio::process::Command;
 use tracing::{debug, error, info, warn};
 
@@ -113,6 +114,83 @@ impl SubprocessExecutor {
         Ok(result)
     }
 
+    /// Execute a command, writing `input` to its stdin
+    pub async fn execute_with_input(
+        command: &str,
+        args: &[&str],
+        input: &str,
+        working_dir: Option<&Path>,
+        timeout_ms: u64,
+    ) -> Result<ExecutionResult> {
+        let start_time = std::time::Instant::now();
+
+        debug!(
+            "Executing command: {} with args: {:?} on stdin",
+            command, args
+        );
+
+        let mut cmd = Command::new(command);
+        cmd.args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+
+        if let Some(dir) = working_dir {
+            cmd.current_dir(dir);
+        }
+
+        let mut child = cmd.spawn().context("Failed to spawn command")?;
+
+        // Feed stdin while the output is read, so a command that fills its stdout
+        // pipe before draining its input can't deadlock
+        let stdin = child.stdin.take();
+        let input = input.as_bytes().to_vec();
+        let writer = tokio::spawn(async move {
+            match stdin {
+                // Dropping stdin closes it so the command sees end of input
+                Some(mut stdin) => stdin.write_all(&input).await,
+                None => Ok(()),
+            }
+        });
+
+        let output = match tokio::time::timeout(
+            std::time::Duration::from_millis(timeout_ms),
+            child.wait_with_output(),
+        )
+        .await
+        {
+            Ok(output) => output.context("Failed to wait for command")?,
+            Err(elapsed) => {
+                writer.abort();
+                return Err(elapsed).context("Command execution timed out");
+            }
+        };
+
+        // A command that exits without reading all of its input only saw part of it
+        let written = writer.await.context("Failed to write command stdin")?;
+        if output.status.success() {
+            written.context("Failed to write command stdin")?;
+        }
+
+        let result = ExecutionResult {
+            success: output.status.success(),
+            exit_code: output.status.code(),
+            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+            duration_ms: start_time.elapsed().as_millis() as u64,
+        };
+
+        if !result.success {
+            warn!(
+                "Command '{}' failed with exit code {:?} in {}ms",
+                command, result.exit_code, result.duration_ms
+            );
+        }
+
+        Ok(result)
+    }
+
     /// Check if a command is available in the system
     pub async fn command_exists(command: &str) -> bool {
         let result = Self::execute_command(command, &["--version"], None, None, Some(5000)).await;
@@ -145,6 +223,22 @@ impl SubprocessExecutor {
         Self::execute_command(command, &full_args, working_dir, None, Some(15000)).await
     }
 
+    /// Run a formatter over in-memory content, returning the formatted output
+    ///
+    /// The formatter must read the source from stdin and write the result to stdout.
+    pub async fn format_content(command: &str, args: &[&str], content: &str) -> Result<String> {
+        let result = Self::execute_with_input(command, args, content, None, 15000).await?;
+        if !result.success {
+            return Err(anyhow::anyhow!(
+                "Formatter '{}' failed with exit code {:?}: {}",
+                command,
+                result.exit_code,
+                result.stderr.trim()
+            ));
+        }
+        Ok(result.stdout)
+    }
+
     /// Execute a test runner command
     pub async fn execute_test_runner(
         command: &str,
@@ -181,3 +275,41 @@ impl SubprocessExecutor {
         Self::execute_command(command, args, working_dir, None, Some(180000)).await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_format_content_pipes_stdin_to_stdout() {
+        let formatted = SubprocessExecutor::format_content("tr", &["a-z", "A-Z"], "fn one() {}")
+            .await
+            .unwrap();
+        assert_eq!(formatted, "FN ONE() {}");
+
+        assert!(
+            SubprocessExecutor::format_content("false", &[], "fn one() {}")
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_execute_with_input_larger_than_pipe_buffer() {
+        // `cat` fills its stdout pipe long before its stdin is drained
+        let input = "x".repeat(1024 * 1024);
+        let result = SubprocessExecutor::execute_with_input("cat", &[], &input, None, 10_000)
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert_eq!(result.stdout.len(), input.len());
+    }
+
+    #[tokio::test]
+    async fn test_execute_with_input_times_out() {
+        let error = SubprocessExecutor::execute_with_input("sleep", &["5"], "", None, 100)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("timed out"));
+    }
+}
@@ -121,3 +121,46 @@ async fn test_execute_package_manager() {
     assert!(execution_result.success);
     assert!(execution_result.stdout.contains("install"));
 }
+
+#[tokio::test]
+async fn test_linter_patterns_record_provenance() {
+    let mut temp_file = NamedTempFile::new().unwrap();
+    writeln!(temp_file, "fn main() {{}}").unwrap();
+
+    let mut config = HashMap::new();
+    config.insert("command".to_string(), "cat".to_string());
+    let tool = ToolIntegration {
+        id: Uuid::new_v4(),
+        name: "mock-linter".to_string(),
+        description: "cat standing in for a linter".to_string(),
+        tool_type: crate::tool_models::ToolType::Linter,
+        status: crate::tool_models::ToolStatus::Connected,
+        config,
+        created: chrono::Utc::now(),
+        last_updated: chrono::Utc::now(),
+    };
+    let file = CodeFile {
+        id: Uuid::new_v4(),
+        path: temp_file.path().to_string_lossy().to_string(),
+        content: "fn main() {}\n".to_string(),
+        language: "rust".to_string(),
+        modified: chrono::Utc::now(),
+        encoding: Default::default(),
+        line_ending: Default::default(),
+    };
+
+    let ltmc_manager = LTMManager::new();
+    assert!(ToolExecutors::execute_linter(&ltmc_manager, &tool, &file)
+        .await
+        .unwrap());
+
+    let patterns = ltmc_manager
+        .get_patterns_by_type(PatternType::CodePattern)
+        .await
+        .unwrap();
+    assert_eq!(patterns.len(), 1);
+    assert_eq!(
+        patterns[0].provenance,
+        PatternProvenance::tool("mock-linter", Some(file.id))
+    );
+}
@@ -0,0 +1,78 @@
+//! Format Policy Module
+//!
+//! This module maps languages to the formatter tools that edited files of that
+//! language are run through before being written.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::manager::executors::subprocess::SubprocessExecutor;
+use crate::tool_models::ToolIntegration;
+
+/// Mapping of language to the formatter tool applied to edited files
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FormatPolicy {
+    /// Formatter tool id by language
+    pub formatters: HashMap<String, Uuid>,
+}
+
+impl FormatPolicy {
+    /// Create an empty policy that formats nothing
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Format files of `language` with the given formatter tool
+    pub fn set_formatter(&mut self, language: &str, tool_id: Uuid) {
+        self.formatters.insert(language.to_lowercase(), tool_id);
+    }
+
+    /// Stop formatting files of `language`
+    pub fn remove_formatter(&mut self, language: &str) -> Option<Uuid> {
+        self.formatters.remove(&language.to_lowercase())
+    }
+
+    /// Get the formatter tool for a language
+    pub fn formatter_for(&self, language: &str) -> Option<Uuid> {
+        self.formatters.get(&language.to_lowercase()).copied()
+    }
+}
+
+/// Formatter command resolved from a formatter tool's configuration
+#[derive(Debug, Clone)]
+pub struct FormatterCommand {
+    /// Name of the formatter tool
+    pub tool_name: String,
+    /// Command to run
+    pub command: String,
+    /// Arguments passed to the command
+    pub args: Vec<String>,
+}
+
+impl FormatterCommand {
+    /// Resolve the command from a tool's `command` and `args` config
+    pub fn from_tool(tool: &ToolIntegration) -> Result<Self> {
+        let command = tool.config.get("command").ok_or_else(|| {
+            anyhow::anyhow!("Formatter command not configured for tool: {}", tool.name)
+        })?;
+        let args = tool
+            .config
+            .get("args")
+            .map(|args| args.split_whitespace().map(str::to_string).collect())
+            .unwrap_or_default();
+
+        Ok(Self {
+            tool_name: tool.name.clone(),
+            command: command.clone(),
+            args,
+        })
+    }
+
+    /// Format content in memory; the command reads stdin and writes stdout
+    pub async fn format(&self, content: &str) -> Result<String> {
+        let args: Vec<&str> = self.args.iter().map(String::as_str).collect();
+        SubprocessExecutor::format_content(&self.command, &args, content).await
+    }
+}
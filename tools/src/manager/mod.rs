@@ -5,19 +5,23 @@
 use anyhow::Result;
 use std::collections::HashMap;
 use tokio::sync::RwLock;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 use uuid::Uuid;
 
 use crate::tool_models::{ToolIntegration, ToolStatus, ToolType};
 use odincode_agents::AgentCoordinator;
 use odincode_core::{CodeEngine, CodeFile};
-use odincode_ltmc::{LTMManager, LearningPattern, PatternType};
+use odincode_ltmc::{LTMManager, LearningPattern, PatternProvenance, PatternType};
 
 pub mod executors;
+pub mod format_policy;
+
+pub use format_policy::{FormatPolicy, FormatterCommand};
 
 use crate::linters::LinterManager;
 use crate::manager::executors::ToolExecutors;
 use crate::multi_edit::{EditTask, MultiEditManager};
+use odincode_core::system_config::FormatterSettings;
 use odincode_core::CodeIssue;
 
 /// Main tool manager that handles all tool integrations
@@ -34,8 +38,15 @@ pub struct ToolManager {
     pub multi_edit_manager: std::sync::Arc<MultiEditManager>,
     /// Linter manager for code quality checks
     pub linter_manager: std::sync::Arc<LinterManager>,
+    /// Formatter applied to edited files, by language
+    pub format_policy: RwLock<FormatPolicy>,
+    /// Whether only read-only tools may run and edits are refused
+    pub safe_mode: bool,
 }
 
+/// Message of the errors returned for work refused in safe mode
+pub const SAFE_MODE_BLOCKED: &str = "blocked by safe mode";
+
 impl ToolManager {
     /// Create a new tool manager
     pub fn new(
@@ -56,6 +67,8 @@ impl ToolManager {
             agent_coordinator,
             multi_edit_manager,
             linter_manager,
+            format_policy: RwLock::new(FormatPolicy::new()),
+            safe_mode: false,
         }
     }
 
@@ -77,9 +90,17 @@ impl ToolManager {
             agent_coordinator,
             multi_edit_manager,
             linter_manager,
+            format_policy: RwLock::new(FormatPolicy::new()),
+            safe_mode: false,
         }
     }
 
+    /// Refuse every tool that is not read-only, and every edit, when `safe_mode` is set
+    pub fn with_safe_mode(mut self, safe_mode: bool) -> Self {
+        self.safe_mode = safe_mode;
+        self
+    }
+
     /// Register a new tool integration
     pub async fn register_tool(
         &self,
@@ -153,6 +174,14 @@ impl ToolManager {
             }
         };
 
+        if self.safe_mode && !tool.tool_type.is_read_only() {
+            return Err(anyhow::anyhow!(
+                "Tool {} is {}",
+                tool.name,
+                SAFE_MODE_BLOCKED
+            ));
+        }
+
         // Check if the tool is connected
         if matches!(tool.status, ToolStatus::Error | ToolStatus::NotConfigured) {
             return Err(anyhow::anyhow!(
@@ -183,6 +212,7 @@ impl ToolManager {
         let file = file.unwrap();
 
         // Execute the appropriate tool logic based on type
+        let started = std::time::Instant::now();
         let success = match tool.tool_type {
             ToolType::Linter => {
                 ToolExecutors::execute_linter(&self.ltmc_manager, &tool, &file).await?
@@ -211,6 +241,8 @@ impl ToolManager {
         };
 
         // Store the execution in LTMC for learning
+        self.ltmc_manager
+            .record_tool_execution(&tool.name, success, started.elapsed());
         self.store_tool_execution(&tool, &file, success).await?;
 
         Ok(success)
@@ -241,6 +273,7 @@ impl ToolManager {
             last_accessed: chrono::Utc::now(),
             access_count: 0,
             confidence: 0.8,
+            provenance: PatternProvenance::tool(&tool.name, Some(file.id)),
         };
 
         self.ltmc_manager.store_pattern(pattern).await?;
@@ -267,12 +300,117 @@ impl ToolManager {
     }
 
     /// Execute a multi-edit operation
+    ///
+    /// Edited files are run through the formatter the format policy assigns to
+    /// their language before being written.
     pub async fn execute_multi_edit_operation(&self, operation_id: Uuid) -> Result<bool> {
+        if self.safe_mode {
+            return Err(anyhow::anyhow!(
+                "Multi-edit operation {} is {}",
+                operation_id,
+                SAFE_MODE_BLOCKED
+            ));
+        }
+
+        let formatters = self.resolve_formatters().await;
         self.multi_edit_manager
-            .execute_operation(operation_id)
+            .execute_operation_with_formatters(operation_id, &formatters)
             .await
     }
 
+    /// Replace the format policy
+    pub async fn set_format_policy(&self, policy: FormatPolicy) {
+        *self.format_policy.write().await = policy;
+    }
+
+    /// Get a copy of the format policy
+    pub async fn get_format_policy(&self) -> FormatPolicy {
+        self.format_policy.read().await.clone()
+    }
+
+    /// Format edited files of `language` with a registered formatter tool
+    pub async fn set_formatter_for_language(&self, language: &str, tool_id: Uuid) -> Result<()> {
+        let tool = self
+            .get_tool(tool_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Tool not found: {}", tool_id))?;
+        if tool.tool_type != ToolType::Formatter {
+            return Err(anyhow::anyhow!("Tool {} is not a formatter", tool.name));
+        }
+
+        self.format_policy
+            .write()
+            .await
+            .set_formatter(language, tool_id);
+        info!("Formatting {} files with {}", language, tool.name);
+        Ok(())
+    }
+
+    /// Register a formatter tool for each `[formatters.<language>]` entry of the
+    /// system config and format files of that language with it
+    ///
+    /// Arguments are stored whitespace-separated in the tool config, so an
+    /// argument containing whitespace is rejected.
+    pub async fn configure_formatters(
+        &self,
+        settings: &HashMap<String, FormatterSettings>,
+    ) -> Result<()> {
+        for (language, formatter) in settings {
+            if formatter
+                .args
+                .iter()
+                .any(|arg| arg.is_empty() || arg.contains(char::is_whitespace))
+            {
+                return Err(anyhow::anyhow!(
+                    "Formatter arguments for {} must not be empty or contain whitespace",
+                    language
+                ));
+            }
+            let mut config = HashMap::new();
+            config.insert("command".to_string(), formatter.command.clone());
+            config.insert("args".to_string(), formatter.args.join(" "));
+            let tool_id = self
+                .register_tool(
+                    format!("{} formatter", language),
+                    format!("Formatter for {} files from the system config", language),
+                    ToolType::Formatter,
+                    config,
+                )
+                .await?;
+            self.set_formatter_for_language(language, tool_id).await?;
+        }
+        Ok(())
+    }
+
+    /// Resolve the format policy into formatter commands by language
+    ///
+    /// Languages whose tool is missing, disabled or misconfigured are skipped.
+    pub async fn resolve_formatters(&self) -> HashMap<String, FormatterCommand> {
+        let policy = self.format_policy.read().await.clone();
+        let tools = self.tools.read().await;
+
+        let mut formatters = HashMap::new();
+        for (language, tool_id) in policy.formatters {
+            let Some(tool) = tools.get(&tool_id) else {
+                warn!(
+                    "Formatter tool {} for {} is not registered",
+                    tool_id, language
+                );
+                continue;
+            };
+            if tool.status == ToolStatus::Disabled {
+                continue;
+            }
+            match FormatterCommand::from_tool(tool) {
+                Ok(command) => {
+                    formatters.insert(language, command);
+                }
+                Err(e) => warn!("Skipping formatter for {}: {}", language, e),
+            }
+        }
+        formatters
+    }
+
     /// Lint a file using the appropriate linter
     pub async fn lint_file(&self, file_id: Uuid) -> Result<Vec<CodeIssue>> {
         self.linter_manager.lint_file(file_id).await
@@ -293,3 +431,222 @@ impl ToolManager {
         &self.linter_manager
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::multi_edit::EditOperationType;
+    use std::sync::Arc;
+
+    /// Tool manager over `core_engine` with in-memory learning and offline agents
+    fn tool_manager(core_engine: &CodeEngine) -> ToolManager {
+        let ltmc_manager = LTMManager::new();
+        let llm_manager = odincode_core::llm_integration::LLMIntegrationManager::new().unwrap();
+        let ltmc_integration = odincode_agents::ltmc_integration::LTMCIntegration::new(
+            Arc::new(ltmc_manager.clone()),
+            Arc::new(core_engine.clone()),
+            Arc::new(llm_manager),
+        );
+        let agent_coordinator = AgentCoordinator::new(
+            Arc::new(core_engine.clone()),
+            Arc::new(ltmc_manager.clone()),
+            Arc::new(ltmc_integration),
+        );
+        ToolManager::new(core_engine.clone(), ltmc_manager, agent_coordinator)
+    }
+
+    #[tokio::test]
+    async fn test_configured_formatters_resolve_through_the_policy() {
+        let core_engine = CodeEngine::new().unwrap();
+        let tool_manager = tool_manager(&core_engine);
+
+        let settings: HashMap<String, FormatterSettings> = [(
+            "Rust".to_string(),
+            FormatterSettings {
+                command: "rustfmt".to_string(),
+                args: vec!["--emit".to_string(), "stdout".to_string()],
+            },
+        )]
+        .into_iter()
+        .collect();
+        tool_manager.configure_formatters(&settings).await.unwrap();
+
+        let formatters = tool_manager.resolve_formatters().await;
+        let rust = &formatters["rust"];
+        assert_eq!(rust.command, "rustfmt");
+        assert_eq!(rust.args, vec!["--emit", "stdout"]);
+        assert!(tool_manager
+            .get_format_policy()
+            .await
+            .formatter_for("rust")
+            .is_some());
+
+        let settings: HashMap<String, FormatterSettings> = [(
+            "python".to_string(),
+            FormatterSettings {
+                command: "black".to_string(),
+                args: vec!["--line-length 100".to_string()],
+            },
+        )]
+        .into_iter()
+        .collect();
+        assert!(tool_manager.configure_formatters(&settings).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_format_policy_applied_before_write() {
+        let core_engine = CodeEngine::new().unwrap();
+        let tool_manager = tool_manager(&core_engine);
+
+        // Mock formatter that upper-cases stdin
+        let mut config = HashMap::new();
+        config.insert("command".to_string(), "tr".to_string());
+        config.insert("args".to_string(), "a-z A-Z".to_string());
+        let formatter_id = tool_manager
+            .register_tool(
+                "upper".to_string(),
+                "Upper-cases its input".to_string(),
+                ToolType::Formatter,
+                config,
+            )
+            .await
+            .unwrap();
+        tool_manager
+            .set_formatter_for_language("rust", formatter_id)
+            .await
+            .unwrap();
+
+        let rust_file = core_engine
+            .load_file(
+                "lib.rs".to_string(),
+                "fn one() {}".to_string(),
+                "rust".to_string(),
+            )
+            .await
+            .unwrap();
+        let text_file = core_engine
+            .load_file(
+                "notes.txt".to_string(),
+                "one".to_string(),
+                "text".to_string(),
+            )
+            .await
+            .unwrap();
+
+        let edit = |file_id| EditTask {
+            id: uuid::Uuid::new_v4(),
+            file_id,
+            operation_type: EditOperationType::Replace,
+            start_pos: (0, 0),
+            end_pos: (0, 0),
+            content: "// two\n".to_string(),
+            description: "prepend a comment".to_string(),
+        };
+        let operation_id = tool_manager
+            .create_multi_edit_operation(
+                "format".to_string(),
+                "edit both files".to_string(),
+                vec![edit(rust_file), edit(text_file)],
+            )
+            .await
+            .unwrap();
+        assert!(tool_manager
+            .execute_multi_edit_operation(operation_id)
+            .await
+            .unwrap());
+
+        let rust = core_engine.get_file(rust_file).await.unwrap().unwrap();
+        assert_eq!(rust.content, "// TWO\nFN ONE() {}");
+        // No formatter is configured for text files
+        let text = core_engine.get_file(text_file).await.unwrap().unwrap();
+        assert_eq!(text.content, "// two\none");
+
+        // A failing formatter leaves the edit in place, unformatted
+        let mut config = HashMap::new();
+        config.insert("command".to_string(), "false".to_string());
+        let failing_id = tool_manager
+            .register_tool(
+                "broken".to_string(),
+                "Always fails".to_string(),
+                ToolType::Formatter,
+                config,
+            )
+            .await
+            .unwrap();
+        tool_manager
+            .set_formatter_for_language("rust", failing_id)
+            .await
+            .unwrap();
+        let operation_id = tool_manager
+            .create_multi_edit_operation(
+                "format".to_string(),
+                "edit again".to_string(),
+                vec![edit(rust_file)],
+            )
+            .await
+            .unwrap();
+        tool_manager
+            .execute_multi_edit_operation(operation_id)
+            .await
+            .unwrap();
+        let rust = core_engine.get_file(rust_file).await.unwrap().unwrap();
+        assert_eq!(rust.content, "// two\n// TWO\nFN ONE() {}");
+    }
+
+    #[tokio::test]
+    async fn test_safe_mode_refuses_write_tools() {
+        let core_engine = CodeEngine::new().unwrap();
+        let tool_manager = tool_manager(&core_engine).with_safe_mode(true);
+
+        let mut tool_ids = Vec::new();
+        for tool_type in [ToolType::Formatter, ToolType::Linter] {
+            let mut config = HashMap::new();
+            config.insert("command".to_string(), "true".to_string());
+            let id = tool_manager
+                .register_tool(
+                    format!("{:?}", tool_type),
+                    "Does nothing".to_string(),
+                    tool_type,
+                    config,
+                )
+                .await
+                .unwrap();
+            tool_manager
+                .update_tool_status(id, ToolStatus::Connected)
+                .await
+                .unwrap();
+            tool_ids.push(id);
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("lib.rs");
+        std::fs::write(&path, "fn one() {}").unwrap();
+        let file_id = core_engine
+            .load_file(
+                path.to_string_lossy().into_owned(),
+                "fn one() {}".to_string(),
+                "rust".to_string(),
+            )
+            .await
+            .unwrap();
+
+        let error = tool_manager
+            .execute_tool_on_file(tool_ids[0], file_id)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains(SAFE_MODE_BLOCKED));
+        assert!(tool_manager
+            .execute_tool_on_file(tool_ids[1], file_id)
+            .await
+            .unwrap());
+
+        let operation_id = tool_manager
+            .create_multi_edit_operation("edit".to_string(), "edit".to_string(), Vec::new())
+            .await
+            .unwrap();
+        assert!(tool_manager
+            .execute_multi_edit_operation(operation_id)
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,172 @@
+//! Multi-Edit Diff Rendering
+//!
+//! This module renders unified diffs of the file changes a multi-edit operation
+//! would make, so they can be reviewed before being applied.
+
+use serde::{Deserialize, Serialize};
+
+/// Number of unchanged lines shown around each change
+const CONTEXT_LINES: usize = 3;
+
+/// Before and after content of a single file touched by a multi-edit operation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileDiff {
+    /// File path
+    pub path: String,
+    /// Content before the edits (empty for new files)
+    pub original: String,
+    /// Content after the edits
+    pub modified: String,
+}
+
+impl FileDiff {
+    /// Whether the edits create the file rather than modify it
+    pub fn is_creation(&self) -> bool {
+        self.original.is_empty() && !self.modified.is_empty()
+    }
+
+    /// Whether the edits change the file at all
+    pub fn has_changes(&self) -> bool {
+        self.original != self.modified
+    }
+
+    /// Render this file's changes as a unified diff
+    pub fn to_unified(&self) -> String {
+        let old_lines: Vec<&str> = self.original.lines().collect();
+        let new_lines: Vec<&str> = self.modified.lines().collect();
+        let ops = diff_lines(&old_lines, &new_lines);
+
+        let mut out = String::new();
+        if self.is_creation() {
+            out.push_str(&format!("new file: {}\n", self.path));
+            out.push_str("--- /dev/null\n");
+        } else {
+            out.push_str(&format!("modified: {}\n", self.path));
+            out.push_str(&format!("--- a/{}\n", self.path));
+        }
+        out.push_str(&format!("+++ b/{}\n", self.path));
+
+        for hunk in group_hunks(&ops) {
+            render_hunk(&mut out, &ops[hunk.0..hunk.1], &old_lines, &new_lines);
+        }
+
+        out
+    }
+}
+
+/// Render a combined unified diff of every changed file
+pub fn render_unified_diff(diffs: &[FileDiff]) -> String {
+    diffs
+        .iter()
+        .filter(|diff| diff.has_changes())
+        .map(FileDiff::to_unified)
+        .collect::<Vec<_>>()
+        .join("")
+}
+
+/// A single line-level diff operation
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum DiffOp {
+    /// Line unchanged (old index, new index)
+    Equal(usize, usize),
+    /// Line removed from the old content
+    Delete(usize),
+    /// Line added in the new content
+    Insert(usize),
+}
+
+/// Compute a line diff using the longest common subsequence
+fn diff_lines(old: &[&str], new: &[&str]) -> Vec<DiffOp> {
+    let (n, m) = (old.len(), new.len());
+    let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut ops = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if old[i] == new[j] {
+            ops.push(DiffOp::Equal(i, j));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            ops.push(DiffOp::Delete(i));
+            i += 1;
+        } else {
+            ops.push(DiffOp::Insert(j));
+            j += 1;
+        }
+    }
+    ops.extend((i..n).map(DiffOp::Delete));
+    ops.extend((j..m).map(DiffOp::Insert));
+    ops
+}
+
+/// Group operations into hunks of changes with surrounding context, as index ranges
+fn group_hunks(ops: &[DiffOp]) -> Vec<(usize, usize)> {
+    let mut hunks: Vec<(usize, usize)> = Vec::new();
+
+    for (idx, op) in ops.iter().enumerate() {
+        if matches!(op, DiffOp::Equal(..)) {
+            continue;
+        }
+        let start = idx.saturating_sub(CONTEXT_LINES);
+        let end = (idx + CONTEXT_LINES + 1).min(ops.len());
+        match hunks.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+
+    hunks
+}
+
+/// Append one hunk with its `@@` header
+fn render_hunk(out: &mut String, ops: &[DiffOp], old: &[&str], new: &[&str]) {
+    let old_count = ops
+        .iter()
+        .filter(|op| !matches!(op, DiffOp::Insert(_)))
+        .count();
+    let new_count = ops
+        .iter()
+        .filter(|op| !matches!(op, DiffOp::Delete(_)))
+        .count();
+
+    // Lines preceding the hunk on each side; a hunk only starts with a change
+    // when it begins at the top of the file
+    let (old_before, new_before) = match ops.first() {
+        Some(DiffOp::Equal(i, j)) => (*i, *j),
+        Some(_) => (0, 0),
+        None => return,
+    };
+    let old_start = if old_count == 0 {
+        old_before
+    } else {
+        old_before + 1
+    };
+    let new_start = if new_count == 0 {
+        new_before
+    } else {
+        new_before + 1
+    };
+
+    out.push_str(&format!(
+        "@@ -{},{} +{},{} @@\n",
+        old_start, old_count, new_start, new_count
+    ));
+
+    for op in ops {
+        match op {
+            DiffOp::Equal(i, _) => out.push_str(&format!(" {}\n", old[*i])),
+            DiffOp::Delete(i) => out.push_str(&format!("-{}\n", old[*i])),
+            DiffOp::Insert(j) => out.push_str(&format!("+{}\n", new[*j])),
+        }
+    }
+}
@@ -10,8 +10,14 @@ use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
 use uuid::Uuid;
 
+use crate::manager::FormatterCommand;
+use odincode_core::line_ending::split_terminated;
 use odincode_core::{CodeEngine, CodeFile};
 
+pub mod diff;
+
+pub use diff::{render_unified_diff, FileDiff};
+
 /// Represents a multi-file edit operation
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MultiEditOperation {
@@ -118,6 +124,20 @@ impl MultiEditManager {
 
     /// Execute a multi-edit operation
     pub async fn execute_operation(&self, operation_id: Uuid) -> Result<bool> {
+        self.execute_operation_with_formatters(operation_id, &HashMap::new())
+            .await
+    }
+
+    /// Execute a multi-edit operation, formatting edited files before they are written
+    ///
+    /// Tasks are applied in memory and each edited file is written once, after being
+    /// run through the formatter for its language in `formatters`. A file whose
+    /// formatter fails is written unformatted.
+    pub async fn execute_operation_with_formatters(
+        &self,
+        operation_id: Uuid,
+        formatters: &HashMap<String, FormatterCommand>,
+    ) -> Result<bool> {
         let operation = {
             let operations = self.operations.read().await;
             match operations.get(&operation_id) {
@@ -139,18 +159,25 @@ impl MultiEditManager {
             operation.name, operation_id
         );
 
-        // Execute each task in the operation
+        // Apply each task to an in-memory copy of its file
         let mut all_success = true;
+        let mut order: Vec<Uuid> = Vec::new();
+        let mut working: HashMap<Uuid, CodeFile> = HashMap::new();
         for task in &operation.tasks {
-            match self.execute_edit_task(task).await {
-                Ok(success) => {
-                    if !success {
-                        warn!("Edit task failed: {}", task.id);
-                        all_success = false;
-                    }
-                }
-                Err(e) => {
-                    warn!("Error executing edit task {}: {}", task.id, e);
+            if let Err(e) = self
+                .apply_task_to_working(task, &mut order, &mut working)
+                .await
+            {
+                warn!("Error executing edit task {}: {}", task.id, e);
+                all_success = false;
+            }
+        }
+
+        for file_id in order {
+            if let Some(file) = working.remove(&file_id) {
+                let content = self.format_content(&file, formatters).await;
+                if let Err(e) = self.core_engine.update_file(file_id, content).await {
+                    warn!("Failed to write edited file {}: {}", file.path, e);
                     all_success = false;
                 }
             }
@@ -171,55 +198,134 @@ impl MultiEditManager {
         Ok(all_success)
     }
 
-    /// Execute a single edit task
-    async fn execute_edit_task(&self, task: &EditTask) -> Result<bool> {
+    /// Apply a single edit task to the working copy of its file
+    async fn apply_task_to_working(
+        &self,
+        task: &EditTask,
+        order: &mut Vec<Uuid>,
+        working: &mut HashMap<Uuid, CodeFile>,
+    ) -> Result<()> {
         debug!("Executing edit task: {} on file {}", task.id, task.file_id);
 
-        // Get the file
-        let file = self.core_engine.get_file(task.file_id).await?;
-        if file.is_none() {
-            return Err(anyhow::anyhow!("File not found: {}", task.file_id));
+        if !working.contains_key(&task.file_id) {
+            let file = self
+                .core_engine
+                .get_file(task.file_id)
+                .await?
+                .ok_or_else(|| anyhow::anyhow!("File not found: {}", task.file_id))?;
+            order.push(task.file_id);
+            working.insert(task.file_id, file);
+        }
+
+        if let Some(file) = working.get_mut(&task.file_id) {
+            file.content = self.apply_task(file, task)?;
         }
-        let file = file.unwrap();
+        Ok(())
+    }
+
+    /// Run edited content through the formatter for the file's language, if any
+    async fn format_content(
+        &self,
+        file: &CodeFile,
+        formatters: &HashMap<String, FormatterCommand>,
+    ) -> String {
+        let Some(formatter) = formatters.get(&file.language.to_lowercase()) else {
+            return file.content.clone();
+        };
 
-        // Perform the edit based on operation type
-        let new_content = match task.operation_type {
-            EditOperationType::Insert => {
-                self.insert_content(&file, task.start_pos, &task.content)?
+        match formatter.format(&file.content).await {
+            Ok(formatted) => {
+                debug!("Formatted {} with {}", file.path, formatter.tool_name);
+                // Formatters may rewrite line endings; keep the file's own
+                file.line_ending.apply(&formatted)
             }
-            EditOperationType::Replace => {
-                self.replace_content(&file, task.start_pos, task.end_pos, &task.content)?
+            Err(e) => {
+                warn!(
+                    "Formatter {} failed on {}, writing unformatted content: {}",
+                    formatter.tool_name, file.path, e
+                );
+                file.content.clone()
             }
-            EditOperationType::Delete => {
-                self.delete_content(&file, task.start_pos, task.end_pos)?
+        }
+    }
+
+    /// Compute the content of a file after applying a single edit task
+    fn apply_task(&self, file: &CodeFile, task: &EditTask) -> Result<String> {
+        match task.operation_type {
+            EditOperationType::Insert => self.insert_content(file, task.start_pos, &task.content),
+            EditOperationType::Replace => {
+                self.replace_content(file, task.start_pos, task.end_pos, &task.content)
             }
-            EditOperationType::PatternReplace => {
-                self.pattern_replace_content(&file, &task.content)?
+            EditOperationType::Delete => self.delete_content(file, task.start_pos, task.end_pos),
+            EditOperationType::PatternReplace => self.pattern_replace_content(file, &task.content),
+        }
+    }
+
+    /// Preview the file changes of a multi-edit operation without applying them
+    ///
+    /// Returns one diff per touched file, in the order files first appear in the
+    /// operation's tasks. Files in the core engine are left unchanged.
+    pub async fn preview_operation(&self, operation_id: Uuid) -> Result<Vec<FileDiff>> {
+        let operation = self
+            .get_operation(operation_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Operation not found: {}", operation_id))?;
+
+        let mut order: Vec<Uuid> = Vec::new();
+        let mut working: HashMap<Uuid, (String, CodeFile)> = HashMap::new();
+
+        for task in &operation.tasks {
+            if !working.contains_key(&task.file_id) {
+                let file = self
+                    .core_engine
+                    .get_file(task.file_id)
+                    .await?
+                    .ok_or_else(|| anyhow::anyhow!("File not found: {}", task.file_id))?;
+                order.push(task.file_id);
+                working.insert(task.file_id, (file.content.clone(), file));
             }
-        };
 
-        // Update the file in the core engine
-        self.core_engine.update_file(file.id, new_content).await?;
+            if let Some((_, file)) = working.get_mut(&task.file_id) {
+                file.content = self.apply_task(file, task)?;
+            }
+        }
 
-        Ok(true)
+        Ok(order
+            .into_iter()
+            .filter_map(|id| working.remove(&id))
+            .map(|(original, file)| FileDiff {
+                path: file.path,
+                original,
+                modified: file.content,
+            })
+            .collect())
     }
 
     /// Insert content at a specific position
+    ///
+    /// Line breaks in the inserted content take the file's line ending, and every
+    /// existing line keeps its own.
     fn insert_content(
         &self,
         file: &CodeFile,
         pos: (usize, usize),
         content: &str,
     ) -> Result<String> {
-        let lines: Vec<&str> = file.content.lines().collect();
+        let lines = split_terminated(&file.content);
         let (line_idx, col_idx) = pos;
+        let content = file.line_ending.apply(content);
+
+        // Inserting into an empty file creates its content
+        if lines.is_empty() && pos == (0, 0) {
+            return Ok(content);
+        }
 
         if line_idx >= lines.len() {
             return Err(anyhow::anyhow!("Line index out of bounds"));
         }
 
         let mut result = String::new();
-        for (i, line) in lines.iter().enumerate() {
+        for (i, (line, ending)) in lines.iter().enumerate() {
             if i == line_idx {
                 // Insert at the specified column
                 if col_idx > line.len() {
@@ -227,22 +333,20 @@ impl MultiEditManager {
                 }
                 let (before, after) = line.split_at(col_idx);
                 result.push_str(before);
-                result.push_str(content);
+                result.push_str(&content);
                 result.push_str(after);
             } else {
                 result.push_str(line);
             }
-
-            // Add newline if not the last line
-            if i < lines.len() - 1 {
-                result.push('\n');
-            }
+            result.push_str(ending);
         }
 
         Ok(result)
     }
 
     /// Replace content between two positions
+    ///
+    /// Line endings are kept as in [`Self::insert_content`].
     fn replace_content(
         &self,
         file: &CodeFile,
@@ -250,9 +354,11 @@ impl MultiEditManager {
         end_pos: (usize, usize),
         replacement: &str,
     ) -> Result<String> {
-        let lines: Vec<&str> = file.content.lines().collect();
+        let lines = split_terminated(&file.content);
         let (start_line, start_col) = start_pos;
         let (end_line, end_col) = end_pos;
+        let replacement = file.line_ending.apply(replacement);
+        let replacement = replacement.as_str();
 
         if start_line >= lines.len() || end_line >= lines.len() {
             return Err(anyhow::anyhow!("Line index out of bounds"));
@@ -260,7 +366,7 @@ impl MultiEditManager {
 
         let mut result = String::new();
 
-        for (i, line) in lines.iter().enumerate() {
+        for (i, (line, ending)) in lines.iter().enumerate() {
             if i < start_line || i > end_line {
                 // Outside the replacement range
                 result.push_str(line);
@@ -292,10 +398,7 @@ impl MultiEditManager {
             }
             // Lines between start and end are skipped (effectively deleted)
 
-            // Add newline if not the last line
-            if i < lines.len() - 1 {
-                result.push('\n');
-            }
+            result.push_str(ending);
         }
 
         Ok(result)
@@ -331,3 +434,86 @@ impl MultiEditManager {
         Ok(result)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    fn task(
+        file_id: Uuid,
+        operation_type: EditOperationType,
+        start: (usize, usize),
+        end: (usize, usize),
+        content: &str,
+    ) -> EditTask {
+        EditTask {
+            id: Uuid::new_v4(),
+            file_id,
+            operation_type,
+            start_pos: start,
+            end_pos: end,
+            content: content.to_string(),
+            description: "test edit".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_preview_operation_diffs_without_applying() {
+        let engine = Arc::new(CodeEngine::new().unwrap());
+        let existing = engine
+            .load_file(
+                "src/lib.rs".to_string(),
+                "fn one() {}\nfn two() {}\n".to_string(),
+                "rust".to_string(),
+            )
+            .await
+            .unwrap();
+        let created = engine
+            .load_file("src/new.rs".to_string(), String::new(), "rust".to_string())
+            .await
+            .unwrap();
+
+        let manager = MultiEditManager::new(Arc::clone(&engine));
+        let operation_id = manager
+            .create_operation(
+                "plan".to_string(),
+                "create one file and edit another".to_string(),
+                vec![
+                    task(
+                        created,
+                        EditOperationType::Insert,
+                        (0, 0),
+                        (0, 0),
+                        "pub fn new() {}\n",
+                    ),
+                    task(
+                        existing,
+                        EditOperationType::Replace,
+                        (1, 3),
+                        (1, 6),
+                        "three",
+                    ),
+                ],
+            )
+            .await
+            .unwrap();
+
+        let diffs = manager.preview_operation(operation_id).await.unwrap();
+        assert_eq!(diffs.len(), 2);
+        assert!(diffs[0].is_creation());
+        assert!(!diffs[1].is_creation());
+
+        let rendered = render_unified_diff(&diffs);
+        assert!(rendered.contains("new file: src/new.rs\n--- /dev/null\n+++ b/src/new.rs\n"));
+        assert!(rendered.contains("@@ -0,0 +1,1 @@\n+pub fn new() {}\n"));
+        assert!(rendered.contains("modified: src/lib.rs\n--- a/src/lib.rs\n+++ b/src/lib.rs\n"));
+        assert!(rendered.contains("-fn two() {}\n+fn three() {}\n"));
+
+        // Nothing was written back to the engine
+        let file = engine.get_file(existing).await.unwrap().unwrap();
+        assert_eq!(file.content, "fn one() {}\nfn two() {}\n");
+        let file = engine.get_file(created).await.unwrap().unwrap();
+        assert!(file.content.is_empty());
+    }
+}
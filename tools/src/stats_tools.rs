@@ -0,0 +1,289 @@
+//! Stats Tools Module
+//!
+//! This module combines git history with AST complexity to find risk hotspots:
+//! files that are both complex and frequently changed.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use tracing::debug;
+
+use crate::git_tools::{ensure_git_repo, run_git};
+use odincode_core::CodeEngine;
+
+/// A file ranked by how risky it is to change
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Hotspot {
+    /// Path relative to the repository root
+    pub path: String,
+    /// Number of commits that touched the file
+    pub churn: usize,
+    /// Summed cyclomatic complexity of the file's functions
+    pub complexity: u32,
+    /// Churn multiplied by complexity
+    pub score: f64,
+}
+
+/// Count the commits touching each path in a repository's history
+pub fn git_churn(repo_root: &str) -> Result<HashMap<String, usize>> {
+    ensure_git_repo(repo_root)?;
+    let log = run_git(repo_root, &["log", "--format=", "--name-only"])?;
+
+    let mut churn = HashMap::new();
+    for path in log.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        *churn.entry(path.to_string()).or_insert(0) += 1;
+    }
+    Ok(churn)
+}
+
+/// Rank the files of a repository by churn times complexity, returning the top `top_n`
+///
+/// Files that no longer exist, or whose language has no grammar, are left out.
+pub async fn hotspot_report(repo_root: &str, top_n: usize) -> Result<Vec<Hotspot>> {
+    let churn = git_churn(repo_root)?;
+    let engine = CodeEngine::new()?;
+
+    let mut hotspots = Vec::new();
+    for (path, churn) in churn {
+        let Ok(content) = std::fs::read_to_string(Path::new(repo_root).join(&path)) else {
+            continue;
+        };
+        let complexity = match engine.load_file_with_detection(path.clone(), content).await {
+            Ok(file_id) => engine.file_complexity(file_id).await,
+            Err(e) => Err(e),
+        };
+        match complexity {
+            Ok(complexity) => hotspots.push(Hotspot {
+                score: churn as f64 * complexity as f64,
+                path,
+                churn,
+                complexity,
+            }),
+            Err(e) => debug!("Skipping {} in hotspot report: {}", path, e),
+        }
+    }
+
+    hotspots.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| a.path.cmp(&b.path))
+    });
+    hotspots.truncate(top_n);
+    Ok(hotspots)
+}
+
+/// Line counts for one language in a directory tree
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LanguageStats {
+    /// Language name, as detected by the engine
+    pub language: String,
+    /// Number of files
+    pub files: usize,
+    /// Lines containing code
+    pub code_lines: usize,
+    /// Lines containing only comments
+    pub comment_lines: usize,
+    /// Empty or whitespace-only lines
+    pub blank_lines: usize,
+}
+
+/// Line comment prefixes and optional block comment delimiters of a language
+type CommentSyntax = (
+    &'static [&'static str],
+    Option<(&'static str, &'static str)>,
+);
+
+/// Comment syntax of a language, by engine language name
+fn comment_syntax(language: &str) -> CommentSyntax {
+    match language {
+        "rust" | "javascript" | "typescript" | "java" | "c" | "cpp" | "csharp" | "go" | "swift"
+        | "kotlin" | "scala" | "dart" | "objective-c" => (&["//"], Some(("/*", "*/"))),
+        "php" => (&["//", "#"], Some(("/*", "*/"))),
+        "python" | "ruby" | "shell" | "perl" | "r" => (&["#"], None),
+        "lua" => (&["--"], None),
+        _ => (&[], None),
+    }
+}
+
+/// Whether file content looks binary (contains NUL bytes near the start)
+fn is_binary(bytes: &[u8]) -> bool {
+    bytes.iter().take(8000).any(|byte| *byte == 0)
+}
+
+/// Add the code, comment and blank line counts of `content` to `stats`
+fn count_lines(content: &str, stats: &mut LanguageStats) {
+    let (line_prefixes, block) = comment_syntax(&stats.language);
+    let mut in_block = false;
+
+    for line in content.lines().map(str::trim) {
+        if line.is_empty() {
+            stats.blank_lines += 1;
+        } else if in_block {
+            stats.comment_lines += 1;
+            if block.is_some_and(|(_, end)| line.contains(end)) {
+                in_block = false;
+            }
+        } else if line_prefixes.iter().any(|prefix| line.starts_with(prefix)) {
+            stats.comment_lines += 1;
+        } else if let Some((start, end)) = block.filter(|(start, _)| line.starts_with(start)) {
+            stats.comment_lines += 1;
+            in_block = !line[start.len()..].contains(end);
+        } else {
+            stats.code_lines += 1;
+        }
+    }
+}
+
+/// Count code, comment and blank lines per language under `root`
+///
+/// Gitignored, hidden and binary files are skipped, as are files whose language
+/// the engine can't detect. Languages are ordered by code lines, largest first.
+pub async fn language_breakdown(root: &str) -> Result<Vec<LanguageStats>> {
+    if !Path::new(root).is_dir() {
+        return Err(anyhow::anyhow!("Directory does not exist: {}", root));
+    }
+    let engine = CodeEngine::new()?;
+    let mut by_language: HashMap<String, LanguageStats> = HashMap::new();
+
+    let walker = ignore::WalkBuilder::new(root).require_git(false).build();
+    for entry in walker.filter_map(|entry| entry.ok()) {
+        if !entry
+            .file_type()
+            .is_some_and(|file_type| file_type.is_file())
+        {
+            continue;
+        }
+        let path = entry.path().to_string_lossy().to_string();
+        let Ok(language) = engine.detect_language_from_path(&path).await else {
+            continue;
+        };
+        let Ok(bytes) = std::fs::read(entry.path()) else {
+            continue;
+        };
+        if is_binary(&bytes) {
+            debug!("Skipping binary file {}", path);
+            continue;
+        }
+
+        let stats = by_language
+            .entry(language.clone())
+            .or_insert_with(|| LanguageStats {
+                language,
+                files: 0,
+                code_lines: 0,
+                comment_lines: 0,
+                blank_lines: 0,
+            });
+        stats.files += 1;
+        count_lines(&String::from_utf8_lossy(&bytes), stats);
+    }
+
+    let mut breakdown: Vec<LanguageStats> = by_language.into_values().collect();
+    breakdown.sort_by(|a, b| {
+        b.code_lines
+            .cmp(&a.code_lines)
+            .then_with(|| a.language.cmp(&b.language))
+    });
+    Ok(breakdown)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {:?} failed", args);
+    }
+
+    fn commit(dir: &Path, path: &str, content: &str) {
+        std::fs::write(dir.join(path), content).unwrap();
+        git(dir, &["add", path]);
+        git(dir, &["commit", "-q", "-m", path]);
+    }
+
+    #[tokio::test]
+    async fn test_hotspot_report_ranks_complex_churning_file_first() {
+        let dir = tempfile::tempdir().unwrap();
+        git(dir.path(), &["init", "-q"]);
+
+        commit(dir.path(), "simple.rs", "fn simple() -> u32 {\n    1\n}\n");
+        for round in 0..3 {
+            let content = format!(
+                "fn complex(x: u32) -> u32 {{\n    let mut total = {};\n    \
+                 for i in 0..x {{\n        if i % 2 == 0 {{\n            total += i;\n        \
+                 }} else if i % 3 == 0 {{\n            total -= 1;\n        }}\n    }}\n    \
+                 match total {{\n        0 => 1,\n        n => n,\n    }}\n}}\n",
+                round
+            );
+            commit(dir.path(), "complex.rs", &content);
+        }
+
+        let report = hotspot_report(dir.path().to_str().unwrap(), 10)
+            .await
+            .unwrap();
+        assert_eq!(report.len(), 2);
+        assert_eq!(report[0].path, "complex.rs");
+        assert_eq!(report[0].churn, 3);
+        assert!(report[0].complexity > report[1].complexity);
+        assert_eq!(report[1].path, "simple.rs");
+        assert_eq!(report[1].churn, 1);
+        assert_eq!(report[0].score, 3.0 * report[0].complexity as f64);
+
+        let top = hotspot_report(dir.path().to_str().unwrap(), 1)
+            .await
+            .unwrap();
+        assert_eq!(top.len(), 1);
+        assert_eq!(top[0].path, "complex.rs");
+    }
+
+    #[tokio::test]
+    async fn test_language_breakdown_counts_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("main.rs"),
+            "// entry point\nfn main() {\n\n    /* block\n       comment */\n    \
+             println!(\"hi\"); // trailing\n}\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("util.py"),
+            "# helpers\n\ndef one():\n    return 1\n",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join(".gitignore"), "generated.rs\n").unwrap();
+        std::fs::write(dir.path().join("generated.rs"), "fn generated() {}\n").unwrap();
+        std::fs::write(dir.path().join("blob.rs"), [0u8, 159, 146, 150]).unwrap();
+
+        let breakdown = language_breakdown(dir.path().to_str().unwrap())
+            .await
+            .unwrap();
+        assert_eq!(
+            breakdown,
+            vec![
+                LanguageStats {
+                    language: "rust".to_string(),
+                    files: 1,
+                    code_lines: 3,
+                    comment_lines: 3,
+                    blank_lines: 1,
+                },
+                LanguageStats {
+                    language: "python".to_string(),
+                    files: 1,
+                    code_lines: 2,
+                    comment_lines: 1,
+                    blank_lines: 1,
+                },
+            ]
+        );
+    }
+}
@@ -49,6 +49,16 @@ pub enum ToolType {
     IDE,
 }
 
+impl ToolType {
+    /// Whether tools of this type only read the project
+    ///
+    /// Every other type writes files or runs project code, so it is refused in
+    /// safe mode.
+    pub fn is_read_only(&self) -> bool {
+        matches!(self, ToolType::Linter)
+    }
+}
+
 /// Tool status enumeration
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum ToolStatus {
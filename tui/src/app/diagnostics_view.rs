@@ -0,0 +1,246 @@
+//! TUI Diagnostics View Module
+//!
+//! This module keeps the state of the analysis results view, which shows the
+//! open file's source next to its issues. Selecting an issue scrolls the source
+//! to its line, and selecting a source line narrows the issues to the lines
+//! around it. Terminals narrower than [`MIN_SPLIT_WIDTH`] show the issues alone.
+
+use odincode_core::CodeIssue;
+
+/// Narrowest terminal width, in columns, the split layout is used at
+pub const MIN_SPLIT_WIDTH: u16 = 100;
+
+/// Issues within this many lines of the selected source line are shown
+pub const REGION_RADIUS: usize = 5;
+
+/// Panel of the diagnostics view that receives navigation keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagnosticsFocus {
+    /// The issue list
+    Issues,
+    /// The source panel
+    Source,
+}
+
+/// State of the diagnostics view
+#[derive(Debug, Clone)]
+pub struct DiagnosticsView {
+    /// Whether source and issues are shown side by side
+    pub split: bool,
+    /// Panel receiving navigation keys
+    pub focus: DiagnosticsFocus,
+    /// Index of the selected issue among the shown issues
+    pub selected_issue: Option<usize>,
+    /// 1-based source line selected in the source panel, if any
+    pub selected_line: Option<usize>,
+    /// First source line shown, 0-based
+    pub source_scroll: usize,
+    /// Height of the source panel at the last render, in lines
+    pub viewport_height: usize,
+    /// Width of the view at the last render, in columns
+    pub width: u16,
+}
+
+impl Default for DiagnosticsView {
+    fn default() -> Self {
+        Self {
+            split: true,
+            focus: DiagnosticsFocus::Issues,
+            selected_issue: None,
+            selected_line: None,
+            source_scroll: 0,
+            viewport_height: 20,
+            width: MIN_SPLIT_WIDTH,
+        }
+    }
+}
+
+/// Source scroll offset that shows a 1-based line near the middle of the
+/// viewport, without scrolling past the end of the source
+pub fn scroll_offset_for_line(line: usize, viewport_height: usize, total_lines: usize) -> usize {
+    let max_scroll = total_lines.saturating_sub(viewport_height);
+    line.saturating_sub(1)
+        .saturating_sub(viewport_height / 2)
+        .min(max_scroll)
+}
+
+/// Whether a terminal this wide shows the split layout when it is enabled
+pub fn fits_split(width: u16) -> bool {
+    width >= MIN_SPLIT_WIDTH
+}
+
+impl DiagnosticsView {
+    /// Whether the source panel is shown: the split layout is enabled and the
+    /// view was wide enough for it at the last render
+    pub fn shows_split(&self) -> bool {
+        self.split && fits_split(self.width)
+    }
+
+    /// Panel receiving navigation keys; always the issues while the source
+    /// panel is hidden
+    pub fn focused_panel(&self) -> DiagnosticsFocus {
+        if self.shows_split() {
+            self.focus
+        } else {
+            DiagnosticsFocus::Issues
+        }
+    }
+
+    /// Indices of the issues shown: all of them, or those near the selected line
+    /// while the source panel is shown
+    pub fn visible_issues(&self, issues: &[CodeIssue]) -> Vec<usize> {
+        (0..issues.len())
+            .filter(|&i| match self.selected_line {
+                Some(line) if self.shows_split() => {
+                    issues[i].line_number.abs_diff(line) <= REGION_RADIUS
+                }
+                _ => true,
+            })
+            .collect()
+    }
+
+    /// Toggle between the split and the single-panel layout
+    pub fn toggle_split(&mut self) {
+        self.split = !self.split;
+        if !self.split {
+            self.focus = DiagnosticsFocus::Issues;
+            self.selected_line = None;
+        }
+    }
+
+    /// Move keyboard focus to the other panel, if the source panel is shown
+    pub fn toggle_focus(&mut self) {
+        if self.shows_split() {
+            self.focus = match self.focus {
+                DiagnosticsFocus::Issues => DiagnosticsFocus::Source,
+                DiagnosticsFocus::Source => DiagnosticsFocus::Issues,
+            };
+        }
+    }
+
+    /// Move the selection of the focused panel by `delta`, wrapping around, and
+    /// sync the other panel to it
+    pub fn move_selection(&mut self, delta: isize, issues: &[CodeIssue], total_lines: usize) {
+        match self.focused_panel() {
+            DiagnosticsFocus::Issues => {
+                let visible = self.visible_issues(issues);
+                if visible.is_empty() {
+                    self.selected_issue = None;
+                    return;
+                }
+                let next = match self.selected_issue {
+                    Some(index) => wrap(index, delta, visible.len()),
+                    None => 0,
+                };
+                self.selected_issue = Some(next);
+                let line = issues[visible[next]].line_number;
+                self.source_scroll =
+                    scroll_offset_for_line(line, self.viewport_height, total_lines);
+            }
+            DiagnosticsFocus::Source => {
+                if total_lines == 0 {
+                    return;
+                }
+                let line = match self.selected_line {
+                    Some(line) => wrap(line - 1, delta, total_lines) + 1,
+                    // The first move selects the top line of the viewport
+                    None => self.source_scroll + 1,
+                };
+                self.selected_line = Some(line);
+                self.selected_issue = None;
+                self.source_scroll =
+                    scroll_offset_for_line(line, self.viewport_height, total_lines);
+            }
+        }
+    }
+}
+
+/// Index moved by `delta` within `0..len`, wrapping at both ends
+fn wrap(index: usize, delta: isize, len: usize) -> usize {
+    (index as isize + delta).rem_euclid(len as isize) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use odincode_core::{IssueType, Severity};
+    use uuid::Uuid;
+
+    fn issue(line_number: usize) -> CodeIssue {
+        CodeIssue {
+            id: Uuid::new_v4(),
+            issue_type: IssueType::Style,
+            severity: Severity::Low,
+            description: format!("issue on line {}", line_number),
+            line_number,
+            column_number: 0,
+            suggestion: None,
+            rule_id: None,
+            source: None,
+        }
+    }
+
+    #[test]
+    fn test_scroll_offset_centers_and_clamps() {
+        // Near the top the source is not scrolled
+        assert_eq!(scroll_offset_for_line(3, 10, 100), 0);
+        // Line 50 ends up in the middle of a 10-line viewport
+        assert_eq!(scroll_offset_for_line(50, 10, 100), 44);
+        // The last page is never scrolled past
+        assert_eq!(scroll_offset_for_line(99, 10, 100), 90);
+        // Sources shorter than the viewport never scroll
+        assert_eq!(scroll_offset_for_line(4, 10, 5), 0);
+    }
+
+    #[test]
+    fn test_selection_syncs_panels() {
+        let issues = vec![issue(2), issue(40), issue(44), issue(90)];
+        let mut view = DiagnosticsView {
+            viewport_height: 10,
+            ..DiagnosticsView::default()
+        };
+
+        view.move_selection(1, &issues, 100);
+        view.move_selection(1, &issues, 100);
+        assert_eq!(view.selected_issue, Some(1));
+        assert_eq!(view.source_scroll, 34);
+
+        // Selecting a source line narrows the issues to that region
+        view.toggle_focus();
+        view.move_selection(1, &issues, 100);
+        assert_eq!(view.selected_line, Some(35));
+        assert_eq!(view.visible_issues(&issues), vec![1]);
+        view.move_selection(5, &issues, 100);
+        assert_eq!(view.visible_issues(&issues), vec![1, 2]);
+
+        view.toggle_split();
+        assert_eq!(view.visible_issues(&issues).len(), 4);
+        assert!(!fits_split(80));
+    }
+
+    #[test]
+    fn test_narrow_view_keeps_focus_on_issues() {
+        let issues = vec![issue(2), issue(40)];
+        let mut view = DiagnosticsView {
+            viewport_height: 10,
+            width: 80,
+            ..DiagnosticsView::default()
+        };
+
+        // The hidden source panel cannot take focus
+        view.toggle_focus();
+        assert_eq!(view.focused_panel(), DiagnosticsFocus::Issues);
+        view.move_selection(1, &issues, 100);
+        assert_eq!(view.selected_issue, Some(0));
+        assert_eq!(view.selected_line, None);
+
+        // A line selected before the terminal shrank no longer filters the issues
+        view.width = MIN_SPLIT_WIDTH;
+        view.toggle_focus();
+        view.move_selection(1, &issues, 100);
+        assert!(view.selected_line.is_some());
+        view.width = 80;
+        assert_eq!(view.focused_panel(), DiagnosticsFocus::Issues);
+        assert_eq!(view.visible_issues(&issues), vec![0, 1]);
+    }
+}
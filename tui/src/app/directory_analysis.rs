@@ -0,0 +1,66 @@
+//! TUI Directory Analysis Module
+//!
+//! This module parses the `/analyze [dir]` command, which analyzes a directory
+//! in the background, and `/cancel`, which stops it. A cancelled analysis
+//! still reports the files it finished.
+
+use odincode_core::AnalysisResult;
+
+/// Parse an `/analyze [dir]` command, returning the directory to analyze
+///
+/// Returns `None` if the input is not a well-formed `/analyze` command.
+pub fn parse_analyze_command(input: &str) -> Option<String> {
+    let mut parts = input.split_whitespace();
+    if parts.next()? != "/analyze" {
+        return None;
+    }
+
+    let root = parts.next().unwrap_or(".").to_string();
+    parts.next().is_none().then_some(root)
+}
+
+/// Whether the input is the `/cancel` command
+pub fn is_cancel_command(input: &str) -> bool {
+    input.trim() == "/cancel"
+}
+
+/// Output line summarizing a finished or cancelled directory analysis
+pub fn analysis_summary(
+    root: &str,
+    results: &[(String, AnalysisResult)],
+    cancelled: bool,
+) -> String {
+    let issues: usize = results.iter().map(|(_, result)| result.issues.len()).sum();
+    let state = if cancelled { "cancelled" } else { "finished" };
+    format!(
+        "Analysis of {} {}: {} files, {} issues",
+        root,
+        state,
+        results.len(),
+        issues
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_analyze_and_cancel_commands() {
+        assert_eq!(parse_analyze_command("/analyze"), Some(".".to_string()));
+        assert_eq!(
+            parse_analyze_command("/analyze src"),
+            Some("src".to_string())
+        );
+        assert_eq!(parse_analyze_command("/analyze src tests"), None);
+        assert_eq!(parse_analyze_command("/analyzer"), None);
+
+        assert!(is_cancel_command(" /cancel "));
+        assert!(!is_cancel_command("/cancel now"));
+
+        assert_eq!(
+            analysis_summary(".", &[], true),
+            "Analysis of . cancelled: 0 files, 0 issues"
+        );
+    }
+}
@@ -0,0 +1,261 @@
+//! TUI Execution History Module
+//!
+//! This module records the commands run from the terminal view and formats
+//! them for the `/history` command. With an action history database connected,
+//! the records are written to it, and `/history` reads them back together with
+//! the agent runs recorded there.
+
+use anyhow::Result;
+use chrono::{DateTime, TimeZone, Utc};
+use std::sync::Arc;
+use tokio::runtime::Handle;
+use tokio::sync::{mpsc, oneshot};
+use tracing::warn;
+
+use odincode_core::action_history::{Action, ActionHistoryManager};
+
+/// Number of executions shown by `/history` without an explicit count
+pub const DEFAULT_HISTORY_LIMIT: usize = 20;
+
+/// A finished command execution
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExecutionRecord {
+    /// Command that was run
+    pub command: String,
+    /// Whether the command succeeded
+    pub success: bool,
+    /// How long the command ran, in milliseconds
+    pub duration_ms: u64,
+    /// File the execution affected, if any
+    pub path: Option<String>,
+    /// When the command finished
+    pub timestamp: DateTime<Utc>,
+}
+
+impl ExecutionRecord {
+    /// Record of an action read from the action history
+    ///
+    /// Agent runs and tool calls are named after their tool; other actions
+    /// after their type.
+    pub fn from_action(action: &Action) -> Self {
+        Self {
+            command: action
+                .tool_name
+                .clone()
+                .unwrap_or_else(|| action.action_type.as_str().to_string()),
+            success: action.success,
+            duration_ms: action.duration_ms.unwrap_or(0),
+            path: action.file_path.clone(),
+            timestamp: Utc
+                .timestamp_opt(action.timestamp, 0)
+                .single()
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Request handled by the history store's task, in the order sent
+enum StoreRequest {
+    /// Write an execution
+    Record(ExecutionRecord),
+    /// Read the most recent actions, newest first
+    Recent(usize, oneshot::Sender<Result<Vec<ExecutionRecord>>>),
+}
+
+/// Execution history kept in the action history database
+///
+/// A single task writes and reads on the database's runtime, so a `/history`
+/// issued right after a command always sees it.
+pub struct HistoryStore {
+    /// Requests to the store's task
+    requests: mpsc::UnboundedSender<StoreRequest>,
+}
+
+impl HistoryStore {
+    /// Start the store's task on `runtime`, recording under `session_id`
+    pub fn spawn(history: Arc<ActionHistoryManager>, session_id: String, runtime: &Handle) -> Self {
+        let (requests, mut receiver) = mpsc::unbounded_channel();
+        runtime.spawn(async move {
+            while let Some(request) = receiver.recv().await {
+                match request {
+                    StoreRequest::Record(record) => {
+                        if let Err(e) = history
+                            .log_tool_call(
+                                &session_id,
+                                &record.command,
+                                None,
+                                None,
+                                record.success,
+                                None,
+                                Some(record.duration_ms),
+                                None,
+                            )
+                            .await
+                        {
+                            warn!("Failed to record execution of {}: {}", record.command, e);
+                        }
+                    }
+                    StoreRequest::Recent(limit, reply) => {
+                        let records = history
+                            .get_recent_actions(limit.min(u32::MAX as usize) as u32)
+                            .await
+                            .map(|actions| {
+                                actions.iter().map(ExecutionRecord::from_action).collect()
+                            });
+                        let _ = reply.send(records);
+                    }
+                }
+            }
+        });
+        Self { requests }
+    }
+
+    /// Write an execution in the background
+    pub fn record(&self, record: ExecutionRecord) {
+        let _ = self.requests.send(StoreRequest::Record(record));
+    }
+
+    /// Read the `limit` most recent actions, newest first
+    ///
+    /// The receiver fails if the store's task has stopped.
+    pub fn recent(&self, limit: usize) -> oneshot::Receiver<Result<Vec<ExecutionRecord>>> {
+        let (reply, receiver) = oneshot::channel();
+        let _ = self.requests.send(StoreRequest::Recent(limit, reply));
+        receiver
+    }
+}
+
+/// Lines of the `/history` table for executions given newest first
+pub fn history_table<'a>(records: impl IntoIterator<Item = &'a ExecutionRecord>) -> String {
+    let mut lines = vec![history_header()];
+    lines.extend(records.into_iter().map(format_execution_row));
+    if lines.len() == 1 {
+        lines.push("No commands have been executed yet".to_string());
+    }
+    lines.join("\n")
+}
+
+/// Parse a `/history [count]` command, returning the number of rows to show
+///
+/// Returns `None` if the input is not a well-formed `/history` command.
+pub fn parse_history_command(input: &str) -> Option<usize> {
+    let mut parts = input.split_whitespace();
+    if parts.next()? != "/history" {
+        return None;
+    }
+
+    let limit = match parts.next() {
+        Some(count) => count.parse::<usize>().ok().filter(|n| *n > 0)?,
+        None => DEFAULT_HISTORY_LIMIT,
+    };
+
+    parts.next().is_none().then_some(limit)
+}
+
+/// Header row of the `/history` table
+pub fn history_header() -> String {
+    format!(
+        "{:<19}  {:<4}  {:>8}  {:<24}  {}",
+        "TIMESTAMP", "OK", "DURATION", "PATH", "COMMAND"
+    )
+}
+
+/// Format one execution as a row of the `/history` table
+pub fn format_execution_row(record: &ExecutionRecord) -> String {
+    format!(
+        "{:<19}  {:<4}  {:>8}  {:<24}  {}",
+        record.timestamp.format("%Y-%m-%d %H:%M:%S"),
+        if record.success { "yes" } else { "no" },
+        format_duration(record.duration_ms),
+        record.path.as_deref().unwrap_or("-"),
+        record.command
+    )
+}
+
+/// Format a duration compactly, e.g. `850ms` or `12.3s`
+fn format_duration(duration_ms: u64) -> String {
+    if duration_ms < 1000 {
+        format!("{}ms", duration_ms)
+    } else {
+        format!("{:.1}s", duration_ms as f64 / 1000.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_history_command() {
+        assert_eq!(
+            parse_history_command("/history"),
+            Some(DEFAULT_HISTORY_LIMIT)
+        );
+        assert_eq!(parse_history_command("/history 50"), Some(50));
+        assert_eq!(parse_history_command("  /history   5 "), Some(5));
+        assert_eq!(parse_history_command("/history 0"), None);
+        assert_eq!(parse_history_command("/history many"), None);
+        assert_eq!(parse_history_command("/history 5 6"), None);
+        assert_eq!(parse_history_command("/historyx"), None);
+        assert_eq!(parse_history_command("history"), None);
+    }
+
+    #[test]
+    fn test_format_execution_row() {
+        let record = ExecutionRecord {
+            command: "cargo build".to_string(),
+            success: false,
+            duration_ms: 12_345,
+            path: None,
+            timestamp: Utc.with_ymd_and_hms(2025, 3, 4, 5, 6, 7).unwrap(),
+        };
+
+        assert_eq!(
+            format_execution_row(&record),
+            "2025-03-04 05:06:07  no       12.3s  -                         cargo build"
+        );
+
+        let record = ExecutionRecord {
+            success: true,
+            duration_ms: 850,
+            path: Some("src/lib.rs".to_string()),
+            ..record
+        };
+        assert!(format_execution_row(&record).contains("  yes   "));
+        assert!(format_execution_row(&record).contains("   850ms  src/lib.rs  "));
+    }
+
+    #[tokio::test]
+    async fn test_history_store_reads_back_records_and_agent_runs() {
+        let pool = sqlx::SqlitePool::connect("sqlite::memory:").await.unwrap();
+        let history = Arc::new(ActionHistoryManager::new(pool));
+        history.init().await.unwrap();
+        history
+            .log_agent_execution("", "Bug Detector", Some("src/lib.rs"), true, None, 40, 2)
+            .await
+            .unwrap();
+
+        let store = HistoryStore::spawn(history, "tui".to_string(), &Handle::current());
+        store.record(ExecutionRecord {
+            command: "cargo test".to_string(),
+            success: false,
+            duration_ms: 1_500,
+            path: None,
+            timestamp: Utc::now(),
+        });
+
+        let records = store.recent(10).await.unwrap().unwrap();
+        let commands: Vec<&str> = records.iter().map(|r| r.command.as_str()).collect();
+        assert_eq!(records.len(), 2);
+        assert!(commands.contains(&"cargo test"));
+        assert!(commands.contains(&"Bug Detector"));
+        let agent_run = records
+            .iter()
+            .find(|r| r.command == "Bug Detector")
+            .unwrap();
+        assert_eq!(agent_run.path.as_deref(), Some("src/lib.rs"));
+        let test_run = records.iter().find(|r| r.command == "cargo test").unwrap();
+        assert!(!test_run.success);
+        assert_eq!(test_run.duration_ms, 1_500);
+    }
+}
@@ -0,0 +1,136 @@
+//! TUI Git Diff Module
+//!
+//! This module parses the `/diff` command and prepares the working-tree diff it
+//! shows for the output panel, where added lines are drawn green and removed
+//! lines red.
+
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+
+use crate::theme::Theme;
+
+/// Output type of diff lines in the output buffer, styled line by line
+pub const DIFF_OUTPUT_TYPE: &str = "diff";
+
+/// A parsed `/diff [path]` command
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiffCommand {
+    /// File or directory the diff is limited to, if any
+    pub path: Option<String>,
+}
+
+/// Parse a `/diff [path]` command
+///
+/// Returns `None` if the input is not a well-formed `/diff` command.
+pub fn parse_diff_command(input: &str) -> Option<DiffCommand> {
+    let mut parts = input.split_whitespace();
+    if parts.next()? != "/diff" {
+        return None;
+    }
+
+    let path = parts.next().map(str::to_string);
+    parts.next().is_none().then_some(DiffCommand { path })
+}
+
+/// Split `git diff` output into display lines
+///
+/// git summarizes binary changes as `Binary files a/x and b/x differ`; these
+/// become a single `Binary file changed: x` line.
+pub fn format_diff(diff: &str) -> Vec<String> {
+    diff.lines()
+        .map(|line| match binary_change_path(line) {
+            Some(path) => format!("Binary file changed: {}", path),
+            None => line.to_string(),
+        })
+        .collect()
+}
+
+/// Path of the file in a `Binary files ... differ` line
+fn binary_change_path(line: &str) -> Option<&str> {
+    let files = line
+        .strip_prefix("Binary files ")?
+        .strip_suffix(" differ")?;
+    let (old, new) = files.split_once(" and ")?;
+    let path = if new == "/dev/null" { old } else { new };
+    Some(
+        path.strip_prefix("a/")
+            .or_else(|| path.strip_prefix("b/"))
+            .unwrap_or(path),
+    )
+}
+
+/// Style for one line of a diff
+pub fn diff_line_style(line: &str, theme: &Theme) -> Style {
+    let style = Style::default();
+    if ["diff --git", "index ", "--- ", "+++ "]
+        .iter()
+        .any(|prefix| line.starts_with(prefix))
+    {
+        style.fg(theme.info).add_modifier(Modifier::BOLD)
+    } else if line.starts_with("@@") {
+        style.fg(theme.success)
+    } else if line.starts_with('+') {
+        style.fg(theme.added)
+    } else if line.starts_with('-') {
+        style.fg(theme.removed)
+    } else if line.starts_with("Binary file changed") {
+        style.fg(theme.info).add_modifier(Modifier::ITALIC)
+    } else {
+        style.fg(theme.info)
+    }
+}
+
+/// Render one line of a diff for the output panel
+pub fn styled_diff_line(line: &str, theme: &Theme) -> Line<'static> {
+    Line::from(Span::styled(line.to_string(), diff_line_style(line, theme)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::style::Color;
+
+    #[test]
+    fn test_parse_diff_command() {
+        assert_eq!(
+            parse_diff_command("/diff"),
+            Some(DiffCommand { path: None })
+        );
+        assert_eq!(
+            parse_diff_command(" /diff src/lib.rs "),
+            Some(DiffCommand {
+                path: Some("src/lib.rs".to_string())
+            })
+        );
+        assert_eq!(parse_diff_command("/diff a.rs b.rs"), None);
+        assert_eq!(parse_diff_command("/diffs"), None);
+    }
+
+    #[test]
+    fn test_diff_lines_are_styled() {
+        let diff = "diff --git a/lib.rs b/lib.rs\n\
+                    --- a/lib.rs\n\
+                    +++ b/lib.rs\n\
+                    @@ -1,2 +1,2 @@\n \
+                    fn one() {}\n\
+                    -fn two() {}\n\
+                    +fn three() {}\n\
+                    diff --git a/logo.png b/logo.png\n\
+                    Binary files a/logo.png and b/logo.png differ\n";
+        let theme = Theme::default();
+
+        let lines = format_diff(diff);
+        assert_eq!(lines[8], "Binary file changed: logo.png");
+
+        let colors: Vec<Option<Color>> = lines
+            .iter()
+            .map(|line| styled_diff_line(line, &theme).spans[0].style.fg)
+            .collect();
+        assert_eq!(colors[4], Some(theme.info));
+        assert_eq!(colors[5], Some(Color::Red));
+        assert_eq!(colors[6], Some(Color::Green));
+        // File headers are not mistaken for removed or added lines
+        assert_eq!(colors[1], Some(theme.info));
+        assert_eq!(colors[2], Some(theme.info));
+    }
+}
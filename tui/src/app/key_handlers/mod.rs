@@ -7,6 +7,7 @@ use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use tracing::info;
 
 use crate::app::TuiApp;
+use crate::approval::ApprovalResponse;
 use crate::models::TuiState;
 
 /// Handle keys in file browser state
@@ -35,6 +36,8 @@ pub fn handle_file_browser_keys(app: &mut TuiApp, key_event: KeyEvent) -> Result
             if app.selected_file_index < app.files.len() {
                 if let Some(file) = app.files.get(app.selected_file_index) {
                     app.code_content = file.content.clone();
+                    app.code_path = Some(file.path.clone());
+                    app.code_scroll = 0;
                     app.current_state = TuiState::CodeEditor;
                 }
             }
@@ -59,6 +62,9 @@ pub fn handle_code_editor_keys(app: &mut TuiApp, key_event: KeyEvent) -> Result<
         KeyCode::Char('b') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
             app.current_state = TuiState::FileBrowser;
         }
+        KeyCode::Char('s') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.save_code_file();
+        }
         KeyCode::Char('a') => {
             app.current_state = TuiState::AgentSelection;
         }
@@ -115,12 +121,29 @@ pub fn handle_agent_selection_keys(app: &mut TuiApp, key_event: KeyEvent) -> Res
 
 /// Handle keys in analysis results state
 pub fn handle_analysis_results_keys(app: &mut TuiApp, key_event: KeyEvent) -> Result<()> {
-    match key_event.code {
+    let delta = match key_event.code {
         KeyCode::Char('b') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
             app.current_state = TuiState::FileBrowser;
+            return Ok(());
         }
-        _ => {}
+        KeyCode::Char('s') | KeyCode::Char('S') => {
+            app.diagnostics.toggle_split();
+            return Ok(());
+        }
+        KeyCode::Tab => {
+            app.diagnostics.toggle_focus();
+            return Ok(());
+        }
+        KeyCode::Up => -1,
+        KeyCode::Down => 1,
+        _ => return Ok(()),
+    };
+
+    let mut diagnostics = app.diagnostics.clone();
+    if let Some((file, issues)) = app.diagnostics_file() {
+        diagnostics.move_selection(delta, issues, file.content.lines().count());
     }
+    app.diagnostics = diagnostics;
     Ok(())
 }
 
@@ -163,6 +186,16 @@ pub fn handle_ltmc_view_keys(app: &mut TuiApp, key_event: KeyEvent) -> Result<()
 
 /// Handle keys in tool selection state
 pub fn handle_tool_selection_keys(app: &mut TuiApp, key_event: KeyEvent) -> Result<()> {
+    // While an approval prompt is open only its answer keys are handled
+    if app.pending_approval.is_some() {
+        if let KeyCode::Char(c) = key_event.code {
+            if let Some(response) = ApprovalResponse::from_key(c) {
+                app.answer_approval(response);
+            }
+        }
+        return Ok(());
+    }
+
     match key_event.code {
         KeyCode::Up => {
             if app.tools.len() > 0 {
@@ -191,11 +224,13 @@ pub fn handle_tool_selection_keys(app: &mut TuiApp, key_event: KeyEvent) -> Resu
             }
         }
         KeyCode::Enter => {
-            // Execute the selected tool
-            if let Some(index) = app.selected_tool_index {
-                if index < app.tools.len() {
-                    info!("Executing tool: {}", app.tools[index]);
-                }
+            // Execute the selected tool, subject to its approval policy
+            if let Some(tool) = app
+                .selected_tool_index
+                .and_then(|i| app.tools.get(i))
+                .cloned()
+            {
+                app.request_tool_execution(&tool);
             }
         }
         KeyCode::Char('b') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
@@ -2,25 +2,37 @@
 //!
 //! This module contains the main TUI application logic.
 
+pub mod diagnostics_view;
+pub mod directory_analysis;
+pub mod execution_history;
+pub mod git_diff;
 pub mod key_handlers;
+pub mod reindex;
+pub mod symbol_search;
 pub mod terminal_integration;
+pub mod tool_progress;
+pub mod usage_report;
 
 use anyhow::Result;
-use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
-use tracing::info;
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers, MouseEvent};
+use tracing::{info, warn};
 use uuid::Uuid;
 
 use odincode_agents::{Agent, AgentCoordinator};
-use odincode_core::{AnalysisResult, CodeEngine, CodeFile};
+use odincode_core::{AnalysisResult, CodeEngine, CodeFile, CodeIssue};
 use odincode_ltmc::{LTMManager, LearningPattern};
-use odincode_tools::ToolManager;
+use odincode_tools::{ToolManager, SAFE_MODE_BLOCKED};
 
+use crate::app::diagnostics_view::DiagnosticsView;
 use crate::app::key_handlers::{
     handle_agent_selection_keys, handle_analysis_results_keys, handle_code_editor_keys,
     handle_file_browser_keys, handle_ltmc_view_keys, handle_tool_selection_keys,
 };
 use crate::app::terminal_integration::TerminalIntegration;
+use crate::approval::{allowed_in_safe_mode, ApprovalConfig, ApprovalDecision, ApprovalResponse};
 use crate::models::TuiState;
+use crate::panels::{panel_for_jump, Panel, PanelConfig, CYCLE_KEY};
+use crate::theme::Theme;
 
 /// Represents the main TUI application
 pub struct TuiApp {
@@ -44,14 +56,34 @@ pub struct TuiApp {
     pub selected_tool_index: Option<usize>,
     /// Code content for the editor
     pub code_content: String,
+    /// Path of the file shown in the code editor
+    pub code_path: Option<String>,
+    /// First line shown in the code editor
+    pub code_scroll: usize,
     /// Analysis results
     pub analysis_results: Vec<AnalysisResult>,
+    /// State of the analysis results view
+    pub diagnostics: DiagnosticsView,
     /// Current tab index
     pub current_tab: usize,
     /// Application title
     pub title: String,
     /// Enhanced terminal integration
     pub terminal_integration: TerminalIntegration,
+    /// Active color theme
+    pub theme: Theme,
+    /// Order the cycle key moves through the panels in
+    pub panels: PanelConfig,
+    /// Approval policies for running tools
+    pub approval: ApprovalConfig,
+    /// Whether every prompted tool is approved for the rest of the session
+    pub approve_all_gated: bool,
+    /// Tool waiting for the user to answer its approval prompt
+    pub pending_approval: Option<String>,
+    /// Message shown in the status bar until the next key press
+    pub status_message: Option<String>,
+    /// Whether tools that write files or run project code are refused
+    pub safe_mode: bool,
 }
 
 impl TuiApp {
@@ -68,10 +100,20 @@ impl TuiApp {
             tools: Vec::new(),
             selected_tool_index: None,
             code_content: String::new(),
+            code_path: None,
+            code_scroll: 0,
             analysis_results: Vec::new(),
+            diagnostics: DiagnosticsView::default(),
             current_tab: 0,
             title: "OdinCode - AI Code Engineering System".to_string(),
             terminal_integration: TerminalIntegration::new(),
+            theme: Theme::default(),
+            panels: PanelConfig::default(),
+            approval: ApprovalConfig::default(),
+            approve_all_gated: false,
+            pending_approval: None,
+            status_message: None,
+            safe_mode: false,
         }
     }
 
@@ -105,6 +147,22 @@ impl TuiApp {
 
     /// Handle key events
     pub fn handle_key_event(&mut self, key_event: KeyEvent) -> Result<bool> {
+        // `q` at an approval prompt dismisses the prompt rather than quitting
+        let answering_approval = self.pending_approval.is_some();
+        self.status_message = None;
+
+        // Panel jumps and cycling work in every panel, except at a prompt
+        if !answering_approval {
+            if let Some(panel) = panel_for_jump(key_event) {
+                self.show_panel(panel);
+                return Ok(true);
+            }
+            if key_event.code == CYCLE_KEY {
+                self.show_panel(self.panels.next(Panel::from_state(&self.current_state)));
+                return Ok(true);
+            }
+        }
+
         match self.current_state {
             TuiState::FileBrowser => handle_file_browser_keys(self, key_event)?,
             TuiState::CodeEditor => handle_code_editor_keys(self, key_event)?,
@@ -123,8 +181,164 @@ impl TuiApp {
             }
         }
 
+        self.open_requested_location();
+
         // Return true if the application should continue running, false to exit
-        Ok(key_event.code != KeyCode::Char('q')
+        Ok(answering_approval
+            || key_event.code != KeyCode::Char('q')
             || key_event.modifiers.contains(KeyModifiers::CONTROL))
     }
+
+    /// Switch to a panel
+    pub fn show_panel(&mut self, panel: Panel) {
+        self.current_state = panel.state();
+        self.current_tab = panel.tab_index();
+    }
+
+    /// The selected file and the issues of its latest analysis, if any
+    pub fn diagnostics_file(&self) -> Option<(&CodeFile, &[CodeIssue])> {
+        let file = self.files.get(self.selected_file_index)?;
+        let issues = self
+            .analysis_results
+            .iter()
+            .rev()
+            .find(|result| result.file_id == file.id)
+            .map_or(&[][..], |result| result.issues.as_slice());
+        Some((file, issues))
+    }
+
+    /// Run a tool, asking for approval first when its policy requires it
+    ///
+    /// In safe mode, tools that are not read-only are refused without a prompt.
+    /// Refusals are shown in the status bar.
+    pub fn request_tool_execution(&mut self, tool: &str) {
+        if self.safe_mode && !allowed_in_safe_mode(tool) {
+            self.refuse_tool(format!("Tool {} is {}", tool, SAFE_MODE_BLOCKED));
+            return;
+        }
+
+        match self.approval.resolve(tool, self.approve_all_gated) {
+            ApprovalDecision::Allow => self.execute_tool(tool),
+            ApprovalDecision::Deny => {
+                self.refuse_tool(format!("Tool {} is denied by the approval policy", tool))
+            }
+            ApprovalDecision::Prompt => self.pending_approval = Some(tool.to_string()),
+        }
+    }
+
+    /// Log why a tool was not run and show it in the status bar
+    fn refuse_tool(&mut self, message: String) {
+        warn!("{}", message);
+        self.status_message = Some(message);
+    }
+
+    /// Handle the answer to the pending approval prompt
+    pub fn answer_approval(&mut self, response: ApprovalResponse) {
+        let Some(tool) = self.pending_approval.take() else {
+            return;
+        };
+
+        match response {
+            ApprovalResponse::Approve => self.execute_tool(&tool),
+            ApprovalResponse::ApproveSessionAllGated => {
+                self.approve_all_gated = true;
+                self.execute_tool(&tool);
+            }
+            ApprovalResponse::Deny => info!("Tool {} was not approved", tool),
+            ApprovalResponse::Cancel => {}
+        }
+    }
+
+    /// Write the code editor's content back to its file
+    ///
+    /// The file's symbols are reindexed in the background.
+    pub fn save_code_file(&mut self) {
+        let Some(path) = self.code_path.clone() else {
+            return;
+        };
+
+        match self
+            .terminal_integration
+            .write_file(&path, &self.code_content)
+        {
+            Ok(()) => {
+                if let Some(file) = self.files.iter_mut().find(|f| f.path == path) {
+                    file.content = self.code_content.clone();
+                }
+                info!("Saved {}", path);
+            }
+            Err(e) => warn!("{}", e),
+        }
+    }
+
+    /// Execute an approved tool
+    fn execute_tool(&self, tool: &str) {
+        info!("Executing tool: {}", tool);
+    }
+
+    /// Handle mouse events
+    pub fn handle_mouse_event(&mut self, mouse_event: MouseEvent) -> Result<()> {
+        if let TuiState::TerminalIntegration = self.current_state {
+            if let Some(new_state) = self
+                .terminal_integration
+                .handle_event(Event::Mouse(mouse_event))?
+            {
+                self.current_state = new_state;
+            }
+            self.open_requested_location();
+        }
+        Ok(())
+    }
+
+    /// Open a file location selected in the terminal output in the code editor
+    fn open_requested_location(&mut self) {
+        let Some(location) = self.terminal_integration.take_open_request() else {
+            return;
+        };
+
+        let content = match self.files.iter().position(|f| f.path == location.path) {
+            Some(index) => {
+                self.selected_file_index = index;
+                Some(self.files[index].content.clone())
+            }
+            None => std::fs::read_to_string(&location.path).ok(),
+        };
+
+        match content {
+            Some(content) => {
+                self.code_content = content;
+                self.code_path = Some(location.path);
+                self.code_scroll = location.line - 1;
+                self.current_state = TuiState::CodeEditor;
+            }
+            None => {
+                info!("Cannot open {}", location.path);
+                self.current_state = TuiState::TerminalIntegration;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::approval::ApprovalPolicy;
+
+    #[test]
+    fn test_denied_tool_is_shown_in_the_status_bar() {
+        let mut app = TuiApp::new();
+        app.approval
+            .tools
+            .insert("shell".to_string(), ApprovalPolicy::AlwaysDeny);
+
+        app.request_tool_execution("shell");
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("Tool shell is denied by the approval policy")
+        );
+
+        // The message stays until the next key press
+        app.handle_key_event(KeyEvent::from(KeyCode::Down)).unwrap();
+        assert_eq!(app.status_message, None);
+    }
 }
@@ -0,0 +1,179 @@
+//! TUI Symbol Reindex Module
+//!
+//! This module defines the symbol indexer searched by `/find` and notified when
+//! the TUI writes a file, so the index follows edits without a full rebuild. The
+//! indexer used by the TUI is backed by the core symbol table.
+
+use anyhow::{anyhow, Result};
+use sqlx::sqlite::SqliteConnectOptions;
+use sqlx::SqlitePool;
+use std::path::Path;
+use std::sync::RwLock;
+use tokio::runtime::Handle;
+
+use odincode_core::symbol_table::{LanguageParsers, SymbolTableManager};
+
+/// File name of the symbol database inside the database root
+pub const SYMBOL_DB_FILE_NAME: &str = "symbols.db";
+
+/// Incrementally updates the symbol index for single files
+pub trait SymbolIndexer: Send + Sync {
+    /// Reindex the symbols of one file, returning how many were indexed
+    ///
+    /// On failure (for example a parse error) the file's previous index entry
+    /// must be left in place.
+    fn reindex_file(&self, path: &str) -> Result<usize>;
+
+    /// Names of the indexed symbols, searched by `/find`
+    fn symbol_names(&self) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+/// Output line reporting the result of a reindex
+pub fn reindex_message(path: &str, result: &Result<usize>) -> String {
+    match result {
+        Ok(count) => format!("Reindexed {} ({} symbols)", path, count),
+        Err(e) => format!(
+            "Error: failed to reindex {}: {}; keeping previous symbols",
+            path, e
+        ),
+    }
+}
+
+/// Symbol indexer backed by the core symbol table
+///
+/// Symbol names are cached so `/find` never waits on the database. Reindexing
+/// blocks on the runtime the indexer was created on, so it must be called from a
+/// thread outside that runtime, like the TUI's background threads.
+pub struct SymbolTableIndexer {
+    /// Symbol table the files are indexed into
+    manager: SymbolTableManager,
+    /// Runtime the symbol table's pool runs on
+    runtime: Handle,
+    /// Sorted, distinct names of the indexed symbols
+    names: RwLock<Vec<String>>,
+}
+
+impl SymbolTableIndexer {
+    /// Open the symbol database at `path`, creating it if it is missing
+    pub async fn open(path: &Path) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        let pool = SqlitePool::connect_with(
+            SqliteConnectOptions::new()
+                .filename(path)
+                .create_if_missing(true),
+        )
+        .await
+        .map_err(|e| anyhow!("Failed to open {}: {}", path.display(), e))?;
+        Self::new(SymbolTableManager::new(pool)).await
+    }
+
+    /// Index into a symbol table, creating its tables if needed
+    pub async fn new(manager: SymbolTableManager) -> Result<Self> {
+        manager.init().await?;
+        let indexer = Self {
+            manager,
+            runtime: Handle::current(),
+            names: RwLock::new(Vec::new()),
+        };
+        indexer.refresh_names().await?;
+        Ok(indexer)
+    }
+
+    /// Replace the symbols, call references and calls of a file with those of its
+    /// current content
+    async fn reindex(&self, path: &str) -> Result<usize> {
+        let content = tokio::fs::read_to_string(path)
+            .await
+            .map_err(|e| anyhow!("Failed to read {}: {}", path, e))?;
+        let language = LanguageParsers::detect_language_from_extension(path)
+            .ok_or_else(|| anyhow!("Unsupported file type"))?;
+        let tree = LanguageParsers::parse_code(&content, language)?
+            .ok_or_else(|| anyhow!("No parser for {}", language))?;
+        if tree.root_node().has_error() {
+            return Err(anyhow!("parse error"));
+        }
+
+        let symbols = self
+            .manager
+            .replace_file_symbols(&tree, &content, path, language)
+            .await?;
+        self.manager.index_references(&tree, &content, path).await?;
+        self.manager.index_calls(&tree, &content, path).await?;
+        self.refresh_names().await?;
+        Ok(symbols.len())
+    }
+
+    /// Reload the cached symbol names from the symbol table
+    async fn refresh_names(&self) -> Result<()> {
+        let mut names: Vec<String> = self
+            .manager
+            .list_symbols(None)
+            .await?
+            .into_iter()
+            .map(|symbol| symbol.name)
+            .collect();
+        names.sort();
+        names.dedup();
+        *self
+            .names
+            .write()
+            .map_err(|_| anyhow!("Symbol names are poisoned"))? = names;
+        Ok(())
+    }
+}
+
+impl SymbolIndexer for SymbolTableIndexer {
+    fn reindex_file(&self, path: &str) -> Result<usize> {
+        self.runtime.block_on(self.reindex(path))
+    }
+
+    fn symbol_names(&self) -> Vec<String> {
+        self.names
+            .read()
+            .map(|names| names.clone())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_symbol_table_indexer_reindexes_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let indexer = Arc::new(
+            SymbolTableIndexer::open(&dir.path().join(SYMBOL_DB_FILE_NAME))
+                .await
+                .unwrap(),
+        );
+        assert!(indexer.symbol_names().is_empty());
+
+        let path = dir.path().join("lib.rs").to_string_lossy().to_string();
+        let reindex = |content: &'static str| {
+            let indexer = indexer.clone();
+            let path = path.clone();
+            std::fs::write(&path, content).unwrap();
+            tokio::task::spawn_blocking(move || indexer.reindex_file(&path))
+        };
+
+        let count = reindex("fn generate_suggestions() {}\n")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(count, 1);
+        assert_eq!(indexer.symbol_names(), vec!["generate_suggestions"]);
+
+        reindex("fn renamed() {}\n").await.unwrap().unwrap();
+        assert_eq!(indexer.symbol_names(), vec!["renamed"]);
+
+        // A file that no longer parses keeps its previous symbols
+        assert!(reindex("fn broken( {\n").await.unwrap().is_err());
+        assert_eq!(indexer.symbol_names(), vec!["renamed"]);
+    }
+}
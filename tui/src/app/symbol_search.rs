@@ -0,0 +1,286 @@
+//! TUI Symbol Search Module
+//!
+//! This module ranks symbol names against the pattern given to the `/find`
+//! command. Names containing the pattern as a substring come first, followed by
+//! names that only contain it as a subsequence.
+
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+
+/// Number of matches shown by `/find`
+pub const DEFAULT_FIND_LIMIT: usize = 20;
+
+/// Output type of `/find` results, rendered with the matched characters highlighted
+pub const SYMBOL_MATCH_OUTPUT_TYPE: &str = "symbol_match";
+
+/// Score for each matched character
+const MATCH_SCORE: i64 = 16;
+/// Bonus for a character matched right after the previous one
+const CONSECUTIVE_BONUS: i64 = 15;
+/// Bonus for a character matched at the start of a word
+const BOUNDARY_BONUS: i64 = 10;
+/// Penalty for each unmatched character between two matches
+const GAP_PENALTY: i64 = 1;
+
+/// A symbol name matched by a `/find` pattern
+#[derive(Debug, Clone, PartialEq)]
+pub struct SymbolMatch {
+    /// Symbol name
+    pub name: String,
+    /// Whether the name contains the pattern as a substring
+    pub exact: bool,
+    /// Match quality; higher is better
+    pub score: i64,
+    /// Character indices of the matched characters, for highlighting
+    pub positions: Vec<usize>,
+}
+
+/// Parse a `/find <pattern>` command
+///
+/// Returns `None` if the input is not a `/find` command with a pattern.
+pub fn parse_find_command(input: &str) -> Option<String> {
+    let pattern = input.trim().strip_prefix("/find")?;
+    if !pattern.starts_with(char::is_whitespace) {
+        return None;
+    }
+
+    let pattern = pattern.trim();
+    (!pattern.is_empty()).then(|| pattern.to_string())
+}
+
+/// Match a pattern against a symbol name, ignoring case
+///
+/// Returns `None` if the pattern is not a subsequence of the name.
+pub fn fuzzy_match(pattern: &str, name: &str) -> Option<SymbolMatch> {
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let chars: Vec<char> = name.chars().collect();
+    let lower: Vec<char> = chars
+        .iter()
+        .map(|c| c.to_lowercase().next().unwrap_or(*c))
+        .collect();
+    if pattern.is_empty() {
+        return None;
+    }
+
+    let (exact, positions) = match substring_positions(&pattern, &chars, &lower) {
+        Some(positions) => (true, positions),
+        None => (false, subsequence_positions(&pattern, &lower)?),
+    };
+
+    Some(SymbolMatch {
+        name: name.to_string(),
+        exact,
+        score: score_positions(&chars, &positions),
+        positions,
+    })
+}
+
+/// Rank symbol names against a pattern, returning at most `limit` matches
+///
+/// Substring matches always come before subsequence matches; ties are broken by
+/// score, then by the shorter name.
+pub fn rank_symbols<'a>(
+    pattern: &str,
+    names: impl IntoIterator<Item = &'a str>,
+    limit: usize,
+) -> Vec<SymbolMatch> {
+    let mut matches: Vec<SymbolMatch> = names
+        .into_iter()
+        .filter_map(|name| fuzzy_match(pattern, name))
+        .collect();
+
+    matches.sort_by(|a, b| {
+        b.exact
+            .cmp(&a.exact)
+            .then(b.score.cmp(&a.score))
+            .then(a.name.len().cmp(&b.name.len()))
+            .then(a.name.cmp(&b.name))
+    });
+    matches.truncate(limit);
+    matches
+}
+
+/// Style a `/find` result line, emphasizing the characters matched by the pattern
+pub fn highlight_symbol_match(name: &str, pattern: &str, style: Style) -> Line<'static> {
+    let positions = fuzzy_match(pattern, name)
+        .map(|found| found.positions)
+        .unwrap_or_default();
+    let matched_style = style.add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+
+    let mut spans: Vec<Span<'static>> = Vec::new();
+    let mut run = String::new();
+    let mut run_matched = false;
+    for (index, c) in name.chars().enumerate() {
+        let matched = positions.contains(&index);
+        if matched != run_matched && !run.is_empty() {
+            let run_style = if run_matched { matched_style } else { style };
+            spans.push(Span::styled(std::mem::take(&mut run), run_style));
+        }
+        run_matched = matched;
+        run.push(c);
+    }
+    if !run.is_empty() {
+        let run_style = if run_matched { matched_style } else { style };
+        spans.push(Span::styled(run, run_style));
+    }
+
+    Line::from(spans)
+}
+
+/// Positions of the pattern as a substring, preferring an occurrence at a word start
+fn substring_positions(pattern: &[char], chars: &[char], lower: &[char]) -> Option<Vec<usize>> {
+    if pattern.len() > lower.len() {
+        return None;
+    }
+
+    let starts: Vec<usize> = (0..=lower.len() - pattern.len())
+        .filter(|&start| lower[start..start + pattern.len()] == *pattern)
+        .collect();
+    let start = starts
+        .iter()
+        .find(|&&start| is_word_start(chars, start))
+        .or_else(|| starts.first())?;
+
+    Some((*start..*start + pattern.len()).collect())
+}
+
+/// Positions of the pattern as a subsequence, matching each character greedily
+fn subsequence_positions(pattern: &[char], lower: &[char]) -> Option<Vec<usize>> {
+    let mut positions = Vec::with_capacity(pattern.len());
+    let mut next = 0;
+    for c in pattern {
+        let offset = lower[next..].iter().position(|l| l == c)?;
+        positions.push(next + offset);
+        next += offset + 1;
+    }
+    Some(positions)
+}
+
+/// Score matched positions, rewarding runs and word starts and penalizing gaps
+fn score_positions(chars: &[char], positions: &[usize]) -> i64 {
+    let mut score = 0;
+    let mut previous: Option<usize> = None;
+
+    for &position in positions {
+        score += MATCH_SCORE;
+        if is_word_start(chars, position) {
+            score += BOUNDARY_BONUS;
+        }
+        match previous {
+            Some(previous) if position == previous + 1 => score += CONSECUTIVE_BONUS,
+            Some(previous) => score -= GAP_PENALTY * (position - previous - 1) as i64,
+            None => score -= GAP_PENALTY * position as i64,
+        }
+        previous = Some(position);
+    }
+
+    score
+}
+
+/// Whether the character at `index` starts a word in a symbol name
+fn is_word_start(chars: &[char], index: usize) -> bool {
+    let Some(previous) = index.checked_sub(1).map(|i| chars[i]) else {
+        return true;
+    };
+    let current = chars[index];
+
+    matches!(previous, '_' | '-' | '.' | ':' | '/' | ' ')
+        || (previous.is_lowercase() && current.is_uppercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_find_command() {
+        assert_eq!(
+            parse_find_command("/find gnrtsug"),
+            Some("gnrtsug".to_string())
+        );
+        assert_eq!(
+            parse_find_command("  /find  Symbol  "),
+            Some("Symbol".to_string())
+        );
+        assert_eq!(parse_find_command("/find"), None);
+        assert_eq!(parse_find_command("/finder x"), None);
+        assert_eq!(parse_find_command("find x"), None);
+    }
+
+    #[test]
+    fn test_fuzzy_match_positions() {
+        let found = fuzzy_match("gnrtsug", "generate_suggestions").unwrap();
+        assert!(!found.exact);
+        assert_eq!(found.positions, vec![0, 2, 4, 6, 9, 10, 11]);
+
+        let found = fuzzy_match("Sugg", "generate_suggestions").unwrap();
+        assert!(found.exact);
+        assert_eq!(found.positions, vec![9, 10, 11, 12]);
+
+        assert!(fuzzy_match("xyz", "generate_suggestions").is_none());
+    }
+
+    #[test]
+    fn test_highlight_symbol_match_emphasizes_matched_characters() {
+        let style = Style::default();
+        let line = highlight_symbol_match("get_user", "gus", style);
+        let runs: Vec<(&str, bool)> = line
+            .spans
+            .iter()
+            .map(|span| {
+                (
+                    span.content.as_ref(),
+                    span.style.add_modifier.contains(Modifier::BOLD),
+                )
+            })
+            .collect();
+        assert_eq!(
+            runs,
+            vec![("g", true), ("et_", false), ("us", true), ("er", false)]
+        );
+
+        // A line the pattern no longer matches is shown unstyled
+        let line = highlight_symbol_match("parse_file", "gus", style);
+        assert_eq!(line.spans.len(), 1);
+        assert_eq!(line.spans[0].style, style);
+    }
+
+    #[test]
+    fn test_rank_symbols_ordering() {
+        let names = [
+            "generate_suggestions",
+            "generate_test_suggestion",
+            "set_user_group",
+            "suggest",
+            "gen_suggestion_table",
+            "parse_file",
+        ];
+
+        let ranked: Vec<String> = rank_symbols("gnrtsug", names, 10)
+            .into_iter()
+            .map(|m| m.name)
+            .collect();
+        assert_eq!(
+            ranked,
+            vec!["generate_suggestions", "generate_test_suggestion"]
+        );
+
+        // Substring matches outrank fuzzy ones, and earlier matches outrank later ones
+        let ranked: Vec<String> = rank_symbols("sug", names, 10)
+            .into_iter()
+            .map(|m| m.name)
+            .collect();
+        assert_eq!(
+            ranked,
+            vec![
+                "suggest",
+                "gen_suggestion_table",
+                "generate_suggestions",
+                "generate_test_suggestion",
+                "set_user_group",
+            ]
+        );
+
+        assert_eq!(rank_symbols("sug", names, 2).len(), 2);
+    }
+}
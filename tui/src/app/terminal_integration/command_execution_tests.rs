@@ -8,6 +8,7 @@ use super::*;
 #[cfg(test)]
 mod command_execution_tests {
     use super::*;
+    use odincode_core::action_history::ActionHistoryManager;
 
     #[test]
     fn test_execute_command_empty() {
@@ -294,4 +295,195 @@ mod command_execution_tests {
         assert_eq!(integration.output_buffer[0].content, "$ echo 'test'");
         assert_eq!(integration.output_buffer[0].output_type, "command");
     }
+
+    #[test]
+    fn test_handle_tool_timeout() {
+        // Test: Commands running past the tool timeout are reported and dropped
+        let mut integration = TerminalIntegration::new();
+        integration.set_tool_timeout(Some(Duration::from_secs(5)));
+        let started = Instant::now();
+        integration.in_flight.push((
+            uuid::Uuid::new_v4(),
+            ToolProgress::new("cargo build", started),
+        ));
+
+        integration.handle_tool_timeout(started + Duration::from_secs(4));
+        assert!(integration.get_tool_progress().is_some());
+        assert!(integration.output_buffer.is_empty());
+
+        integration.handle_tool_timeout(started + Duration::from_secs(5));
+        assert!(integration.get_tool_progress().is_none());
+        assert_eq!(integration.output_buffer.len(), 1);
+        assert_eq!(integration.output_buffer[0].output_type, "error");
+        assert!(integration.output_buffer[0]
+            .content
+            .contains("timed out after 5s"));
+    }
+
+    #[test]
+    fn test_tool_timeout_kills_shell_command() {
+        // Test: A shell command that times out is killed, not left running
+        let mut integration = TerminalIntegration::new();
+        integration.set_tool_timeout(Some(Duration::from_secs(5)));
+        let started = Instant::now();
+        let id = uuid::Uuid::new_v4();
+        let child = Arc::new(Mutex::new(spawn_shell_command("sleep 30").unwrap()));
+        integration.shell_children.insert(id, child.clone());
+        integration
+            .in_flight
+            .push((id, ToolProgress::new("sleep 30", started)));
+
+        integration.handle_tool_timeout(started + Duration::from_secs(5));
+        assert!(integration.shell_children.is_empty());
+
+        let status = child.lock().unwrap().wait().unwrap();
+        assert!(!status.success());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_killing_shell_command_kills_its_children() {
+        // Test: Killing a shell command also kills what it started, closing its output
+        let child = Arc::new(Mutex::new(spawn_shell_command("sleep 30 & wait").unwrap()));
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let waited = child.clone();
+        std::thread::spawn(move || {
+            let _ = sender.send(wait_for_shell_command(&waited));
+        });
+
+        // Give the shell time to start its child
+        std::thread::sleep(Duration::from_millis(200));
+        kill_shell_command(&mut child.lock().unwrap()).unwrap();
+
+        let (success, _) = receiver
+            .recv_timeout(Duration::from_secs(10))
+            .expect("output pipes stayed open")
+            .unwrap();
+        assert!(!success);
+    }
+
+    #[test]
+    fn test_wait_for_shell_command_captures_output() {
+        // Test: A background shell command reports stdout, or stderr on failure
+        let child = Mutex::new(spawn_shell_command("echo out; echo err >&2").unwrap());
+        assert_eq!(
+            wait_for_shell_command(&child).unwrap(),
+            (true, "out\n".to_string())
+        );
+
+        let child = Mutex::new(spawn_shell_command("echo err >&2; exit 3").unwrap());
+        assert_eq!(
+            wait_for_shell_command(&child).unwrap(),
+            (false, "Error: err\n".to_string())
+        );
+    }
+
+    #[test]
+    fn test_tool_timeout_disabled() {
+        // Test: With no timeout, commands are never reported as timed out
+        let mut integration = TerminalIntegration::new();
+        integration.set_tool_timeout(None);
+        let started = Instant::now();
+        integration.in_flight.push((
+            uuid::Uuid::new_v4(),
+            ToolProgress::new("cargo build", started),
+        ));
+
+        integration.handle_tool_timeout(started + Duration::from_secs(3600));
+        assert!(integration.get_tool_progress().is_some());
+        assert!(integration.output_buffer.is_empty());
+    }
+
+    #[test]
+    fn test_history_command_lists_newest_first() {
+        // Test: /history shows recorded executions without running a shell command
+        let mut integration = TerminalIntegration::new();
+        for (command, success) in [("ls", true), ("cargo build", false), ("pwd", true)] {
+            integration.record_execution(&ToolProgress::new(command, Instant::now()), success);
+        }
+
+        integration.current_command = "/history 2".to_string();
+        integration.execute_command().unwrap();
+
+        assert_eq!(integration.get_execution_history().len(), 3);
+        assert_eq!(integration.output_buffer.len(), 2);
+        assert_eq!(integration.output_buffer[0].content, "$ /history 2");
+
+        let rows: Vec<&str> = integration.output_buffer[1].content.lines().collect();
+        assert_eq!(rows.len(), 3);
+        assert!(rows[1].ends_with("pwd"));
+        assert!(rows[2].ends_with("cargo build"));
+        assert!(rows[2].contains(" no "));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_history_command_reads_the_history_store() {
+        let pool = sqlx::SqlitePool::connect("sqlite::memory:").await.unwrap();
+        let history = Arc::new(ActionHistoryManager::new(pool));
+        history.init().await.unwrap();
+        history
+            .log_agent_execution("", "Refactorer", None, true, None, 10, 0)
+            .await
+            .unwrap();
+
+        let mut integration = TerminalIntegration::new();
+        integration.set_history_store(HistoryStore::spawn(
+            history,
+            "session".to_string(),
+            &tokio::runtime::Handle::current(),
+        ));
+        integration.record_execution(&ToolProgress::new("make", Instant::now()), true);
+        integration.current_command = "/history".to_string();
+        integration.execute_command().unwrap();
+
+        // The rows are shown once the store answers
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while integration.output_buffer.len() < 2 && Instant::now() < deadline {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            integration.process_command_results().unwrap();
+        }
+        let table = &integration.output_buffer[1].content;
+        assert!(table.lines().any(|row| row.ends_with("make")));
+        assert!(table.lines().any(|row| row.ends_with("Refactorer")));
+    }
+
+    #[test]
+    fn test_cost_command_without_llm_manager() {
+        let mut integration = TerminalIntegration::new();
+        integration.current_command = "/cost".to_string();
+
+        integration.execute_command().unwrap();
+
+        // Handled locally, not sent to the shell
+        assert_eq!(integration.output_buffer.len(), 2);
+        assert_eq!(integration.output_buffer[0].content, "$ /cost");
+        assert_eq!(integration.output_buffer[1].output_type, "error");
+        let receiver = integration.command_receiver.as_mut().unwrap();
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_safe_mode_refuses_shell_commands() {
+        let mut integration = TerminalIntegration::new();
+        integration.set_safe_mode(true);
+        integration.current_command = "rm -rf build".to_string();
+
+        integration.execute_command().unwrap();
+
+        assert_eq!(integration.output_buffer.len(), 2);
+        assert_eq!(integration.output_buffer[1].output_type, "error");
+        assert!(integration.output_buffer[1]
+            .content
+            .contains(SAFE_MODE_BLOCKED));
+        let receiver = integration.command_receiver.as_mut().unwrap();
+        assert!(receiver.try_recv().is_err());
+
+        // Built-in commands only read, so they still run
+        integration.current_command = "/history".to_string();
+        integration.execute_command().unwrap();
+        assert_eq!(integration.output_buffer[2].content, "$ /history");
+        assert_eq!(integration.output_buffer[3].output_type, "output");
+
+        assert!(integration.write_file("/tmp/odincode-safe", "x").is_err());
+    }
 }
@@ -0,0 +1,152 @@
+//! Tests for Terminal Integration Directory Analysis
+//!
+//! This module contains tests for the `/analyze` and `/cancel` commands.
+
+use super::*;
+
+#[cfg(test)]
+mod directory_analysis_tests {
+    use super::*;
+
+    /// Run a command typed into the terminal
+    fn run(integration: &mut TerminalIntegration, command: &str) {
+        integration.current_command = command.to_string();
+        integration.execute_command().unwrap();
+    }
+
+    /// Create an empty directory under the system temp directory
+    fn temp_dir() -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("odincode-analyze-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir(&dir).unwrap();
+        dir
+    }
+
+    /// Process results until no background work is left
+    fn wait_for_results(integration: &mut TerminalIntegration) {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while integration.get_tool_progress().is_some() && Instant::now() < deadline {
+            integration.process_command_results().unwrap();
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn test_analyze_reports_summary() {
+        let dir = temp_dir();
+        std::fs::write(dir.join("main.rs"), "fn main() {} \n").unwrap();
+        let root = dir.to_string_lossy().to_string();
+
+        let mut integration = TerminalIntegration::new();
+        run(&mut integration, &format!("/analyze {}", root));
+        assert_eq!(
+            integration.output_buffer.last().unwrap().content,
+            "No code engine is connected"
+        );
+
+        integration.set_code_engine(Arc::new(CodeEngine::new().unwrap()));
+        run(&mut integration, &format!("/analyze {}", root));
+        wait_for_results(&mut integration);
+
+        let output = integration.output_buffer.last().unwrap();
+        assert!(output
+            .content
+            .starts_with(&format!("Analysis of {} finished: 1 files", root)));
+        assert!(integration.directory_analysis.is_none());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_cancel_stops_running_analysis() {
+        let dir = temp_dir();
+        for i in 0..20 {
+            std::fs::write(dir.join(format!("f{}.rs", i)), "fn f() {}\n").unwrap();
+        }
+        let root = dir.to_string_lossy().to_string();
+
+        let mut integration = TerminalIntegration::new();
+        run(&mut integration, "/cancel");
+        assert_eq!(
+            integration.output_buffer.last().unwrap().output_type,
+            "error"
+        );
+
+        integration.set_code_engine(Arc::new(CodeEngine::new().unwrap()));
+        run(&mut integration, &format!("/analyze {}", root));
+        run(&mut integration, "/cancel");
+        let (_, _, cancel) = integration.directory_analysis.as_ref().unwrap();
+        assert!(cancel.is_cancelled());
+
+        wait_for_results(&mut integration);
+        // Files finished before the cancellation are still reported
+        let output = integration.output_buffer.last().unwrap();
+        assert!(output.content.starts_with(&format!("Analysis of {}", root)));
+        assert!(integration.directory_analysis.is_none());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_analysis_is_exempt_from_tool_timeout() {
+        let dir = temp_dir();
+        for i in 0..20 {
+            std::fs::write(dir.join(format!("f{}.rs", i)), "fn f() {}\n").unwrap();
+        }
+        let root = dir.to_string_lossy().to_string();
+
+        let mut integration = TerminalIntegration::new();
+        integration.set_code_engine(Arc::new(CodeEngine::new().unwrap()));
+        run(&mut integration, &format!("/analyze {}", root));
+
+        let lines = integration.output_buffer.len();
+        integration.handle_tool_timeout(Instant::now() + DEFAULT_TOOL_TIMEOUT);
+        let (_, _, cancel) = integration.directory_analysis.as_ref().unwrap();
+        assert!(!cancel.is_cancelled());
+        assert!(integration.get_tool_progress().is_some());
+        assert_eq!(integration.output_buffer.len(), lines);
+
+        wait_for_results(&mut integration);
+        let output = integration.output_buffer.last().unwrap();
+        assert!(output.content.starts_with(&format!("Analysis of {}", root)));
+        assert!(integration.directory_analysis.is_none());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    /// Indexer that indexes each file as one symbol named after the file
+    struct FileNameIndexer {
+        names: std::sync::Mutex<Vec<String>>,
+    }
+
+    impl SymbolIndexer for FileNameIndexer {
+        fn reindex_file(&self, path: &str) -> Result<usize> {
+            let name = std::path::Path::new(path).file_stem().unwrap();
+            let mut names = self.names.lock().unwrap();
+            names.push(name.to_string_lossy().to_string());
+            Ok(1)
+        }
+
+        fn symbol_names(&self) -> Vec<String> {
+            self.names.lock().unwrap().clone()
+        }
+    }
+
+    #[test]
+    fn test_analyze_indexes_symbols_for_find() {
+        let dir = temp_dir();
+        std::fs::write(dir.join("generate_suggestions.rs"), "fn main() {}\n").unwrap();
+        let root = dir.to_string_lossy().to_string();
+
+        let mut integration = TerminalIntegration::new();
+        integration.set_code_engine(Arc::new(CodeEngine::new().unwrap()));
+        integration.set_symbol_indexer(Arc::new(FileNameIndexer {
+            names: std::sync::Mutex::new(Vec::new()),
+        }));
+        run(&mut integration, &format!("/analyze {}", root));
+        wait_for_results(&mut integration);
+        let output = integration.output_buffer.last().unwrap();
+        assert!(output.content.ends_with("1 symbols indexed"));
+
+        run(&mut integration, "/find gnrtsug");
+        let output = integration.output_buffer.last().unwrap();
+        assert_eq!(output.content, "generate_suggestions");
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,165 @@
+//! Tests for Terminal Integration History Search
+//!
+//! This module contains tests for Ctrl-R reverse incremental search
+//! through the command history.
+
+use super::*;
+
+#[cfg(test)]
+mod history_search_tests {
+    use super::*;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    fn ctrl_r() -> KeyEvent {
+        KeyEvent::new(KeyCode::Char('r'), KeyModifiers::CONTROL)
+    }
+
+    fn integration_with_history() -> TerminalIntegration {
+        let mut integration = TerminalIntegration::new();
+        integration.command_history = vec![
+            "cargo build".to_string(),
+            "git status".to_string(),
+            "cargo test --workspace".to_string(),
+            "ls -la".to_string(),
+        ];
+        integration.history_index = integration.command_history.len();
+        integration
+    }
+
+    #[test]
+    fn test_ctrl_r_enters_search_state() {
+        let mut integration = integration_with_history();
+
+        integration.handle_key_event(ctrl_r()).unwrap();
+
+        assert_eq!(
+            integration.get_history_search(),
+            Some(&HistorySearch::default())
+        );
+        assert_eq!(integration.get_current_command(), "");
+    }
+
+    #[test]
+    fn test_typing_narrows_to_newest_match() {
+        let mut integration = integration_with_history();
+        integration.handle_key_event(ctrl_r()).unwrap();
+
+        integration
+            .handle_key_event(key(KeyCode::Char('c')))
+            .unwrap();
+        integration
+            .handle_key_event(key(KeyCode::Char('a')))
+            .unwrap();
+        let search = integration.get_history_search().unwrap();
+        assert_eq!(search.query, "ca");
+        assert_eq!(search.match_index, Some(2));
+
+        integration
+            .handle_key_event(key(KeyCode::Char('r')))
+            .unwrap();
+        integration
+            .handle_key_event(key(KeyCode::Char('g')))
+            .unwrap();
+        integration
+            .handle_key_event(key(KeyCode::Char('o')))
+            .unwrap();
+        integration
+            .handle_key_event(key(KeyCode::Char(' ')))
+            .unwrap();
+        integration
+            .handle_key_event(key(KeyCode::Char('b')))
+            .unwrap();
+        let search = integration.get_history_search().unwrap();
+        assert_eq!(search.match_index, Some(0));
+
+        // Typing never executes or edits the input buffer
+        assert_eq!(integration.get_current_command(), "");
+        assert_eq!(integration.get_command_history().len(), 4);
+    }
+
+    #[test]
+    fn test_repeated_ctrl_r_steps_to_older_match() {
+        let mut integration = integration_with_history();
+        integration.handle_key_event(ctrl_r()).unwrap();
+        integration
+            .handle_key_event(key(KeyCode::Char('c')))
+            .unwrap();
+        integration
+            .handle_key_event(key(KeyCode::Char('a')))
+            .unwrap();
+
+        integration.handle_key_event(ctrl_r()).unwrap();
+        assert_eq!(
+            integration.get_history_search().unwrap().match_index,
+            Some(0)
+        );
+
+        // No older match keeps the current one
+        integration.handle_key_event(ctrl_r()).unwrap();
+        assert_eq!(
+            integration.get_history_search().unwrap().match_index,
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn test_enter_accepts_match_into_input() {
+        let mut integration = integration_with_history();
+        integration.handle_key_event(ctrl_r()).unwrap();
+        integration
+            .handle_key_event(key(KeyCode::Char('g')))
+            .unwrap();
+        integration
+            .handle_key_event(key(KeyCode::Char('i')))
+            .unwrap();
+
+        let result = integration.handle_key_event(key(KeyCode::Enter)).unwrap();
+
+        assert!(result.is_none());
+        assert!(integration.get_history_search().is_none());
+        assert_eq!(integration.get_current_command(), "git status");
+        // Accepting does not run the command
+        assert_eq!(integration.get_command_history().len(), 4);
+    }
+
+    #[test]
+    fn test_esc_cancels_search() {
+        let mut integration = integration_with_history();
+        integration.current_command = "echo".to_string();
+        integration.handle_key_event(ctrl_r()).unwrap();
+        integration
+            .handle_key_event(key(KeyCode::Char('l')))
+            .unwrap();
+
+        let result = integration.handle_key_event(key(KeyCode::Esc)).unwrap();
+
+        // Esc leaves search mode without leaving the terminal view
+        assert!(result.is_none());
+        assert!(integration.get_history_search().is_none());
+        assert_eq!(integration.get_current_command(), "echo");
+    }
+
+    #[test]
+    fn test_backspace_widens_search() {
+        let mut integration = integration_with_history();
+        integration.handle_key_event(ctrl_r()).unwrap();
+        integration
+            .handle_key_event(key(KeyCode::Char('x')))
+            .unwrap();
+        assert_eq!(integration.get_history_search().unwrap().match_index, None);
+
+        integration
+            .handle_key_event(key(KeyCode::Backspace))
+            .unwrap();
+        integration
+            .handle_key_event(key(KeyCode::Char('s')))
+            .unwrap();
+        assert_eq!(
+            integration.get_history_search().unwrap().match_index,
+            Some(3)
+        );
+    }
+}
@@ -10,12 +10,20 @@ mod command_execution_tests;
 #[cfg(test)]
 mod creation_tests;
 #[cfg(test)]
+mod directory_analysis_tests;
+#[cfg(test)]
 mod event_handling_tests;
+#[cfg(test)]
+mod history_search_tests;
+#[cfg(test)]
+mod mouse_selection_tests;
+#[cfg(test)]
+mod reindex_tests;
 
 use anyhow::Result;
 use crossterm::{
     cursor,
-    event::{Event, KeyCode, KeyEvent, MouseEvent, MouseEventKind},
+    event::{Event, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind},
     execute,
     style::ResetColor,
     terminal::{self, ClearType},
@@ -24,14 +32,53 @@ use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span, Text},
-    widgets::{Block, Borders, Paragraph, Wrap},
+    widgets::{Block, Borders, Paragraph},
     Frame,
 };
-use std::io::{self, Write};
-use tokio::sync::mpsc;
-use tracing::{debug, info};
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::io::{self, Read, Write};
+use std::process::{Child, Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, oneshot};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
 
+use crate::app::directory_analysis::{analysis_summary, is_cancel_command, parse_analyze_command};
+use crate::app::execution_history::{
+    history_table, parse_history_command, ExecutionRecord, HistoryStore,
+};
+use crate::app::git_diff::{
+    format_diff, parse_diff_command, styled_diff_line, DiffCommand, DIFF_OUTPUT_TYPE,
+};
+use crate::app::reindex::{reindex_message, SymbolIndexer};
+use crate::app::symbol_search::{
+    highlight_symbol_match, parse_find_command, rank_symbols, DEFAULT_FIND_LIMIT,
+    SYMBOL_MATCH_OUTPUT_TYPE,
+};
+use crate::app::tool_progress::ToolProgress;
+use crate::app::usage_report::{format_usage_report, parse_cost_command, CostCommand};
 use crate::models::{TerminalCommand, TerminalOutput, TuiState};
+use crate::theme::Theme;
+use odincode_core::llm_integration::LLMIntegrationManager;
+use odincode_core::CodeEngine;
+use odincode_tools::SAFE_MODE_BLOCKED;
+
+/// Default time a background command may run before it is reported as timed out
+pub const DEFAULT_TOOL_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// How often a background shell command is checked for having exited
+const SHELL_POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// State of an in-progress reverse incremental history search
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HistorySearch {
+    /// Text being searched for
+    pub query: String,
+    /// Index into the command history of the current match
+    pub match_index: Option<usize>,
+}
 
 /// Enhanced terminal integration with native shell support
 pub struct TerminalIntegration {
@@ -53,12 +100,93 @@ pub struct TerminalIntegration {
     auto_completion: bool,
     /// Syntax highlighting enabled
     syntax_highlighting: bool,
+    /// Reverse history search, active after Ctrl-R
+    history_search: Option<HistorySearch>,
+    /// Index of the first output line shown in the output area
+    output_scroll: usize,
+    /// Screen area of the output panel from the last render
+    output_area: Cell<Rect>,
+    /// File location selected by clicking an output line, waiting to be opened
+    open_request: Option<FileLocation>,
+    /// Sender for results of commands running in the background
+    result_sender: mpsc::UnboundedSender<(uuid::Uuid, bool, String)>,
+    /// Receiver for results of commands running in the background
+    result_receiver: mpsc::UnboundedReceiver<(uuid::Uuid, bool, String)>,
+    /// Commands still running in the background, oldest first
+    in_flight: Vec<(uuid::Uuid, ToolProgress)>,
+    /// Shell processes of background commands, killed when they time out
+    shell_children: HashMap<uuid::Uuid, Arc<Mutex<Child>>>,
+    /// How long a background command may run before it is reported as timed out
+    tool_timeout: Option<Duration>,
+    /// Finished command executions, oldest first
+    execution_history: Vec<ExecutionRecord>,
+    /// Action history the executions are also written to, and `/history` reads
+    history_store: Option<HistoryStore>,
+    /// `/history` rows being read from the history store
+    pending_history: Option<oneshot::Receiver<Result<Vec<ExecutionRecord>>>>,
+    /// LLM manager whose token usage is shown by `/cost`
+    llm_manager: Option<Arc<LLMIntegrationManager>>,
+    /// Indexer notified when a file is written
+    symbol_indexer: Option<Arc<dyn SymbolIndexer>>,
+    /// `/find` pattern of each symbol match output, keyed by output id
+    find_patterns: HashMap<uuid::Uuid, String>,
+    /// Whether shell commands and file writes are refused
+    safe_mode: bool,
+    /// Engine that runs `/analyze`
+    code_engine: Option<Arc<CodeEngine>>,
+    /// Running `/analyze`, with its background id, directory and cancellation token
+    directory_analysis: Option<(uuid::Uuid, String, CancellationToken)>,
+}
+
+/// A `path:line` location referenced by a line of output
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileLocation {
+    /// File path
+    pub path: String,
+    /// One-based line number
+    pub line: usize,
+}
+
+impl FileLocation {
+    /// Parse a location from the start of a line such as `src/main.rs:42:` or
+    /// `src/main.rs:42:7: message`
+    pub fn parse(line: &str) -> Option<Self> {
+        let mut parts = line.trim_start().splitn(3, ':');
+        let path = parts.next()?.trim();
+        let line_number = parts.next()?.trim().parse::<usize>().ok()?;
+
+        if path.is_empty() || path.contains(char::is_whitespace) || line_number == 0 {
+            return None;
+        }
+
+        Some(Self {
+            path: path.to_string(),
+            line: line_number,
+        })
+    }
+}
+
+/// Map a screen row to an index into a bordered panel's lines
+///
+/// Returns `None` for clicks on the border or outside the panel.
+pub fn row_to_line_index(area: Rect, scroll: usize, column: u16, row: u16) -> Option<usize> {
+    let inner_top = area.y.saturating_add(1);
+    let inner_bottom = area.y.saturating_add(area.height).saturating_sub(1);
+    let inner_left = area.x.saturating_add(1);
+    let inner_right = area.x.saturating_add(area.width).saturating_sub(1);
+
+    if row < inner_top || row >= inner_bottom || column < inner_left || column >= inner_right {
+        return None;
+    }
+
+    Some(scroll + (row - inner_top) as usize)
 }
 
 impl TerminalIntegration {
     /// Create a new terminal integration instance
     pub fn new() -> Self {
         let (sender, receiver) = mpsc::unbounded_channel();
+        let (result_sender, result_receiver) = mpsc::unbounded_channel();
 
         Self {
             command_history: Vec::new(),
@@ -70,6 +198,24 @@ impl TerminalIntegration {
             shell_integration: true,
             auto_completion: true,
             syntax_highlighting: true,
+            history_search: None,
+            output_scroll: 0,
+            output_area: Cell::new(Rect::default()),
+            open_request: None,
+            result_sender,
+            result_receiver,
+            in_flight: Vec::new(),
+            shell_children: HashMap::new(),
+            tool_timeout: Some(DEFAULT_TOOL_TIMEOUT),
+            execution_history: Vec::new(),
+            history_store: None,
+            pending_history: None,
+            llm_manager: None,
+            symbol_indexer: None,
+            find_patterns: HashMap::new(),
+            safe_mode: false,
+            code_engine: None,
+            directory_analysis: None,
         }
     }
 
@@ -109,7 +255,16 @@ impl TerminalIntegration {
 
     /// Handle keyboard events
     fn handle_key_event(&mut self, key_event: KeyEvent) -> Result<Option<TuiState>> {
+        if self.history_search.is_some() {
+            self.handle_history_search_key(key_event);
+            return Ok(None);
+        }
+
         match key_event.code {
+            KeyCode::Char('r') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.history_search = Some(HistorySearch::default());
+                Ok(None)
+            }
             KeyCode::Enter => {
                 if !self.current_command.trim().is_empty() {
                     self.execute_command()?;
@@ -168,29 +323,109 @@ impl TerminalIntegration {
         }
     }
 
+    /// Handle keyboard events while reverse history search is active
+    fn handle_history_search_key(&mut self, key_event: KeyEvent) {
+        let Some(mut search) = self.history_search.take() else {
+            return;
+        };
+
+        match key_event.code {
+            KeyCode::Char('r') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+                // Step to the next older match
+                if let Some(index) = search.match_index {
+                    if let Some(older) = self.find_history_match(&search.query, index) {
+                        search.match_index = Some(older);
+                    }
+                }
+            }
+            KeyCode::Char(c) => {
+                search.query.push(c);
+                search.match_index =
+                    self.find_history_match(&search.query, self.command_history.len());
+            }
+            KeyCode::Backspace => {
+                search.query.pop();
+                search.match_index =
+                    self.find_history_match(&search.query, self.command_history.len());
+            }
+            KeyCode::Enter => {
+                // Accept the match into the input buffer without executing it
+                if let Some(index) = search.match_index {
+                    self.current_command = self.command_history[index].clone();
+                    self.history_index = index;
+                }
+                return;
+            }
+            KeyCode::Esc => return,
+            _ => {}
+        }
+
+        self.history_search = Some(search);
+    }
+
+    /// Find the newest history entry before `before` that contains `query`
+    fn find_history_match(&self, query: &str, before: usize) -> Option<usize> {
+        if query.is_empty() {
+            return None;
+        }
+
+        self.command_history[..before.min(self.command_history.len())]
+            .iter()
+            .rposition(|command| command.contains(query))
+    }
+
     /// Handle mouse events
     fn handle_mouse_event(&mut self, mouse_event: MouseEvent) -> Result<Option<TuiState>> {
         match mouse_event.kind {
             MouseEventKind::ScrollUp => {
-                // Scroll output buffer up
+                self.output_scroll = self.output_scroll.saturating_sub(1);
                 Ok(None)
             }
             MouseEventKind::ScrollDown => {
-                // Scroll output buffer down
+                let max_scroll = self.output_lines().len().saturating_sub(1);
+                self.output_scroll = (self.output_scroll + 1).min(max_scroll);
                 Ok(None)
             }
-            MouseEventKind::Down(_button) => {
-                // Handle mouse click
+            MouseEventKind::Down(MouseButton::Left) => {
                 debug!(
                     "Mouse click at: ({}, {})",
                     mouse_event.column, mouse_event.row
                 );
-                Ok(None)
+                Ok(self.select_output_line(mouse_event.column, mouse_event.row))
             }
             _ => Ok(None),
         }
     }
 
+    /// Open the file location of the clicked output line, if it has one
+    fn select_output_line(&mut self, column: u16, row: u16) -> Option<TuiState> {
+        let index = row_to_line_index(self.output_area.get(), self.output_scroll, column, row)?;
+        let lines = self.output_lines();
+        let location = FileLocation::parse(lines.get(index)?.0)?;
+
+        info!("Opening {}:{}", location.path, location.line);
+        self.open_request = Some(location);
+        Some(TuiState::CodeEditor)
+    }
+
+    /// Output buffer split into display lines with their output type
+    fn output_lines(&self) -> Vec<(&str, &str)> {
+        self.output_buffer
+            .iter()
+            .flat_map(|output| {
+                output
+                    .content
+                    .lines()
+                    .map(move |line| (line, output.output_type.as_str()))
+            })
+            .collect()
+    }
+
+    /// Take the file location selected by the last click, if any
+    pub fn take_open_request(&mut self) -> Option<FileLocation> {
+        self.open_request.take()
+    }
+
     /// Handle paste events
     fn handle_paste_event(&mut self, pasted_text: String) -> Result<Option<TuiState>> {
         self.current_command.push_str(&pasted_text);
@@ -210,6 +445,58 @@ impl TerminalIntegration {
         self.command_history.push(command.clone());
         self.history_index = self.command_history.len();
 
+        if let Some(limit) = parse_history_command(&command) {
+            self.push_output(format!("$ {}", command), "command");
+            self.show_execution_history(limit);
+            self.current_command.clear();
+            return Ok(());
+        }
+
+        if let Some(cost_command) = parse_cost_command(&command) {
+            self.push_output(format!("$ {}", command), "command");
+            self.show_usage_report(cost_command);
+            self.current_command.clear();
+            return Ok(());
+        }
+
+        if let Some(pattern) = parse_find_command(&command) {
+            self.push_output(format!("$ {}", command), "command");
+            self.show_symbol_matches(&pattern);
+            self.current_command.clear();
+            return Ok(());
+        }
+
+        if let Some(diff_command) = parse_diff_command(&command) {
+            self.push_output(format!("$ {}", command), "command");
+            self.show_git_diff(&diff_command);
+            self.current_command.clear();
+            return Ok(());
+        }
+
+        if let Some(root) = parse_analyze_command(&command) {
+            self.push_output(format!("$ {}", command), "command");
+            self.start_directory_analysis(&root);
+            self.current_command.clear();
+            return Ok(());
+        }
+
+        if is_cancel_command(&command) {
+            self.push_output(format!("$ {}", command), "command");
+            self.cancel_directory_analysis();
+            self.current_command.clear();
+            return Ok(());
+        }
+
+        if self.safe_mode {
+            self.push_output(format!("$ {}", command), "command");
+            self.push_output(
+                format!("Error: '{}' is {}", command, SAFE_MODE_BLOCKED),
+                "error",
+            );
+            self.current_command.clear();
+            return Ok(());
+        }
+
         // Create command for execution
         let terminal_command = TerminalCommand {
             id: uuid::Uuid::new_v4(),
@@ -221,12 +508,7 @@ impl TerminalIntegration {
         self.command_sender.send(terminal_command.clone())?;
 
         // Add command to output buffer
-        self.output_buffer.push(TerminalOutput {
-            id: uuid::Uuid::new_v4(),
-            content: format!("$ {}", command),
-            output_type: "command".to_string(),
-            timestamp: chrono::Utc::now(),
-        });
+        self.push_output(format!("$ {}", command), "command");
 
         // Clear current command
         self.current_command.clear();
@@ -235,6 +517,212 @@ impl TerminalIntegration {
         Ok(())
     }
 
+    /// Append an entry of the given output type to the output buffer
+    fn push_output(&mut self, content: impl Into<String>, output_type: &str) {
+        self.output_buffer.push(TerminalOutput {
+            id: uuid::Uuid::new_v4(),
+            content: content.into(),
+            output_type: output_type.to_string(),
+            timestamp: chrono::Utc::now(),
+        });
+    }
+
+    /// Show the most recent executions in the output buffer, newest first
+    ///
+    /// With a history store the rows, including agent runs, are read from it
+    /// and shown once they arrive.
+    fn show_execution_history(&mut self, limit: usize) {
+        if let Some(store) = &self.history_store {
+            self.pending_history = Some(store.recent(limit));
+            return;
+        }
+
+        self.push_output(
+            history_table(self.execution_history.iter().rev().take(limit)),
+            "output",
+        );
+    }
+
+    /// Show the `/history` rows read from the history store, once they arrive
+    fn process_pending_history(&mut self) {
+        let Some(pending) = self.pending_history.as_mut() else {
+            return;
+        };
+        let (content, output_type) = match pending.try_recv() {
+            Err(oneshot::error::TryRecvError::Empty) => return,
+            Ok(Ok(records)) => (history_table(&records), "output"),
+            Ok(Err(e)) => (format!("Error: failed to read the history: {}", e), "error"),
+            Err(oneshot::error::TryRecvError::Closed) => {
+                ("Error: the history store stopped".to_string(), "error")
+            }
+        };
+        self.pending_history = None;
+
+        self.push_output(content, output_type);
+    }
+
+    /// Analyze a directory with the code engine on a background thread
+    ///
+    /// The summary is reported through the background result channel like any
+    /// other command. Only one analysis runs at a time.
+    fn start_directory_analysis(&mut self, root: &str) {
+        let (content, output_type) = if let Some((_, running, _)) = &self.directory_analysis {
+            (
+                format!(
+                    "Analysis of {} is already running; /cancel it first",
+                    running
+                ),
+                "error",
+            )
+        } else if let Some(engine) = self.code_engine.clone() {
+            self.spawn_directory_analysis(engine, root);
+            (format!("Analyzing {}...", root), "output")
+        } else {
+            ("No code engine is connected".to_string(), "error")
+        };
+
+        self.push_output(content, output_type);
+    }
+
+    /// Track an `/analyze` of `root` and run it on its own thread and runtime
+    ///
+    /// The analysis stops early once its cancellation token is cancelled; the
+    /// summary of the files it finished, with their symbols indexed, is then
+    /// sent on the result channel under the tracked id.
+    fn spawn_directory_analysis(&mut self, engine: Arc<CodeEngine>, root: &str) {
+        let id = uuid::Uuid::new_v4();
+        let cancel = CancellationToken::new();
+        self.in_flight.push((
+            id,
+            ToolProgress::new(format!("analyze {}", root), Instant::now()),
+        ));
+        self.directory_analysis = Some((id, root.to_string(), cancel.clone()));
+
+        let root = root.to_string();
+        let sender = self.result_sender.clone();
+        let indexer = self.symbol_indexer.clone();
+        std::thread::spawn(move || {
+            let result = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .map_err(anyhow::Error::from)
+                .and_then(|runtime| {
+                    runtime.block_on(engine.analyze_directory_cancellable(
+                        &root,
+                        true,
+                        &cancel,
+                        |_, _| {},
+                    ))
+                });
+            let (success, output) = match result {
+                Ok(results) => {
+                    let summary = analysis_summary(&root, &results, cancel.is_cancelled());
+                    match &indexer {
+                        Some(indexer) => {
+                            let symbols = index_analyzed_files(indexer.as_ref(), &results);
+                            (true, format!("{}, {} symbols indexed", summary, symbols))
+                        }
+                        None => (true, summary),
+                    }
+                }
+                Err(e) => (false, format!("Error: failed to analyze {}: {}", root, e)),
+            };
+            let _ = sender.send((id, success, output));
+        });
+    }
+
+    /// Stop the running `/analyze`; the files it finished are still reported
+    fn cancel_directory_analysis(&mut self) {
+        let (content, output_type) = match &self.directory_analysis {
+            Some((_, root, cancel)) => {
+                cancel.cancel();
+                (format!("Cancelling analysis of {}...", root), "output")
+            }
+            None => ("No analysis is running".to_string(), "error"),
+        };
+
+        self.push_output(content, output_type);
+    }
+
+    /// Show or reset the LLM token usage in the output buffer
+    fn show_usage_report(&mut self, command: CostCommand) {
+        let (content, output_type) = match (&self.llm_manager, command) {
+            (None, _) => ("No LLM manager is connected".to_string(), "error"),
+            (Some(manager), CostCommand::Reset) => {
+                manager.reset_usage();
+                ("LLM usage reset".to_string(), "success")
+            }
+            (Some(manager), CostCommand::Show) => (
+                format_usage_report(&manager.usage_stats()).join("\n"),
+                "output",
+            ),
+        };
+
+        self.push_output(content, output_type);
+    }
+
+    /// Show the indexed symbols best matching a `/find` pattern in the output buffer
+    fn show_symbol_matches(&mut self, pattern: &str) {
+        let (content, output_type) = match &self.symbol_indexer {
+            None => ("No symbol index is connected".to_string(), "error"),
+            Some(indexer) => {
+                let names = indexer.symbol_names();
+                let matches = rank_symbols(
+                    pattern,
+                    names.iter().map(String::as_str),
+                    DEFAULT_FIND_LIMIT,
+                );
+                if matches.is_empty() {
+                    (format!("No symbols match '{}'", pattern), "output")
+                } else {
+                    let lines: Vec<String> = matches.into_iter().map(|m| m.name).collect();
+                    (lines.join("\n"), SYMBOL_MATCH_OUTPUT_TYPE)
+                }
+            }
+        };
+
+        self.push_output(content, output_type);
+        if output_type == SYMBOL_MATCH_OUTPUT_TYPE {
+            if let Some(output) = self.output_buffer.last() {
+                self.find_patterns.insert(output.id, pattern.to_string());
+            }
+        }
+    }
+
+    /// Show the working-tree `git diff` of the current directory in the output buffer
+    fn show_git_diff(&mut self, command: &DiffCommand) {
+        let (content, output_type) =
+            match odincode_tools::git_working_diff(".", command.path.as_deref()) {
+                Ok(diff) if diff.trim().is_empty() => {
+                    ("No changes in the working tree".to_string(), "output")
+                }
+                Ok(diff) => (format_diff(&diff).join("\n"), DIFF_OUTPUT_TYPE),
+                Err(e) => (format!("git diff failed: {}", e), "error"),
+            };
+
+        self.push_output(content, output_type);
+    }
+
+    /// Record a finished execution
+    fn record_execution(&mut self, progress: &ToolProgress, success: bool) {
+        let record = ExecutionRecord {
+            command: progress.name.clone(),
+            success,
+            duration_ms: progress.started.elapsed().as_millis() as u64,
+            path: None,
+            timestamp: chrono::Utc::now(),
+        };
+        if let Some(store) = &self.history_store {
+            store.record(record.clone());
+        }
+        self.execution_history.push(record);
+    }
+
+    /// Get finished command executions, oldest first
+    pub fn get_execution_history(&self) -> &[ExecutionRecord] {
+        &self.execution_history
+    }
+
     /// Navigate command history
     fn navigate_history(&mut self, direction: isize) {
         if self.command_history.is_empty() {
@@ -314,7 +802,7 @@ impl TerminalIntegration {
     }
 
     /// Render the terminal interface
-    pub fn render(&self, frame: &mut Frame, area: Rect) {
+    pub fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
@@ -324,98 +812,257 @@ impl TerminalIntegration {
             .split(area);
 
         // Render output area
-        self.render_output_area(frame, chunks[0]);
+        self.render_output_area(frame, chunks[0], theme);
 
         // Render command input area
-        self.render_command_input(frame, chunks[1]);
+        self.render_command_input(frame, chunks[1], theme);
     }
 
     /// Render the output area
-    fn render_output_area(&self, frame: &mut Frame, area: Rect) {
+    fn render_output_area(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        // Remember the geometry so clicks can be mapped back to lines
+        self.output_area.set(area);
+
         let output_text: Vec<Line> = self
             .output_buffer
             .iter()
-            .map(|output| {
-                let style = match output.output_type.as_str() {
-                    "command" => Style::default()
-                        .fg(ratatui::style::Color::Green)
-                        .add_modifier(ratatui::style::Modifier::BOLD),
-                    "error" => Style::default().fg(ratatui::style::Color::Red),
-                    "success" => Style::default().fg(ratatui::style::Color::Cyan),
-                    _ => Style::default().fg(ratatui::style::Color::White),
-                };
-
-                Line::from(Span::styled(output.content.clone(), style))
+            .flat_map(|output| {
+                output
+                    .content
+                    .lines()
+                    .map(move |line| self.styled_output_line(line, output, theme))
             })
             .collect();
 
+        // Lines are not wrapped so each screen row maps to one output line
         let paragraph = Paragraph::new(Text::from(output_text))
             .block(
                 Block::default()
                     .title("Terminal Output")
-                    .borders(Borders::ALL),
+                    .borders(Borders::ALL)
+                    .border_style(theme.border_style()),
             )
-            .wrap(Wrap { trim: true });
+            .scroll((self.output_scroll.min(u16::MAX as usize) as u16, 0));
 
         frame.render_widget(paragraph, area);
     }
 
+    /// Style one display line of an output entry according to its output type
+    fn styled_output_line(
+        &self,
+        line: &str,
+        output: &TerminalOutput,
+        theme: &Theme,
+    ) -> Line<'static> {
+        let output_type = output.output_type.as_str();
+        if output_type == DIFF_OUTPUT_TYPE {
+            return styled_diff_line(line, theme);
+        }
+        let style = theme.output_style(output_type);
+        if let Some(pattern) = self.find_patterns.get(&output.id) {
+            return highlight_symbol_match(line, pattern, style);
+        }
+
+        Line::from(Span::styled(line.to_string(), style))
+    }
+
     /// Render the command input area
-    fn render_command_input(&self, frame: &mut Frame, area: Rect) {
-        let input_text = format!("$ {}", self.current_command);
+    fn render_command_input(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        let input_text = match &self.history_search {
+            Some(search) => {
+                let matched = search
+                    .match_index
+                    .map(|index| self.command_history[index].as_str())
+                    .unwrap_or("");
+                format!("(reverse-i-search)`{}': {}", search.query, matched)
+            }
+            None => format!("$ {}", self.current_command),
+        };
 
         let paragraph = Paragraph::new(input_text)
             .block(
                 Block::default()
                     .title("Command Input")
-                    .borders(Borders::ALL),
+                    .borders(Borders::ALL)
+                    .border_style(theme.border_style()),
             )
-            .style(Style::default().fg(ratatui::style::Color::Yellow));
+            .style(Style::default().fg(theme.input));
 
         frame.render_widget(paragraph, area);
     }
 
     /// Process command execution results
+    ///
+    /// Queued commands are started on background threads so the event loop is never
+    /// blocked; finished outputs are collected without waiting.
     pub fn process_command_results(&mut self) -> Result<()> {
         if let Some(mut receiver) = self.command_receiver.take() {
             while let Ok(command) = receiver.try_recv() {
-                // Execute command and capture output
-                let output = self.execute_shell_command(&command.command)?;
-
-                // Add output to buffer
-                self.output_buffer.push(TerminalOutput {
-                    id: uuid::Uuid::new_v4(),
-                    content: output,
-                    output_type: "output".to_string(),
-                    timestamp: chrono::Utc::now(),
+                self.in_flight.push((
+                    command.id,
+                    ToolProgress::new(command.command.clone(), Instant::now()),
+                ));
+
+                let sender = self.result_sender.clone();
+                let child = match spawn_shell_command(&command.command) {
+                    Ok(child) => Arc::new(Mutex::new(child)),
+                    Err(e) => {
+                        let _ = sender.send((command.id, false, format!("Error: {}", e)));
+                        continue;
+                    }
+                };
+                self.shell_children.insert(command.id, child.clone());
+                std::thread::spawn(move || {
+                    let (success, output) = wait_for_shell_command(&child)
+                        .unwrap_or_else(|e| (false, format!("Error: {}", e)));
+                    let _ = sender.send((command.id, success, output));
                 });
             }
             self.command_receiver = Some(receiver);
         }
+
+        while let Ok((id, success, output)) = self.result_receiver.try_recv() {
+            // Results of commands that already timed out are dropped
+            let Some(position) = self.in_flight.iter().position(|(i, _)| *i == id) else {
+                continue;
+            };
+            let (_, progress) = self.in_flight.remove(position);
+            self.shell_children.remove(&id);
+            self.record_execution(&progress, success);
+            if self.is_directory_analysis(id) {
+                self.directory_analysis = None;
+            }
+
+            // Add output to buffer
+            self.push_output(output, "output");
+        }
+
+        self.process_pending_history();
+        self.handle_tool_timeout(Instant::now());
         Ok(())
     }
 
-    /// Execute shell command and capture output
-    fn execute_shell_command(&self, command: &str) -> Result<String> {
-        use std::process::Command;
+    /// Report and stop tracking background commands that exceeded the tool timeout
+    ///
+    /// `/analyze` is exempt: analyzing a large tree can take minutes, and it is
+    /// stopped with `/cancel` instead.
+    pub fn handle_tool_timeout(&mut self, now: Instant) {
+        let Some(timeout) = self.tool_timeout else {
+            return;
+        };
 
-        debug!("Executing shell command: {}", command);
+        let mut expired = Vec::new();
+        for (id, progress) in std::mem::take(&mut self.in_flight) {
+            if self.is_directory_analysis(id)
+                || now.saturating_duration_since(progress.started) < timeout
+            {
+                self.in_flight.push((id, progress));
+                continue;
+            }
 
-        let output = if cfg!(target_os = "windows") {
-            Command::new("cmd").args(&["/C", command]).output()?
-        } else {
-            Command::new("sh").args(&["-c", command]).output()?
-        };
+            // A timed-out shell command is killed rather than left running unseen
+            if let Some(child) = self.shell_children.remove(&id) {
+                if let Ok(mut child) = child.lock() {
+                    if let Err(e) = kill_shell_command(&mut child) {
+                        warn!("Failed to kill '{}' after timeout: {}", progress.name, e);
+                    }
+                }
+            }
+            self.record_execution(&progress, false);
+            expired.push(progress.name);
+        }
 
-        let result = if output.status.success() {
-            String::from_utf8_lossy(&output.stdout).to_string()
-        } else {
-            let error = String::from_utf8_lossy(&output.stderr).to_string();
-            format!("Error: {}", error)
+        for name in expired {
+            self.push_output(
+                format!("Error: '{}' timed out after {}s", name, timeout.as_secs()),
+                "error",
+            );
+        }
+    }
+
+    /// Whether a background id belongs to the running `/analyze`
+    fn is_directory_analysis(&self, id: uuid::Uuid) -> bool {
+        matches!(&self.directory_analysis, Some((analysis_id, _, _)) if *analysis_id == id)
+    }
+
+    /// Set the tool timeout; `None` lets background commands run indefinitely
+    pub fn set_tool_timeout(&mut self, timeout: Option<Duration>) {
+        self.tool_timeout = timeout;
+    }
+
+    /// Refuse shell commands and file writes; built-in commands still work
+    pub fn set_safe_mode(&mut self, safe_mode: bool) {
+        self.safe_mode = safe_mode;
+    }
+
+    /// Set the LLM manager whose token usage is shown by `/cost`
+    pub fn set_llm_manager(&mut self, llm_manager: Arc<LLMIntegrationManager>) {
+        self.llm_manager = Some(llm_manager);
+    }
+
+    /// Set the engine that runs `/analyze`
+    pub fn set_code_engine(&mut self, engine: Arc<CodeEngine>) {
+        self.code_engine = Some(engine);
+    }
+
+    /// Write executions to an action history and read `/history` from it
+    pub fn set_history_store(&mut self, store: HistoryStore) {
+        self.history_store = Some(store);
+    }
+
+    /// Set the indexer that reindexes files written by the TUI
+    pub fn set_symbol_indexer(&mut self, indexer: Arc<dyn SymbolIndexer>) {
+        self.symbol_indexer = Some(indexer);
+    }
+
+    /// Write a file and reindex its symbols in the background
+    pub fn write_file(&mut self, path: &str, content: &str) -> Result<()> {
+        if self.safe_mode {
+            return Err(anyhow::anyhow!("Writing {} is {}", path, SAFE_MODE_BLOCKED));
+        }
+        std::fs::write(path, content)
+            .map_err(|e| anyhow::anyhow!("Failed to write {}: {}", path, e))?;
+        self.enqueue_reindex(path);
+        Ok(())
+    }
+
+    /// Reindex a file on a background thread
+    ///
+    /// The result is reported through the background result channel like any
+    /// other command; does nothing when no indexer is set.
+    pub fn enqueue_reindex(&mut self, path: &str) {
+        let Some(indexer) = self.symbol_indexer.clone() else {
+            return;
         };
 
-        debug!("Command output: {}", result);
-        Ok(result)
+        let id = uuid::Uuid::new_v4();
+        self.in_flight.push((
+            id,
+            ToolProgress::new(format!("reindex {}", path), Instant::now()),
+        ));
+
+        let path = path.to_string();
+        let sender = self.result_sender.clone();
+        std::thread::spawn(move || {
+            let result = indexer.reindex_file(&path);
+            if let Err(e) = &result {
+                warn!(
+                    "Failed to reindex {}, keeping previous symbols: {}",
+                    path, e
+                );
+            }
+            let _ = sender.send((id, result.is_ok(), reindex_message(&path, &result)));
+        });
+    }
+
+    /// Execute shell command and capture output
+    fn execute_shell_command(&self, command: &str) -> Result<String> {
+        run_shell_command(command).map(|(_, output)| output)
+    }
+
+    /// Get the most recently started command still running in the background, if any
+    pub fn get_tool_progress(&self) -> Option<&ToolProgress> {
+        self.in_flight.last().map(|(_, progress)| progress)
     }
 
     /// Get command history
@@ -428,9 +1075,15 @@ impl TerminalIntegration {
         &self.current_command
     }
 
+    /// Get the active reverse history search, if any
+    pub fn get_history_search(&self) -> Option<&HistorySearch> {
+        self.history_search.as_ref()
+    }
+
     /// Clear output buffer
     pub fn clear_output(&mut self) {
         self.output_buffer.clear();
+        self.find_patterns.clear();
     }
 
     /// Toggle shell integration
@@ -467,6 +1120,133 @@ impl TerminalIntegration {
     }
 }
 
+/// Index the symbols of analyzed files so `/find` can search them
+///
+/// Files that fail to index are logged and skipped. Returns the number of
+/// symbols indexed.
+fn index_analyzed_files(
+    indexer: &dyn SymbolIndexer,
+    results: &[(String, odincode_core::AnalysisResult)],
+) -> usize {
+    results
+        .iter()
+        .filter_map(|(path, _)| match indexer.reindex_file(path) {
+            Ok(count) => Some(count),
+            Err(e) => {
+                warn!("Failed to index {}, keeping previous symbols: {}", path, e);
+                None
+            }
+        })
+        .sum()
+}
+
+/// Execute a shell command and capture whether it succeeded and its output
+fn run_shell_command(command: &str) -> Result<(bool, String)> {
+    debug!("Executing shell command: {}", command);
+
+    let output = shell_command(command).output()?;
+    Ok(shell_result(
+        output.status.success(),
+        &output.stdout,
+        &output.stderr,
+    ))
+}
+
+/// The platform shell running `command`
+fn shell_command(command: &str) -> Command {
+    let mut shell = if cfg!(target_os = "windows") {
+        let mut shell = Command::new("cmd");
+        shell.args(["/C", command]);
+        shell
+    } else {
+        let mut shell = Command::new("sh");
+        shell.args(["-c", command]);
+        shell
+    };
+    shell
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    // The shell leads its own process group, so everything it starts can be killed
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(&mut shell, 0);
+    shell
+}
+
+/// Start a shell command without waiting for it
+fn spawn_shell_command(command: &str) -> Result<Child> {
+    debug!("Starting shell command: {}", command);
+    Ok(shell_command(command).spawn()?)
+}
+
+/// Kill a started shell command along with every process it started
+///
+/// Killing only the shell would leave its children running, still holding the
+/// output pipes open.
+fn kill_shell_command(child: &mut Child) -> std::io::Result<()> {
+    #[cfg(unix)]
+    {
+        // The group id is the shell's pid, as it leads the group
+        let group = -(child.id() as libc::pid_t);
+        if unsafe { libc::kill(group, libc::SIGKILL) } == 0 {
+            return Ok(());
+        }
+        let error = std::io::Error::last_os_error();
+        // The whole group has exited already
+        if error.raw_os_error() == Some(libc::ESRCH) {
+            return Ok(());
+        }
+        Err(error)
+    }
+    #[cfg(not(unix))]
+    child.kill()
+}
+
+/// Wait for a started shell command and capture its output
+///
+/// The child is only locked while it is polled, so it can be killed meanwhile.
+fn wait_for_shell_command(child: &Mutex<Child>) -> Result<(bool, String)> {
+    let lock_error = || anyhow::anyhow!("Shell command state is poisoned");
+    let (stdout, stderr) = {
+        let mut child = child.lock().map_err(|_| lock_error())?;
+        (child.stdout.take(), child.stderr.take())
+    };
+
+    // Stderr is drained on its own thread so neither pipe can fill up and block
+    let stderr_reader = std::thread::spawn(move || read_pipe(stderr));
+    let stdout = read_pipe(stdout);
+    let status = loop {
+        if let Some(status) = child.lock().map_err(|_| lock_error())?.try_wait()? {
+            break status;
+        }
+        std::thread::sleep(SHELL_POLL_INTERVAL);
+    };
+    let stderr = stderr_reader.join().unwrap_or_default();
+
+    Ok(shell_result(status.success(), &stdout, &stderr))
+}
+
+/// Read a child's pipe to its end
+fn read_pipe(pipe: Option<impl Read>) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    if let Some(mut pipe) = pipe {
+        let _ = pipe.read_to_end(&mut bytes);
+    }
+    bytes
+}
+
+/// Result of a finished shell command: its stdout, or its stderr on failure
+fn shell_result(success: bool, stdout: &[u8], stderr: &[u8]) -> (bool, String) {
+    let result = if success {
+        String::from_utf8_lossy(stdout).to_string()
+    } else {
+        format!("Error: {}", String::from_utf8_lossy(stderr))
+    };
+
+    debug!("Command output: {}", result);
+    (success, result)
+}
+
 // Import all test modules
 #[cfg(test)]
 mod tests {
@@ -475,4 +1255,6 @@ mod tests {
     pub use super::command_execution_tests::*;
     pub use super::creation_tests::*;
     pub use super::event_handling_tests::*;
+    pub use super::history_search_tests::*;
+    pub use super::mouse_selection_tests::*;
 }
@@ -0,0 +1,116 @@
+//! Tests for Terminal Integration Mouse Selection
+//!
+//! This module contains tests for mapping clicks in the output area to
+//! output lines and opening the file locations they reference.
+
+use super::*;
+
+#[cfg(test)]
+mod mouse_selection_tests {
+    use super::*;
+
+    fn click(column: u16, row: u16) -> MouseEvent {
+        MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column,
+            row,
+            modifiers: KeyModifiers::NONE,
+        }
+    }
+
+    fn output(content: &str) -> TerminalOutput {
+        TerminalOutput {
+            id: uuid::Uuid::new_v4(),
+            content: content.to_string(),
+            output_type: "output".to_string(),
+            timestamp: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_row_to_line_index_with_scroll() {
+        let area = Rect::new(0, 2, 40, 10);
+
+        // First inner row shows the first visible line
+        assert_eq!(row_to_line_index(area, 0, 5, 3), Some(0));
+        assert_eq!(row_to_line_index(area, 4, 5, 3), Some(4));
+        assert_eq!(row_to_line_index(area, 4, 5, 7), Some(8));
+        // Last inner row
+        assert_eq!(row_to_line_index(area, 4, 5, 10), Some(11));
+    }
+
+    #[test]
+    fn test_row_to_line_index_ignores_borders_and_outside() {
+        let area = Rect::new(0, 2, 40, 10);
+
+        assert_eq!(row_to_line_index(area, 4, 5, 2), None); // top border
+        assert_eq!(row_to_line_index(area, 4, 5, 11), None); // bottom border
+        assert_eq!(row_to_line_index(area, 4, 0, 5), None); // left border
+        assert_eq!(row_to_line_index(area, 4, 39, 5), None); // right border
+        assert_eq!(row_to_line_index(area, 4, 5, 20), None); // below the panel
+    }
+
+    #[test]
+    fn test_file_location_parse() {
+        assert_eq!(
+            FileLocation::parse("src/main.rs:42: fn main() {"),
+            Some(FileLocation {
+                path: "src/main.rs".to_string(),
+                line: 42,
+            })
+        );
+        assert_eq!(
+            FileLocation::parse("src/lib.rs:7:13: warning: unused variable").map(|l| l.line),
+            Some(7)
+        );
+        assert_eq!(FileLocation::parse("total 42"), None);
+        assert_eq!(FileLocation::parse("Error: something: went wrong"), None);
+        assert_eq!(FileLocation::parse("src/main.rs:0:"), None);
+    }
+
+    #[test]
+    fn test_click_on_grep_hit_requests_open() {
+        let mut integration = TerminalIntegration::new();
+        integration.output_buffer.push(output("$ grep -n fn src"));
+        integration.output_buffer.push(output(
+            "src/a.rs:1:fn a() {}\nsrc/b.rs:10:fn b() {}\nsrc/c.rs:3:fn c() {}",
+        ));
+        integration.output_area.set(Rect::new(0, 0, 40, 10));
+        integration.output_scroll = 1;
+
+        // Row 2 is the second visible line, i.e. output line 2 with scroll 1
+        let result = integration.handle_mouse_event(click(5, 2)).unwrap();
+
+        assert!(matches!(result, Some(TuiState::CodeEditor)));
+        assert_eq!(
+            integration.take_open_request(),
+            Some(FileLocation {
+                path: "src/b.rs".to_string(),
+                line: 10,
+            })
+        );
+        assert!(integration.take_open_request().is_none());
+    }
+
+    #[test]
+    fn test_click_on_plain_line_is_ignored() {
+        let mut integration = TerminalIntegration::new();
+        integration.output_buffer.push(output("$ ls"));
+        integration.output_area.set(Rect::new(0, 0, 40, 10));
+
+        // Plain output line, border and empty space are all ignored
+        assert!(integration
+            .handle_mouse_event(click(5, 1))
+            .unwrap()
+            .is_none());
+        assert!(integration
+            .handle_mouse_event(click(5, 0))
+            .unwrap()
+            .is_none());
+        assert!(integration
+            .handle_mouse_event(click(5, 5))
+            .unwrap()
+            .is_none());
+        assert!(integration.take_open_request().is_none());
+    }
+}
@@ -0,0 +1,91 @@
+//! Tests for Terminal Integration Symbol Reindexing
+//!
+//! This module contains tests for reindexing files written by the TUI.
+
+use super::*;
+
+#[cfg(test)]
+mod reindex_tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Indexer that records the paths it is asked to reindex
+    struct RecordingIndexer {
+        paths: Mutex<Vec<String>>,
+        fail: bool,
+    }
+
+    impl SymbolIndexer for RecordingIndexer {
+        fn reindex_file(&self, path: &str) -> Result<usize> {
+            self.paths.lock().unwrap().push(path.to_string());
+            if self.fail {
+                return Err(anyhow::anyhow!("parse error"));
+            }
+            Ok(3)
+        }
+    }
+
+    /// Process results until no background work is left
+    fn wait_for_results(integration: &mut TerminalIntegration) {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while integration.get_tool_progress().is_some() && Instant::now() < deadline {
+            integration.process_command_results().unwrap();
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn test_write_file_enqueues_reindex() {
+        let indexer = Arc::new(RecordingIndexer {
+            paths: Mutex::new(Vec::new()),
+            fail: false,
+        });
+        let mut integration = TerminalIntegration::new();
+        integration.set_symbol_indexer(indexer.clone());
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("main.rs").to_string_lossy().to_string();
+        integration.write_file(&path, "fn main() {}\n").unwrap();
+        assert_eq!(
+            integration.get_tool_progress().map(|p| p.name.clone()),
+            Some(format!("reindex {}", path))
+        );
+
+        wait_for_results(&mut integration);
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "fn main() {}\n");
+        assert_eq!(*indexer.paths.lock().unwrap(), vec![path.clone()]);
+        let output = integration.output_buffer.last().unwrap();
+        assert_eq!(output.content, format!("Reindexed {} (3 symbols)", path));
+        assert!(integration.get_execution_history()[0].success);
+    }
+
+    #[test]
+    fn test_failed_reindex_is_reported() {
+        let indexer = Arc::new(RecordingIndexer {
+            paths: Mutex::new(Vec::new()),
+            fail: true,
+        });
+        let mut integration = TerminalIntegration::new();
+        integration.set_symbol_indexer(indexer.clone());
+
+        integration.enqueue_reindex("src/broken.rs");
+        wait_for_results(&mut integration);
+
+        assert_eq!(
+            *indexer.paths.lock().unwrap(),
+            vec!["src/broken.rs".to_string()]
+        );
+        let output = integration.output_buffer.last().unwrap();
+        assert!(output.content.contains("keeping previous symbols"));
+        assert!(!integration.get_execution_history()[0].success);
+    }
+
+    #[test]
+    fn test_reindex_without_indexer_does_nothing() {
+        let mut integration = TerminalIntegration::new();
+        integration.enqueue_reindex("src/lib.rs");
+
+        assert!(integration.get_tool_progress().is_none());
+    }
+}
@@ -0,0 +1,79 @@
+//! TUI Tool Progress Module
+//!
+//! This module tracks tools that are running in the background so the status bar
+//! can show a spinner with the elapsed time until their result arrives.
+
+use std::time::{Duration, Instant};
+
+/// Characters the status bar spinner cycles through, one every 100ms
+const SPINNER_FRAMES: [char; 4] = ['|', '/', '-', '\\'];
+
+/// A tool running in the background
+#[derive(Debug, Clone)]
+pub struct ToolProgress {
+    /// Name of the running tool or command
+    pub name: String,
+    /// When the tool was started
+    pub started: Instant,
+}
+
+impl ToolProgress {
+    /// Start tracking a tool
+    pub fn new(name: impl Into<String>, started: Instant) -> Self {
+        Self {
+            name: name.into(),
+            started,
+        }
+    }
+
+    /// Whole seconds elapsed since the tool started
+    pub fn elapsed_secs(&self, now: Instant) -> u64 {
+        now.saturating_duration_since(self.started).as_secs()
+    }
+
+    /// Status bar text for the tool at `now`
+    pub fn status_text(&self, now: Instant) -> String {
+        let elapsed = now.saturating_duration_since(self.started);
+        format!(
+            "{} Running {} ({}s)",
+            spinner_frame(elapsed),
+            self.name,
+            elapsed.as_secs()
+        )
+    }
+}
+
+/// Spinner frame for the given elapsed time, changing every 100ms
+fn spinner_frame(elapsed: Duration) -> char {
+    SPINNER_FRAMES[(elapsed.as_millis() / 100) as usize % SPINNER_FRAMES.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_elapsed_secs() {
+        let started = Instant::now();
+        let progress = ToolProgress::new("cargo build", started);
+
+        assert_eq!(progress.elapsed_secs(started), 0);
+        assert_eq!(
+            progress.elapsed_secs(started + Duration::from_millis(2500)),
+            2
+        );
+        assert_eq!(progress.elapsed_secs(started + Duration::from_secs(61)), 61);
+    }
+
+    #[test]
+    fn test_status_text() {
+        let started = Instant::now();
+        let progress = ToolProgress::new("cargo build", started);
+
+        assert_eq!(progress.status_text(started), "| Running cargo build (0s)");
+        assert_eq!(
+            progress.status_text(started + Duration::from_millis(3100)),
+            "\\ Running cargo build (3s)"
+        );
+    }
+}
@@ -0,0 +1,123 @@
+//! TUI Usage Report Module
+//!
+//! This module formats the LLM token usage and estimated cost shown by the
+//! `/cost` command.
+
+use odincode_core::llm_integration::UsageStats;
+
+/// A parsed `/cost` command
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CostCommand {
+    /// Show the accumulated usage
+    Show,
+    /// Reset the accumulated usage
+    Reset,
+}
+
+/// Parse a `/cost [reset]` command
+///
+/// Returns `None` if the input is not a well-formed `/cost` command.
+pub fn parse_cost_command(input: &str) -> Option<CostCommand> {
+    let mut parts = input.split_whitespace();
+    if parts.next()? != "/cost" {
+        return None;
+    }
+
+    let command = match parts.next() {
+        Some("reset") => CostCommand::Reset,
+        Some(_) => return None,
+        None => CostCommand::Show,
+    };
+
+    parts.next().is_none().then_some(command)
+}
+
+/// Format usage stats as a table with one row per model and a total row
+pub fn format_usage_report(stats: &UsageStats) -> Vec<String> {
+    if stats.requests == 0 {
+        return vec!["No LLM requests have been made yet".to_string()];
+    }
+
+    let row = |model: &str, requests: usize, prompt: usize, completion: usize, cost: f64| {
+        format!(
+            "{:<24}  {:>8}  {:>10}  {:>10}  {:>9}",
+            model,
+            requests,
+            prompt,
+            completion,
+            format!("${:.4}", cost)
+        )
+    };
+
+    let mut lines = vec![format!(
+        "{:<24}  {:>8}  {:>10}  {:>10}  {:>9}",
+        "MODEL", "REQUESTS", "PROMPT", "COMPLETION", "COST"
+    )];
+    lines.extend(stats.by_model.iter().map(|(model, usage)| {
+        row(
+            model,
+            usage.requests,
+            usage.prompt_tokens,
+            usage.completion_tokens,
+            usage.estimated_cost,
+        )
+    }));
+    lines.push(row(
+        "total",
+        stats.requests,
+        stats.prompt_tokens,
+        stats.completion_tokens,
+        stats.estimated_cost,
+    ));
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use odincode_core::llm_integration::ModelUsage;
+
+    #[test]
+    fn test_parse_cost_command() {
+        assert_eq!(parse_cost_command("/cost"), Some(CostCommand::Show));
+        assert_eq!(
+            parse_cost_command(" /cost reset "),
+            Some(CostCommand::Reset)
+        );
+        assert_eq!(parse_cost_command("/cost everything"), None);
+        assert_eq!(parse_cost_command("/cost reset now"), None);
+        assert_eq!(parse_cost_command("/costs"), None);
+    }
+
+    #[test]
+    fn test_format_usage_report() {
+        assert_eq!(
+            format_usage_report(&UsageStats::default()),
+            vec!["No LLM requests have been made yet".to_string()]
+        );
+
+        let mut stats = UsageStats {
+            requests: 2,
+            prompt_tokens: 1200,
+            completion_tokens: 300,
+            estimated_cost: 0.054,
+            ..Default::default()
+        };
+        stats.by_model.insert(
+            "gpt-4".to_string(),
+            ModelUsage {
+                requests: 2,
+                prompt_tokens: 1200,
+                completion_tokens: 300,
+                estimated_cost: 0.054,
+            },
+        );
+
+        let lines = format_usage_report(&stats);
+        assert_eq!(lines.len(), 3);
+        assert!(lines[1].starts_with("gpt-4 "));
+        assert!(lines[2].starts_with("total "));
+        assert!(lines[2].ends_with("$0.0540"));
+    }
+}
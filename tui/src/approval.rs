@@ -0,0 +1,224 @@
+//! TUI Tool Approval Module
+//!
+//! This module decides whether running a gated tool needs the user's approval.
+//! Per-tool policies are loaded from `<db_root>/approval.toml`; tools without a
+//! policy use the file's default, which is to prompt.
+
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+use tracing::warn;
+
+/// File name of the approval policy inside the database root
+pub const APPROVAL_FILE_NAME: &str = "approval.toml";
+
+/// How running a tool is approved
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApprovalPolicy {
+    /// Run without asking
+    AlwaysAllow,
+    /// Never run
+    AlwaysDeny,
+    /// Ask before running
+    #[default]
+    Prompt,
+}
+
+/// What to do with a request to run a tool
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ApprovalDecision {
+    /// Run the tool
+    Allow,
+    /// Reject the tool without running it
+    Deny,
+    /// Ask the user first
+    Prompt,
+}
+
+/// Answer to an approval prompt
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ApprovalResponse {
+    /// `y`: run the tool this time
+    Approve,
+    /// `a`: run this and every other prompted tool for the rest of the session
+    ApproveSessionAllGated,
+    /// `n`: reject the tool
+    Deny,
+    /// `q`: dismiss the prompt without running the tool
+    Cancel,
+}
+
+impl ApprovalResponse {
+    /// Parse the key pressed at an approval prompt
+    pub fn from_key(key: char) -> Option<Self> {
+        match key.to_ascii_lowercase() {
+            'y' => Some(Self::Approve),
+            'a' => Some(Self::ApproveSessionAllGated),
+            'n' => Some(Self::Deny),
+            'q' => Some(Self::Cancel),
+            _ => None,
+        }
+    }
+}
+
+/// Per-tool approval policies
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct ApprovalConfig {
+    /// Policy for tools without their own entry
+    #[serde(default)]
+    pub default: ApprovalPolicy,
+    /// Policies by tool name
+    #[serde(default)]
+    pub tools: HashMap<String, ApprovalPolicy>,
+}
+
+impl ApprovalConfig {
+    /// Parse approval policies from TOML
+    pub fn from_toml_str(content: &str) -> Result<Self> {
+        let config: Self =
+            toml::from_str(content).map_err(|e| anyhow!("Invalid approval file: {}", e))?;
+
+        // Tool names are matched case-insensitively, with spaces as underscores
+        let tools = config
+            .tools
+            .into_iter()
+            .map(|(name, policy)| (normalize_tool_name(&name), policy))
+            .collect();
+        Ok(Self { tools, ..config })
+    }
+
+    /// Load approval policies from `<db_root>/approval.toml`
+    ///
+    /// A missing or invalid file falls back to prompting for every tool.
+    pub fn load(db_root: &Path) -> Self {
+        let path = db_root.join(APPROVAL_FILE_NAME);
+        if !path.exists() {
+            return Self::default();
+        }
+
+        match std::fs::read_to_string(&path)
+            .map_err(|e| anyhow!("Failed to read {}: {}", path.display(), e))
+            .and_then(|content| Self::from_toml_str(&content))
+        {
+            Ok(config) => config,
+            Err(e) => {
+                warn!("Prompting for every tool: {}", e);
+                Self::default()
+            }
+        }
+    }
+
+    /// Policy for a tool
+    pub fn policy_for(&self, tool: &str) -> ApprovalPolicy {
+        self.tools
+            .get(&normalize_tool_name(tool))
+            .copied()
+            .unwrap_or(self.default)
+    }
+
+    /// Decide what to do with a request to run a tool
+    ///
+    /// `approve_all_gated` is the session-wide override from answering `a` at a
+    /// prompt; it skips prompts but never runs an `always_deny` tool.
+    pub fn resolve(&self, tool: &str, approve_all_gated: bool) -> ApprovalDecision {
+        match self.policy_for(tool) {
+            ApprovalPolicy::AlwaysAllow => ApprovalDecision::Allow,
+            ApprovalPolicy::AlwaysDeny => ApprovalDecision::Deny,
+            ApprovalPolicy::Prompt if approve_all_gated => ApprovalDecision::Allow,
+            ApprovalPolicy::Prompt => ApprovalDecision::Prompt,
+        }
+    }
+}
+
+/// Tools that only read the project and so may run in safe mode
+pub const SAFE_MODE_TOOLS: &[&str] = &["linter", "file_read"];
+
+/// Whether a tool may run in safe mode
+pub fn allowed_in_safe_mode(tool: &str) -> bool {
+    SAFE_MODE_TOOLS.contains(&normalize_tool_name(tool).as_str())
+}
+
+/// Normalize a tool name for policy lookup
+fn normalize_tool_name(name: &str) -> String {
+    name.trim().to_lowercase().replace([' ', '-'], "_")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"
+        [tools]
+        file_read = "always_allow"
+        shell = "always_deny"
+        file_write = "prompt"
+    "#;
+
+    #[test]
+    fn test_policy_resolution() {
+        let config = ApprovalConfig::from_toml_str(CONFIG).unwrap();
+
+        assert_eq!(config.resolve("file_read", false), ApprovalDecision::Allow);
+        assert_eq!(config.resolve("shell", false), ApprovalDecision::Deny);
+        assert_eq!(
+            config.resolve("file_write", false),
+            ApprovalDecision::Prompt
+        );
+        assert_eq!(
+            config.resolve("Test Runner", false),
+            ApprovalDecision::Prompt
+        );
+        assert_eq!(config.resolve("File-Read", false), ApprovalDecision::Allow);
+    }
+
+    #[test]
+    fn test_session_override_skips_prompts_only() {
+        let config = ApprovalConfig::from_toml_str(CONFIG).unwrap();
+
+        assert_eq!(config.resolve("file_write", true), ApprovalDecision::Allow);
+        assert_eq!(config.resolve("shell", true), ApprovalDecision::Deny);
+    }
+
+    #[test]
+    fn test_default_policy() {
+        let config = ApprovalConfig::from_toml_str("default = \"always_allow\"").unwrap();
+        assert_eq!(config.resolve("linter", false), ApprovalDecision::Allow);
+
+        assert!(ApprovalConfig::from_toml_str("default = \"sometimes\"").is_err());
+        assert_eq!(
+            ApprovalConfig::load(Path::new("/nonexistent/odincode")),
+            ApprovalConfig::default()
+        );
+    }
+
+    #[test]
+    fn test_safe_mode_tools() {
+        assert!(allowed_in_safe_mode("Linter"));
+        assert!(allowed_in_safe_mode("file-read"));
+        assert!(!allowed_in_safe_mode("Formatter"));
+        assert!(!allowed_in_safe_mode("Build System"));
+    }
+
+    #[test]
+    fn test_response_keys() {
+        assert_eq!(
+            ApprovalResponse::from_key('y'),
+            Some(ApprovalResponse::Approve)
+        );
+        assert_eq!(
+            ApprovalResponse::from_key('A'),
+            Some(ApprovalResponse::ApproveSessionAllGated)
+        );
+        assert_eq!(
+            ApprovalResponse::from_key('n'),
+            Some(ApprovalResponse::Deny)
+        );
+        assert_eq!(
+            ApprovalResponse::from_key('q'),
+            Some(ApprovalResponse::Cancel)
+        );
+        assert_eq!(ApprovalResponse::from_key('x'), None);
+    }
+}
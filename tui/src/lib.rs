@@ -4,10 +4,18 @@
 //! allowing users to interact with the AI coding assistant directly from the terminal.
 
 pub mod app;
+pub mod approval;
 pub mod models;
+pub mod panels;
+pub mod theme;
 pub mod ui;
 
+pub use app::execution_history::HistoryStore;
+pub use app::reindex::{SymbolTableIndexer, SYMBOL_DB_FILE_NAME};
 pub use app::TuiApp;
+pub use approval::{ApprovalConfig, ApprovalPolicy};
+pub use panels::PanelConfig;
+pub use theme::Theme;
 pub use ui::render;
 
 use anyhow::Result;
@@ -18,13 +26,17 @@ use crossterm::{
 };
 use ratatui::Terminal;
 use std::io;
-use tracing::info;
+use std::path::PathBuf;
+use tracing::{info, warn};
 
 use odincode_agents::AgentCoordinator;
+use odincode_core::llm_integration::LLMIntegrationManager;
 use odincode_core::CodeEngine;
 use odincode_ltmc::LTMManager;
 use odincode_tools::ToolManager;
 
+pub use odincode_agents::DEFAULT_DB_ROOT;
+
 /// Main TUI application runner
 pub struct TuiRunner {
     /// Shared core engine
@@ -35,6 +47,16 @@ pub struct TuiRunner {
     agent_coordinator: AgentCoordinator,
     /// Shared tool manager
     tool_manager: ToolManager,
+    /// Database root holding the TUI theme, panel order and symbol index
+    db_root: PathBuf,
+    /// Timeout for background commands; `None` disables it
+    tool_timeout: Option<std::time::Duration>,
+    /// LLM manager whose token usage is shown by `/cost`
+    llm_manager: Option<std::sync::Arc<LLMIntegrationManager>>,
+    /// Approval policies reloaded while the TUI is running
+    approval_updates: Option<tokio::sync::watch::Receiver<ApprovalConfig>>,
+    /// Whether tools that write files or run project code are refused
+    safe_mode: bool,
 }
 
 impl TuiRunner {
@@ -50,9 +72,47 @@ impl TuiRunner {
             ltmc_manager,
             agent_coordinator,
             tool_manager,
+            db_root: PathBuf::from(DEFAULT_DB_ROOT),
+            tool_timeout: Some(app::terminal_integration::DEFAULT_TOOL_TIMEOUT),
+            llm_manager: None,
+            approval_updates: None,
+            safe_mode: false,
         }
     }
 
+    /// Set the timeout for background commands; `None` disables it
+    pub fn with_tool_timeout(mut self, tool_timeout: Option<std::time::Duration>) -> Self {
+        self.tool_timeout = tool_timeout;
+        self
+    }
+
+    /// Set the LLM manager whose token usage is shown by `/cost`
+    pub fn with_llm_manager(mut self, llm_manager: std::sync::Arc<LLMIntegrationManager>) -> Self {
+        self.llm_manager = Some(llm_manager);
+        self
+    }
+
+    /// Apply approval policies received on `updates` while running
+    pub fn with_approval_updates(
+        mut self,
+        updates: tokio::sync::watch::Receiver<ApprovalConfig>,
+    ) -> Self {
+        self.approval_updates = Some(updates);
+        self
+    }
+
+    /// Refuse tools that write files or run project code, without prompting
+    pub fn with_safe_mode(mut self, safe_mode: bool) -> Self {
+        self.safe_mode = safe_mode;
+        self
+    }
+
+    /// Set the database root the theme, panel order and symbol index are loaded from
+    pub fn with_db_root(mut self, db_root: PathBuf) -> Self {
+        self.db_root = db_root;
+        self
+    }
+
     /// Run the TUI application
     pub async fn run(&self) -> Result<()> {
         info!("Starting OdinCode TUI application...");
@@ -66,19 +126,58 @@ impl TuiRunner {
 
         // Create and initialize the application
         let mut app = TuiApp::new();
+        app.theme = Theme::load(&self.db_root);
+        app.approval = ApprovalConfig::load(&self.db_root);
+        app.panels = PanelConfig::load(&self.db_root);
+        app.terminal_integration.set_tool_timeout(self.tool_timeout);
+        app.safe_mode = self.safe_mode;
+        app.terminal_integration.set_safe_mode(self.safe_mode);
+        app.terminal_integration
+            .set_code_engine(std::sync::Arc::clone(&self.core_engine));
+        if let Some(llm_manager) = &self.llm_manager {
+            app.terminal_integration
+                .set_llm_manager(std::sync::Arc::clone(llm_manager));
+        }
+        if let Some(action_history) = &self.agent_coordinator.action_history {
+            app.terminal_integration
+                .set_history_store(HistoryStore::spawn(
+                    std::sync::Arc::clone(action_history),
+                    uuid::Uuid::new_v4().to_string(),
+                    &tokio::runtime::Handle::current(),
+                ));
+        }
+        match SymbolTableIndexer::open(&self.db_root.join(SYMBOL_DB_FILE_NAME)).await {
+            Ok(indexer) => app
+                .terminal_integration
+                .set_symbol_indexer(std::sync::Arc::new(indexer)),
+            Err(e) => warn!("Symbol index is unavailable: {}", e),
+        }
         app.initialize(&self.core_engine, &self.agent_coordinator)
             .await?;
 
         // Run the application
+        let mut approval_updates = self.approval_updates.clone();
         let mut continue_running = true;
         while continue_running {
+            // Collect finished background commands
+            app.terminal_integration.process_command_results()?;
+
+            // Pick up reloaded approval policies
+            if let Some(updates) = approval_updates.as_mut() {
+                if updates.has_changed().unwrap_or(false) {
+                    app.approval = updates.borrow_and_update().clone();
+                }
+            }
+
             // Draw the UI
             terminal.draw(|f| render(&mut app, f))?;
 
             // Wait for an event
             if event::poll(std::time::Duration::from_millis(50))? {
-                if let Event::Key(key) = event::read()? {
-                    continue_running = app.handle_key_event(key)?;
+                match event::read()? {
+                    Event::Key(key) => continue_running = app.handle_key_event(key)?,
+                    Event::Mouse(mouse) => app.handle_mouse_event(mouse)?,
+                    _ => {}
                 }
             }
         }
@@ -100,6 +199,9 @@ impl TuiRunner {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::app::reindex::SymbolIndexer;
+    use crate::models::TuiState;
+    use crossterm::event::{KeyEvent, KeyModifiers};
 
     #[test]
     fn test_tui_app_creation() {
@@ -107,4 +209,38 @@ mod tests {
         assert_eq!(app.title, "OdinCode - AI Code Engineering System");
         // Note: Can't directly match enum variants without PartialEq, so we'll just verify creation
     }
+
+    /// Indexer that counts the files it is asked to reindex
+    struct CountingIndexer(std::sync::atomic::AtomicUsize);
+
+    impl SymbolIndexer for CountingIndexer {
+        fn reindex_file(&self, _path: &str) -> Result<usize> {
+            self.0.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(1)
+        }
+    }
+
+    #[test]
+    fn test_saving_the_editor_writes_and_reindexes_the_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("lib.rs").to_string_lossy().to_string();
+        let indexer = std::sync::Arc::new(CountingIndexer(Default::default()));
+
+        let mut app = TuiApp::new();
+        app.terminal_integration.set_symbol_indexer(indexer.clone());
+        app.current_state = TuiState::CodeEditor;
+        app.code_path = Some(path.clone());
+        app.code_content = "fn saved() {}\n".to_string();
+
+        let save = KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL);
+        assert!(app.handle_key_event(save).unwrap());
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "fn saved() {}\n");
+        assert_eq!(
+            app.terminal_integration
+                .get_tool_progress()
+                .map(|p| p.name.clone()),
+            Some(format!("reindex {}", path))
+        );
+    }
 }
@@ -0,0 +1,210 @@
+//! TUI Panel Navigation Module
+//!
+//! This module maps keys to the TUI's panels. Alt+1 to Alt+6 jump straight to
+//! a panel and F6 cycles through them. The cycle order is loaded from
+//! `<db_root>/panels.toml`, for example `cycle = ["files", "editor", "terminal"]`;
+//! without a valid file every panel is cycled in tab order.
+
+use anyhow::{anyhow, Result};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::Deserialize;
+use std::path::Path;
+use tracing::warn;
+
+use crate::models::TuiState;
+
+/// File name of the panel configuration inside the database root
+pub const PANELS_FILE_NAME: &str = "panels.toml";
+
+/// Key that moves to the next panel of the cycle
+pub const CYCLE_KEY: KeyCode = KeyCode::F(6);
+
+/// A top-level panel of the TUI, in tab order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Panel {
+    /// File browser
+    Files,
+    /// Code editor
+    Editor,
+    /// Agent selection
+    Agents,
+    /// LTMC patterns
+    Ltmc,
+    /// Tool selection
+    Tools,
+    /// Integrated terminal
+    Terminal,
+}
+
+impl Panel {
+    /// Every panel, in tab order
+    pub const ALL: [Panel; 6] = [
+        Panel::Files,
+        Panel::Editor,
+        Panel::Agents,
+        Panel::Ltmc,
+        Panel::Tools,
+        Panel::Terminal,
+    ];
+
+    /// Position of the panel's tab
+    pub fn tab_index(self) -> usize {
+        Self::ALL
+            .iter()
+            .position(|panel| *panel == self)
+            .unwrap_or(0)
+    }
+
+    /// State that shows the panel
+    pub fn state(self) -> TuiState {
+        match self {
+            Panel::Files => TuiState::FileBrowser,
+            Panel::Editor => TuiState::CodeEditor,
+            Panel::Agents => TuiState::AgentSelection,
+            Panel::Ltmc => TuiState::LTMCView,
+            Panel::Tools => TuiState::ToolSelection,
+            Panel::Terminal => TuiState::TerminalIntegration,
+        }
+    }
+
+    /// Panel shown in a state; analysis results belong to the editor
+    pub fn from_state(state: &TuiState) -> Self {
+        match state {
+            TuiState::FileBrowser => Panel::Files,
+            TuiState::CodeEditor | TuiState::AnalysisResults => Panel::Editor,
+            TuiState::AgentSelection => Panel::Agents,
+            TuiState::LTMCView => Panel::Ltmc,
+            TuiState::ToolSelection => Panel::Tools,
+            TuiState::TerminalIntegration => Panel::Terminal,
+        }
+    }
+}
+
+/// Panel a key jumps to directly: Alt+1 for the first tab through Alt+6
+pub fn panel_for_jump(key_event: KeyEvent) -> Option<Panel> {
+    if !key_event.modifiers.contains(KeyModifiers::ALT) {
+        return None;
+    }
+    match key_event.code {
+        KeyCode::Char(c @ '1'..='6') => Some(Panel::ALL[c as usize - '1' as usize]),
+        _ => None,
+    }
+}
+
+/// Order the cycle key moves through the panels in
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct PanelConfig {
+    /// Panels in cycle order; panels left out are only reached by their jump key
+    pub cycle: Vec<Panel>,
+}
+
+impl Default for PanelConfig {
+    fn default() -> Self {
+        Self {
+            cycle: Panel::ALL.to_vec(),
+        }
+    }
+}
+
+impl PanelConfig {
+    /// Parse a panel configuration from TOML
+    ///
+    /// The cycle must name at least one panel and no panel twice.
+    pub fn from_toml_str(content: &str) -> Result<Self> {
+        let config: Self =
+            toml::from_str(content).map_err(|e| anyhow!("Invalid panel file: {}", e))?;
+
+        if config.cycle.is_empty() {
+            return Err(anyhow!("Invalid panel file: cycle is empty"));
+        }
+        for (i, panel) in config.cycle.iter().enumerate() {
+            if config.cycle[..i].contains(panel) {
+                return Err(anyhow!("Invalid panel file: {:?} is repeated", panel));
+            }
+        }
+        Ok(config)
+    }
+
+    /// Load the panel configuration from `<db_root>/panels.toml`
+    ///
+    /// A missing or invalid file falls back to cycling every panel in tab order.
+    pub fn load(db_root: &Path) -> Self {
+        let path = db_root.join(PANELS_FILE_NAME);
+        if !path.exists() {
+            return Self::default();
+        }
+
+        match std::fs::read_to_string(&path)
+            .map_err(|e| anyhow!("Failed to read {}: {}", path.display(), e))
+            .and_then(|content| Self::from_toml_str(&content))
+        {
+            Ok(config) => config,
+            Err(e) => {
+                warn!("Using default panel order: {}", e);
+                Self::default()
+            }
+        }
+    }
+
+    /// Panel after `current` in the cycle, or the first if `current` is not in it
+    pub fn next(&self, current: Panel) -> Panel {
+        match self.cycle.iter().position(|panel| *panel == current) {
+            Some(index) => self.cycle[(index + 1) % self.cycle.len()],
+            None => self.cycle[0],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    #[test]
+    fn test_jump_keys() {
+        assert_eq!(
+            panel_for_jump(key(KeyCode::Char('1'), KeyModifiers::ALT)),
+            Some(Panel::Files)
+        );
+        assert_eq!(
+            panel_for_jump(key(KeyCode::Char('4'), KeyModifiers::ALT)),
+            Some(Panel::Ltmc)
+        );
+        assert_eq!(
+            panel_for_jump(key(KeyCode::Char('6'), KeyModifiers::ALT)),
+            Some(Panel::Terminal)
+        );
+        assert_eq!(
+            panel_for_jump(key(KeyCode::Char('7'), KeyModifiers::ALT)),
+            None
+        );
+        assert_eq!(
+            panel_for_jump(key(KeyCode::Char('1'), KeyModifiers::NONE)),
+            None
+        );
+        assert_eq!(Panel::Terminal.tab_index(), 5);
+    }
+
+    #[test]
+    fn test_custom_cycle_order() {
+        let config =
+            PanelConfig::from_toml_str(r#"cycle = ["terminal", "editor", "files"]"#).unwrap();
+        assert_eq!(config.next(Panel::Terminal), Panel::Editor);
+        assert_eq!(config.next(Panel::Files), Panel::Terminal);
+        // Panels outside the cycle continue at its start
+        assert_eq!(config.next(Panel::Agents), Panel::Terminal);
+
+        assert_eq!(PanelConfig::default().next(Panel::Files), Panel::Editor);
+        assert!(PanelConfig::from_toml_str("cycle = []").is_err());
+        assert!(PanelConfig::from_toml_str(r#"cycle = ["files", "files"]"#).is_err());
+        assert!(PanelConfig::from_toml_str(r#"cycle = ["sidebar"]"#).is_err());
+        assert_eq!(
+            PanelConfig::load(Path::new("/nonexistent/odincode")),
+            PanelConfig::default()
+        );
+    }
+}
@@ -0,0 +1,274 @@
+//! TUI Theme Module
+//!
+//! This module contains the color scheme used when rendering the TUI. Themes are
+//! loaded from `<db_root>/theme.toml`, falling back to the built-in default.
+
+use anyhow::{anyhow, Result};
+use ratatui::style::{Color, Modifier, Style};
+use serde::Deserialize;
+use std::path::Path;
+use std::str::FromStr;
+use tracing::warn;
+
+/// File name of the theme inside the database root
+pub const THEME_FILE_NAME: &str = "theme.toml";
+
+/// Named color roles used by the TUI
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Theme {
+    /// Executed commands in the terminal output
+    pub command: Color,
+    /// Text typed at the command prompt
+    pub input: Color,
+    /// Errors
+    pub error: Color,
+    /// Successful results and titles
+    pub success: Color,
+    /// Regular text
+    pub info: Color,
+    /// Borders; unset leaves them in the terminal's default color
+    pub border: Option<Color>,
+    /// Status bar text
+    pub status: Color,
+    /// Background of selected items
+    pub highlight: Color,
+    /// Text of the selected tab
+    pub tab_highlight: Color,
+    /// Lines added in a diff
+    pub added: Color,
+    /// Lines removed in a diff
+    pub removed: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            command: Color::Green,
+            input: Color::Yellow,
+            error: Color::Red,
+            success: Color::Cyan,
+            info: Color::White,
+            border: None,
+            status: Color::Gray,
+            highlight: Color::Blue,
+            tab_highlight: Color::Yellow,
+            added: Color::Green,
+            removed: Color::Red,
+        }
+    }
+}
+
+/// Theme file contents; unset roles keep the preset's color
+#[derive(Debug, Default, Deserialize)]
+struct ThemeFile {
+    /// Preset to start from ("default" or "high_contrast")
+    preset: Option<String>,
+    command: Option<String>,
+    input: Option<String>,
+    error: Option<String>,
+    success: Option<String>,
+    info: Option<String>,
+    border: Option<String>,
+    status: Option<String>,
+    highlight: Option<String>,
+    tab_highlight: Option<String>,
+    added: Option<String>,
+    removed: Option<String>,
+}
+
+impl Theme {
+    /// High-contrast preset for low-visibility terminals
+    pub fn high_contrast() -> Self {
+        Self {
+            command: Color::LightGreen,
+            input: Color::LightYellow,
+            error: Color::LightRed,
+            success: Color::LightCyan,
+            info: Color::White,
+            border: Some(Color::White),
+            status: Color::White,
+            highlight: Color::Magenta,
+            tab_highlight: Color::LightYellow,
+            added: Color::LightGreen,
+            removed: Color::LightRed,
+        }
+    }
+
+    /// Get a built-in preset by name
+    pub fn preset(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "default" => Some(Self::default()),
+            "high_contrast" | "high-contrast" => Some(Self::high_contrast()),
+            _ => None,
+        }
+    }
+
+    /// Parse a theme from TOML
+    pub fn from_toml_str(content: &str) -> Result<Self> {
+        let file: ThemeFile =
+            toml::from_str(content).map_err(|e| anyhow!("Invalid theme file: {}", e))?;
+
+        let mut theme = match &file.preset {
+            Some(name) => {
+                Self::preset(name).ok_or_else(|| anyhow!("Unknown theme preset: {}", name))?
+            }
+            None => Self::default(),
+        };
+
+        let roles = [
+            (&file.command, &mut theme.command),
+            (&file.input, &mut theme.input),
+            (&file.error, &mut theme.error),
+            (&file.success, &mut theme.success),
+            (&file.info, &mut theme.info),
+            (&file.status, &mut theme.status),
+            (&file.highlight, &mut theme.highlight),
+            (&file.tab_highlight, &mut theme.tab_highlight),
+            (&file.added, &mut theme.added),
+            (&file.removed, &mut theme.removed),
+        ];
+        for (value, color) in roles {
+            if let Some(value) = value {
+                *color = parse_color(value)?;
+            }
+        }
+        if let Some(value) = &file.border {
+            theme.border = Some(parse_color(value)?);
+        }
+
+        Ok(theme)
+    }
+
+    /// Load the theme from `<db_root>/theme.toml`
+    ///
+    /// A missing or invalid theme file falls back to the default theme.
+    pub fn load(db_root: &Path) -> Self {
+        let path = db_root.join(THEME_FILE_NAME);
+        if !path.exists() {
+            return Self::default();
+        }
+
+        match std::fs::read_to_string(&path)
+            .map_err(anyhow::Error::from)
+            .and_then(|content| Self::from_toml_str(&content))
+        {
+            Ok(theme) => theme,
+            Err(e) => {
+                warn!(
+                    "Failed to load theme file {}, using the default theme: {}",
+                    path.display(),
+                    e
+                );
+                Self::default()
+            }
+        }
+    }
+
+    /// Style for a line of terminal output of the given type
+    pub fn output_style(&self, output_type: &str) -> Style {
+        match output_type {
+            "command" => Style::default()
+                .fg(self.command)
+                .add_modifier(Modifier::BOLD),
+            "error" => Style::default().fg(self.error),
+            "success" => Style::default().fg(self.success),
+            _ => Style::default().fg(self.info),
+        }
+    }
+
+    /// Style for selected list items
+    pub fn highlight_style(&self) -> Style {
+        Style::default()
+            .bg(self.highlight)
+            .add_modifier(Modifier::BOLD)
+    }
+
+    /// Style for the selected tab
+    pub fn tab_highlight_style(&self) -> Style {
+        Style::default().fg(self.tab_highlight)
+    }
+
+    /// Style for block borders
+    pub fn border_style(&self) -> Style {
+        match self.border {
+            Some(color) => Style::default().fg(color),
+            None => Style::default(),
+        }
+    }
+}
+
+/// Parse a theme color
+///
+/// Accepts a color name such as `"red"` or `"light blue"`, a `"#rrggbb"` hex
+/// color, or an ANSI palette index from `"0"` to `"255"`.
+fn parse_color(value: &str) -> Result<Color> {
+    Color::from_str(value).map_err(|_| anyhow!("Invalid color: {}", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_theme_file_overrides_colors() {
+        let theme = Theme::from_toml_str(
+            r##"
+            command = "magenta"
+            error = "#ff8800"
+            "##,
+        )
+        .unwrap();
+
+        assert_eq!(theme.command, Color::Magenta);
+        assert_eq!(theme.error, Color::Rgb(0xff, 0x88, 0x00));
+        assert_eq!(theme.success, Theme::default().success);
+        assert_eq!(
+            theme.output_style("error").fg,
+            Some(Color::Rgb(0xff, 0x88, 0x00))
+        );
+        assert_eq!(theme.output_style("command").fg, Some(Color::Magenta));
+    }
+
+    #[test]
+    fn test_theme_preset() {
+        let theme =
+            Theme::from_toml_str("preset = \"high_contrast\"\nborder = \"yellow\"").unwrap();
+        assert_eq!(theme.command, Theme::high_contrast().command);
+        assert_eq!(theme.border, Some(Color::Yellow));
+    }
+
+    #[test]
+    fn test_default_theme_keeps_original_styles() {
+        let theme = Theme::default();
+        assert_eq!(theme.border_style(), Style::default());
+        assert_eq!(
+            theme.tab_highlight_style(),
+            Style::default().fg(Color::Yellow)
+        );
+        assert_eq!(theme.input, Color::Yellow);
+        assert_eq!(theme.status, Color::Gray);
+        assert_eq!(
+            theme.highlight_style(),
+            Style::default()
+                .bg(Color::Blue)
+                .add_modifier(Modifier::BOLD)
+        );
+    }
+
+    #[test]
+    fn test_invalid_theme_falls_back_to_default() {
+        assert!(Theme::from_toml_str("command = \"not-a-color\"").is_err());
+        assert!(Theme::from_toml_str("preset = \"neon\"").is_err());
+
+        let dir = std::env::temp_dir().join(format!("odincode-theme-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join(THEME_FILE_NAME), "command = [").unwrap();
+        assert_eq!(Theme::load(&dir), Theme::default());
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(
+            Theme::load(Path::new("/nonexistent/odincode")),
+            Theme::default()
+        );
+    }
+}
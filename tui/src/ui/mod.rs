@@ -5,13 +5,16 @@
 use ratatui::{
     backend::Backend,
     layout::{Constraint, Direction, Layout},
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
+    text::Line,
     widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Tabs},
     Frame,
 };
 
 use crate::app::TuiApp;
 use crate::models::TuiState;
+use crate::panels::Panel;
+use crate::theme::Theme;
 
 /// Render the UI
 pub fn render(app: &mut TuiApp, frame: &mut Frame) {
@@ -27,24 +30,26 @@ pub fn render(app: &mut TuiApp, frame: &mut Frame) {
         ])
         .split(size);
 
+    let theme = app.theme;
+
     // Render title bar
     let title = Paragraph::new(app.title.as_str())
         .style(
             Style::default()
-                .fg(Color::Cyan)
+                .fg(theme.success)
                 .add_modifier(Modifier::BOLD),
         )
-        .block(Block::default().borders(Borders::BOTTOM));
+        .block(border_block(&theme, Borders::BOTTOM));
     frame.render_widget(title, chunks[0]);
 
     // Render tabs
     let tabs = Tabs::new(vec![
         "Files", "Editor", "Agents", "LTMC", "Tools", "Terminal",
     ])
-    .block(Block::default().borders(Borders::BOTTOM))
-    .select(app.current_tab.min(5))
-    .style(Style::default().fg(Color::White))
-    .highlight_style(Style::default().fg(Color::Yellow));
+    .block(border_block(&theme, Borders::BOTTOM))
+    .select(Panel::from_state(&app.current_state).tab_index())
+    .style(Style::default().fg(theme.info))
+    .highlight_style(theme.tab_highlight_style());
     frame.render_widget(tabs, chunks[1]);
 
     // Render main content based on current state
@@ -55,26 +60,58 @@ pub fn render(app: &mut TuiApp, frame: &mut Frame) {
         TuiState::AnalysisResults => render_analysis_results(app, frame, chunks[2]),
         TuiState::LTMCView => render_ltmc_view(app, frame, chunks[2]),
         TuiState::ToolSelection => render_tool_selection(app, frame, chunks[2]),
-        TuiState::TerminalIntegration => app.terminal_integration.render(frame, chunks[2]),
+        TuiState::TerminalIntegration => app.terminal_integration.render(frame, chunks[2], &theme),
     }
 
     // Render status bar
     let status_text = match app.current_state {
         TuiState::FileBrowser => "File Browser - Use ↑↓ to navigate, Enter to open, A for agents, T for tools, L for LTMC",
-        TuiState::CodeEditor => "Code Editor - Use Ctrl+B to go back to file browser",
+        TuiState::CodeEditor => "Code Editor - Ctrl+S to save, Ctrl+B to go back to file browser",
         TuiState::AgentSelection => "Agent Selection - Use ↑↓ to navigate, Enter to execute",
-        TuiState::AnalysisResults => "Analysis Results",
+        TuiState::AnalysisResults => "Analysis Results - Use ↑↓ to select, Tab to switch panels, S to toggle the split view",
         TuiState::LTMCView => "LTMC View - Persistent learning and memory",
         TuiState::ToolSelection => "Tool Selection - Use ↑↓ to navigate, Enter to execute",
         TuiState::TerminalIntegration => "Terminal Integration - Execute shell commands with auto-completion",
     };
 
+    // A status message, such as a refused tool, replaces the key hints
+    let status_text = match &app.status_message {
+        Some(message) => message.clone(),
+        None => status_text.to_string(),
+    };
+
+    // So does a pending approval prompt
+    let status_text = match &app.pending_approval {
+        Some(tool) => format!(
+            "Run {}? y: yes, a: yes to all this session, n: no, q: cancel",
+            tool
+        ),
+        None => status_text,
+    };
+
+    // Show the running tool, if any, ahead of the key hints
+    let status_text = match app.terminal_integration.get_tool_progress() {
+        Some(progress) => format!(
+            "{} | {}",
+            progress.status_text(std::time::Instant::now()),
+            status_text
+        ),
+        None => status_text,
+    };
+
     let status = Paragraph::new(status_text)
-        .style(Style::default().fg(Color::Gray))
-        .block(Block::default().borders(Borders::TOP));
+        .style(Style::default().fg(theme.status))
+        .block(border_block(&theme, Borders::TOP));
     frame.render_widget(status, chunks[3]);
 }
 
+/// Block with borders in the theme's border style
+fn border_block<'a>(theme: &Theme, borders: Borders) -> Block<'a> {
+    Block::default()
+        .borders(borders)
+        .border_style(theme.border_style())
+}
+
 /// Render file browser view
 fn render_file_browser(app: &mut TuiApp, frame: &mut Frame, area: ratatui::layout::Rect) {
     let items: Vec<ListItem> = app
@@ -82,7 +119,7 @@ fn render_file_browser(app: &mut TuiApp, frame: &mut Frame, area: ratatui::layou
         .iter()
         .map(|file| {
             ListItem::new(format!("{} - {}", file.path, file.language))
-                .style(Style::default().fg(Color::White))
+                .style(Style::default().fg(app.theme.info))
         })
         .collect();
 
@@ -90,23 +127,20 @@ fn render_file_browser(app: &mut TuiApp, frame: &mut Frame, area: ratatui::layou
     state.select(Some(app.selected_file_index));
 
     let list = List::new(items)
-        .block(Block::default().borders(Borders::ALL).title("Files"))
-        .highlight_style(
-            Style::default()
-                .bg(Color::Blue)
-                .add_modifier(Modifier::BOLD),
-        );
+        .block(border_block(&app.theme, Borders::ALL).title("Files"))
+        .highlight_style(app.theme.highlight_style());
 
     frame.render_stateful_widget(list, area, &mut state);
 }
 
 /// Render code editor view
 fn render_code_editor(app: &mut TuiApp, frame: &mut Frame, area: ratatui::layout::Rect) {
-    let block = Block::default().borders(Borders::ALL).title("Code Editor");
+    let block = border_block(&app.theme, Borders::ALL).title("Code Editor");
 
     let paragraph = Paragraph::new(app.code_content.as_str())
         .block(block)
-        .style(Style::default().fg(Color::White));
+        .style(Style::default().fg(app.theme.info))
+        .scroll((app.code_scroll.min(u16::MAX as usize) as u16, 0));
 
     frame.render_widget(paragraph, area);
 }
@@ -118,7 +152,7 @@ fn render_agent_selection(app: &mut TuiApp, frame: &mut Frame, area: ratatui::la
         .iter()
         .map(|agent| {
             ListItem::new(format!("{} - {}", agent.name, agent.description))
-                .style(Style::default().fg(Color::White))
+                .style(Style::default().fg(app.theme.info))
         })
         .collect();
 
@@ -126,36 +160,84 @@ fn render_agent_selection(app: &mut TuiApp, frame: &mut Frame, area: ratatui::la
     state.select(app.selected_agent_index);
 
     let list = List::new(items)
-        .block(Block::default().borders(Borders::ALL).title("AI Agents"))
-        .highlight_style(
-            Style::default()
-                .bg(Color::Blue)
-                .add_modifier(Modifier::BOLD),
-        );
+        .block(border_block(&app.theme, Borders::ALL).title("AI Agents"))
+        .highlight_style(app.theme.highlight_style());
 
     frame.render_stateful_widget(list, area, &mut state);
 }
 
 /// Render analysis results view
+///
+/// Wide terminals show the selected file's source next to its issues, unless
+/// the split layout is toggled off.
 fn render_analysis_results(app: &mut TuiApp, frame: &mut Frame, area: ratatui::layout::Rect) {
-    let block = Block::default()
-        .borders(Borders::ALL)
-        .title("Analysis Results");
-
-    let content = if app.analysis_results.is_empty() {
-        "No analysis results available".to_string()
-    } else {
-        format!(
-            "Found {} issues and suggestions",
-            app.analysis_results.len()
-        )
+    let theme = app.theme;
+    app.diagnostics.width = area.width;
+    let Some((file, issues)) = app
+        .diagnostics_file()
+        .filter(|_| !app.analysis_results.is_empty())
+    else {
+        let paragraph = Paragraph::new("No analysis results available")
+            .block(border_block(&theme, Borders::ALL).title("Analysis Results"))
+            .style(Style::default().fg(theme.info));
+        frame.render_widget(paragraph, area);
+        return;
     };
+    let view = &app.diagnostics;
 
-    let paragraph = Paragraph::new(content)
-        .block(block)
-        .style(Style::default().fg(Color::White));
+    let visible = view.visible_issues(issues);
+    let items: Vec<ListItem> = visible
+        .iter()
+        .map(|&index| {
+            let issue = &issues[index];
+            ListItem::new(format!(
+                "{}:{} {:?}: {}",
+                issue.line_number, issue.column_number, issue.severity, issue.description
+            ))
+            .style(Style::default().fg(theme.info))
+        })
+        .collect();
+    let mut state = ListState::default();
+    state.select(view.selected_issue);
+    let issue_list = List::new(items)
+        .block(border_block(&theme, Borders::ALL).title(format!(
+            "Issues ({}/{})",
+            visible.len(),
+            issues.len()
+        )))
+        .highlight_style(theme.highlight_style());
 
-    frame.render_widget(paragraph, area);
+    if !view.shows_split() {
+        frame.render_stateful_widget(issue_list, area, &mut state);
+        return;
+    }
+
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+        .split(area);
+
+    let source: Vec<Line> = file
+        .content
+        .lines()
+        .enumerate()
+        .map(|(index, text)| {
+            let line = Line::from(format!("{:>4} {}", index + 1, text));
+            if view.selected_line == Some(index + 1) {
+                line.style(theme.highlight_style())
+            } else {
+                line
+            }
+        })
+        .collect();
+    let source = Paragraph::new(source)
+        .block(border_block(&theme, Borders::ALL).title(file.path.clone()))
+        .style(Style::default().fg(theme.info))
+        .scroll((view.source_scroll.min(u16::MAX as usize) as u16, 0));
+
+    frame.render_widget(source, chunks[0]);
+    frame.render_stateful_widget(issue_list, chunks[1], &mut state);
+    app.diagnostics.viewport_height = chunks[0].height.saturating_sub(2) as usize;
 }
 
 /// Render LTMC view
@@ -165,7 +247,7 @@ fn render_ltmc_view(app: &mut TuiApp, frame: &mut Frame, area: ratatui::layout::
         .iter()
         .map(|pattern| {
             ListItem::new(format!("{:?}: {}", pattern.pattern_type, pattern.content))
-                .style(Style::default().fg(Color::White))
+                .style(Style::default().fg(app.theme.info))
         })
         .collect();
 
@@ -173,16 +255,8 @@ fn render_ltmc_view(app: &mut TuiApp, frame: &mut Frame, area: ratatui::layout::
     state.select(app.selected_pattern_index);
 
     let list = List::new(items)
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .title("LTMC Patterns"),
-        )
-        .highlight_style(
-            Style::default()
-                .bg(Color::Blue)
-                .add_modifier(Modifier::BOLD),
-        );
+        .block(border_block(&app.theme, Borders::ALL).title("LTMC Patterns"))
+        .highlight_style(app.theme.highlight_style());
 
     frame.render_stateful_widget(list, area, &mut state);
 }
@@ -192,23 +266,15 @@ fn render_tool_selection(app: &mut TuiApp, frame: &mut Frame, area: ratatui::lay
     let items: Vec<ListItem> = app
         .tools
         .iter()
-        .map(|tool| ListItem::new(tool.as_str()).style(Style::default().fg(Color::White)))
+        .map(|tool| ListItem::new(tool.as_str()).style(Style::default().fg(app.theme.info)))
         .collect();
 
     let mut state = ListState::default();
     state.select(app.selected_tool_index);
 
     let list = List::new(items)
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .title("Development Tools"),
-        )
-        .highlight_style(
-            Style::default()
-                .bg(Color::Blue)
-                .add_modifier(Modifier::BOLD),
-        );
+        .block(border_block(&app.theme, Borders::ALL).title("Development Tools"))
+        .highlight_style(app.theme.highlight_style());
 
     frame.render_stateful_widget(list, area, &mut state);
 }